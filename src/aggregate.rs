@@ -0,0 +1,194 @@
+use cglinalg::{
+    Magnitude,
+    Vector3,
+    ScalarFloat,
+};
+use crate::{
+    Light,
+    PointLight,
+    PointLightModelSpec,
+};
+use crate::color::luminance;
+use crate::presets::default_attitude_spec;
+
+
+/// A small emissive point such as a particle or a spark.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Emitter<S> {
+    /// The position of the emitter in world space.
+    pub position: Vector3<S>,
+    /// The emitted intensity of the emitter in each color channel.
+    pub intensity: Vector3<S>,
+}
+
+impl<S> Emitter<S> where S: ScalarFloat {
+    /// Construct a new emitter.
+    #[inline]
+    pub fn new(position: Vector3<S>, intensity: Vector3<S>) -> Emitter<S> {
+        Emitter {
+            position: position,
+            intensity: intensity,
+        }
+    }
+
+    /// The weight of the emitter in the aggregation, given by the luminance
+    /// of its intensity.
+    #[inline]
+    fn weight(&self) -> S {
+        luminance(&self.intensity)
+    }
+}
+
+/// A point light standing in for a cluster of emitters.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct ProxyLight<S> {
+    /// The intensity weighted centroid of the emitters in the cluster.
+    pub position: Vector3<S>,
+    /// The total intensity of the emitters in the cluster.
+    pub intensity: Vector3<S>,
+    /// The distance from the centroid to the farthest emitter in the cluster.
+    pub radius: S,
+    /// The number of emitters in the cluster.
+    pub emitter_count: usize,
+}
+
+impl<S> ProxyLight<S> where S: ScalarFloat {
+    /// Construct a point light from the proxy. The proxy's intensity
+    /// becomes the diffuse and specular color of the light.
+    pub fn to_point_light(&self) -> PointLight<S> {
+        let model_spec = PointLightModelSpec::new(
+            Vector3::zero(),
+            self.intensity,
+            self.intensity,
+        );
+        let attitude_spec = default_attitude_spec(self.position);
+
+        Light::new(&model_spec, &attitude_spec)
+    }
+}
+
+/// An aggregator merging large numbers of small emitters into a handful of
+/// representative point lights.
+///
+/// The aggregator clusters the emitters with a few iterations of weighted
+/// k-means each frame. The clusters from the previous frame seed the next
+/// one, so the proxies move coherently and a single iteration per frame is
+/// usually enough for slowly evolving particle systems.
+#[derive(Clone, Debug)]
+pub struct EmitterAggregator<S> {
+    max_proxies: usize,
+    iterations: usize,
+    proxies: Vec<ProxyLight<S>>,
+    assignments: Vec<usize>,
+}
+
+impl<S> EmitterAggregator<S> where S: ScalarFloat {
+    /// Construct a new aggregator producing at most `max_proxies` proxy
+    /// lights using `iterations` clustering passes per update.
+    pub fn new(max_proxies: usize, iterations: usize) -> EmitterAggregator<S> {
+        EmitterAggregator {
+            max_proxies: max_proxies,
+            iterations: iterations.max(1),
+            proxies: Vec::with_capacity(max_proxies),
+            assignments: Vec::new(),
+        }
+    }
+
+    /// Recompute the proxy lights for the current frame's emitters.
+    pub fn update(&mut self, emitters: &[Emitter<S>]) -> &[ProxyLight<S>] {
+        let cluster_count = self.max_proxies.min(emitters.len());
+        if cluster_count == 0 {
+            self.proxies.clear();
+            self.assignments.clear();
+            return &self.proxies;
+        }
+
+        let mut centers: Vec<Vector3<S>> = if self.proxies.len() == cluster_count {
+            self.proxies.iter().map(|proxy| proxy.position).collect()
+        } else {
+            // Seed the clusters with emitters spread evenly through the input.
+            (0..cluster_count)
+                .map(|i| emitters[i * emitters.len() / cluster_count].position)
+                .collect()
+        };
+
+        self.assignments.resize(emitters.len(), 0);
+        let mut position_sums = vec![Vector3::zero(); cluster_count];
+        let mut weight_sums = vec![S::zero(); cluster_count];
+        let mut counts = vec![0_usize; cluster_count];
+        for _ in 0..self.iterations {
+            for sum in position_sums.iter_mut() {
+                *sum = Vector3::zero();
+            }
+            for sum in weight_sums.iter_mut() {
+                *sum = S::zero();
+            }
+            for count in counts.iter_mut() {
+                *count = 0;
+            }
+
+            for (emitter, assignment) in emitters.iter().zip(self.assignments.iter_mut()) {
+                let cluster = nearest_center(&centers, &emitter.position);
+                let weight = emitter.weight();
+                *assignment = cluster;
+                position_sums[cluster] += emitter.position * weight;
+                weight_sums[cluster] += weight;
+                counts[cluster] += 1;
+            }
+
+            for (cluster, center) in centers.iter_mut().enumerate() {
+                // Empty clusters keep their previous center.
+                if weight_sums[cluster] > S::zero() {
+                    *center = position_sums[cluster] / weight_sums[cluster];
+                }
+            }
+        }
+
+        self.proxies.clear();
+        for center in centers.iter() {
+            self.proxies.push(ProxyLight {
+                position: *center,
+                intensity: Vector3::zero(),
+                radius: S::zero(),
+                emitter_count: 0,
+            });
+        }
+        for (emitter, &cluster) in emitters.iter().zip(self.assignments.iter()) {
+            let proxy = &mut self.proxies[cluster];
+            let distance = (emitter.position - proxy.position).magnitude();
+            proxy.intensity += emitter.intensity;
+            proxy.radius = proxy.radius.max(distance);
+            proxy.emitter_count += 1;
+        }
+        self.proxies.retain(|proxy| proxy.emitter_count > 0);
+
+        &self.proxies
+    }
+
+    /// The proxy lights computed by the most recent update.
+    #[inline]
+    pub fn proxies(&self) -> &[ProxyLight<S>] {
+        &self.proxies
+    }
+
+    /// The maximum number of proxy lights the aggregator produces.
+    #[inline]
+    pub fn max_proxies(&self) -> usize {
+        self.max_proxies
+    }
+}
+
+/// Find the index of the center closest to a point.
+fn nearest_center<S: ScalarFloat>(centers: &[Vector3<S>], point: &Vector3<S>) -> usize {
+    let mut nearest = 0;
+    let mut nearest_distance_squared = S::infinity();
+    for (i, center) in centers.iter().enumerate() {
+        let distance_squared = (point - center).magnitude_squared();
+        if distance_squared < nearest_distance_squared {
+            nearest = i;
+            nearest_distance_squared = distance_squared;
+        }
+    }
+
+    nearest
+}
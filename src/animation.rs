@@ -0,0 +1,453 @@
+use cglinalg::{
+    ScalarFloat,
+};
+use crate::{
+    Dimmable,
+    IlluminationModel,
+    Light,
+    LightAttitudeSpec,
+};
+use crate::expression::{
+    BindableModel,
+    LightProperty,
+};
+use crate::random::Random;
+
+
+/// A specification describing the temporal profile of a lightning flash.
+///
+/// All times are measured in seconds.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct LightningFlashSpec<S> {
+    /// The intensity multiplier at the peak of the first strike.
+    pub peak_intensity: S,
+    /// The number of re-strikes following the initial strike.
+    pub restrikes: usize,
+    /// The time each strike takes to rise from darkness to its peak.
+    pub attack_time: S,
+    /// The time constant of the exponential decay following each peak.
+    pub decay_time: S,
+    /// The average time between consecutive strikes.
+    pub restrike_interval: S,
+    /// The seed for the random number generator. The same seed always
+    /// produces the same flash.
+    pub seed: u64,
+}
+
+impl<S> LightningFlashSpec<S> where S: ScalarFloat {
+    /// Construct a new lightning flash specification.
+    #[inline]
+    pub fn new(
+        peak_intensity: S,
+        restrikes: usize,
+        attack_time: S,
+        decay_time: S,
+        restrike_interval: S,
+        seed: u64) -> LightningFlashSpec<S>
+    {
+        LightningFlashSpec {
+            peak_intensity: peak_intensity,
+            restrikes: restrikes,
+            attack_time: attack_time,
+            decay_time: decay_time,
+            restrike_interval: restrike_interval,
+            seed: seed,
+        }
+    }
+}
+
+/// A single stroke of a lightning flash.
+#[derive(Copy, Clone, Debug, PartialEq)]
+struct Strike<S> {
+    /// The time at which the strike begins.
+    time: S,
+    /// The intensity multiplier at the peak of the strike.
+    peak: S,
+}
+
+/// A controller producing the intensity envelope of a lightning flash.
+///
+/// A flash consists of an initial strike followed by a stutter of weaker
+/// re-strikes. Each strike has a sharp linear attack followed by an
+/// exponential decay. The intensity at a given time is the brightest of
+/// the overlapping strikes.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LightningFlash<S> {
+    strikes: Vec<Strike<S>>,
+    attack_time: S,
+    decay_time: S,
+}
+
+impl<S> LightningFlash<S> where S: ScalarFloat {
+    /// Construct a new lightning flash from its specification.
+    pub fn new(spec: &LightningFlashSpec<S>) -> LightningFlash<S> {
+        let one_half: S = num_traits::cast(0.5).unwrap();
+        let mut random = Random::new(spec.seed);
+        let mut strikes = Vec::with_capacity(spec.restrikes + 1);
+        let mut time = S::zero();
+        let mut peak = spec.peak_intensity;
+        strikes.push(Strike { time: time, peak: peak });
+        for _ in 0..spec.restrikes {
+            // Re-strikes arrive at irregular intervals and are usually weaker.
+            let interval = spec.restrike_interval * (one_half + random.next_scalar::<S>());
+            let falloff = one_half + random.next_scalar::<S>() * one_half;
+            time += interval;
+            peak *= falloff;
+            strikes.push(Strike { time: time, peak: peak });
+        }
+
+        LightningFlash {
+            strikes: strikes,
+            attack_time: spec.attack_time,
+            decay_time: spec.decay_time,
+        }
+    }
+
+    /// The intensity multiplier of the flash at the given time since the
+    /// flash began.
+    pub fn intensity(&self, time: S) -> S {
+        self.strikes.iter().fold(S::zero(), |intensity, strike| {
+            let elapsed = time - strike.time;
+            let strike_intensity = if elapsed < S::zero() {
+                S::zero()
+            } else if elapsed < self.attack_time {
+                strike.peak * (elapsed / self.attack_time)
+            } else {
+                let decay_elapsed = elapsed - self.attack_time;
+                strike.peak * (-decay_elapsed / self.decay_time).exp()
+            };
+
+            intensity.max(strike_intensity)
+        })
+    }
+
+    /// The time after which the flash has decayed to below one percent of
+    /// its last strike's peak intensity.
+    pub fn duration(&self) -> S {
+        let last_strike_time = self.strikes[self.strikes.len() - 1].time;
+        let hundred: S = num_traits::cast(100).unwrap();
+
+        last_strike_time + self.attack_time + self.decay_time * hundred.ln()
+    }
+
+    /// Determine whether the flash has finished at the given time.
+    #[inline]
+    pub fn is_finished(&self, time: S) -> bool {
+        time >= self.duration()
+    }
+
+    /// Drive a light's intensity with the flash. The light's illumination
+    /// model becomes the base model scaled by the flash intensity at the
+    /// given time.
+    #[inline]
+    pub fn apply<M>(&self, time: S, base_model: &M, light: &mut Light<S, M>)
+        where M: IlluminationModel + Dimmable<S>
+    {
+        *light.model_mut() = base_model.dimmed(self.intensity(time));
+    }
+}
+
+/// The shape of the intensity decay of a transient light over its lifetime.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum DecayCurve<S> {
+    /// The intensity falls off linearly to zero.
+    Linear,
+    /// The intensity falls off quadratically, fading quickly at first.
+    Quadratic,
+    /// The intensity falls off with a smoothstep, lingering near its peak
+    /// before fading out.
+    Smooth,
+    /// The intensity decays exponentially at the given rate, and is faded
+    /// to exactly zero at the end of the lifetime.
+    Exponential(S),
+}
+
+impl<S> DecayCurve<S> where S: ScalarFloat {
+    /// Evaluate the decay curve at the normalized age `t` in `[0, 1]`. The
+    /// result is one at the beginning of the lifetime and zero at its end.
+    pub fn evaluate(&self, t: S) -> S {
+        let t = t.max(S::zero()).min(S::one());
+        let remaining = S::one() - t;
+        match *self {
+            DecayCurve::Linear => remaining,
+            DecayCurve::Quadratic => remaining * remaining,
+            DecayCurve::Smooth => {
+                let two: S = num_traits::cast(2).unwrap();
+                let three: S = num_traits::cast(3).unwrap();
+                S::one() - t * t * (three - two * t)
+            }
+            DecayCurve::Exponential(rate) => (-rate * t).exp() * remaining,
+        }
+    }
+}
+
+/// A short-lived light, such as a muzzle flash or an explosion, whose 
+/// intensity decays from a peak value to zero over a fixed lifetime.
+pub struct TransientLight<S, M> {
+    light: Light<S, M>,
+    decay: TransientDecay<S, M>,
+}
+
+/// The decay of a transient light, apart from the light it dims. A
+/// `scene::LightScene` keeps the decays of its transient lights next to the
+/// lights it stores.
+#[derive(Clone, Debug)]
+pub(crate) struct TransientDecay<S, M> {
+    base_model: M,
+    peak_intensity: S,
+    lifetime: S,
+    age: S,
+    curve: DecayCurve<S>,
+}
+
+impl<S, M> TransientDecay<S, M>
+    where S: ScalarFloat,
+          M: IlluminationModel + Dimmable<S>,
+{
+    /// Advance the decay by `elapsed` seconds and dim the light to its new
+    /// intensity.
+    pub(crate) fn update(&mut self, elapsed: S, light: &mut Light<S, M>) {
+        self.age += elapsed;
+        *light.model_mut() = self.base_model.dimmed(self.intensity());
+    }
+
+    /// The current intensity multiplier of the light.
+    #[inline]
+    pub(crate) fn intensity(&self) -> S {
+        if self.lifetime <= S::zero() {
+            return S::zero();
+        }
+
+        self.peak_intensity * self.curve.evaluate(self.age / self.lifetime)
+    }
+
+    /// Determine whether the light has outlived its lifetime.
+    #[inline]
+    pub(crate) fn is_expired(&self) -> bool {
+        self.age >= self.lifetime
+    }
+}
+
+impl<S, M> TransientLight<S, M>
+    where S: ScalarFloat,
+          M: IlluminationModel + Dimmable<S>,
+{
+    /// Spawn a new transient light at its peak intensity. The lifetime is
+    /// measured in seconds.
+    pub fn spawn(
+        model_spec: &M::Spec,
+        attitude_spec: &LightAttitudeSpec<S>,
+        peak_intensity: S,
+        lifetime: S,
+        curve: DecayCurve<S>) -> TransientLight<S, M>
+    {
+        let mut light = Light::new(model_spec, attitude_spec);
+        let base_model = M::from_spec(model_spec);
+        *light.model_mut() = base_model.dimmed(peak_intensity);
+
+        TransientLight {
+            light: light,
+            decay: TransientDecay {
+                base_model: base_model,
+                peak_intensity: peak_intensity,
+                lifetime: lifetime,
+                age: S::zero(),
+                curve: curve,
+            },
+        }
+    }
+
+    /// Advance the light's age by `elapsed` seconds and update its intensity.
+    #[inline]
+    pub fn update(&mut self, elapsed: S) {
+        self.decay.update(elapsed, &mut self.light);
+    }
+
+    /// The current intensity multiplier of the light.
+    #[inline]
+    pub fn intensity(&self) -> S {
+        self.decay.intensity()
+    }
+
+    /// Determine whether the light has outlived its lifetime.
+    #[inline]
+    pub fn is_expired(&self) -> bool {
+        self.decay.is_expired()
+    }
+
+    /// The time in seconds since the light was spawned.
+    #[inline]
+    pub fn age(&self) -> S {
+        self.decay.age
+    }
+
+    /// The total lifetime of the light in seconds.
+    #[inline]
+    pub fn lifetime(&self) -> S {
+        self.decay.lifetime
+    }
+
+    /// Get the underlying light.
+    #[inline]
+    pub fn light(&self) -> &Light<S, M> {
+        &self.light
+    }
+
+    /// Get a mutable reference to the underlying light, e.g. to move it with
+    /// the object that emitted it.
+    #[inline]
+    pub fn light_mut(&mut self) -> &mut Light<S, M> {
+        &mut self.light
+    }
+
+    /// Split the transient light into its light and its decay.
+    #[inline]
+    pub(crate) fn into_parts(self) -> (Light<S, M>, TransientDecay<S, M>) {
+        (self.light, self.decay)
+    }
+}
+
+/// Advance a collection of transient lights by `elapsed` seconds, removing
+/// the lights that have expired.
+pub fn update_transients<S, M>(transients: &mut Vec<TransientLight<S, M>>, elapsed: S)
+    where S: ScalarFloat,
+          M: IlluminationModel + Dimmable<S>,
+{
+    for transient in transients.iter_mut() {
+        transient.update(elapsed);
+    }
+    transients.retain(|transient| !transient.is_expired());
+}
+
+/// A specification describing how a modulation source drives a light.
+///
+/// All times are measured in seconds.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct ModulationSpec<S> {
+    /// The light property the modulation drives.
+    pub property: LightProperty,
+    /// The time constant with which the envelope follows a rising input.
+    pub attack_time: S,
+    /// The time constant with which the envelope follows a falling input.
+    pub release_time: S,
+    /// The input level mapped to `output_min`.
+    pub input_min: S,
+    /// The input level mapped to `output_max`.
+    pub input_max: S,
+    /// The property value at or below `input_min`.
+    pub output_min: S,
+    /// The property value at or above `input_max`.
+    pub output_max: S,
+}
+
+impl<S> ModulationSpec<S> where S: ScalarFloat {
+    /// Construct a new modulation specification.
+    #[inline]
+    pub fn new(
+        property: LightProperty,
+        attack_time: S,
+        release_time: S,
+        input_min: S,
+        input_max: S,
+        output_min: S,
+        output_max: S) -> ModulationSpec<S>
+    {
+        ModulationSpec {
+            property: property,
+            attack_time: attack_time,
+            release_time: release_time,
+            input_min: input_min,
+            input_max: input_max,
+            output_min: output_min,
+            output_max: output_max,
+        }
+    }
+}
+
+/// A modulation source mapping a caller-provided signal, such as the
+/// amplitude of an audio stream or a beat envelope, onto a light property.
+///
+/// The modulator smooths the signal with an envelope follower that rises
+/// with the attack time and falls with the release time, then maps the
+/// envelope linearly from the input range onto the output range. A short
+/// attack and a long release makes lights punch on the beat and fade out
+/// between beats.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Modulator<S> {
+    spec: ModulationSpec<S>,
+    envelope: S,
+}
+
+impl<S> Modulator<S> where S: ScalarFloat {
+    /// Construct a new modulator with its envelope at rest at the bottom of
+    /// the input range.
+    #[inline]
+    pub fn new(spec: &ModulationSpec<S>) -> Modulator<S> {
+        Modulator {
+            spec: *spec,
+            envelope: spec.input_min,
+        }
+    }
+
+    /// Feed the latest input level to the modulator, `elapsed` seconds after
+    /// the previous one, and return the new property value.
+    pub fn update(&mut self, input: S, elapsed: S) -> S {
+        let time_constant = if input > self.envelope {
+            self.spec.attack_time
+        } else {
+            self.spec.release_time
+        };
+        let blend = if time_constant > S::zero() {
+            S::one() - (-elapsed / time_constant).exp()
+        } else {
+            S::one()
+        };
+        self.envelope += (input - self.envelope) * blend;
+
+        self.value()
+    }
+
+    /// The current level of the envelope follower, in input units.
+    #[inline]
+    pub fn envelope(&self) -> S {
+        self.envelope
+    }
+
+    /// The current property value.
+    pub fn value(&self) -> S {
+        let input_range = self.spec.input_max - self.spec.input_min;
+        let t = if input_range != S::zero() {
+            ((self.envelope - self.spec.input_min) / input_range).max(S::zero()).min(S::one())
+        } else {
+            S::zero()
+        };
+
+        self.spec.output_min + (self.spec.output_max - self.spec.output_min) * t
+    }
+
+    /// The modulator's specification.
+    #[inline]
+    pub fn spec(&self) -> &ModulationSpec<S> {
+        &self.spec
+    }
+
+    /// Apply the current property value to a lighting model. Several
+    /// modulators can drive the same model by modulating it in turn.
+    #[inline]
+    pub fn modulate<M>(&self, model: &mut M)
+        where M: BindableModel<S>
+    {
+        model.apply_property(self.spec.property, self.value());
+    }
+
+    /// Drive a light with the modulator. The light's illumination model
+    /// becomes the base model with the current property value applied.
+    #[inline]
+    pub fn apply<M>(&self, base_model: &M, light: &mut Light<S, M>)
+        where M: IlluminationModel + BindableModel<S> + Clone
+    {
+        let mut model = base_model.clone();
+        self.modulate(&mut model);
+        *light.model_mut() = model;
+    }
+}
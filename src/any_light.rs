@@ -0,0 +1,784 @@
+use cglinalg::{
+    Magnitude,
+    Vector3,
+    ScalarFloat,
+};
+use crate::{
+    AmbientLightModel,
+    BeamLightModel,
+    DirectionalLight,
+    DirectionalLightModel,
+    DiskLight,
+    DiskLightModel,
+    HemisphereLightModel,
+    IlluminationModel,
+    Light,
+    PointLight,
+    PointLightModel,
+    SphereLight,
+    SphereLightModel,
+    SpotLight,
+    SpotLightModel,
+    TubeLight,
+    TubeLightModel,
+};
+use crate::environment::{
+    DomeLight,
+    DomeLightModel,
+    EnvironmentLight,
+    EnvironmentLightModel,
+    PortalLight,
+    PortalLightModel,
+};
+use crate::ies::IesLightModel;
+use crate::mesh::{
+    MeshLight,
+    MeshLightModel,
+};
+use crate::pbr::PbrLightModel;
+use crate::photometry::{
+    Lumens,
+    PowerEmitter,
+};
+use crate::polygon::{
+    NGonLight,
+    NGonLightModel,
+};
+#[cfg(feature = "spectral")]
+use crate::spectral::SpectralLightModel;
+use crate::sampling::halton;
+use crate::std430::Std430Light;
+use core::any::Any;
+
+
+/// A tag identifying the kind of a light without knowing its concrete type.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum LightKind {
+    Ambient,
+    Hemisphere,
+    Point,
+    Spot,
+    Directional,
+    Disk,
+    Sphere,
+    Tube,
+    Environment,
+    Portal,
+    Mesh,
+    Ies,
+    NGon,
+    Beam,
+    Dome,
+    Pbr,
+    /// A spectral light, from the `spectral` feature. The variant exists
+    /// without the feature so that enabling it does not change the enum.
+    Spectral,
+}
+
+/// A sphere bounding the region a light affects.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct BoundingSphere<S> {
+    pub center: Vector3<S>,
+    /// The radius of the sphere. Lights without distance attenuation have an
+    /// infinite radius.
+    pub radius: S,
+}
+
+/// The properties of a lighting model needed to handle lights without
+/// knowing the model's concrete type.
+pub trait LightModelInfo<S> {
+    /// The kind of light the model describes.
+    fn kind(&self) -> LightKind;
+
+    /// The distance from the light beyond which the model's distance
+    /// attenuation falls below `threshold`. Models without distance
+    /// attenuation have an infinite influence radius.
+    fn influence_radius(&self, threshold: S) -> S;
+
+    /// Append the std430 representation of a light with this model to a
+    /// buffer. Models without a std430 representation write nothing and
+    /// return `false`.
+    #[inline]
+    fn write_light_std430(_light: &Light<S, Self>, _bytes: &mut Vec<u8>) -> bool
+        where Self: Sized
+    {
+        false
+    }
+
+    /// Sample a direction from `point` towards a light with this model, as
+    /// described by [`AnyLight::sample`]. Models without a sampling routine
+    /// return `None`.
+    #[inline]
+    fn sample_light(_light: &Light<S, Self>, _u: S, _v: S, _point: &Vector3<S>) -> Option<(Vector3<S>, S)>
+        where Self: Sized
+    {
+        None
+    }
+
+    /// The radiance a light with this model emits from a point on its
+    /// surface, as described by [`AnyLight::emitted_radiance`]. Models that
+    /// rays cannot hit, such as punctual lights, emit nothing.
+    #[inline]
+    fn emitted_light_radiance(_light: &Light<S, Self>, _point: &Vector3<S>, _direction: &Vector3<S>) -> Vector3<S>
+        where S: ScalarFloat,
+              Self: Sized
+    {
+        Vector3::zero()
+    }
+
+    /// Intersect a ray with the emitting surface of a light with this model,
+    /// as described by [`AnyLight::intersect`]. Models that rays cannot hit
+    /// return `None`.
+    #[inline]
+    fn intersect_light(_light: &Light<S, Self>, _origin: &Vector3<S>, _direction: &Vector3<S>) -> Option<S>
+        where Self: Sized
+    {
+        None
+    }
+
+    /// The luminous flux the model emits, as described by
+    /// [`photometry::PowerEmitter`](../photometry/trait.PowerEmitter.html).
+    /// Models without a finite power return `None`.
+    #[inline]
+    fn emitted_light_power(&self) -> Option<Lumens<S>> {
+        None
+    }
+}
+
+/// Sample the single direction from `point` towards a punctual light at
+/// `position`, with a density of one.
+#[inline]
+fn sample_punctual<S: ScalarFloat>(position: &Vector3<S>, point: &Vector3<S>) -> Option<(Vector3<S>, S)> {
+    let to_light = position - point;
+    let distance = to_light.magnitude();
+    if distance <= S::zero() {
+        return None;
+    }
+
+    Some((to_light / distance, S::one()))
+}
+
+impl<S> LightModelInfo<S> for AmbientLightModel<S> where S: ScalarFloat {
+    #[inline]
+    fn kind(&self) -> LightKind {
+        LightKind::Ambient
+    }
+
+    #[inline]
+    fn influence_radius(&self, _threshold: S) -> S {
+        S::infinity()
+    }
+}
+
+impl<S> LightModelInfo<S> for HemisphereLightModel<S> where S: ScalarFloat {
+    #[inline]
+    fn kind(&self) -> LightKind {
+        LightKind::Hemisphere
+    }
+
+    #[inline]
+    fn influence_radius(&self, _threshold: S) -> S {
+        S::infinity()
+    }
+}
+
+impl<S> LightModelInfo<S> for PointLightModel<S> where S: ScalarFloat {
+    #[inline]
+    fn kind(&self) -> LightKind {
+        LightKind::Point
+    }
+
+    #[inline]
+    fn influence_radius(&self, threshold: S) -> S {
+        self.range(threshold)
+    }
+
+    #[inline]
+    fn write_light_std430(light: &PointLight<S>, bytes: &mut Vec<u8>) -> bool {
+        <PointLight<S> as Std430Light>::write_std430(light, bytes);
+        true
+    }
+
+    #[inline]
+    fn sample_light(light: &PointLight<S>, _u: S, _v: S, point: &Vector3<S>) -> Option<(Vector3<S>, S)> {
+        sample_punctual(&light.position(), point)
+    }
+
+    #[inline]
+    fn emitted_light_power(&self) -> Option<Lumens<S>> {
+        Some(self.emitted_power())
+    }
+}
+
+impl<S> LightModelInfo<S> for SpotLightModel<S> where S: ScalarFloat {
+    #[inline]
+    fn kind(&self) -> LightKind {
+        LightKind::Spot
+    }
+
+    #[inline]
+    fn influence_radius(&self, threshold: S) -> S {
+        self.range(threshold)
+    }
+
+    #[inline]
+    fn write_light_std430(light: &SpotLight<S>, bytes: &mut Vec<u8>) -> bool {
+        <SpotLight<S> as Std430Light>::write_std430(light, bytes);
+        true
+    }
+
+    #[inline]
+    fn sample_light(light: &SpotLight<S>, _u: S, _v: S, point: &Vector3<S>) -> Option<(Vector3<S>, S)> {
+        sample_punctual(&light.position(), point)
+    }
+
+    #[inline]
+    fn emitted_light_power(&self) -> Option<Lumens<S>> {
+        Some(self.emitted_power())
+    }
+}
+
+impl<S> LightModelInfo<S> for DirectionalLightModel<S> where S: ScalarFloat {
+    #[inline]
+    fn kind(&self) -> LightKind {
+        LightKind::Directional
+    }
+
+    #[inline]
+    fn influence_radius(&self, _threshold: S) -> S {
+        S::infinity()
+    }
+
+    #[inline]
+    fn write_light_std430(light: &DirectionalLight<S>, bytes: &mut Vec<u8>) -> bool {
+        <DirectionalLight<S> as Std430Light>::write_std430(light, bytes);
+        true
+    }
+
+    /// Directional light arrives from behind the light's forward axis.
+    #[inline]
+    fn sample_light(light: &DirectionalLight<S>, _u: S, _v: S, _point: &Vector3<S>) -> Option<(Vector3<S>, S)> {
+        Some((-light.forward_axis(), S::one()))
+    }
+}
+
+impl<S> LightModelInfo<S> for DiskLightModel<S> where S: ScalarFloat {
+    #[inline]
+    fn kind(&self) -> LightKind {
+        LightKind::Disk
+    }
+
+    #[inline]
+    fn influence_radius(&self, _threshold: S) -> S {
+        S::infinity()
+    }
+
+    #[inline]
+    fn sample_light(light: &DiskLight<S>, u: S, v: S, point: &Vector3<S>) -> Option<(Vector3<S>, S)> {
+        light.sample(u, v, point)
+    }
+
+    #[inline]
+    fn emitted_light_radiance(light: &DiskLight<S>, point: &Vector3<S>, direction: &Vector3<S>) -> Vector3<S> {
+        light.emitted_radiance(point, direction)
+    }
+
+    #[inline]
+    fn emitted_light_power(&self) -> Option<Lumens<S>> {
+        Some(self.emitted_power())
+    }
+
+    #[inline]
+    fn intersect_light(light: &DiskLight<S>, origin: &Vector3<S>, direction: &Vector3<S>) -> Option<S> {
+        light.intersect(origin, direction)
+    }
+}
+
+impl<S> LightModelInfo<S> for SphereLightModel<S> where S: ScalarFloat {
+    #[inline]
+    fn kind(&self) -> LightKind {
+        LightKind::Sphere
+    }
+
+    /// The irradiance from a sphere light falls off with the sine squared of
+    /// the half angle it subtends, `(r / d)^2`, which falls below
+    /// `threshold` beyond `r / sqrt(threshold)`.
+    #[inline]
+    fn influence_radius(&self, threshold: S) -> S {
+        if threshold <= S::zero() {
+            return S::infinity();
+        }
+
+        self.radius / threshold.sqrt()
+    }
+
+    #[inline]
+    fn sample_light(light: &SphereLight<S>, u: S, v: S, point: &Vector3<S>) -> Option<(Vector3<S>, S)> {
+        light.sample(u, v, point)
+    }
+
+    #[inline]
+    fn emitted_light_radiance(light: &SphereLight<S>, point: &Vector3<S>, direction: &Vector3<S>) -> Vector3<S> {
+        light.emitted_radiance(point, direction)
+    }
+
+    #[inline]
+    fn emitted_light_power(&self) -> Option<Lumens<S>> {
+        Some(self.emitted_power())
+    }
+
+    #[inline]
+    fn intersect_light(light: &SphereLight<S>, origin: &Vector3<S>, direction: &Vector3<S>) -> Option<S> {
+        light.intersect(origin, direction)
+    }
+}
+
+impl<S> LightModelInfo<S> for TubeLightModel<S> where S: ScalarFloat {
+    #[inline]
+    fn kind(&self) -> LightKind {
+        LightKind::Tube
+    }
+
+    /// Far from the tube, its irradiance falls off like that of a sphere with
+    /// the same projected area `A`, as `A / (πd^2)`.
+    #[inline]
+    fn influence_radius(&self, threshold: S) -> S {
+        if threshold <= S::zero() {
+            return S::infinity();
+        }
+        let pi: S = num_traits::cast(core::f64::consts::PI).unwrap();
+
+        (self.projected_area() / (pi * threshold)).sqrt()
+    }
+
+    #[inline]
+    fn emitted_light_radiance(light: &TubeLight<S>, point: &Vector3<S>, direction: &Vector3<S>) -> Vector3<S> {
+        light.emitted_radiance(point, direction)
+    }
+
+    #[inline]
+    fn emitted_light_power(&self) -> Option<Lumens<S>> {
+        Some(self.emitted_power())
+    }
+}
+
+impl<S> LightModelInfo<S> for EnvironmentLightModel<S> where S: ScalarFloat {
+    #[inline]
+    fn kind(&self) -> LightKind {
+        LightKind::Environment
+    }
+
+    #[inline]
+    fn influence_radius(&self, _threshold: S) -> S {
+        S::infinity()
+    }
+
+    #[inline]
+    fn sample_light(light: &EnvironmentLight<S>, u: S, v: S, _point: &Vector3<S>) -> Option<(Vector3<S>, S)> {
+        light.sample(u, v)
+    }
+
+    #[inline]
+    fn emitted_light_radiance(light: &EnvironmentLight<S>, point: &Vector3<S>, direction: &Vector3<S>) -> Vector3<S> {
+        light.emitted_radiance(point, direction)
+    }
+
+    /// Every ray escaping the scene reaches the environment.
+    #[inline]
+    fn intersect_light(_light: &Light<S, Self>, _origin: &Vector3<S>, _direction: &Vector3<S>) -> Option<S> {
+        Some(S::infinity())
+    }
+}
+
+impl<S> LightModelInfo<S> for DomeLightModel<S> where S: ScalarFloat {
+    #[inline]
+    fn kind(&self) -> LightKind {
+        LightKind::Dome
+    }
+
+    #[inline]
+    fn influence_radius(&self, _threshold: S) -> S {
+        S::infinity()
+    }
+
+    #[inline]
+    fn sample_light(light: &DomeLight<S>, u: S, v: S, _point: &Vector3<S>) -> Option<(Vector3<S>, S)> {
+        light.sample(u, v)
+    }
+
+    #[inline]
+    fn emitted_light_radiance(light: &DomeLight<S>, point: &Vector3<S>, direction: &Vector3<S>) -> Vector3<S> {
+        light.emitted_radiance(point, direction)
+    }
+
+    /// Every ray escaping the scene reaches the environment.
+    #[inline]
+    fn intersect_light(_light: &Light<S, Self>, _origin: &Vector3<S>, _direction: &Vector3<S>) -> Option<S> {
+        Some(S::infinity())
+    }
+}
+
+impl<S> LightModelInfo<S> for PortalLightModel<S> where S: ScalarFloat {
+    #[inline]
+    fn kind(&self) -> LightKind {
+        LightKind::Portal
+    }
+
+    #[inline]
+    fn influence_radius(&self, _threshold: S) -> S {
+        S::infinity()
+    }
+
+    #[inline]
+    fn sample_light(light: &PortalLight<S>, u: S, v: S, point: &Vector3<S>) -> Option<(Vector3<S>, S)> {
+        light.sample(u, v, point)
+    }
+
+    #[inline]
+    fn emitted_light_radiance(light: &PortalLight<S>, point: &Vector3<S>, direction: &Vector3<S>) -> Vector3<S> {
+        light.emitted_radiance(point, direction)
+    }
+
+    #[inline]
+    fn intersect_light(light: &PortalLight<S>, origin: &Vector3<S>, direction: &Vector3<S>) -> Option<S> {
+        light.intersect(origin, direction)
+    }
+}
+
+impl<S> LightModelInfo<S> for MeshLightModel<S> where S: ScalarFloat {
+    #[inline]
+    fn kind(&self) -> LightKind {
+        LightKind::Mesh
+    }
+
+    #[inline]
+    fn influence_radius(&self, _threshold: S) -> S {
+        S::infinity()
+    }
+
+    #[inline]
+    fn emitted_light_radiance(light: &MeshLight<S>, point: &Vector3<S>, direction: &Vector3<S>) -> Vector3<S> {
+        light.emitted_radiance(point, direction)
+    }
+
+    #[inline]
+    fn emitted_light_power(&self) -> Option<Lumens<S>> {
+        Some(self.emitted_power())
+    }
+}
+
+impl<S> LightModelInfo<S> for IesLightModel<S> where S: ScalarFloat {
+    #[inline]
+    fn kind(&self) -> LightKind {
+        LightKind::Ies
+    }
+
+    #[inline]
+    fn influence_radius(&self, _threshold: S) -> S {
+        S::infinity()
+    }
+
+    #[inline]
+    fn sample_light(light: &Light<S, Self>, _u: S, _v: S, point: &Vector3<S>) -> Option<(Vector3<S>, S)> {
+        sample_punctual(&light.position(), point)
+    }
+
+    #[inline]
+    fn emitted_light_power(&self) -> Option<Lumens<S>> {
+        Some(self.emitted_power())
+    }
+}
+
+impl<S> LightModelInfo<S> for NGonLightModel<S> where S: ScalarFloat {
+    #[inline]
+    fn kind(&self) -> LightKind {
+        LightKind::NGon
+    }
+
+    /// Far from the polygon, its irradiance falls off like that of a sphere
+    /// with the same projected area `A`, as `A / (πd^2)`.
+    #[inline]
+    fn influence_radius(&self, threshold: S) -> S {
+        if threshold <= S::zero() {
+            return S::infinity();
+        }
+        let pi: S = num_traits::cast(core::f64::consts::PI).unwrap();
+
+        (self.area() / (pi * threshold)).sqrt()
+    }
+
+    #[inline]
+    fn sample_light(light: &NGonLight<S>, u: S, v: S, point: &Vector3<S>) -> Option<(Vector3<S>, S)> {
+        light.sample(u, v, point)
+    }
+
+    #[inline]
+    fn emitted_light_radiance(light: &NGonLight<S>, point: &Vector3<S>, direction: &Vector3<S>) -> Vector3<S> {
+        light.emitted_radiance(point, direction)
+    }
+
+    #[inline]
+    fn emitted_light_power(&self) -> Option<Lumens<S>> {
+        Some(self.emitted_power())
+    }
+
+    #[inline]
+    fn intersect_light(light: &NGonLight<S>, origin: &Vector3<S>, direction: &Vector3<S>) -> Option<S> {
+        light.intersect(origin, direction)
+    }
+}
+
+impl<S> LightModelInfo<S> for BeamLightModel<S> where S: ScalarFloat {
+    #[inline]
+    fn kind(&self) -> LightKind {
+        LightKind::Beam
+    }
+
+    /// The beam ends abruptly, so its influence reaches the far rim of its
+    /// cylinder regardless of the threshold.
+    #[inline]
+    fn influence_radius(&self, _threshold: S) -> S {
+        (self.length * self.length + self.radius * self.radius).sqrt()
+    }
+}
+
+impl<S> LightModelInfo<S> for PbrLightModel<S> where S: ScalarFloat {
+    #[inline]
+    fn kind(&self) -> LightKind {
+        LightKind::Pbr
+    }
+
+    #[inline]
+    fn influence_radius(&self, threshold: S) -> S {
+        self.attenuation.range(threshold / self.intensity)
+    }
+
+    #[inline]
+    fn sample_light(light: &Light<S, Self>, _u: S, _v: S, point: &Vector3<S>) -> Option<(Vector3<S>, S)> {
+        sample_punctual(&light.position(), point)
+    }
+
+    #[inline]
+    fn emitted_light_power(&self) -> Option<Lumens<S>> {
+        Some(self.emitted_power())
+    }
+}
+
+#[cfg(feature = "spectral")]
+impl<S> LightModelInfo<S> for SpectralLightModel<S> where S: ScalarFloat {
+    #[inline]
+    fn kind(&self) -> LightKind {
+        LightKind::Spectral
+    }
+
+    #[inline]
+    fn influence_radius(&self, threshold: S) -> S {
+        self.attenuation.range(threshold / self.intensity)
+    }
+
+    #[inline]
+    fn sample_light(light: &Light<S, Self>, _u: S, _v: S, point: &Vector3<S>) -> Option<(Vector3<S>, S)> {
+        sample_punctual(&light.position(), point)
+    }
+
+    #[inline]
+    fn emitted_light_power(&self) -> Option<Lumens<S>> {
+        Some(self.emitted_power())
+    }
+}
+
+/// A light of any illumination model, for storing heterogeneous lights in a
+/// single collection such as a `Vec<Box<dyn AnyLight<S>>>`.
+///
+/// The concrete light can be recovered with [`downcast_ref`](#method.downcast_ref)
+/// and [`downcast_mut`](#method.downcast_mut).
+pub trait AnyLight<S>: Any {
+    /// Get the light's position in world space.
+    fn position(&self) -> Vector3<S>;
+
+    /// Get the kind of the light.
+    fn kind(&self) -> LightKind;
+
+    /// Get a sphere bounding the region where the light's distance attenuation
+    /// is at least `threshold`.
+    fn bounding_sphere(&self, threshold: S) -> BoundingSphere<S>;
+
+    /// Append the light's std430 representation to a buffer, as
+    /// `std430::Std430Light::write_std430` does. Lights without a std430
+    /// representation write nothing and return `false`.
+    fn write_std430(&self, bytes: &mut Vec<u8>) -> bool;
+
+    /// Sample a direction from `point` towards the light from a uniform
+    /// sample in the unit square.
+    ///
+    /// The result is the unit direction and its probability density with
+    /// respect to solid angle. Punctual and directional lights return their
+    /// single direction with a density of one. The result is `None` if the
+    /// light cannot be sampled from `point`, or its model has no sampling
+    /// routine.
+    fn sample(&self, u: S, v: S, point: &Vector3<S>) -> Option<(Vector3<S>, S)>;
+
+    /// Evaluate the radiance `Le(p, ω)` the light emits from the point
+    /// `point` on its surface towards a receiver, where `direction` is the
+    /// unit direction from the receiver towards the point, as returned by
+    /// [`sample`](#tymethod.sample). Lights that rays cannot hit, such as
+    /// punctual lights, emit nothing.
+    fn emitted_radiance(&self, point: &Vector3<S>, direction: &Vector3<S>) -> Vector3<S>;
+
+    /// Intersect a ray with the light's emitting surface. The result is the
+    /// distance along the ray to the hit, in units of the ray direction's
+    /// length, or infinity for environment lights, which every escaping ray
+    /// reaches. The result is `None` if the ray misses the light, or the
+    /// light cannot be hit by rays, such as a punctual light.
+    fn intersect(&self, origin: &Vector3<S>, direction: &Vector3<S>) -> Option<S>;
+
+    /// The luminous flux the light emits, including its exposure
+    /// compensation, as described by
+    /// [`photometry::PowerEmitter`](../photometry/trait.PowerEmitter.html).
+    /// The result is `None` for lights without a finite power, such as
+    /// ambient, directional and environment lights.
+    fn emitted_power(&self) -> Option<Lumens<S>>;
+
+    /// Get the light as a dynamically typed value for downcasting.
+    fn as_any(&self) -> &dyn Any;
+
+    /// Get the light as a mutable dynamically typed value for downcasting.
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+}
+
+impl<S, M> AnyLight<S> for Light<S, M>
+    where S: ScalarFloat + 'static,
+          M: IlluminationModel + LightModelInfo<S> + 'static,
+{
+    #[inline]
+    fn position(&self) -> Vector3<S> {
+        Light::position(self)
+    }
+
+    #[inline]
+    fn kind(&self) -> LightKind {
+        self.model().kind()
+    }
+
+    #[inline]
+    fn bounding_sphere(&self, threshold: S) -> BoundingSphere<S> {
+        BoundingSphere {
+            center: Light::position(self),
+            radius: self.model().influence_radius(threshold),
+        }
+    }
+
+    #[inline]
+    fn write_std430(&self, bytes: &mut Vec<u8>) -> bool {
+        M::write_light_std430(self, bytes)
+    }
+
+    #[inline]
+    fn sample(&self, u: S, v: S, point: &Vector3<S>) -> Option<(Vector3<S>, S)> {
+        M::sample_light(self, u, v, point)
+    }
+
+    #[inline]
+    fn emitted_radiance(&self, point: &Vector3<S>, direction: &Vector3<S>) -> Vector3<S> {
+        M::emitted_light_radiance(self, point, direction)
+    }
+
+    #[inline]
+    fn intersect(&self, origin: &Vector3<S>, direction: &Vector3<S>) -> Option<S> {
+        M::intersect_light(self, origin, direction)
+    }
+
+    #[inline]
+    fn emitted_power(&self) -> Option<Lumens<S>> {
+        self.model()
+            .emitted_light_power()
+            .map(|power| Lumens(power.0 * self.exposure_multiplier()))
+    }
+
+    #[inline]
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    #[inline]
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+impl<S> dyn AnyLight<S> where S: ScalarFloat + 'static {
+    /// Determine whether the light uses the illumination model `M`.
+    #[inline]
+    pub fn is<M: 'static>(&self) -> bool {
+        self.as_any().is::<Light<S, M>>()
+    }
+
+    /// Recover the concrete light, if it uses the illumination model `M`.
+    #[inline]
+    pub fn downcast_ref<M: 'static>(&self) -> Option<&Light<S, M>> {
+        self.as_any().downcast_ref::<Light<S, M>>()
+    }
+
+    /// Recover the concrete light mutably, if it uses the illumination model `M`.
+    #[inline]
+    pub fn downcast_mut<M: 'static>(&mut self) -> Option<&mut Light<S, M>> {
+        self.as_any_mut().downcast_mut::<Light<S, M>>()
+    }
+}
+
+/// Estimate the irradiance a set of lights delivers to a point on a surface
+/// with unit normal `normal`, accounting for occlusion, e.g. for AI
+/// perception or CPU baking.
+///
+/// Each light that rays can hit is sampled `samples` times with
+/// [`AnyLight::sample`] at the points of a Halton sequence. The callback
+/// `visible(origin, direction, t_max)` reports whether the ray from `origin`
+/// along the unit vector `direction` travels the distance `t_max` to the
+/// sampled point on the light without hitting an occluder, and is called
+/// with an infinite `t_max` for environment lights. The visible samples are
+/// weighted by the radiance from [`AnyLight::emitted_radiance`], so the
+/// estimate converges to the shadowed irradiance as the sample count grows.
+/// Punctual and directional lights, which rays cannot hit, contribute
+/// nothing; `photometry::illuminance_lux` measures photometric punctual
+/// lights.
+pub fn irradiance_at<S, F>(
+    lights: &[Box<dyn AnyLight<S>>],
+    point: &Vector3<S>,
+    normal: &Vector3<S>,
+    samples: u32,
+    visible: F) -> Vector3<S>
+    where S: ScalarFloat + 'static,
+          F: Fn(&Vector3<S>, &Vector3<S>, S) -> bool,
+{
+    if samples == 0 {
+        return Vector3::zero();
+    }
+
+    let sample_count: S = num_traits::cast(samples).unwrap();
+    lights.iter().fold(Vector3::zero(), |irradiance, light| {
+        let sum = (0..samples).fold(Vector3::zero(), |sum, index| {
+            let (direction, pdf) = match light.sample(halton(index, 0), halton(index, 1), point) {
+                Some(sample) => sample,
+                None => return sum,
+            };
+            let cos_angle = normal.dot(&direction);
+            if cos_angle <= S::zero() || pdf <= S::zero() {
+                return sum;
+            }
+            let distance = match light.intersect(point, &direction) {
+                Some(distance) => distance,
+                None => return sum,
+            };
+            if !visible(point, &direction, distance) {
+                return sum;
+            }
+            // Environment lights ignore the point they emit from, so the
+            // shading point stands in for the point at infinity.
+            let emitter = if distance.is_finite() { point + direction * distance } else { *point };
+
+            sum + light.emitted_radiance(&emitter, &direction) * (cos_angle / pdf)
+        });
+
+        irradiance + sum / sample_count
+    })
+}
@@ -0,0 +1,200 @@
+use cglinalg::{
+    ScalarFloat,
+};
+
+
+/// The coefficients of the classic constant-linear-quadratic distance
+/// attenuation model
+/// ```text
+/// attenuation(d) = 1 / (constant + linear * d + quadratic * d^2)
+/// ```
+/// used by fixed-function style shaders.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct AttenuationCoefficients<S> {
+    pub constant: S,
+    pub linear: S,
+    pub quadratic: S,
+}
+
+impl<S> AttenuationCoefficients<S> where S: ScalarFloat {
+    /// Construct a new set of attenuation coefficients.
+    #[inline]
+    pub fn new(constant: S, linear: S, quadratic: S) -> AttenuationCoefficients<S> {
+        AttenuationCoefficients {
+            constant: constant,
+            linear: linear,
+            quadratic: quadratic,
+        }
+    }
+
+    /// Evaluate the attenuation factor at a distance from the light.
+    #[inline]
+    pub fn evaluate(&self, distance: S) -> S {
+        S::one() / (self.constant + self.linear * distance + self.quadratic * distance * distance)
+    }
+
+    /// The distance from the light beyond which the attenuation falls below
+    /// `threshold`.
+    pub fn range(&self, threshold: S) -> S {
+        // Solve constant + linear * d + quadratic * d^2 = 1 / threshold for d.
+        let target = S::one() / threshold - self.constant;
+        if target <= S::zero() {
+            return S::zero();
+        }
+
+        if self.quadratic > S::zero() {
+            let two = S::one() + S::one();
+            let four = two + two;
+            let discriminant = self.linear * self.linear + four * self.quadratic * target;
+
+            (discriminant.sqrt() - self.linear) / (two * self.quadratic)
+        } else if self.linear > S::zero() {
+            target / self.linear
+        } else {
+            S::infinity()
+        }
+    }
+}
+
+/// The smallest distance the inverse square law is evaluated at, in world
+/// units, which keeps the attenuation finite at the light's position.
+pub(crate) const MIN_DISTANCE: f64 = 0.01;
+
+/// A distance attenuation model for positional lights.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Attenuation<S> {
+    /// No distance attenuation.
+    None,
+    /// The classic constant-linear-quadratic attenuation.
+    Polynomial(AttenuationCoefficients<S>),
+    /// The physically based inverse square law, `1 / d^2`, evaluated no
+    /// closer than one centimeter to the light assuming meters as world
+    /// units.
+    InverseSquare,
+    /// The constant-linear-quadratic attenuation multiplied by a linear ramp
+    /// `1 - d / range`, which brings the light to exactly zero at `range`.
+    Windowed {
+        coefficients: AttenuationCoefficients<S>,
+        range: S,
+    },
+    /// The inverse square law multiplied by the smooth window
+    /// `(1 - (d / range)^4)^2`, which brings the light to zero at `range`
+    /// with a continuous slope, as in the Frostbite and Unreal engines.
+    WindowedInverseSquare {
+        range: S,
+    },
+}
+
+/// The smooth window `(1 - (d / range)^4)^2`, clamped to zero beyond `range`.
+/// The window is close to one near the light and reaches zero at `range` with
+/// a zero slope, so multiplying it into a falloff gives the light a finite
+/// range without a visible edge.
+#[inline]
+pub fn smooth_window<S: ScalarFloat>(distance: S, range: S) -> S {
+    if range <= S::zero() {
+        return S::zero();
+    }
+    let ratio = distance / range;
+    let ratio_squared = ratio * ratio;
+    let window = (S::one() - ratio_squared * ratio_squared).max(S::zero());
+
+    window * window
+}
+
+/// The inverse square law, evaluated no closer than `MIN_DISTANCE`.
+#[inline]
+fn inverse_square<S: ScalarFloat>(distance: S) -> S {
+    let distance = distance.max(num_traits::cast(MIN_DISTANCE).unwrap());
+
+    S::one() / (distance * distance)
+}
+
+impl<S> Attenuation<S> where S: ScalarFloat {
+    /// Evaluate the attenuation factor at a distance from the light.
+    pub fn evaluate(&self, distance: S) -> S {
+        match *self {
+            Attenuation::None => S::one(),
+            Attenuation::Polynomial(ref coefficients) => coefficients.evaluate(distance),
+            Attenuation::InverseSquare => inverse_square(distance),
+            Attenuation::Windowed { ref coefficients, range } => {
+                if range <= S::zero() {
+                    return S::zero();
+                }
+                let window = (S::one() - distance / range).max(S::zero());
+
+                coefficients.evaluate(distance) * window
+            }
+            Attenuation::WindowedInverseSquare { range } => {
+                inverse_square(distance) * smooth_window(distance, range)
+            }
+        }
+    }
+
+    /// The distance from the light beyond which the attenuation falls below
+    /// `threshold`. Lights without attenuation have an infinite range.
+    pub fn range(&self, threshold: S) -> S {
+        match *self {
+            Attenuation::None => S::infinity(),
+            Attenuation::Polynomial(ref coefficients) => coefficients.range(threshold),
+            Attenuation::InverseSquare => {
+                if threshold <= S::zero() {
+                    return S::infinity();
+                }
+
+                S::one() / threshold.sqrt()
+            }
+            Attenuation::Windowed { ref coefficients, range } => coefficients.range(threshold).min(range),
+            Attenuation::WindowedInverseSquare { range } => {
+                // The window only shortens the range of the inverse square law.
+                Attenuation::<S>::InverseSquare.range(threshold).min(range)
+            }
+        }
+    }
+}
+
+impl<S> Default for Attenuation<S> {
+    #[inline]
+    fn default() -> Attenuation<S> {
+        Attenuation::None
+    }
+}
+
+/// Solve for the attenuation coefficients producing a desired falloff.
+///
+/// The fitted attenuation is one at the light, one half at `half_distance`,
+/// and falls to `threshold` at `radius`, the effective radius beyond which
+/// the light is considered to contribute nothing. A typical threshold for
+/// 8-bit output is `1 / 256`.
+///
+/// The result is `None` when no attenuation with non-negative coefficients
+/// has the desired shape, which happens when `half_distance` is not smaller
+/// than `radius`, or when the half intensity distance is too close to either
+/// end for the falloff to be reached by a linear or quadratic term alone.
+pub fn fit_attenuation<S>(radius: S, half_distance: S, threshold: S) -> Option<AttenuationCoefficients<S>>
+    where S: ScalarFloat
+{
+    if half_distance <= S::zero() || radius <= half_distance {
+        return None;
+    }
+    if threshold <= S::zero() || threshold >= S::one() {
+        return None;
+    }
+
+    // With the constant term fixed at one, the two constraints
+    //     linear * h + quadratic * h^2 = 1
+    //     linear * r + quadratic * r^2 = 1 / threshold - 1
+    // form a linear system in the remaining coefficients.
+    let h = half_distance;
+    let r = radius;
+    let k = S::one() / threshold - S::one();
+    let determinant = h * r * (r - h);
+    let linear = (r * r - k * h * h) / determinant;
+    let quadratic = (k * h - r) / determinant;
+    if linear < S::zero() || quadratic < S::zero() {
+        return None;
+    }
+
+    Some(AttenuationCoefficients::new(S::one(), linear, quadratic))
+}
@@ -0,0 +1,369 @@
+use cglinalg::{
+    ScalarFloat,
+};
+use crate::falloff::FalloffCurve;
+use crate::ltc::{
+    LtcEntry,
+    LtcTable,
+};
+use crate::profile::AngularProfile;
+use crate::sampling::HierarchicalWarp;
+use std::error;
+use core::fmt;
+
+
+/// The bytes every cache file starts with.
+pub const CACHE_MAGIC: [u8; 4] = *b"CGLC";
+
+/// The version of the cache format written by `write_cache`. Readers reject
+/// caches of any other version, so stale caches are rebaked rather than
+/// misread.
+pub const CACHE_VERSION: u32 = 1;
+
+/// The kind tag of a baked falloff curve.
+const KIND_FALLOFF: u32 = 0;
+
+/// The kind tag of a baked angular profile.
+const KIND_PROFILE: u32 = 1;
+
+/// The kind tag of a hierarchical warp.
+const KIND_WARP: u32 = 2;
+
+/// The kind tag of a table of linearly transformed cosines.
+const KIND_LTC: u32 = 3;
+
+/// The largest number of levels of a cached warp, for a grid of 32768 by
+/// 32768 weights.
+const MAX_WARP_LEVELS: u32 = 16;
+
+/// An error produced while reading a cache.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum CacheError {
+    /// The bytes do not start with `CACHE_MAGIC`.
+    BadMagic,
+    /// The cache was written with a different version of the format.
+    UnsupportedVersion(u32),
+    /// The bytes end in the middle of the cache.
+    UnexpectedEnd,
+    /// The entry with the given index is malformed.
+    InvalidEntry(usize),
+}
+
+impl fmt::Display for CacheError {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CacheError::BadMagic => write!(formatter, "the data is not a light cache"),
+            CacheError::UnsupportedVersion(version) => {
+                write!(formatter, "unsupported light cache version {}, expected {}", version, CACHE_VERSION)
+            }
+            CacheError::UnexpectedEnd => write!(formatter, "the light cache ends unexpectedly"),
+            CacheError::InvalidEntry(index) => write!(formatter, "light cache entry {} is malformed", index),
+        }
+    }
+}
+
+impl error::Error for CacheError {}
+
+/// A product baked from the crate's light data, ready to store in a cache.
+#[derive(Clone, Debug, PartialEq)]
+pub enum BakedProduct<S> {
+    /// A lookup table baked by `FalloffCurve::bake`, with the curve's
+    /// largest distance to index it by.
+    FalloffTable {
+        max_distance: S,
+        values: Vec<S>,
+    },
+    /// A lookup table baked by `AngularProfile::bake`, with the cosine of
+    /// the profile's largest angle to index it by.
+    ProfileTable {
+        cos_max: S,
+        values: Vec<S>,
+    },
+    /// The mip pyramid of a hierarchical warp, such as the warp of an
+    /// `EquirectangularSource`.
+    Warp(HierarchicalWarp<S>),
+    /// A table of linearly transformed cosines fitted by `LtcTable::fit`.
+    LtcTable(LtcTable<S>),
+}
+
+impl<S> BakedProduct<S> where S: ScalarFloat {
+    /// Bake a falloff curve into a lookup table of `resolution` entries.
+    pub fn falloff(curve: &FalloffCurve<S>, resolution: usize) -> BakedProduct<S> {
+        BakedProduct::FalloffTable {
+            max_distance: curve.max_distance(),
+            values: curve.bake(resolution),
+        }
+    }
+
+    /// Bake an angular profile into a lookup table of `resolution` entries.
+    pub fn profile(profile: &AngularProfile<S>, resolution: usize) -> BakedProduct<S> {
+        BakedProduct::ProfileTable {
+            cos_max: profile.max_angle().0.cos(),
+            values: profile.bake(resolution),
+        }
+    }
+}
+
+/// An entry of a cache: a baked product and the key identifying the data it
+/// was baked from, such as a hash of the source asset and bake settings.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CacheEntry<S> {
+    pub key: u64,
+    pub product: BakedProduct<S>,
+}
+
+#[inline]
+fn to_f32<S: ScalarFloat>(value: S) -> f32 {
+    num_traits::cast(value).unwrap()
+}
+
+#[inline]
+fn push_u32(bytes: &mut Vec<u8>, value: u32) {
+    bytes.extend_from_slice(&value.to_le_bytes());
+}
+
+#[inline]
+fn push_values<S: ScalarFloat>(bytes: &mut Vec<u8>, values: &[S]) {
+    for value in values {
+        bytes.extend_from_slice(&to_f32(*value).to_le_bytes());
+    }
+}
+
+/// Append a cache of baked products to a buffer.
+///
+/// The cache is little-endian and every field is four byte aligned, so a
+/// memory-mapped cache can be read in place. It starts with `CACHE_MAGIC`,
+/// then the format version and the entry count as `u32`s. Each entry then
+/// holds its `u64` key, a `u32` kind tag, a `u32` parameter, the `u32`
+/// number of values, and the values as `f32`s.
+///
+/// The parameter of a lookup table is the bits of its `f32` indexing
+/// parameter. The parameter of a warp is its number of levels, whose values
+/// follow from the coarsest level to the finest. The parameter of an LTC
+/// table is its size, and its entries follow row by row as six values each:
+/// the inverse transformation, the magnitude, and the Fresnel integral.
+pub fn write_cache<S>(entries: &[CacheEntry<S>], bytes: &mut Vec<u8>)
+    where S: ScalarFloat
+{
+    bytes.extend_from_slice(&CACHE_MAGIC);
+    push_u32(bytes, CACHE_VERSION);
+    push_u32(bytes, entries.len() as u32);
+    for entry in entries {
+        bytes.extend_from_slice(&entry.key.to_le_bytes());
+        match &entry.product {
+            BakedProduct::FalloffTable { max_distance, values } => {
+                push_u32(bytes, KIND_FALLOFF);
+                push_u32(bytes, to_f32(*max_distance).to_bits());
+                push_u32(bytes, values.len() as u32);
+                push_values(bytes, values);
+            }
+            BakedProduct::ProfileTable { cos_max, values } => {
+                push_u32(bytes, KIND_PROFILE);
+                push_u32(bytes, to_f32(*cos_max).to_bits());
+                push_u32(bytes, values.len() as u32);
+                push_values(bytes, values);
+            }
+            BakedProduct::Warp(warp) => {
+                let levels = warp.levels();
+                push_u32(bytes, KIND_WARP);
+                push_u32(bytes, levels.len() as u32);
+                push_u32(bytes, levels.iter().map(|level| level.len() as u32).sum());
+                for level in levels {
+                    push_values(bytes, level);
+                }
+            }
+            BakedProduct::LtcTable(table) => {
+                push_u32(bytes, KIND_LTC);
+                push_u32(bytes, table.size() as u32);
+                push_u32(bytes, 6 * table.entries().len() as u32);
+                for entry in table.entries() {
+                    push_values(bytes, &entry.inverse);
+                    push_values(bytes, &[entry.magnitude, entry.fresnel]);
+                }
+            }
+        }
+    }
+}
+
+/// A cursor over the bytes of a cache.
+struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, count: usize) -> Result<&'a [u8], CacheError> {
+        if self.bytes.len() < count {
+            return Err(CacheError::UnexpectedEnd);
+        }
+        let (taken, rest) = self.bytes.split_at(count);
+        self.bytes = rest;
+
+        Ok(taken)
+    }
+
+    fn read_u32(&mut self) -> Result<u32, CacheError> {
+        let mut word = [0; 4];
+        word.copy_from_slice(self.take(4)?);
+
+        Ok(u32::from_le_bytes(word))
+    }
+
+    fn read_u64(&mut self) -> Result<u64, CacheError> {
+        let mut word = [0; 8];
+        word.copy_from_slice(self.take(8)?);
+
+        Ok(u64::from_le_bytes(word))
+    }
+
+    fn read_values<S: ScalarFloat>(&mut self, count: usize) -> Result<Vec<S>, CacheError> {
+        let data = self.take(count.checked_mul(4).ok_or(CacheError::UnexpectedEnd)?)?;
+
+        Ok(data.chunks_exact(4)
+            .map(|word| {
+                let value = f32::from_le_bytes([word[0], word[1], word[2], word[3]]);
+                num_traits::cast(value).unwrap()
+            })
+            .collect())
+    }
+}
+
+/// Read a cache written by `write_cache`.
+pub fn read_cache<S>(bytes: &[u8]) -> Result<Vec<CacheEntry<S>>, CacheError>
+    where S: ScalarFloat
+{
+    let mut reader = Reader { bytes: bytes };
+    if reader.take(4).map_err(|_| CacheError::BadMagic)? != CACHE_MAGIC {
+        return Err(CacheError::BadMagic);
+    }
+    let version = reader.read_u32()?;
+    if version != CACHE_VERSION {
+        return Err(CacheError::UnsupportedVersion(version));
+    }
+
+    let count = reader.read_u32()? as usize;
+    // Each entry takes at least 20 bytes, which bounds the allocation for a
+    // corrupt count.
+    let mut entries = Vec::with_capacity(count.min(reader.bytes.len() / 20));
+    for index in 0..count {
+        let key = reader.read_u64()?;
+        let kind = reader.read_u32()?;
+        let parameter = reader.read_u32()?;
+        let value_count = reader.read_u32()? as usize;
+        let values = reader.read_values::<S>(value_count)?;
+        let product = match kind {
+            KIND_FALLOFF => BakedProduct::FalloffTable {
+                max_distance: num_traits::cast(f32::from_bits(parameter)).unwrap(),
+                values: values,
+            },
+            KIND_PROFILE => BakedProduct::ProfileTable {
+                cos_max: num_traits::cast(f32::from_bits(parameter)).unwrap(),
+                values: values,
+            },
+            KIND_WARP => {
+                if parameter == 0 || parameter > MAX_WARP_LEVELS {
+                    return Err(CacheError::InvalidEntry(index));
+                }
+                let mut levels = Vec::with_capacity(parameter as usize);
+                let mut start = 0;
+                for level in 0..parameter {
+                    let end = start + (1 << (2 * level));
+                    if end > values.len() {
+                        return Err(CacheError::InvalidEntry(index));
+                    }
+                    levels.push(values[start..end].to_vec());
+                    start = end;
+                }
+                if start != values.len() {
+                    return Err(CacheError::InvalidEntry(index));
+                }
+                let warp = HierarchicalWarp::from_levels(levels).ok_or(CacheError::InvalidEntry(index))?;
+
+                BakedProduct::Warp(warp)
+            }
+            KIND_LTC => {
+                let entries = values.chunks_exact(6)
+                    .map(|entry| LtcEntry {
+                        inverse: [entry[0], entry[1], entry[2], entry[3]],
+                        magnitude: entry[4],
+                        fresnel: entry[5],
+                    })
+                    .collect::<Vec<LtcEntry<S>>>();
+                let size = parameter as usize;
+                if values.len() % 6 != 0 || size.checked_mul(size) != Some(entries.len()) {
+                    return Err(CacheError::InvalidEntry(index));
+                }
+                let table = LtcTable::from_entries(size, entries).ok_or(CacheError::InvalidEntry(index))?;
+
+                BakedProduct::LtcTable(table)
+            }
+            _ => return Err(CacheError::InvalidEntry(index)),
+        };
+        entries.push(CacheEntry {
+            key: key,
+            product: product,
+        });
+    }
+
+    Ok(entries)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::falloff::Interpolation;
+    use cglinalg::Radians;
+
+
+    fn entries() -> Vec<CacheEntry<f32>> {
+        let curve = FalloffCurve::new(Interpolation::Linear, vec![(0.0, 1.0), (4.0, 0.25), (10.0, 0.0)]).unwrap();
+        let profile = AngularProfile::new(vec![(Radians(0.0), 1.0), (Radians(0.5), 0.0)]).unwrap();
+        let ltc = LtcTable::from_entries(2, vec![
+            LtcEntry { inverse: [1.0, 0.0, 0.0, 1.0], magnitude: 1.0, fresnel: 0.0 },
+            LtcEntry { inverse: [0.5, 0.25, -0.125, 0.75], magnitude: 0.5, fresnel: 0.125 },
+            LtcEntry { inverse: [2.0, 0.0, 0.0, 2.0], magnitude: 0.25, fresnel: 0.0625 },
+            LtcEntry { inverse: [4.0, -1.0, 1.0, 3.0], magnitude: 0.75, fresnel: 0.5 },
+        ]).unwrap();
+        let warp = HierarchicalWarp::new(4, &[1.0, 2.0, 3.0, 4.0, 0.0, 1.0, 0.5, 0.25, 8.0, 0.0, 1.0, 1.0, 2.0, 2.0, 3.0, 0.0]).unwrap();
+
+        vec![
+            CacheEntry { key: 1, product: BakedProduct::falloff(&curve, 16) },
+            CacheEntry { key: u64::MAX, product: BakedProduct::profile(&profile, 8) },
+            CacheEntry { key: 7, product: BakedProduct::Warp(warp) },
+            CacheEntry { key: 9, product: BakedProduct::LtcTable(ltc) },
+        ]
+    }
+
+    #[test]
+    fn test_cache_round_trip() {
+        let entries = entries();
+        let mut bytes = Vec::new();
+        write_cache(&entries, &mut bytes);
+
+        assert_eq!(bytes.len() % 4, 0);
+        assert_eq!(read_cache::<f32>(&bytes), Ok(entries));
+    }
+
+    #[test]
+    fn test_cache_rejects_other_versions() {
+        let mut bytes = Vec::new();
+        write_cache(&entries(), &mut bytes);
+        bytes[4..8].copy_from_slice(&(CACHE_VERSION + 1).to_le_bytes());
+
+        assert_eq!(read_cache::<f32>(&bytes), Err(CacheError::UnsupportedVersion(CACHE_VERSION + 1)));
+        assert_eq!(read_cache::<f32>(b"not a cache"), Err(CacheError::BadMagic));
+    }
+
+    #[test]
+    fn test_cache_rejects_truncated_and_malformed_entries() {
+        let mut bytes = Vec::new();
+        write_cache(&entries(), &mut bytes);
+
+        assert_eq!(read_cache::<f32>(&bytes[..bytes.len() - 4]), Err(CacheError::UnexpectedEnd));
+
+        // Claim one more level than the warp entry holds.
+        let warp_levels = bytes.len() - 4 * (5 + 24) - 4 * (1 + 4 + 16) - 8;
+        bytes[warp_levels..warp_levels + 4].copy_from_slice(&4u32.to_le_bytes());
+        assert_eq!(read_cache::<f32>(&bytes), Err(CacheError::InvalidEntry(2)));
+    }
+}
@@ -0,0 +1,231 @@
+use cglinalg::{
+    Magnitude,
+    Vector3,
+    Matrix4x4,
+    Radians,
+    ScalarFloat,
+};
+use crate::DirectionalLight;
+use crate::shadow_map::OrthographicShadow;
+
+
+/// The view frustum of a perspective camera, for fitting shadow cascades to
+/// the part of the scene the camera sees.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct CameraFrustum<S> {
+    /// The position of the camera in world space.
+    pub position: Vector3<S>,
+    /// The unit direction the camera looks in.
+    pub forward: Vector3<S>,
+    /// The camera's up direction. It need not be perpendicular to `forward`.
+    pub up: Vector3<S>,
+    /// The camera's vertical field of view.
+    pub vertical_fov: Radians<S>,
+    /// The ratio of the width of the camera's image to its height.
+    pub aspect: S,
+    /// The distance from the camera to its near plane.
+    pub near: S,
+    /// The distance from the camera to its far plane.
+    pub far: S,
+}
+
+impl<S> CameraFrustum<S> where S: ScalarFloat {
+    /// The corners of the slice of the frustum between the distances `near`
+    /// and `far` along the view direction: the four corners at `near`
+    /// followed by the four corners at `far`, each in the order bottom left,
+    /// bottom right, top right, top left.
+    pub fn slice_corners(&self, near: S, far: S) -> [Vector3<S>; 8] {
+        let one_half: S = num_traits::cast(0.5).unwrap();
+        let forward = self.forward.normalize();
+        let right = forward.cross(&self.up).normalize();
+        let up = right.cross(&forward);
+        let tan_half_fov = (self.vertical_fov.0 * one_half).tan();
+
+        let mut corners = [Vector3::zero(); 8];
+        for (i, distance) in [near, far].iter().enumerate() {
+            let center = self.position + forward * *distance;
+            let half_height = up * (*distance * tan_half_fov);
+            let half_width = right * (*distance * tan_half_fov * self.aspect);
+            corners[4 * i] = center - half_width - half_height;
+            corners[4 * i + 1] = center + half_width - half_height;
+            corners[4 * i + 2] = center + half_width + half_height;
+            corners[4 * i + 3] = center - half_width + half_height;
+        }
+
+        corners
+    }
+
+    /// The corners of the whole frustum, in the order of
+    /// [`slice_corners`](#method.slice_corners).
+    #[inline]
+    pub fn corners(&self) -> [Vector3<S>; 8] {
+        self.slice_corners(self.near, self.far)
+    }
+}
+
+/// Compute the distances from the camera at which a frustum is split into
+/// `count` cascades. The result holds `count + 1` distances, starting at
+/// `near` and ending at `far`.
+///
+/// The splits blend the logarithmic scheme, which keeps the shadow map's
+/// texels a constant size on screen, with the uniform scheme, which spends
+/// more of the resolution in the distance, by `lambda` in the unit interval:
+/// zero gives uniform splits and one gives logarithmic splits.
+pub fn split_distances<S: ScalarFloat>(near: S, far: S, count: usize, lambda: S) -> Vec<S> {
+    let count_scalar: S = num_traits::cast(count.max(1)).unwrap();
+    let ratio = far / near;
+
+    (0..(count + 1)).map(|i| {
+        if i == 0 {
+            return near;
+        }
+        if i == count {
+            return far;
+        }
+        let fraction = num_traits::cast::<usize, S>(i).unwrap() / count_scalar;
+        let logarithmic = near * ratio.powf(fraction);
+        let uniform = near + (far - near) * fraction;
+
+        lambda * logarithmic + (S::one() - lambda) * uniform
+    }).collect()
+}
+
+/// The parameters for splitting a camera frustum into shadow cascades.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct CascadeParameters<S> {
+    /// The number of cascades.
+    pub count: usize,
+    /// The blend between uniform and logarithmic splits.
+    pub lambda: S,
+    /// The padding around each cascade's view volume.
+    pub padding: S,
+    /// The distance each cascade's near plane is pulled towards the light to
+    /// capture casters outside the frustum.
+    pub caster_extension: S,
+    /// The shadow map resolution to snap the cascades to, if any.
+    pub texel_snapping: Option<usize>,
+}
+
+impl<S> CascadeParameters<S> where S: ScalarFloat {
+    /// Construct new cascade parameters with no padding or caster extension.
+    #[inline]
+    pub fn new(count: usize, lambda: S) -> CascadeParameters<S> {
+        CascadeParameters {
+            count: count,
+            lambda: lambda,
+            padding: S::zero(),
+            caster_extension: S::zero(),
+            texel_snapping: None,
+        }
+    }
+
+    /// Set the padding around each cascade's view volume.
+    #[inline]
+    pub fn with_padding(self, padding: S) -> CascadeParameters<S> {
+        CascadeParameters {
+            padding: padding,
+            ..self
+        }
+    }
+
+    /// Set the distance each cascade's near plane is pulled towards the
+    /// light.
+    #[inline]
+    pub fn with_caster_extension(self, caster_extension: S) -> CascadeParameters<S> {
+        CascadeParameters {
+            caster_extension: caster_extension,
+            ..self
+        }
+    }
+
+    /// Stabilize the cascades for shadow maps with `resolution` texels on a
+    /// side: fit each cascade around its slice's bounding sphere and snap it
+    /// to the shadow map's texel grid, so shadows do not shimmer as the
+    /// camera moves and turns.
+    #[inline]
+    pub fn with_texel_snapping(self, resolution: usize) -> CascadeParameters<S> {
+        CascadeParameters {
+            texel_snapping: Some(resolution),
+            ..self
+        }
+    }
+}
+
+/// A single shadow cascade covering a slice of a camera frustum.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Cascade<S> {
+    /// The distance from the camera where the cascade begins.
+    pub near: S,
+    /// The distance from the camera where the cascade ends.
+    pub far: S,
+    /// The cascade's shadow map projection.
+    pub shadow: OrthographicShadow<S>,
+}
+
+/// A set of cascaded shadow maps for a directional light, ordered from the
+/// camera outwards.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CascadeSet<S> {
+    cascades: Vec<Cascade<S>>,
+}
+
+impl<S> CascadeSet<S> where S: ScalarFloat {
+    /// Split a camera frustum into cascades and fit a shadow map projection
+    /// for a directional light to each of them.
+    pub fn compute(
+        light: &DirectionalLight<S>,
+        frustum: &CameraFrustum<S>,
+        parameters: &CascadeParameters<S>) -> CascadeSet<S>
+    {
+        let splits = split_distances(frustum.near, frustum.far, parameters.count, parameters.lambda);
+        let cascades = splits.windows(2).map(|window| {
+            let corners = frustum.slice_corners(window[0], window[1]);
+            let shadow = match parameters.texel_snapping {
+                Some(resolution) => light
+                    .fit_stable_orthographic_shadow(&corners, parameters.padding, parameters.caster_extension)
+                    .snapped_to_texels(resolution),
+                None => light.fit_orthographic_shadow(&corners, parameters.padding, parameters.caster_extension),
+            };
+
+            Cascade {
+                near: window[0],
+                far: window[1],
+                shadow: shadow,
+            }
+        }).collect();
+
+        CascadeSet {
+            cascades: cascades,
+        }
+    }
+
+    /// The cascades, ordered from the camera outwards.
+    #[inline]
+    pub fn cascades(&self) -> &[Cascade<S>] {
+        &self.cascades
+    }
+
+    /// The number of cascades.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.cascades.len()
+    }
+
+    /// Determine whether the set has no cascades.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.cascades.is_empty()
+    }
+
+    /// The far distance of each cascade, which shaders compare with a
+    /// fragment's view depth to select its cascade.
+    pub fn split_depths(&self) -> Vec<S> {
+        self.cascades.iter().map(|cascade| cascade.far).collect()
+    }
+
+    /// The matrix mapping world space to clip space of each cascade's shadow
+    /// map.
+    pub fn view_projections(&self) -> Vec<Matrix4x4<S>> {
+        self.cascades.iter().map(|cascade| cascade.shadow.view_projection()).collect()
+    }
+}
@@ -0,0 +1,799 @@
+use cglinalg::{
+    Quaternion,
+    Radians,
+    Vector2,
+    Vector3,
+    ScalarFloat,
+};
+use crate::{
+    AmbientLightModel,
+    AmbientLightModelSpec,
+    BeamLightModel,
+    BeamLightModelSpec,
+    ConeFalloff,
+    DirectionalLightModel,
+    DirectionalLightModelSpec,
+    DiskLightModel,
+    DiskLightModelSpec,
+    HemisphereLightModel,
+    HemisphereLightModelSpec,
+    IlluminationModel,
+    Light,
+    LightAttitude,
+    PointLightModel,
+    PointLightModelSpec,
+    SphereLightModel,
+    SphereLightModelSpec,
+    SpotLightModel,
+    SpotLightModelSpec,
+    TubeLightModel,
+    TubeLightModelSpec,
+};
+use crate::attenuation::{
+    Attenuation,
+    AttenuationCoefficients,
+};
+use crate::color::{
+    CorrelatedColorTemperature,
+    LightColor,
+};
+use crate::environment::{
+    DomeLightModel,
+    DomeLightModelSpec,
+    EnvironmentFrame,
+    EnvironmentLightModel,
+    EnvironmentLightModelSpec,
+    EnvironmentSource,
+    PortalLightModel,
+    PortalLightModelSpec,
+};
+use crate::falloff::FalloffCurve;
+use crate::ies::{
+    IesLightModel,
+    IesLightModelSpec,
+    PhotometricWeb,
+};
+use crate::mesh::{
+    EmissiveMesh,
+    MeshLightModel,
+    MeshLightModelSpec,
+};
+use crate::pbr::{
+    PbrLightModel,
+    PbrLightModelSpec,
+};
+use crate::polygon::{
+    NGonLightModel,
+    NGonLightModelSpec,
+};
+use crate::profile::AngularProfile;
+use crate::sampling::LightJitter;
+use crate::shadow::{
+    ShadowFilter,
+    ShadowSettings,
+};
+#[cfg(feature = "spectral")]
+use crate::spectral::{
+    SpectralLightModel,
+    SpectralLightModelSpec,
+    Spectrum,
+};
+use crate::texture::EmissionTexture;
+use crate::volumetric::VolumetricParameters;
+use core::any::Any;
+use core::fmt;
+use core::marker;
+use std::sync::Arc;
+
+
+/// A value whose scalar parameters can be converted to the scalar type `T`,
+/// e.g. to simulate lights in double precision and upload them to the GPU in
+/// single precision.
+pub trait Cast<T> {
+    /// The value with scalar type `T`.
+    type Output;
+
+    /// Convert every scalar parameter of the value. The result is `None` if
+    /// a finite parameter is out of the range of `T`.
+    fn cast(&self) -> Option<Self::Output>;
+}
+
+/// Convert a scalar. Float to float conversions saturate to infinity
+/// instead of failing, so a finite value that becomes infinite is out of
+/// range.
+#[inline]
+fn scalar<S: ScalarFloat, T: ScalarFloat>(value: S) -> Option<T> {
+    let converted: T = num_traits::cast(value)?;
+    if value.is_finite() && !converted.is_finite() {
+        return None;
+    }
+
+    Some(converted)
+}
+
+#[inline]
+fn vector<S: ScalarFloat, T: ScalarFloat>(vector: &Vector3<S>) -> Option<Vector3<T>> {
+    Some(Vector3::new(scalar(vector.x)?, scalar(vector.y)?, scalar(vector.z)?))
+}
+
+#[inline]
+fn scalars<S: ScalarFloat, T: ScalarFloat>(values: &[S]) -> Option<Vec<T>> {
+    values.iter().map(|&value| scalar(value)).collect()
+}
+
+/// Convert a shared value, sharing the same allocation instead of copying
+/// the value when the scalar types are the same.
+#[inline]
+fn shared<A, B, T>(value: &Arc<A>) -> Option<Arc<B>>
+    where A: Cast<T, Output = B> + 'static,
+          B: 'static,
+{
+    if let Some(same) = (value as &dyn Any).downcast_ref::<Arc<B>>() {
+        return Some(same.clone());
+    }
+
+    Some(Arc::new(value.cast()?))
+}
+
+#[inline]
+fn points<S: ScalarFloat, T: ScalarFloat>(points: &[(S, S)]) -> Option<Vec<(T, T)>> {
+    points.iter().map(|&(x, y)| Some((scalar(x)?, scalar(y)?))).collect()
+}
+
+impl<S, T> Cast<T> for AttenuationCoefficients<S> where S: ScalarFloat, T: ScalarFloat {
+    type Output = AttenuationCoefficients<T>;
+
+    fn cast(&self) -> Option<Self::Output> {
+        Some(AttenuationCoefficients::new(scalar(self.constant)?, scalar(self.linear)?, scalar(self.quadratic)?))
+    }
+}
+
+impl<S, T> Cast<T> for Attenuation<S> where S: ScalarFloat, T: ScalarFloat {
+    type Output = Attenuation<T>;
+
+    fn cast(&self) -> Option<Self::Output> {
+        let attenuation = match *self {
+            Attenuation::None => Attenuation::None,
+            Attenuation::Polynomial(ref coefficients) => Attenuation::Polynomial(coefficients.cast()?),
+            Attenuation::InverseSquare => Attenuation::InverseSquare,
+            Attenuation::Windowed { ref coefficients, range } => Attenuation::Windowed {
+                coefficients: coefficients.cast()?,
+                range: scalar(range)?,
+            },
+            Attenuation::WindowedInverseSquare { range } => Attenuation::WindowedInverseSquare {
+                range: scalar(range)?,
+            },
+        };
+
+        Some(attenuation)
+    }
+}
+
+impl<S, T> Cast<T> for ConeFalloff<S> where S: ScalarFloat, T: ScalarFloat {
+    type Output = ConeFalloff<T>;
+
+    fn cast(&self) -> Option<Self::Output> {
+        match *self {
+            ConeFalloff::Smoothstep => Some(ConeFalloff::Smoothstep),
+            ConeFalloff::Exponent(exponent) => Some(ConeFalloff::Exponent(scalar(exponent)?)),
+        }
+    }
+}
+
+impl<S, T> Cast<T> for FalloffCurve<S> where S: ScalarFloat, T: ScalarFloat {
+    type Output = FalloffCurve<T>;
+
+    fn cast(&self) -> Option<Self::Output> {
+        FalloffCurve::new(self.interpolation(), points(self.points())?)
+    }
+}
+
+impl<S, T> Cast<T> for AngularProfile<S> where S: ScalarFloat, T: ScalarFloat {
+    type Output = AngularProfile<T>;
+
+    fn cast(&self) -> Option<Self::Output> {
+        let samples = points(self.samples())?
+            .into_iter()
+            .map(|(angle, value)| (Radians(angle), value))
+            .collect();
+
+        AngularProfile::new(samples)
+    }
+}
+
+impl<S, T> Cast<T> for VolumetricParameters<S> where S: ScalarFloat, T: ScalarFloat {
+    type Output = VolumetricParameters<T>;
+
+    fn cast(&self) -> Option<Self::Output> {
+        Some(VolumetricParameters {
+            scattering: scalar(self.scattering)?,
+            anisotropy: scalar(self.anisotropy)?,
+            intensity: scalar(self.intensity)?,
+        })
+    }
+}
+
+impl<S, T> Cast<T> for CorrelatedColorTemperature<S> where S: ScalarFloat, T: ScalarFloat {
+    type Output = CorrelatedColorTemperature<T>;
+
+    fn cast(&self) -> Option<Self::Output> {
+        Some(CorrelatedColorTemperature {
+            kelvin: scalar(self.kelvin)?,
+            duv: scalar(self.duv)?,
+        })
+    }
+}
+
+impl<S, T> Cast<T> for LightColor<S> where S: ScalarFloat, T: ScalarFloat {
+    type Output = LightColor<T>;
+
+    fn cast(&self) -> Option<Self::Output> {
+        match *self {
+            LightColor::Rgb(ref color) => Some(LightColor::Rgb(vector(color)?)),
+            LightColor::Temperature { ref temperature, color_space } => Some(LightColor::Temperature {
+                temperature: temperature.cast()?,
+                color_space: color_space,
+            }),
+        }
+    }
+}
+
+impl<S, T> Cast<T> for LightJitter<S> where S: ScalarFloat, T: ScalarFloat {
+    type Output = LightJitter<T>;
+
+    fn cast(&self) -> Option<Self::Output> {
+        Some(LightJitter::new(scalar(self.radius)?, self.seed))
+    }
+}
+
+impl<S, T> Cast<T> for ShadowFilter<S> where S: ScalarFloat, T: ScalarFloat {
+    type Output = ShadowFilter<T>;
+
+    fn cast(&self) -> Option<Self::Output> {
+        let filter = match *self {
+            ShadowFilter::Depth => ShadowFilter::Depth,
+            ShadowFilter::Variance { min_variance, light_bleeding_reduction } => ShadowFilter::Variance {
+                min_variance: scalar(min_variance)?,
+                light_bleeding_reduction: scalar(light_bleeding_reduction)?,
+            },
+            ShadowFilter::Exponential { exponent } => ShadowFilter::Exponential {
+                exponent: scalar(exponent)?,
+            },
+        };
+
+        Some(filter)
+    }
+}
+
+impl<S, T> Cast<T> for ShadowSettings<S> where S: ScalarFloat, T: ScalarFloat {
+    type Output = ShadowSettings<T>;
+
+    fn cast(&self) -> Option<Self::Output> {
+        Some(ShadowSettings {
+            constant_bias: scalar(self.constant_bias)?,
+            slope_scaled_bias: scalar(self.slope_scaled_bias)?,
+            normal_offset: scalar(self.normal_offset)?,
+            near: scalar(self.near)?,
+            far: scalar(self.far)?,
+            resolution_hint: self.resolution_hint,
+            filter: self.filter.cast()?,
+        })
+    }
+}
+
+impl<S, T> Cast<T> for AmbientLightModel<S> where S: ScalarFloat, T: ScalarFloat {
+    type Output = AmbientLightModel<T>;
+
+    fn cast(&self) -> Option<Self::Output> {
+        Some(AmbientLightModel {
+            ambient: vector(&self.ambient)?,
+        })
+    }
+}
+
+impl<S, T> Cast<T> for PointLightModel<S> where S: ScalarFloat + 'static, T: ScalarFloat + 'static {
+    type Output = PointLightModel<T>;
+
+    fn cast(&self) -> Option<Self::Output> {
+        let falloff = match self.falloff {
+            Some(ref falloff) => Some(shared(falloff)?),
+            None => None,
+        };
+        let volumetric = match self.volumetric {
+            Some(ref volumetric) => Some(volumetric.cast()?),
+            None => None,
+        };
+
+        Some(PointLightModel {
+            ambient: vector(&self.ambient)?,
+            diffuse: vector(&self.diffuse)?,
+            specular: vector(&self.specular)?,
+            attenuation: self.attenuation.cast()?,
+            falloff: falloff,
+            shadow_tint: vector(&self.shadow_tint)?,
+            volumetric: volumetric,
+            polarity: self.polarity,
+        })
+    }
+}
+
+impl<S, T> Cast<T> for SpotLightModel<S> where S: ScalarFloat + 'static, T: ScalarFloat + 'static {
+    type Output = SpotLightModel<T>;
+
+    fn cast(&self) -> Option<Self::Output> {
+        let falloff = match self.falloff {
+            Some(ref falloff) => Some(shared(falloff)?),
+            None => None,
+        };
+        let profile = match self.profile {
+            Some(ref profile) => Some(shared(profile)?),
+            None => None,
+        };
+        let volumetric = match self.volumetric {
+            Some(ref volumetric) => Some(volumetric.cast()?),
+            None => None,
+        };
+
+        Some(SpotLightModel {
+            cutoff: scalar(self.cutoff)?,
+            outer_cutoff: scalar(self.outer_cutoff)?,
+            ambient: vector(&self.ambient)?,
+            diffuse: vector(&self.diffuse)?,
+            specular: vector(&self.specular)?,
+            cone_falloff: self.cone_falloff.cast()?,
+            attenuation: self.attenuation.cast()?,
+            falloff: falloff,
+            profile: profile,
+            shadow_tint: vector(&self.shadow_tint)?,
+            volumetric: volumetric,
+            polarity: self.polarity,
+        })
+    }
+}
+
+impl<S, T> Cast<T> for DirectionalLightModel<S> where S: ScalarFloat, T: ScalarFloat {
+    type Output = DirectionalLightModel<T>;
+
+    fn cast(&self) -> Option<Self::Output> {
+        Some(DirectionalLightModel {
+            ambient: vector(&self.ambient)?,
+            diffuse: vector(&self.diffuse)?,
+            specular: vector(&self.specular)?,
+            shadow_tint: vector(&self.shadow_tint)?,
+        })
+    }
+}
+
+impl<S, T> Cast<T> for PbrLightModel<S> where S: ScalarFloat, T: ScalarFloat {
+    type Output = PbrLightModel<T>;
+
+    fn cast(&self) -> Option<Self::Output> {
+        Some(PbrLightModel {
+            color: self.color.cast()?,
+            intensity: scalar(self.intensity)?,
+            attenuation: self.attenuation.cast()?,
+            shadow_tint: vector(&self.shadow_tint)?,
+            polarity: self.polarity,
+        })
+    }
+}
+
+impl<S, T> Cast<T> for HemisphereLightModel<S> where S: ScalarFloat, T: ScalarFloat {
+    type Output = HemisphereLightModel<T>;
+
+    fn cast(&self) -> Option<Self::Output> {
+        Some(HemisphereLightModel {
+            sky: vector(&self.sky)?,
+            ground: vector(&self.ground)?,
+        })
+    }
+}
+
+impl<S, T> Cast<T> for DiskLightModel<S> where S: ScalarFloat, T: ScalarFloat {
+    type Output = DiskLightModel<T>;
+
+    fn cast(&self) -> Option<Self::Output> {
+        Some(DiskLightModel {
+            radius: scalar(self.radius)?,
+            ambient: vector(&self.ambient)?,
+            diffuse: vector(&self.diffuse)?,
+            specular: vector(&self.specular)?,
+            shadow_tint: vector(&self.shadow_tint)?,
+        })
+    }
+}
+
+impl<S, T> Cast<T> for SphereLightModel<S> where S: ScalarFloat, T: ScalarFloat {
+    type Output = SphereLightModel<T>;
+
+    fn cast(&self) -> Option<Self::Output> {
+        Some(SphereLightModel {
+            radius: scalar(self.radius)?,
+            radiance: vector(&self.radiance)?,
+            shadow_tint: vector(&self.shadow_tint)?,
+        })
+    }
+}
+
+impl<S, T> Cast<T> for TubeLightModel<S> where S: ScalarFloat, T: ScalarFloat {
+    type Output = TubeLightModel<T>;
+
+    fn cast(&self) -> Option<Self::Output> {
+        Some(TubeLightModel {
+            length: scalar(self.length)?,
+            radius: scalar(self.radius)?,
+            radiance: vector(&self.radiance)?,
+            shadow_tint: vector(&self.shadow_tint)?,
+        })
+    }
+}
+
+impl<S, T> Cast<T> for BeamLightModel<S> where S: ScalarFloat, T: ScalarFloat {
+    type Output = BeamLightModel<T>;
+
+    fn cast(&self) -> Option<Self::Output> {
+        Some(BeamLightModel {
+            radius: scalar(self.radius)?,
+            length: scalar(self.length)?,
+            color: vector(&self.color)?,
+        })
+    }
+}
+
+/// The vertices are validated again by `NGonLightModelSpec::new`, so the
+/// result is also `None` if rounding makes the polygon degenerate.
+impl<S, T> Cast<T> for NGonLightModel<S> where S: ScalarFloat + 'static, T: ScalarFloat + 'static {
+    type Output = NGonLightModel<T>;
+
+    fn cast(&self) -> Option<Self::Output> {
+        let vertices = self.vertices()
+            .iter()
+            .map(|vertex| Some(Vector2::new(scalar(vertex.x)?, scalar(vertex.y)?)))
+            .collect::<Option<Vec<_>>>()?;
+        let spec = NGonLightModelSpec::new(vertices, vector(&self.radiance)?)?
+            .with_shadow_tint(vector(&self.shadow_tint)?);
+        let spec = match self.emission() {
+            Some(emission) => spec.with_emission(shared(emission)?)?,
+            None => spec,
+        };
+
+        Some(NGonLightModel::from_spec(&spec))
+    }
+}
+
+impl<S, T> Cast<T> for EmissionTexture<S> where S: ScalarFloat, T: ScalarFloat {
+    type Output = EmissionTexture<T>;
+
+    fn cast(&self) -> Option<Self::Output> {
+        let texels = self.texels().iter().map(vector).collect::<Option<Vec<_>>>()?;
+
+        EmissionTexture::new(self.width(), self.height(), texels)
+    }
+}
+
+impl<S, T> Cast<T> for EmissiveMesh<S> where S: ScalarFloat, T: ScalarFloat {
+    type Output = EmissiveMesh<T>;
+
+    fn cast(&self) -> Option<Self::Output> {
+        let mut positions = Vec::with_capacity(3 * self.len());
+        let mut emission = Vec::with_capacity(self.len());
+        for i in 0..self.len() {
+            for vertex in self.triangle(i).iter() {
+                positions.push(vector(vertex)?);
+            }
+            emission.push(vector(&self.emission(i))?);
+        }
+
+        EmissiveMesh::new(positions, emission)
+    }
+}
+
+impl<S, T> Cast<T> for MeshLightModel<S> where S: ScalarFloat + 'static, T: ScalarFloat + 'static {
+    type Output = MeshLightModel<T>;
+
+    fn cast(&self) -> Option<Self::Output> {
+        Some(MeshLightModel {
+            mesh: shared(&self.mesh)?,
+            intensity: scalar(self.intensity)?,
+        })
+    }
+}
+
+/// An environment source looked up in another scalar type. Sources are
+/// trait objects, so casting an environment wraps its source instead of
+/// converting its data, and each lookup converts the direction and the
+/// radiance.
+struct CastEnvironmentSource<S, T> {
+    source: Arc<dyn EnvironmentSource<S>>,
+    _marker: marker::PhantomData<fn() -> T>,
+}
+
+impl<S, T> fmt::Debug for CastEnvironmentSource<S, T> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.debug_struct("CastEnvironmentSource")
+            .field("source", &self.source)
+            .finish()
+    }
+}
+
+impl<S, T> EnvironmentSource<T> for CastEnvironmentSource<S, T>
+    where S: ScalarFloat + 'static,
+          T: ScalarFloat + 'static,
+{
+    fn radiance(&self, direction: &Vector3<T>) -> Vector3<T> {
+        let nan = T::nan();
+        vector(direction)
+            .and_then(|direction| vector(&self.source.radiance(&direction)))
+            .unwrap_or_else(|| Vector3::new(nan, nan, nan))
+    }
+
+    fn sample(&self, u: T, v: T) -> Option<(Vector3<T>, T)> {
+        let (direction, pdf) = self.source.sample(scalar(u)?, scalar(v)?)?;
+
+        Some((vector(&direction)?, scalar(pdf)?))
+    }
+
+    fn pdf(&self, direction: &Vector3<T>) -> T {
+        vector(direction)
+            .and_then(|direction| scalar(self.source.pdf(&direction)))
+            .unwrap_or_else(T::nan)
+    }
+
+    fn cast_origin(&self) -> Option<&dyn Any> {
+        Some(self)
+    }
+}
+
+/// Convert an environment source. A source of the same scalar type is
+/// shared as is, and a source that was itself converted from `T` is
+/// unwrapped to the original, so round trips do not stack wrappers.
+#[inline]
+fn cast_source<S, T>(source: &Arc<dyn EnvironmentSource<S>>) -> Arc<dyn EnvironmentSource<T>>
+    where S: ScalarFloat + 'static,
+          T: ScalarFloat + 'static,
+{
+    if let Some(same) = (source as &dyn Any).downcast_ref::<Arc<dyn EnvironmentSource<T>>>() {
+        return same.clone();
+    }
+    let origin = source.cast_origin().and_then(|origin| origin.downcast_ref::<CastEnvironmentSource<T, S>>());
+    if let Some(wrapper) = origin {
+        return wrapper.source.clone();
+    }
+
+    Arc::new(CastEnvironmentSource {
+        source: source.clone(),
+        _marker: marker::PhantomData,
+    })
+}
+
+impl<S, T> Cast<T> for EnvironmentLightModel<S> where S: ScalarFloat + 'static, T: ScalarFloat + 'static {
+    type Output = EnvironmentLightModel<T>;
+
+    fn cast(&self) -> Option<Self::Output> {
+        Some(EnvironmentLightModel {
+            source: cast_source(&self.source),
+            intensity: scalar(self.intensity)?,
+        })
+    }
+}
+
+impl<S, T> Cast<T> for DomeLightModel<S> where S: ScalarFloat + 'static, T: ScalarFloat + 'static {
+    type Output = DomeLightModel<T>;
+
+    fn cast(&self) -> Option<Self::Output> {
+        Some(DomeLightModel {
+            source: cast_source(&self.source),
+            camera_intensity: scalar(self.camera_intensity)?,
+            lighting_intensity: scalar(self.lighting_intensity)?,
+        })
+    }
+}
+
+impl<S, T> Cast<T> for PortalLightModel<S> where S: ScalarFloat + 'static, T: ScalarFloat + 'static {
+    type Output = PortalLightModel<T>;
+
+    fn cast(&self) -> Option<Self::Output> {
+        Some(PortalLightModel {
+            width: scalar(self.width)?,
+            height: scalar(self.height)?,
+            environment: self.environment.cast()?,
+            environment_frame: EnvironmentFrame {
+                right: vector(&self.environment_frame.right)?,
+                up: vector(&self.environment_frame.up)?,
+                forward: vector(&self.environment_frame.forward)?,
+            },
+        })
+    }
+}
+
+impl<S, T> Cast<T> for PhotometricWeb<S> where S: ScalarFloat, T: ScalarFloat {
+    type Output = PhotometricWeb<T>;
+
+    fn cast(&self) -> Option<Self::Output> {
+        PhotometricWeb::new(
+            scalars(self.vertical_angles())?,
+            scalars(self.horizontal_angles())?,
+            scalars(self.candela())?,
+        )
+    }
+}
+
+impl<S, T> Cast<T> for IesLightModel<S> where S: ScalarFloat + 'static, T: ScalarFloat + 'static {
+    type Output = IesLightModel<T>;
+
+    fn cast(&self) -> Option<Self::Output> {
+        Some(IesLightModel {
+            web: shared(&self.web)?,
+            color: vector(&self.color)?,
+        })
+    }
+}
+
+#[cfg(feature = "spectral")]
+impl<S, T> Cast<T> for Spectrum<S> where S: ScalarFloat, T: ScalarFloat {
+    type Output = Spectrum<T>;
+
+    fn cast(&self) -> Option<Self::Output> {
+        Spectrum::new(scalars(self.samples())?)
+    }
+}
+
+#[cfg(feature = "spectral")]
+impl<S, T> Cast<T> for SpectralLightModel<S> where S: ScalarFloat, T: ScalarFloat {
+    type Output = SpectralLightModel<T>;
+
+    fn cast(&self) -> Option<Self::Output> {
+        Some(SpectralLightModel {
+            spectrum: self.spectrum.cast()?,
+            intensity: scalar(self.intensity)?,
+            attenuation: self.attenuation.cast()?,
+            shadow_tint: vector(&self.shadow_tint)?,
+        })
+    }
+}
+
+macro_rules! impl_cast_spec {
+    ($Spec:ident, $Model:ident $(, $bound:tt)*) => {
+        impl<S, T> Cast<T> for $Spec<S> where S: ScalarFloat $(+ $bound)*, T: ScalarFloat $(+ $bound)* {
+            type Output = $Spec<T>;
+
+            /// Convert the specification through its illumination model,
+            /// which holds the same parameters.
+            #[inline]
+            fn cast(&self) -> Option<Self::Output> {
+                let model: $Model<T> = $Model::from_spec(self).cast()?;
+
+                Some(model.to_spec())
+            }
+        }
+    }
+}
+
+impl_cast_spec!(AmbientLightModelSpec, AmbientLightModel);
+impl_cast_spec!(PointLightModelSpec, PointLightModel, 'static);
+impl_cast_spec!(SpotLightModelSpec, SpotLightModel, 'static);
+impl_cast_spec!(DirectionalLightModelSpec, DirectionalLightModel);
+impl_cast_spec!(PbrLightModelSpec, PbrLightModel);
+impl_cast_spec!(HemisphereLightModelSpec, HemisphereLightModel);
+impl_cast_spec!(DiskLightModelSpec, DiskLightModel);
+impl_cast_spec!(SphereLightModelSpec, SphereLightModel);
+impl_cast_spec!(TubeLightModelSpec, TubeLightModel);
+impl_cast_spec!(BeamLightModelSpec, BeamLightModel);
+impl_cast_spec!(NGonLightModelSpec, NGonLightModel, 'static);
+impl_cast_spec!(MeshLightModelSpec, MeshLightModel, 'static);
+impl_cast_spec!(EnvironmentLightModelSpec, EnvironmentLightModel, 'static);
+impl_cast_spec!(DomeLightModelSpec, DomeLightModel, 'static);
+impl_cast_spec!(PortalLightModelSpec, PortalLightModel, 'static);
+impl_cast_spec!(IesLightModelSpec, IesLightModel, 'static);
+#[cfg(feature = "spectral")]
+impl_cast_spec!(SpectralLightModelSpec, SpectralLightModel);
+
+impl<S, M> Light<S, M>
+    where S: ScalarFloat,
+          M: IlluminationModel,
+{
+    /// Convert the light and its illumination model to the scalar type `T`.
+    /// The converted light keeps the view matrix policy, and computes its
+    /// view matrix afresh in the new precision. The result is `None` if a
+    /// parameter is out of the range of `T`.
+    pub fn cast<T, N>(&self) -> Option<Light<T, N>>
+        where T: ScalarFloat,
+              M: Cast<T, Output = N>,
+              N: IlluminationModel,
+    {
+        let orientation = &self.attitude.orientation;
+        let orientation = Quaternion::from_parts(scalar(orientation.s)?, vector(&orientation.v)?);
+        let shadow_settings = match self.shadow_settings {
+            Some(ref settings) => Some(settings.cast()?),
+            None => None,
+        };
+
+        Some(Light {
+            model: self.model.cast()?,
+            attitude: LightAttitude::from_parts(vector(&self.attitude.position)?, orientation, self.attitude.policy),
+            jitter: self.jitter.cast()?,
+            exposure_compensation: scalar(self.exposure_compensation)?,
+            layers: self.layers,
+            diffuse_factor: scalar(self.diffuse_factor)?,
+            specular_factor: scalar(self.specular_factor)?,
+            shadow_settings: shadow_settings,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        LightAttitudeSpec,
+        PointLight,
+        ViewMatrixPolicy,
+    };
+    use crate::falloff::Interpolation;
+
+
+    fn attitude_spec() -> LightAttitudeSpec<f64> {
+        LightAttitudeSpec::new(
+            Vector3::new(1.0, 2.0, 3.0),
+            Vector3::new(0.0, 0.0, -1.0),
+            Vector3::new(1.0, 0.0, 0.0),
+            Vector3::new(0.0, 1.0, 0.0),
+            Vector3::new(0.0, 0.0, 1.0),
+        )
+    }
+
+    fn point_light(diffuse: Vector3<f64>) -> PointLight<f64> {
+        let falloff = FalloffCurve::new(Interpolation::Linear, vec![(0.0, 1.0), (10.0, 0.0)]).unwrap();
+        let model_spec = PointLightModelSpec::new(Vector3::new(0.1, 0.1, 0.1), diffuse, Vector3::new(1.0, 1.0, 1.0))
+            .with_attenuation(Attenuation::WindowedInverseSquare { range: 12.0 })
+            .with_falloff(Arc::new(falloff));
+        let mut light = PointLight::with_view_matrix_policy(&model_spec, &attitude_spec(), ViewMatrixPolicy::OnDemand);
+        light.set_jitter(LightJitter::new(0.25, 7));
+        light.set_shadow_settings(Some(ShadowSettings::new(0.1, 50.0).with_filter(ShadowFilter::Exponential { exponent: 40.0 })));
+
+        light
+    }
+
+    #[test]
+    fn test_cast_light_to_f32_preserves_parameters_and_policy() {
+        let light = point_light(Vector3::new(0.8, 0.7, 0.6));
+        let cast: PointLight<f32> = light.cast().unwrap();
+
+        assert_eq!(cast.position(), Vector3::new(1.0, 2.0, 3.0));
+        assert_eq!(cast.model().diffuse, Vector3::new(0.8, 0.7, 0.6));
+        assert_eq!(cast.model().attenuation, Attenuation::WindowedInverseSquare { range: 12.0 });
+        assert_eq!(cast.model().falloff.as_ref().unwrap().points(), &[(0.0, 1.0), (10.0, 0.0)]);
+        assert_eq!(cast.jitter(), &LightJitter::new(0.25, 7));
+        assert_eq!(cast.shadow_settings().unwrap().filter, ShadowFilter::Exponential { exponent: 40.0 });
+        assert_eq!(cast.view_matrix_policy(), ViewMatrixPolicy::OnDemand);
+
+        let view_matrix = light.view_matrix();
+        let cast_view_matrix = cast.view_matrix();
+        for column in 0..4 {
+            for row in 0..4 {
+                assert!((cast_view_matrix[column][row] as f64 - view_matrix[column][row]).abs() < 1e-6);
+            }
+        }
+    }
+
+    #[test]
+    fn test_cast_out_of_range_is_none() {
+        let light = point_light(Vector3::new(1e300, 0.7, 0.6));
+
+        assert!(light.cast::<f32, PointLightModel<f32>>().is_none());
+        assert_eq!(scalar::<f64, f32>(1e300), None);
+        assert_eq!(scalar::<f64, f32>(f64::INFINITY), Some(f32::INFINITY));
+    }
+
+    #[test]
+    fn test_cast_to_same_type_shares_curves() {
+        let light = point_light(Vector3::new(0.8, 0.7, 0.6));
+        let same: PointLight<f64> = light.cast().unwrap();
+        let widened: PointLight<f64> = light.cast::<f32, PointLightModel<f32>>().unwrap().cast().unwrap();
+
+        assert!(Arc::ptr_eq(same.model().falloff.as_ref().unwrap(), light.model().falloff.as_ref().unwrap()));
+        assert!(!Arc::ptr_eq(widened.model().falloff.as_ref().unwrap(), light.model().falloff.as_ref().unwrap()));
+        assert_eq!(same.model(), light.model());
+        assert_eq!(same.view_matrix(), light.view_matrix());
+    }
+}
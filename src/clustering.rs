@@ -0,0 +1,459 @@
+use cglinalg::{
+    Magnitude,
+    Vector3,
+    ScalarFloat,
+};
+use crate::{
+    IlluminationModel,
+    Light,
+};
+use crate::any_light::{
+    BoundingSphere,
+    LightModelInfo,
+};
+use crate::cascades::CameraFrustum;
+use crate::stats;
+use crate::stats::Counter;
+use core::mem;
+
+
+#[inline]
+fn to_f32<S: ScalarFloat>(value: S) -> f32 {
+    num_traits::cast(value).unwrap()
+}
+
+#[inline]
+fn vector_to_f32<S: ScalarFloat>(vector: &Vector3<S>) -> [f32; 3] {
+    [to_f32(vector.x), to_f32(vector.y), to_f32(vector.z)]
+}
+
+/// The camera's orthonormal view basis: its right, up, and forward axes.
+fn view_basis<S: ScalarFloat>(frustum: &CameraFrustum<S>) -> (Vector3<S>, Vector3<S>, Vector3<S>) {
+    let forward = frustum.forward.normalize();
+    let right = forward.cross(&frustum.up).normalize();
+    let up = right.cross(&forward);
+
+    (right, up, forward)
+}
+
+/// A froxel grid for clustered shading: the camera frustum divided into
+/// tiles on the screen and into slices in depth, with a list of the lights
+/// reaching each cluster.
+///
+/// The depth slices are spaced exponentially, so clusters keep roughly the
+/// same proportions from the near plane to the far plane. Slice `k` of `z`
+/// covers the view depths from `near * (far / near)^(k / z)` to
+/// `near * (far / near)^((k + 1) / z)`, and shaders find a fragment's slice
+/// with the inverse formula, as in [`depth_slice`](#method.depth_slice).
+///
+/// The light lists are stored in the layout shaders read them in: one
+/// `[offset, count]` pair per cluster into one shared array of light
+/// indices. Cluster `(x, y, z)` is at index `x + width * (y + height * z)`,
+/// with tile `(0, 0)` at the bottom left of the screen.
+#[derive(Clone, Debug)]
+pub struct ClusterGrid<S> {
+    dimensions: [u32; 3],
+    near: S,
+    far: S,
+    clusters: Vec<[u32; 2]>,
+    light_indices: Vec<u32>,
+}
+
+impl<S> ClusterGrid<S> where S: ScalarFloat {
+    /// Construct an empty grid with `dimensions` clusters along the screen's
+    /// width, its height, and depth. Each dimension is at least one.
+    pub fn new(dimensions: [u32; 3]) -> ClusterGrid<S> {
+        let dimensions = [dimensions[0].max(1), dimensions[1].max(1), dimensions[2].max(1)];
+        let cluster_count = (dimensions[0] * dimensions[1] * dimensions[2]) as usize;
+
+        ClusterGrid {
+            dimensions: dimensions,
+            near: S::one(),
+            far: S::one(),
+            clusters: vec![[0, 0]; cluster_count],
+            light_indices: Vec::new(),
+        }
+    }
+
+    /// The number of clusters along the screen's width, its height, and
+    /// depth.
+    #[inline]
+    pub fn dimensions(&self) -> [u32; 3] {
+        self.dimensions
+    }
+
+    /// The total number of clusters.
+    #[inline]
+    pub fn cluster_count(&self) -> usize {
+        self.clusters.len()
+    }
+
+    /// The index of a cluster in the cluster array.
+    #[inline]
+    pub fn cluster_index(&self, x: u32, y: u32, z: u32) -> usize {
+        (x + self.dimensions[0] * (y + self.dimensions[1] * z)) as usize
+    }
+
+    /// The view depth at which depth slice `slice` begins, for the depth
+    /// range of the last assignment.
+    #[inline]
+    pub fn slice_depth(&self, slice: u32) -> S {
+        let fraction = num_traits::cast::<u32, S>(slice).unwrap() / num_traits::cast(self.dimensions[2]).unwrap();
+
+        self.near * (self.far / self.near).powf(fraction)
+    }
+
+    /// The depth slice containing a view depth, for the depth range of the
+    /// last assignment. The result is `None` outside the depth range.
+    pub fn depth_slice(&self, depth: S) -> Option<u32> {
+        if depth < self.near || depth > self.far {
+            return None;
+        }
+        let slices: S = num_traits::cast(self.dimensions[2]).unwrap();
+        let slice = ((depth / self.near).ln() / (self.far / self.near).ln() * slices).floor();
+
+        num_traits::cast::<S, u32>(slice).map(|slice| slice.min(self.dimensions[2] - 1))
+    }
+
+    /// The `[offset, count]` pair of each cluster into the light indices.
+    #[inline]
+    pub fn clusters(&self) -> &[[u32; 2]] {
+        &self.clusters
+    }
+
+    /// The light indices of every cluster, concatenated.
+    #[inline]
+    pub fn light_indices(&self) -> &[u32] {
+        &self.light_indices
+    }
+
+    /// The indices of the lights reaching a cluster.
+    #[inline]
+    pub fn cluster_lights(&self, index: usize) -> &[u32] {
+        let [offset, count] = self.clusters[index];
+
+        &self.light_indices[offset as usize..(offset + count) as usize]
+    }
+
+    /// Bin a slice of lights into the clusters of a camera frustum,
+    /// replacing the previous assignment. Each light's influence is bounded
+    /// by the sphere in which its attenuation stays above `threshold`.
+    /// Lights without distance attenuation, such as directional and ambient
+    /// lights, reach every cluster, so they are left out of the lists for
+    /// the renderer to shade separately.
+    pub fn assign<M>(&mut self, frustum: &CameraFrustum<S>, lights: &[Light<S, M>], threshold: S)
+        where M: IlluminationModel + LightModelInfo<S>
+    {
+        let spheres: Vec<BoundingSphere<S>> = lights.iter().map(|light| {
+            BoundingSphere {
+                center: light.position(),
+                radius: light.model().influence_radius(threshold),
+            }
+        }).collect();
+
+        self.assign_spheres(frustum, &spheres);
+    }
+
+    /// Bin lights of any illumination model into the clusters of a camera
+    /// frustum by their bounding spheres, such as those of
+    /// `AnyLight::bounding_sphere`, replacing the previous assignment. The
+    /// light indices in the lists are indices into `spheres`. Spheres with an
+    /// infinite radius reach every cluster, and are left out of the lists as
+    /// in [`assign`](#method.assign).
+    pub fn assign_spheres(&mut self, frustum: &CameraFrustum<S>, spheres: &[BoundingSphere<S>]) {
+        let one_half: S = num_traits::cast(0.5).unwrap();
+        let two = S::one() + S::one();
+        let (right, up, forward) = view_basis(frustum);
+        let tan_half_fov = (frustum.vertical_fov.0 * one_half).tan();
+        let half_width = tan_half_fov * frustum.aspect;
+        let [width, height, depth] = self.dimensions;
+        let width_scalar: S = num_traits::cast(width).unwrap();
+        let height_scalar: S = num_traits::cast(height).unwrap();
+        self.near = frustum.near;
+        self.far = frustum.far;
+
+        // Each light's bounding sphere in view space, as its center's
+        // coordinates along the right, up, and forward axes.
+        let spheres: Vec<(u32, Vector3<S>, S)> = spheres.iter().enumerate().filter_map(|(i, sphere)| {
+            if !sphere.radius.is_finite() {
+                return None;
+            }
+            let offset = sphere.center - frustum.position;
+            let center = Vector3::new(right.dot(&offset), up.dot(&offset), forward.dot(&offset));
+
+            Some((i as u32, center, sphere.radius))
+        }).collect();
+
+        let mut lists: Vec<Vec<u32>> = vec![Vec::new(); self.clusters.len()];
+        for z in 0..depth {
+            let near = self.slice_depth(z);
+            let far = self.slice_depth(z + 1);
+            for y in 0..height {
+                let ndc_bottom = two * num_traits::cast::<u32, S>(y).unwrap() / height_scalar - S::one();
+                let ndc_top = two * num_traits::cast::<u32, S>(y + 1).unwrap() / height_scalar - S::one();
+                for x in 0..width {
+                    let ndc_left = two * num_traits::cast::<u32, S>(x).unwrap() / width_scalar - S::one();
+                    let ndc_right = two * num_traits::cast::<u32, S>(x + 1).unwrap() / width_scalar - S::one();
+                    // The bounding box of the froxel, whose sides spread
+                    // out with depth.
+                    let min = Vector3::new(
+                        (ndc_left * half_width * near).min(ndc_left * half_width * far),
+                        (ndc_bottom * tan_half_fov * near).min(ndc_bottom * tan_half_fov * far),
+                        near,
+                    );
+                    let max = Vector3::new(
+                        (ndc_right * half_width * near).max(ndc_right * half_width * far),
+                        (ndc_top * tan_half_fov * near).max(ndc_top * tan_half_fov * far),
+                        far,
+                    );
+
+                    let index = self.cluster_index(x, y, z);
+                    for &(light, center, radius) in spheres.iter() {
+                        let closest = Vector3::new(
+                            center.x.max(min.x).min(max.x),
+                            center.y.max(min.y).min(max.y),
+                            center.z.max(min.z).min(max.z),
+                        );
+                        if (center - closest).magnitude_squared() <= radius * radius {
+                            lists[index].push(light);
+                        }
+                    }
+                }
+            }
+        }
+
+        self.light_indices.clear();
+        for (cluster, list) in self.clusters.iter_mut().zip(lists.iter()) {
+            *cluster = [self.light_indices.len() as u32, list.len() as u32];
+            self.light_indices.extend_from_slice(list);
+        }
+        stats::record(Counter::ClusterEntries, self.light_indices.len());
+    }
+}
+
+/// A light's bounding sphere in single precision, for the light bounds
+/// buffer read by the GPU culling kernel. Lights without distance
+/// attenuation have a negative radius, and the kernel leaves them out of the
+/// cluster lists, as [`ClusterGrid::assign`] does.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct GpuLightBounds {
+    pub center: [f32; 3],
+    pub radius: f32,
+}
+
+/// The camera and grid parameters of the GPU culling kernel, laid out for a
+/// uniform buffer. The camera basis is orthonormalized the same way as in
+/// [`ClusterGrid::assign`].
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct GpuClusterParams {
+    pub position: [f32; 3],
+    /// The tangent of half the camera's vertical field of view.
+    pub tan_half_fov: f32,
+    pub right: [f32; 3],
+    pub aspect: f32,
+    pub up: [f32; 3],
+    pub near: f32,
+    pub forward: [f32; 3],
+    pub far: f32,
+    pub dimensions: [u32; 3],
+    /// The number of lights in the light bounds buffer.
+    pub light_count: u32,
+    /// The capacity of the light index buffer.
+    pub max_light_indices: u32,
+    pub _padding: [u32; 3],
+}
+
+/// The counters the GPU culling kernel allocates light index storage with.
+/// They must be reset to zero before each dispatch. After the dispatch,
+/// `light_index_count` holds the number of light indices written and
+/// `overflow` is one if the light index buffer ran out of room.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct GpuClusterCounters {
+    pub light_index_count: u32,
+    pub overflow: u32,
+}
+
+/// The arguments of an indirect compute dispatch: the number of workgroups
+/// along each axis. The layout matches `VkDispatchIndirectCommand` and the
+/// indirect buffers of `wgpu::ComputePass::dispatch_workgroups_indirect`.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct DispatchIndirectArgs {
+    pub x: u32,
+    pub y: u32,
+    pub z: u32,
+}
+
+// The WGSL declarations in `shadergen::wgsl_cluster_culling` lay the
+// structs out by the uniform and storage address space rules.
+const _: () = {
+    assert!(mem::size_of::<GpuLightBounds>() == 16);
+    assert!(mem::offset_of!(GpuClusterParams, forward) == 48);
+    assert!(mem::offset_of!(GpuClusterParams, dimensions) == 64);
+    assert!(mem::offset_of!(GpuClusterParams, max_light_indices) == 80);
+    assert!(mem::size_of::<GpuClusterParams>() == 96);
+    assert!(mem::size_of::<GpuClusterCounters>() == 8);
+    assert!(mem::size_of::<DispatchIndirectArgs>() == 12);
+};
+
+#[cfg(feature = "bytemuck")]
+unsafe impl bytemuck::Zeroable for GpuLightBounds {}
+
+#[cfg(feature = "bytemuck")]
+unsafe impl bytemuck::Pod for GpuLightBounds {}
+
+#[cfg(feature = "bytemuck")]
+unsafe impl bytemuck::Zeroable for GpuClusterParams {}
+
+#[cfg(feature = "bytemuck")]
+unsafe impl bytemuck::Pod for GpuClusterParams {}
+
+#[cfg(feature = "bytemuck")]
+unsafe impl bytemuck::Zeroable for GpuClusterCounters {}
+
+#[cfg(feature = "bytemuck")]
+unsafe impl bytemuck::Pod for GpuClusterCounters {}
+
+#[cfg(feature = "bytemuck")]
+unsafe impl bytemuck::Zeroable for DispatchIndirectArgs {}
+
+#[cfg(feature = "bytemuck")]
+unsafe impl bytemuck::Pod for DispatchIndirectArgs {}
+
+/// Compute the light bounds buffer of a slice of lights for the GPU culling
+/// kernel, one bounding sphere per light in the order of the slice. Each
+/// light's influence is bounded by the sphere in which its attenuation stays
+/// above `threshold`.
+pub fn gpu_light_bounds<S, M>(lights: &[Light<S, M>], threshold: S) -> Vec<GpuLightBounds>
+    where S: ScalarFloat,
+          M: IlluminationModel + LightModelInfo<S>
+{
+    lights.iter().map(|light| {
+        let radius = light.model().influence_radius(threshold);
+
+        GpuLightBounds {
+            center: vector_to_f32(&light.position()),
+            radius: if radius.is_finite() { to_f32(radius) } else { -1.0 },
+        }
+    }).collect()
+}
+
+impl<S> ClusterGrid<S> where S: ScalarFloat {
+    /// The parameters of the GPU culling kernel for binning `light_count`
+    /// lights into the clusters of a camera frustum, with room for
+    /// `max_light_indices` light indices.
+    pub fn gpu_params(&self, frustum: &CameraFrustum<S>, light_count: u32, max_light_indices: u32) -> GpuClusterParams {
+        let one_half: S = num_traits::cast(0.5).unwrap();
+        let (right, up, forward) = view_basis(frustum);
+
+        GpuClusterParams {
+            position: vector_to_f32(&frustum.position),
+            tan_half_fov: to_f32((frustum.vertical_fov.0 * one_half).tan()),
+            right: vector_to_f32(&right),
+            aspect: to_f32(frustum.aspect),
+            up: vector_to_f32(&up),
+            near: to_f32(frustum.near),
+            forward: vector_to_f32(&forward),
+            far: to_f32(frustum.far),
+            dimensions: self.dimensions,
+            light_count: light_count,
+            max_light_indices: max_light_indices,
+            _padding: [0; 3],
+        }
+    }
+
+    /// The indirect dispatch arguments of the GPU culling kernel, with one
+    /// invocation per cluster in workgroups of `workgroup_size` invocations.
+    pub fn dispatch_args(&self, workgroup_size: u32) -> DispatchIndirectArgs {
+        let cluster_count = self.cluster_count() as u32;
+
+        DispatchIndirectArgs {
+            x: cluster_count.div_ceil(workgroup_size.max(1)),
+            y: 1,
+            z: 1,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cglinalg::Radians;
+
+
+    fn frustum() -> CameraFrustum<f64> {
+        CameraFrustum {
+            position: Vector3::new(0.0, 0.0, 0.0),
+            forward: Vector3::new(0.0, 0.0, -1.0),
+            up: Vector3::new(0.0, 1.0, 0.0),
+            vertical_fov: Radians(core::f64::consts::FRAC_PI_2),
+            aspect: 1.0,
+            near: 1.0,
+            far: 100.0,
+        }
+    }
+
+    /// The clusters a light was assigned to, as `(x, y, z)` coordinates.
+    fn clusters_of(grid: &ClusterGrid<f64>, light: u32) -> Vec<(u32, u32, u32)> {
+        let [width, height, depth] = grid.dimensions();
+        let mut clusters = Vec::new();
+        for z in 0..depth {
+            for y in 0..height {
+                for x in 0..width {
+                    if grid.cluster_lights(grid.cluster_index(x, y, z)).contains(&light) {
+                        clusters.push((x, y, z));
+                    }
+                }
+            }
+        }
+
+        clusters
+    }
+
+    #[test]
+    fn test_depth_slices_are_exponential() {
+        let mut grid = ClusterGrid::new([4, 4, 8]);
+        grid.assign_spheres(&frustum(), &[]);
+
+        assert!((grid.slice_depth(0) - 1.0).abs() < 1e-12);
+        assert!((grid.slice_depth(4) - 10.0).abs() < 1e-12);
+        assert!((grid.slice_depth(8) - 100.0).abs() < 1e-12);
+        assert_eq!(grid.depth_slice(20.0), Some(5));
+        assert_eq!(grid.depth_slice(100.0), Some(7));
+        assert_eq!(grid.depth_slice(0.5), None);
+        assert_eq!(grid.depth_slice(150.0), None);
+    }
+
+    #[test]
+    fn test_assign_spheres_to_clusters() {
+        let mut grid = ClusterGrid::new([4, 4, 8]);
+        let spheres = [
+            BoundingSphere { center: Vector3::new(0.5, 0.5, -20.0), radius: 0.1 },
+            BoundingSphere { center: Vector3::new(0.0, 0.0, -20.0), radius: 0.5 },
+            BoundingSphere { center: Vector3::new(0.0, 0.0, 0.0), radius: f64::INFINITY },
+            BoundingSphere { center: Vector3::new(0.0, 0.0, 20.0), radius: 1.0 },
+        ];
+        grid.assign_spheres(&frustum(), &spheres);
+
+        assert_eq!(clusters_of(&grid, 0), vec![(2, 2, 5)]);
+        assert_eq!(clusters_of(&grid, 1), vec![(1, 1, 5), (2, 1, 5), (1, 2, 5), (2, 2, 5)]);
+        // Lights without distance attenuation and lights behind the camera
+        // are in no cluster.
+        assert!(clusters_of(&grid, 2).is_empty());
+        assert!(clusters_of(&grid, 3).is_empty());
+        assert_eq!(grid.light_indices().len(), 5);
+    }
+
+    #[test]
+    fn test_assign_spheres_replaces_previous_assignment() {
+        let mut grid = ClusterGrid::new([4, 4, 8]);
+        grid.assign_spheres(&frustum(), &[BoundingSphere { center: Vector3::new(0.5, 0.5, -20.0), radius: 0.1 }]);
+        grid.assign_spheres(&frustum(), &[]);
+
+        assert!(grid.light_indices().is_empty());
+        assert!(grid.clusters().iter().all(|&[_, count]| count == 0));
+    }
+}
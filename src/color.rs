@@ -0,0 +1,520 @@
+use cglinalg::{
+    Vector3,
+    ScalarFloat,
+};
+
+
+/// A color's chromaticity coordinates in the CIE 1931 **xyY** color space.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Chromaticity<S> {
+    pub x: S,
+    pub y: S,
+}
+
+impl<S> Chromaticity<S> where S: ScalarFloat {
+    /// Construct a new chromaticity.
+    #[inline]
+    pub fn new(x: S, y: S) -> Chromaticity<S> {
+        Chromaticity {
+            x: x,
+            y: y,
+        }
+    }
+
+    /// Construct a chromaticity from `f64` coordinates.
+    #[inline]
+    fn from_f64(x: f64, y: f64) -> Chromaticity<S> {
+        Chromaticity {
+            x: num_traits::cast(x).unwrap(),
+            y: num_traits::cast(y).unwrap(),
+        }
+    }
+
+    /// Compute the CIE **XYZ** tristimulus values of the color with this
+    /// chromaticity and the given luminance `Y`.
+    #[inline]
+    pub fn to_xyz(&self, luminance: S) -> Vector3<S> {
+        let scale = luminance / self.y;
+
+        Vector3::new(
+            self.x * scale,
+            luminance,
+            (S::one() - self.x - self.y) * scale,
+        )
+    }
+
+    /// Compute the chromaticity of a color from its CIE **XYZ** tristimulus
+    /// values.
+    #[inline]
+    pub fn from_xyz(xyz: &Vector3<S>) -> Chromaticity<S> {
+        let sum = xyz.x + xyz.y + xyz.z;
+
+        Chromaticity {
+            x: xyz.x / sum,
+            y: xyz.y / sum,
+        }
+    }
+}
+
+/// An RGB working color space, defined by the chromaticities of its primaries
+/// and its white point. Colors in these spaces are linear, i.e. without the
+/// transfer function used for encoding images.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ColorSpace {
+    /// The ITU-R BT.709 primaries shared by sRGB, with a **D65** white point.
+    LinearSrgb,
+    /// The DCI-P3 primaries with a **D65** white point.
+    DisplayP3,
+    /// The ITU-R BT.2020 primaries with a **D65** white point.
+    Rec2020,
+    /// The ACES AP1 primaries with the ACES white point.
+    AcesCg,
+}
+
+impl ColorSpace {
+    /// The chromaticities of the red, green, and blue primaries.
+    fn primaries<S: ScalarFloat>(self) -> [Chromaticity<S>; 3] {
+        match self {
+            ColorSpace::LinearSrgb => [
+                Chromaticity::from_f64(0.640, 0.330),
+                Chromaticity::from_f64(0.300, 0.600),
+                Chromaticity::from_f64(0.150, 0.060),
+            ],
+            ColorSpace::DisplayP3 => [
+                Chromaticity::from_f64(0.680, 0.320),
+                Chromaticity::from_f64(0.265, 0.690),
+                Chromaticity::from_f64(0.150, 0.060),
+            ],
+            ColorSpace::Rec2020 => [
+                Chromaticity::from_f64(0.708, 0.292),
+                Chromaticity::from_f64(0.170, 0.797),
+                Chromaticity::from_f64(0.131, 0.046),
+            ],
+            ColorSpace::AcesCg => [
+                Chromaticity::from_f64(0.713, 0.293),
+                Chromaticity::from_f64(0.165, 0.830),
+                Chromaticity::from_f64(0.128, 0.044),
+            ],
+        }
+    }
+
+    /// The chromaticity of the color space's white point.
+    pub fn white_point<S: ScalarFloat>(self) -> Chromaticity<S> {
+        match self {
+            ColorSpace::LinearSrgb | ColorSpace::DisplayP3 | ColorSpace::Rec2020 => {
+                StandardIlluminant::D65.chromaticity()
+            }
+            ColorSpace::AcesCg => Chromaticity::from_f64(0.32168, 0.33767),
+        }
+    }
+
+    /// The matrix mapping linear RGB values in this color space to CIE **XYZ**.
+    /// The matrix is stored in row-major order.
+    fn rgb_to_xyz_matrix<S: ScalarFloat>(self) -> [[S; 3]; 3] {
+        let [red, green, blue] = self.primaries::<S>();
+        let r = red.to_xyz(S::one());
+        let g = green.to_xyz(S::one());
+        let b = blue.to_xyz(S::one());
+        let primaries = [
+            [r.x, g.x, b.x],
+            [r.y, g.y, b.y],
+            [r.z, g.z, b.z],
+        ];
+        let white = self.white_point::<S>().to_xyz(S::one());
+        // Scale the primaries so that RGB (1, 1, 1) maps to the white point.
+        let scale = mul_matrix_vector(&invert_matrix(&primaries), &white);
+
+        [
+            [primaries[0][0] * scale.x, primaries[0][1] * scale.y, primaries[0][2] * scale.z],
+            [primaries[1][0] * scale.x, primaries[1][1] * scale.y, primaries[1][2] * scale.z],
+            [primaries[2][0] * scale.x, primaries[2][1] * scale.y, primaries[2][2] * scale.z],
+        ]
+    }
+
+    /// Convert a linear RGB color in this color space to CIE **XYZ**.
+    #[inline]
+    pub fn rgb_to_xyz<S: ScalarFloat>(self, rgb: &Vector3<S>) -> Vector3<S> {
+        mul_matrix_vector(&self.rgb_to_xyz_matrix(), rgb)
+    }
+
+    /// Convert a CIE **XYZ** color to linear RGB in this color space.
+    #[inline]
+    pub fn xyz_to_rgb<S: ScalarFloat>(self, xyz: &Vector3<S>) -> Vector3<S> {
+        mul_matrix_vector(&invert_matrix(&self.rgb_to_xyz_matrix()), xyz)
+    }
+}
+
+/// The CIE standard illuminants, as defined for the CIE 1931 2° standard
+/// observer.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum StandardIlluminant {
+    /// Average noon daylight, with a correlated color temperature of about
+    /// 6504 K.
+    D65,
+    /// Horizon daylight, with a correlated color temperature of about 5003 K.
+    /// This is the usual reference white for print and product photography.
+    D50,
+    /// A typical tungsten filament lamp at about 2856 K.
+    A,
+    /// A cool white fluorescent lamp at about 4230 K.
+    F2,
+    /// A narrow tri-band fluorescent lamp at about 4000 K.
+    F11,
+}
+
+impl StandardIlluminant {
+    /// The chromaticity of the illuminant's white point.
+    pub fn chromaticity<S: ScalarFloat>(self) -> Chromaticity<S> {
+        match self {
+            StandardIlluminant::D65 => Chromaticity::from_f64(0.31271, 0.32902),
+            StandardIlluminant::D50 => Chromaticity::from_f64(0.34567, 0.35850),
+            StandardIlluminant::A   => Chromaticity::from_f64(0.44757, 0.40745),
+            StandardIlluminant::F2  => Chromaticity::from_f64(0.37208, 0.37529),
+            StandardIlluminant::F11 => Chromaticity::from_f64(0.38052, 0.37713),
+        }
+    }
+
+    /// The linear RGB color of the illuminant in the given working color
+    /// space, scaled to unit luminance.
+    ///
+    /// No chromatic adaptation is applied, so the illuminant matching the
+    /// color space's white point is neutral, and every other illuminant is
+    /// tinted relative to it.
+    #[inline]
+    pub fn color<S: ScalarFloat>(self, color_space: ColorSpace) -> Vector3<S> {
+        let xyz = self.chromaticity::<S>().to_xyz(S::one());
+
+        color_space.xyz_to_rgb(&xyz)
+    }
+}
+
+/// The temperature of a black body, in kelvin, specifying the color of the
+/// light it emits. Lighting fixtures are commonly rated this way, from warm
+/// incandescent light around 2700 K to cool daylight around 6500 K.
+/// Conversions are accurate between 1000 K and 15000 K.
+#[derive(Copy, Clone, Debug, PartialEq, PartialOrd)]
+pub struct ColorTemperature<S> {
+    /// The temperature in kelvin.
+    pub kelvin: S,
+}
+
+impl<S> ColorTemperature<S> where S: ScalarFloat {
+    /// Construct a new color temperature.
+    #[inline]
+    pub fn new(kelvin: S) -> ColorTemperature<S> {
+        ColorTemperature {
+            kelvin: kelvin,
+        }
+    }
+
+    /// The temperature in mireds, a scale on which equal steps are roughly
+    /// equally noticeable.
+    #[inline]
+    pub fn mireds(&self) -> S {
+        convert_mireds(self.kelvin)
+    }
+
+    /// The chromaticity of the black body's emission.
+    #[inline]
+    pub fn to_chromaticity(&self) -> Chromaticity<S> {
+        CorrelatedColorTemperature::from(*self).to_chromaticity()
+    }
+
+    /// The linear RGB color of the black body's emission in the given working
+    /// color space, scaled to unit luminance. Very warm temperatures fall
+    /// outside narrow gamuts such as sRGB; their negative channels are clamped
+    /// to zero.
+    #[inline]
+    pub fn to_rgb(&self, color_space: ColorSpace) -> Vector3<S> {
+        let rgb = CorrelatedColorTemperature::from(*self).to_rgb(color_space);
+
+        Vector3::new(rgb.x.max(S::zero()), rgb.y.max(S::zero()), rgb.z.max(S::zero()))
+    }
+}
+
+impl<S> From<ColorTemperature<S>> for CorrelatedColorTemperature<S> where S: ScalarFloat {
+    #[inline]
+    fn from(temperature: ColorTemperature<S>) -> CorrelatedColorTemperature<S> {
+        CorrelatedColorTemperature::from_kelvin(temperature.kelvin)
+    }
+}
+
+/// A correlated color temperature with its distance from the Planckian locus.
+///
+/// The temperature is the temperature of the black body whose color is
+/// closest to the color in the CIE 1960 **uv** chromaticity diagram, and
+/// `duv` is the signed distance from the locus. A positive `duv` lies above
+/// the locus (greenish), and a negative `duv` lies below it (pinkish).
+/// Conversions are accurate between 1000 K and 15000 K.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct CorrelatedColorTemperature<S> {
+    /// The correlated color temperature in kelvin.
+    pub kelvin: S,
+    /// The signed distance from the Planckian locus in the **uv** diagram.
+    pub duv: S,
+}
+
+impl<S> CorrelatedColorTemperature<S> where S: ScalarFloat {
+    /// The lowest temperature at which the conversions are accurate.
+    const MIN_KELVIN: f64 = 1000.0;
+    /// The highest temperature at which the conversions are accurate.
+    const MAX_KELVIN: f64 = 15000.0;
+
+    /// Construct a new correlated color temperature.
+    #[inline]
+    pub fn new(kelvin: S, duv: S) -> CorrelatedColorTemperature<S> {
+        CorrelatedColorTemperature {
+            kelvin: kelvin,
+            duv: duv,
+        }
+    }
+
+    /// Construct a correlated color temperature lying on the Planckian locus.
+    #[inline]
+    pub fn from_kelvin(kelvin: S) -> CorrelatedColorTemperature<S> {
+        Self::new(kelvin, S::zero())
+    }
+
+    /// Compute the chromaticity of the color with this correlated color
+    /// temperature.
+    pub fn to_chromaticity(&self) -> Chromaticity<S> {
+        let (u, v) = planckian_locus_uv(self.kelvin);
+        let (normal_u, normal_v) = planckian_locus_normal(self.kelvin);
+
+        chromaticity_from_uv(u + normal_u * self.duv, v + normal_v * self.duv)
+    }
+
+    /// Find the correlated color temperature of a chromaticity.
+    pub fn from_chromaticity(chromaticity: &Chromaticity<S>) -> CorrelatedColorTemperature<S> {
+        let (u, v) = uv_from_chromaticity(chromaticity);
+        let distance_squared = |mired: S| {
+            let (locus_u, locus_v) = planckian_locus_uv(convert_mireds(mired));
+            (u - locus_u) * (u - locus_u) + (v - locus_v) * (v - locus_v)
+        };
+
+        // The locus is close to uniformly parameterized in mireds, so search
+        // on a coarse grid in mireds and then refine with a ternary search.
+        let min_mired = convert_mireds::<S>(num_traits::cast(Self::MAX_KELVIN).unwrap());
+        let max_mired = convert_mireds::<S>(num_traits::cast(Self::MIN_KELVIN).unwrap());
+        let steps = 64;
+        let step = (max_mired - min_mired) / num_traits::cast(steps).unwrap();
+        let mut best_mired = min_mired;
+        let mut best_distance_squared = distance_squared(min_mired);
+        for i in 1..(steps + 1) {
+            let mired = min_mired + step * num_traits::cast(i).unwrap();
+            let candidate = distance_squared(mired);
+            if candidate < best_distance_squared {
+                best_mired = mired;
+                best_distance_squared = candidate;
+            }
+        }
+
+        let three: S = num_traits::cast(3).unwrap();
+        let mut low = (best_mired - step).max(min_mired);
+        let mut high = (best_mired + step).min(max_mired);
+        for _ in 0..48 {
+            let third = (high - low) / three;
+            if distance_squared(low + third) < distance_squared(high - third) {
+                high -= third;
+            } else {
+                low += third;
+            }
+        }
+
+        let kelvin = convert_mireds((low + high) / (S::one() + S::one()));
+        let (locus_u, locus_v) = planckian_locus_uv(kelvin);
+        let (normal_u, normal_v) = planckian_locus_normal(kelvin);
+        let duv = (u - locus_u) * normal_u + (v - locus_v) * normal_v;
+
+        CorrelatedColorTemperature::new(kelvin, duv)
+    }
+
+    /// Find the correlated color temperature of a linear RGB color in the
+    /// given working color space.
+    #[inline]
+    pub fn from_rgb(rgb: &Vector3<S>, color_space: ColorSpace) -> CorrelatedColorTemperature<S> {
+        let xyz = color_space.rgb_to_xyz(rgb);
+
+        Self::from_chromaticity(&Chromaticity::from_xyz(&xyz))
+    }
+
+    /// The linear RGB color with this correlated color temperature in the
+    /// given working color space, scaled to unit luminance.
+    #[inline]
+    pub fn to_rgb(&self, color_space: ColorSpace) -> Vector3<S> {
+        let xyz = self.to_chromaticity().to_xyz(S::one());
+
+        color_space.xyz_to_rgb(&xyz)
+    }
+}
+
+/// The color of a light as an artist specified it, either as a linear RGB
+/// color or as a correlated color temperature with a tint.
+///
+/// Keeping the temperature and tint instead of baking them into RGB lets
+/// tools show and edit the values the artist chose, and resolve them again
+/// if the pipeline's working color space changes.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum LightColor<S> {
+    /// A linear RGB color.
+    Rgb(
+        #[cfg_attr(feature = "serde", serde(with = "crate::serialization::vector3"))]
+        Vector3<S>,
+    ),
+    /// A correlated color temperature, whose `duv` is the tint: positive
+    /// values tint the light green and negative values magenta. The color
+    /// resolves to unit luminance in the working color space.
+    Temperature {
+        temperature: CorrelatedColorTemperature<S>,
+        color_space: ColorSpace,
+    },
+}
+
+impl<S> LightColor<S> where S: ScalarFloat {
+    /// Construct a light color from a temperature in kelvin and a tint,
+    /// resolving in the given working color space.
+    #[inline]
+    pub fn from_temperature(kelvin: S, tint: S, color_space: ColorSpace) -> LightColor<S> {
+        LightColor::Temperature {
+            temperature: CorrelatedColorTemperature::new(kelvin, tint),
+            color_space: color_space,
+        }
+    }
+
+    /// The linear RGB color. Temperatures resolve in their working color
+    /// space, with channels outside the space's gamut clamped to zero.
+    #[inline]
+    pub fn resolved_color(&self) -> Vector3<S> {
+        match *self {
+            LightColor::Rgb(rgb) => rgb,
+            LightColor::Temperature { ref temperature, color_space } => {
+                let rgb = temperature.to_rgb(color_space);
+
+                Vector3::new(rgb.x.max(S::zero()), rgb.y.max(S::zero()), rgb.z.max(S::zero()))
+            }
+        }
+    }
+}
+
+impl<S> From<Vector3<S>> for LightColor<S> {
+    #[inline]
+    fn from(rgb: Vector3<S>) -> LightColor<S> {
+        LightColor::Rgb(rgb)
+    }
+}
+
+/// Convert between kelvin and mireds. The conversion is its own inverse.
+#[inline]
+fn convert_mireds<S: ScalarFloat>(value: S) -> S {
+    let million: S = num_traits::cast(1_000_000).unwrap();
+
+    million / value
+}
+
+/// Krystek's rational approximation of the Planckian locus in the CIE 1960
+/// **uv** chromaticity diagram.
+fn planckian_locus_uv<S: ScalarFloat>(kelvin: S) -> (S, S) {
+    let t = num_traits::cast::<S, f64>(kelvin).unwrap();
+    let t2 = t * t;
+    let u = (0.860117757 + 1.54118254e-4 * t + 1.28641212e-7 * t2)
+        / (1.0 + 8.42420235e-4 * t + 7.08145163e-7 * t2);
+    let v = (0.317398726 + 4.22806245e-5 * t + 4.20481691e-8 * t2)
+        / (1.0 - 2.89741816e-5 * t + 1.61456053e-7 * t2);
+
+    (num_traits::cast(u).unwrap(), num_traits::cast(v).unwrap())
+}
+
+/// The unit normal to the Planckian locus in the **uv** diagram, pointing
+/// towards positive `duv`.
+fn planckian_locus_normal<S: ScalarFloat>(kelvin: S) -> (S, S) {
+    let delta: S = num_traits::cast(0.5).unwrap();
+    let (u0, v0) = planckian_locus_uv(kelvin - delta);
+    let (u1, v1) = planckian_locus_uv(kelvin + delta);
+    let (du, dv) = (u1 - u0, v1 - v0);
+    let length = (du * du + dv * dv).sqrt();
+    // The locus runs towards decreasing u as the temperature rises, so
+    // rotating the tangent clockwise points the normal towards increasing v.
+    let (normal_u, normal_v) = (dv / length, -du / length);
+    if normal_v < S::zero() {
+        (-normal_u, -normal_v)
+    } else {
+        (normal_u, normal_v)
+    }
+}
+
+/// Convert a CIE 1931 **xy** chromaticity to CIE 1960 **uv** coordinates.
+#[inline]
+fn uv_from_chromaticity<S: ScalarFloat>(chromaticity: &Chromaticity<S>) -> (S, S) {
+    let two: S = num_traits::cast(2).unwrap();
+    let three: S = num_traits::cast(3).unwrap();
+    let four: S = num_traits::cast(4).unwrap();
+    let six: S = num_traits::cast(6).unwrap();
+    let twelve: S = num_traits::cast(12).unwrap();
+    let denominator = -two * chromaticity.x + twelve * chromaticity.y + three;
+
+    (four * chromaticity.x / denominator, six * chromaticity.y / denominator)
+}
+
+/// Convert CIE 1960 **uv** coordinates to a CIE 1931 **xy** chromaticity.
+#[inline]
+fn chromaticity_from_uv<S: ScalarFloat>(u: S, v: S) -> Chromaticity<S> {
+    let two: S = num_traits::cast(2).unwrap();
+    let three: S = num_traits::cast(3).unwrap();
+    let four: S = num_traits::cast(4).unwrap();
+    let eight: S = num_traits::cast(8).unwrap();
+    let denominator = two * u - eight * v + four;
+
+    Chromaticity::new(three * u / denominator, two * v / denominator)
+}
+
+/// The luminance of a linear sRGB color.
+#[inline]
+pub(crate) fn luminance<S: ScalarFloat>(color: &Vector3<S>) -> S {
+    let r: S = num_traits::cast(0.2126).unwrap();
+    let g: S = num_traits::cast(0.7152).unwrap();
+    let b: S = num_traits::cast(0.0722).unwrap();
+
+    r * color.x + g * color.y + b * color.z
+}
+
+/// Multiply a row-major 3x3 matrix by a vector.
+#[inline]
+pub(crate) fn mul_matrix_vector<S: ScalarFloat>(matrix: &[[S; 3]; 3], vector: &Vector3<S>) -> Vector3<S> {
+    Vector3::new(
+        matrix[0][0] * vector.x + matrix[0][1] * vector.y + matrix[0][2] * vector.z,
+        matrix[1][0] * vector.x + matrix[1][1] * vector.y + matrix[1][2] * vector.z,
+        matrix[2][0] * vector.x + matrix[2][1] * vector.y + matrix[2][2] * vector.z,
+    )
+}
+
+/// Invert a row-major 3x3 matrix using the adjugate. The matrices used here
+/// are built from linearly independent primaries, so they are always
+/// invertible.
+fn invert_matrix<S: ScalarFloat>(m: &[[S; 3]; 3]) -> [[S; 3]; 3] {
+    let c00 = m[1][1] * m[2][2] - m[1][2] * m[2][1];
+    let c01 = m[1][2] * m[2][0] - m[1][0] * m[2][2];
+    let c02 = m[1][0] * m[2][1] - m[1][1] * m[2][0];
+    let determinant = m[0][0] * c00 + m[0][1] * c01 + m[0][2] * c02;
+    let inv_det = S::one() / determinant;
+
+    [
+        [
+            c00 * inv_det,
+            (m[0][2] * m[2][1] - m[0][1] * m[2][2]) * inv_det,
+            (m[0][1] * m[1][2] - m[0][2] * m[1][1]) * inv_det,
+        ],
+        [
+            c01 * inv_det,
+            (m[0][0] * m[2][2] - m[0][2] * m[2][0]) * inv_det,
+            (m[0][2] * m[1][0] - m[0][0] * m[1][2]) * inv_det,
+        ],
+        [
+            c02 * inv_det,
+            (m[0][1] * m[2][0] - m[0][0] * m[2][1]) * inv_det,
+            (m[0][0] * m[1][1] - m[0][1] * m[1][0]) * inv_det,
+        ],
+    ]
+}
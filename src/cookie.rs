@@ -0,0 +1,74 @@
+use cglinalg::{
+    Vector2,
+    Vector3,
+    Matrix4x4,
+    ScalarFloat,
+};
+use crate::SpotLight;
+
+
+impl<S> SpotLight<S> where S: ScalarFloat {
+    /// The perspective projection of the spotlight's cookie (or gobo), the
+    /// texture mask projected through its cone.
+    ///
+    /// The projection maps the light's local coordinate frame to OpenGL clip
+    /// space. Its square frustum circumscribes the spotlight's outer cone,
+    /// so the cone's edge touches the edges of the cookie texture, and depths
+    /// between `near` and `far` map to the unit depth range.
+    pub fn cookie_projection(&self, near: S, far: S) -> Matrix4x4<S> {
+        let zero = S::zero();
+        let one = S::one();
+        let two = one + one;
+        let half_angle = self.model().outer_cutoff.max(-one).min(one).acos();
+        let focal_length = one / half_angle.tan();
+        let depth = near - far;
+
+        Matrix4x4::new(
+            focal_length, zero,         zero,                       zero,
+            zero,         focal_length, zero,                       zero,
+            zero,         zero,         (far + near) / depth,       -one,
+            zero,         zero,         two * far * near / depth,   zero,
+        )
+    }
+
+    /// The matrix mapping world space to cookie texture space, for use as a
+    /// projective texture matrix in a shader.
+    ///
+    /// After the perspective divide, points inside the spotlight's frustum
+    /// map to texture coordinates in the unit square, with the origin at the
+    /// bottom left of the texture as seen from the light, and to depths in
+    /// the unit interval.
+    pub fn cookie_matrix(&self, near: S, far: S) -> Matrix4x4<S> {
+        let zero = S::zero();
+        let one = S::one();
+        let one_half: S = num_traits::cast(0.5).unwrap();
+        let bias = Matrix4x4::new(
+            one_half, zero,     zero,     zero,
+            zero,     one_half, zero,     zero,
+            zero,     zero,     one_half, zero,
+            one_half, one_half, one_half, one,
+        );
+
+        bias * self.cookie_projection(near, far) * self.view_matrix()
+    }
+
+    /// The cookie texture coordinate a point in world space receives light
+    /// through. The result is `None` for points behind the light or outside
+    /// its frustum.
+    pub fn cookie_uv(&self, point: &Vector3<S>) -> Option<Vector2<S>> {
+        // The depth range does not affect the texture coordinates.
+        let one = S::one();
+        let projected = self.cookie_matrix(one, one + one) * point.extend(one);
+        if projected.w <= S::zero() {
+            return None;
+        }
+
+        let uv = Vector2::new(projected.x / projected.w, projected.y / projected.w);
+        let inside = |t: S| t >= S::zero() && t <= one;
+        if !inside(uv.x) || !inside(uv.y) {
+            return None;
+        }
+
+        Some(uv)
+    }
+}
@@ -0,0 +1,293 @@
+use cglinalg::{
+    Vector3,
+    Radians,
+    ScalarFloat,
+};
+use crate::{
+    DirectionalLightModel,
+    DiskLightModel,
+    IlluminationModel,
+    Light,
+    PointLightModel,
+    SphereLightModel,
+    SpotLightModel,
+    TubeLightModel,
+};
+use crate::pbr::PbrLightModel;
+
+
+/// The number of channels in a DMX512 universe.
+pub const UNIVERSE_SIZE: usize = 512;
+
+/// The channel values of a single DMX512 universe.
+#[derive(Clone, PartialEq, Eq)]
+pub struct DmxUniverse {
+    channels: [u8; UNIVERSE_SIZE],
+}
+
+impl DmxUniverse {
+    /// Construct a universe with every channel set to zero.
+    #[inline]
+    pub fn new() -> DmxUniverse {
+        DmxUniverse {
+            channels: [0; UNIVERSE_SIZE],
+        }
+    }
+
+    /// Get the value of a channel. Channels are addressed from `1` to `512`,
+    /// as on a lighting console.
+    #[inline]
+    pub fn get(&self, address: usize) -> Option<u8> {
+        if address == 0 {
+            return None;
+        }
+
+        self.channels.get(address - 1).copied()
+    }
+
+    /// Set the value of a channel. Addresses outside the universe are ignored.
+    #[inline]
+    pub fn set(&mut self, address: usize, value: u8) {
+        if address > 0 && address <= UNIVERSE_SIZE {
+            self.channels[address - 1] = value;
+        }
+    }
+
+    /// Set every channel to zero.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.channels = [0; UNIVERSE_SIZE];
+    }
+
+    /// Get the raw channel values, starting with channel `1`.
+    #[inline]
+    pub fn channels(&self) -> &[u8] {
+        &self.channels
+    }
+}
+
+impl Default for DmxUniverse {
+    #[inline]
+    fn default() -> DmxUniverse {
+        DmxUniverse::new()
+    }
+}
+
+impl core::fmt::Debug for DmxUniverse {
+    fn fmt(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+        formatter.debug_struct("DmxUniverse")
+            .field("channels", &&self.channels[..])
+            .finish()
+    }
+}
+
+/// The channel layout of a fixture, given as offsets from the fixture's start
+/// address. Channels the fixture does not have are `None`.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct FixtureChannels {
+    pub dimmer: Option<usize>,
+    pub red: Option<usize>,
+    pub green: Option<usize>,
+    pub blue: Option<usize>,
+    pub pan: Option<usize>,
+    /// The low byte of a 16 bit pan channel.
+    pub pan_fine: Option<usize>,
+    pub tilt: Option<usize>,
+    /// The low byte of a 16 bit tilt channel.
+    pub tilt_fine: Option<usize>,
+}
+
+/// A lighting model whose emission can be sent to a stage fixture.
+pub trait FixtureModel<S> {
+    /// The linear RGB color the light emits.
+    fn emitted_color(&self) -> Vector3<S>;
+}
+
+impl<S> FixtureModel<S> for PointLightModel<S> where S: ScalarFloat {
+    #[inline]
+    fn emitted_color(&self) -> Vector3<S> {
+        self.diffuse
+    }
+}
+
+impl<S> FixtureModel<S> for DirectionalLightModel<S> where S: ScalarFloat {
+    #[inline]
+    fn emitted_color(&self) -> Vector3<S> {
+        self.diffuse
+    }
+}
+
+impl<S> FixtureModel<S> for DiskLightModel<S> where S: ScalarFloat {
+    #[inline]
+    fn emitted_color(&self) -> Vector3<S> {
+        self.diffuse
+    }
+}
+
+impl<S> FixtureModel<S> for SphereLightModel<S> where S: ScalarFloat {
+    #[inline]
+    fn emitted_color(&self) -> Vector3<S> {
+        self.radiance
+    }
+}
+
+impl<S> FixtureModel<S> for TubeLightModel<S> where S: ScalarFloat {
+    #[inline]
+    fn emitted_color(&self) -> Vector3<S> {
+        self.radiance
+    }
+}
+
+impl<S> FixtureModel<S> for PbrLightModel<S> where S: ScalarFloat {
+    #[inline]
+    fn emitted_color(&self) -> Vector3<S> {
+        self.emission()
+    }
+}
+
+impl<S> FixtureModel<S> for SpotLightModel<S> where S: ScalarFloat {
+    #[inline]
+    fn emitted_color(&self) -> Vector3<S> {
+        self.diffuse
+    }
+}
+
+/// A physical stage fixture patched into a DMX universe that a virtual light
+/// drives.
+///
+/// The dimmer channel carries the brightest channel of the light's color
+/// relative to the fixture's maximum intensity, and the color channels carry
+/// the color normalized to its brightest channel. Pan and tilt are derived
+/// from the light's forward axis: pan is the angle about the world's
+/// **positive y-axis** measured from the **negative z-axis**, and tilt is the
+/// elevation above the horizontal plane. Both are centered in the fixture's
+/// range of motion.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Fixture<S> {
+    /// The start address of the fixture, from `1` to `512`.
+    pub address: usize,
+    /// The fixture's channel layout.
+    pub channels: FixtureChannels,
+    /// The light intensity that maps to a full dimmer value.
+    pub max_intensity: S,
+    /// The fixture's total range of pan motion.
+    pub pan_range: Radians<S>,
+    /// The fixture's total range of tilt motion.
+    pub tilt_range: Radians<S>,
+}
+
+impl<S> Fixture<S> where S: ScalarFloat {
+    /// Construct a new fixture.
+    #[inline]
+    pub fn new<A: Into<Radians<S>>>(
+        address: usize,
+        channels: FixtureChannels,
+        max_intensity: S,
+        pan_range: A,
+        tilt_range: A) -> Fixture<S>
+    {
+        Fixture {
+            address: address,
+            channels: channels,
+            max_intensity: max_intensity,
+            pan_range: pan_range.into(),
+            tilt_range: tilt_range.into(),
+        }
+    }
+
+    /// Write the state of a light into the fixture's channels of a universe.
+    pub fn write<M>(&self, light: &Light<S, M>, universe: &mut DmxUniverse)
+        where M: IlluminationModel + FixtureModel<S>
+    {
+        let color = light.model().emitted_color();
+        let peak = color.x.max(color.y).max(color.z).max(S::zero());
+        let (red, green, blue) = if peak > S::zero() {
+            (color.x / peak, color.y / peak, color.z / peak)
+        } else {
+            (S::zero(), S::zero(), S::zero())
+        };
+        let dimmer = if self.max_intensity > S::zero() {
+            peak / self.max_intensity
+        } else {
+            S::zero()
+        };
+        self.write_coarse(universe, self.channels.dimmer, dimmer);
+        self.write_coarse(universe, self.channels.red, red);
+        self.write_coarse(universe, self.channels.green, green);
+        self.write_coarse(universe, self.channels.blue, blue);
+
+        let forward = light.forward_axis();
+        let one_half: S = num_traits::cast(0.5).unwrap();
+        let pan = forward.x.atan2(-forward.z);
+        let tilt = forward.y.max(-S::one()).min(S::one()).asin();
+        let pan_range = self.pan_range.0;
+        let tilt_range = self.tilt_range.0;
+        let pan_level = if pan_range > S::zero() { pan / pan_range + one_half } else { one_half };
+        let tilt_level = if tilt_range > S::zero() { tilt / tilt_range + one_half } else { one_half };
+        self.write_fine(universe, self.channels.pan, self.channels.pan_fine, pan_level);
+        self.write_fine(universe, self.channels.tilt, self.channels.tilt_fine, tilt_level);
+    }
+
+    /// Write a level in `[0, 1]` to an 8 bit channel.
+    fn write_coarse(&self, universe: &mut DmxUniverse, offset: Option<usize>, level: S) {
+        if let Some(offset) = offset {
+            let max: S = num_traits::cast(255).unwrap();
+            let value = (level.max(S::zero()).min(S::one()) * max).round();
+            universe.set(self.address + offset, num_traits::cast(value).unwrap());
+        }
+    }
+
+    /// Write a level in `[0, 1]` to a 16 bit channel pair.
+    fn write_fine(&self, universe: &mut DmxUniverse, coarse: Option<usize>, fine: Option<usize>, level: S) {
+        let max: S = num_traits::cast(65535).unwrap();
+        let value: u16 = num_traits::cast((level.max(S::zero()).min(S::one()) * max).round()).unwrap();
+        if let Some(offset) = coarse {
+            universe.set(self.address + offset, (value >> 8) as u8);
+        }
+        if let Some(offset) = fine {
+            universe.set(self.address + offset, (value & 0xFF) as u8);
+        }
+    }
+}
+
+/// Encode the channel values of a universe as an Art-Net `ArtDmx` packet.
+///
+/// The `universe` is the 15 bit Art-Net port address. The `sequence` number
+/// lets receivers reorder packets; pass zero to disable reordering.
+#[cfg(feature = "artnet")]
+pub fn art_dmx_packet(universe: u16, sequence: u8, data: &DmxUniverse) -> Vec<u8> {
+    const HEADER: &[u8; 8] = b"Art-Net\0";
+    const OP_DMX: u16 = 0x5000;
+    const PROTOCOL_VERSION: u16 = 14;
+
+    let length = UNIVERSE_SIZE as u16;
+    let mut packet = Vec::with_capacity(18 + UNIVERSE_SIZE);
+    packet.extend_from_slice(HEADER);
+    packet.extend_from_slice(&OP_DMX.to_le_bytes());
+    packet.extend_from_slice(&PROTOCOL_VERSION.to_be_bytes());
+    packet.push(sequence);
+    // The physical input port the data originated from.
+    packet.push(0);
+    packet.push((universe & 0xFF) as u8);
+    packet.push(((universe >> 8) & 0x7F) as u8);
+    packet.extend_from_slice(&length.to_be_bytes());
+    packet.extend_from_slice(data.channels());
+
+    packet
+}
+
+/// Send the channel values of a universe to an Art-Net node over UDP.
+#[cfg(feature = "artnet")]
+pub fn send_art_dmx<A>(
+    socket: &std::net::UdpSocket,
+    target: A,
+    universe: u16,
+    sequence: u8,
+    data: &DmxUniverse) -> std::io::Result<()>
+    where A: std::net::ToSocketAddrs
+{
+    let packet = art_dmx_packet(universe, sequence, data);
+    socket.send_to(&packet, target)?;
+
+    Ok(())
+}
@@ -0,0 +1,746 @@
+use cglinalg::{
+    Magnitude,
+    Vector2,
+    Vector3,
+    ScalarFloat,
+};
+use crate::{
+    Dimmable,
+    IlluminationModel,
+    Light,
+};
+use crate::color::luminance;
+use crate::sampling::{
+    equirectangular_direction,
+    equirectangular_pdf,
+    HierarchicalWarp,
+};
+use crate::texture::EmissionTexture;
+use core::any::Any;
+use core::fmt;
+use std::sync::Arc;
+
+
+/// A source of radiance arriving from every direction, such as a captured
+/// or rendered sky.
+pub trait EnvironmentSource<S>: fmt::Debug + Send + Sync {
+    /// The radiance arriving from the unit direction `direction` in the
+    /// source's own coordinate frame.
+    fn radiance(&self, direction: &Vector3<S>) -> Vector3<S>;
+
+    /// Sample a unit direction in the source's own coordinate frame from a
+    /// uniform sample in the unit square, returning the direction and its
+    /// probability density with respect to solid angle. Sources that cannot
+    /// importance sample their radiance sample the sphere uniformly, as the
+    /// default does.
+    fn sample(&self, u: S, v: S) -> Option<(Vector3<S>, S)> where S: ScalarFloat {
+        let pi: S = num_traits::cast(core::f64::consts::PI).unwrap();
+        let two = S::one() + S::one();
+        let y = S::one() - two * v;
+        let radius = (S::one() - y * y).max(S::zero()).sqrt();
+        let phi = two * pi * u;
+
+        Some((Vector3::new(radius * phi.cos(), y, radius * phi.sin()), S::one() / (two * two * pi)))
+    }
+
+    /// The probability density with respect to solid angle of sampling the
+    /// unit direction `direction` in the source's own coordinate frame with
+    /// [`sample`](#method.sample).
+    fn pdf(&self, _direction: &Vector3<S>) -> S where S: ScalarFloat {
+        let pi: S = num_traits::cast(core::f64::consts::PI).unwrap();
+
+        S::one() / ((S::one() + S::one()) * (S::one() + S::one()) * pi)
+    }
+
+    /// The source as a value of its concrete type, if it is a source that
+    /// `Cast` converted from another scalar type. Other sources keep the
+    /// default.
+    #[doc(hidden)]
+    #[inline]
+    fn cast_origin(&self) -> Option<&dyn Any> {
+        None
+    }
+}
+
+/// An environment stored as an equirectangular (latitude-longitude) image.
+///
+/// The image maps to directions with `sampling::equirectangular_direction`:
+/// the horizontal coordinate is the azimuth around the **positive y-axis**
+/// starting from the **positive x-axis**, and the vertical coordinate is the
+/// polar angle from the **positive y-axis**.
+///
+/// The source importance samples its luminance with a
+/// `sampling::HierarchicalWarp` over the image, weighted by the solid angle
+/// each row of texels covers.
+#[derive(Clone, Debug, PartialEq)]
+pub struct EquirectangularSource<S> {
+    texture: EmissionTexture<S>,
+    warp: HierarchicalWarp<S>,
+}
+
+/// The largest number of rows and columns of the warp an equirectangular
+/// source samples with.
+const MAX_WARP_SIZE: usize = 1024;
+
+impl<S> EquirectangularSource<S> where S: ScalarFloat {
+    /// Construct an equirectangular environment from an image of linear RGB
+    /// radiance.
+    pub fn new(texture: EmissionTexture<S>) -> EquirectangularSource<S> {
+        let warp = Self::build_warp(&texture);
+
+        EquirectangularSource {
+            texture: texture,
+            warp: warp,
+        }
+    }
+
+    /// Construct an equirectangular environment from an image and a warp
+    /// built for it earlier, such as one read back from a `cache`, skipping
+    /// the cost of building the warp. The result is `None` unless the warp
+    /// has the size `new` would build for the image.
+    pub fn with_warp(texture: EmissionTexture<S>, warp: HierarchicalWarp<S>) -> Option<EquirectangularSource<S>> {
+        let size = texture.width().max(texture.height()).next_power_of_two().min(MAX_WARP_SIZE);
+        if warp.size() != size {
+            return None;
+        }
+
+        Some(EquirectangularSource {
+            texture: texture,
+            warp: warp,
+        })
+    }
+
+    /// Build the sampling warp of an image. The warp is a square grid with
+    /// a power of two size, so each cell takes the largest luminance of the
+    /// texels its bilinear lookups can reach. Every direction with nonzero
+    /// radiance then has a nonzero density.
+    fn build_warp(texture: &EmissionTexture<S>) -> HierarchicalWarp<S> {
+        let one_half: S = num_traits::cast(0.5).unwrap();
+        let pi: S = num_traits::cast(core::f64::consts::PI).unwrap();
+        let (width, height) = (texture.width(), texture.height());
+        let width_scalar: S = num_traits::cast(width).unwrap();
+        let height_scalar: S = num_traits::cast(height).unwrap();
+        let mut texel_luminance = Vec::with_capacity(width * height);
+        for row in 0..height {
+            for column in 0..width {
+                let uv = Vector2::new(
+                    (num_traits::cast::<usize, S>(column).unwrap() + one_half) / width_scalar,
+                    (num_traits::cast::<usize, S>(row).unwrap() + one_half) / height_scalar,
+                );
+                texel_luminance.push(luminance(&texture.texel(&uv)).max(S::zero()));
+            }
+        }
+
+        let size = width.max(height).next_power_of_two().min(MAX_WARP_SIZE);
+        let size_scalar: S = num_traits::cast(size).unwrap();
+        // The texels a run of cells covers, widened by one texel on each
+        // side for the bilinear filter.
+        let span = |cell: usize, texels: usize| {
+            let start = (cell * texels / size).saturating_sub(1);
+            let end = ((cell + 1) * texels).div_ceil(size) + 1;
+
+            (start, end.min(texels))
+        };
+        let mut weights = Vec::with_capacity(size * size);
+        for row in 0..size {
+            let (row_start, row_end) = span(row, height);
+            let theta = (num_traits::cast::<usize, S>(row).unwrap() + one_half) / size_scalar * pi;
+            for column in 0..size {
+                let (column_start, column_end) = span(column, width);
+                let mut peak = S::zero();
+                for texel_row in row_start..row_end {
+                    for texel_column in column_start..column_end {
+                        peak = peak.max(texel_luminance[texel_row * width + texel_column]);
+                    }
+                }
+                weights.push(peak * theta.sin());
+            }
+        }
+
+        HierarchicalWarp::new(size, &weights).unwrap()
+    }
+
+    /// The image the environment is stored in.
+    #[inline]
+    pub fn texture(&self) -> &EmissionTexture<S> {
+        &self.texture
+    }
+
+    /// The warp the environment samples directions with. Its levels map to
+    /// the unit square like the image, ready to upload for shader-side
+    /// sampling.
+    #[inline]
+    pub fn warp(&self) -> &HierarchicalWarp<S> {
+        &self.warp
+    }
+
+    /// The levels of the sampling warp in single precision, concatenated
+    /// from the coarsest to the finest into one buffer for shader-side
+    /// sampling. Level `i` is a `2^i` by `2^i` grid in row-major order
+    /// starting at offset `(4^i - 1) / 3`.
+    pub fn warp_levels_f32(&self) -> Vec<f32> {
+        self.warp.levels()
+            .iter()
+            .flat_map(|level| level.iter().map(|&weight| num_traits::cast::<S, f32>(weight).unwrap()))
+            .collect()
+    }
+
+    /// The texture coordinate of a unit direction, the inverse of
+    /// `sampling::equirectangular_direction`.
+    pub fn texture_coordinate(direction: &Vector3<S>) -> Vector2<S> {
+        let pi: S = num_traits::cast(core::f64::consts::PI).unwrap();
+        let two_pi = pi + pi;
+        let phi = direction.z.atan2(direction.x);
+        let phi = if phi < S::zero() { phi + two_pi } else { phi };
+        let theta = direction.y.max(-S::one()).min(S::one()).acos();
+
+        Vector2::new(phi / two_pi, theta / pi)
+    }
+}
+
+impl<S> EnvironmentSource<S> for EquirectangularSource<S> where S: ScalarFloat + Send + Sync {
+    #[inline]
+    fn radiance(&self, direction: &Vector3<S>) -> Vector3<S> {
+        self.texture.lookup(&Self::texture_coordinate(direction))
+    }
+
+    /// Importance sample the environment's luminance.
+    fn sample(&self, u: S, v: S) -> Option<(Vector3<S>, S)> {
+        let (point, pdf) = self.warp.sample(u, v);
+        let pdf = equirectangular_pdf(&point, pdf);
+        if pdf <= S::zero() {
+            return None;
+        }
+
+        Some((equirectangular_direction(&point), pdf))
+    }
+
+    #[inline]
+    fn pdf(&self, direction: &Vector3<S>) -> S {
+        let point = Self::texture_coordinate(direction);
+
+        equirectangular_pdf(&point, self.warp.pdf(&point))
+    }
+}
+
+/// The faces of a cube map, in the conventional order of the positive and
+/// negative x, y, and z faces.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum CubeFace {
+    PositiveX,
+    NegativeX,
+    PositiveY,
+    NegativeY,
+    PositiveZ,
+    NegativeZ,
+}
+
+impl CubeFace {
+    /// The faces in the order their images are stored in a cube map.
+    pub const ALL: [CubeFace; 6] = [
+        CubeFace::PositiveX,
+        CubeFace::NegativeX,
+        CubeFace::PositiveY,
+        CubeFace::NegativeY,
+        CubeFace::PositiveZ,
+        CubeFace::NegativeZ,
+    ];
+}
+
+/// An environment stored as six square images on the faces of a cube,
+/// following the OpenGL cube map conventions.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CubemapSource<S> {
+    size: usize,
+    faces: Vec<Vec<Vector3<S>>>,
+}
+
+impl<S> CubemapSource<S> where S: ScalarFloat {
+    /// Construct a cube map environment from six `size` by `size` images of
+    /// linear RGB radiance stored in row-major order, in the order of
+    /// `CubeFace::ALL`. The result is `None` if the images are empty or any
+    /// image does not match the size.
+    pub fn new(size: usize, faces: Vec<Vec<Vector3<S>>>) -> Option<CubemapSource<S>> {
+        if size == 0 || faces.len() != 6 || faces.iter().any(|face| face.len() != size * size) {
+            return None;
+        }
+
+        Some(CubemapSource {
+            size: size,
+            faces: faces,
+        })
+    }
+
+    /// The width and height of each face in texels.
+    #[inline]
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// The face a direction points into, and the texture coordinate of the
+    /// direction on that face in the unit square.
+    pub fn face_coordinate(direction: &Vector3<S>) -> (CubeFace, Vector2<S>) {
+        let one_half: S = num_traits::cast(0.5).unwrap();
+        let (x, y, z) = (direction.x, direction.y, direction.z);
+        let (face, major, sc, tc) = if x.abs() >= y.abs() && x.abs() >= z.abs() {
+            if x >= S::zero() {
+                (CubeFace::PositiveX, x.abs(), -z, -y)
+            } else {
+                (CubeFace::NegativeX, x.abs(), z, -y)
+            }
+        } else if y.abs() >= z.abs() {
+            if y >= S::zero() {
+                (CubeFace::PositiveY, y.abs(), x, z)
+            } else {
+                (CubeFace::NegativeY, y.abs(), x, -z)
+            }
+        } else if z >= S::zero() {
+            (CubeFace::PositiveZ, z.abs(), x, -y)
+        } else {
+            (CubeFace::NegativeZ, z.abs(), -x, -y)
+        };
+        let major = major.max(S::epsilon());
+
+        (face, Vector2::new((sc / major + S::one()) * one_half, (tc / major + S::one()) * one_half))
+    }
+}
+
+impl<S> EnvironmentSource<S> for CubemapSource<S> where S: ScalarFloat + Send + Sync {
+    fn radiance(&self, direction: &Vector3<S>) -> Vector3<S> {
+        let (face, uv) = Self::face_coordinate(direction);
+        let index = CubeFace::ALL.iter().position(|&other| other == face).unwrap();
+        let size: S = num_traits::cast(self.size).unwrap();
+        let last = self.size - 1;
+        let column = num_traits::cast::<S, usize>((uv.x * size).floor()).unwrap_or(0).min(last);
+        let row = num_traits::cast::<S, usize>((uv.y * size).floor()).unwrap_or(0).min(last);
+
+        self.faces[index][row * self.size + column]
+    }
+}
+
+/// A specification describing an environment light, which surrounds the
+/// scene with radiance from an environment source. The light's orientation
+/// rotates the environment, and its position is ignored.
+#[derive(Clone, Debug)]
+pub struct EnvironmentLightModelSpec<S> {
+    pub source: Arc<dyn EnvironmentSource<S>>,
+    /// A multiplier on the radiance of the source.
+    pub intensity: S,
+}
+
+impl<S> EnvironmentLightModelSpec<S> where S: ScalarFloat {
+    /// Construct a new environment light specification.
+    #[inline]
+    pub fn new(source: Arc<dyn EnvironmentSource<S>>, intensity: S) -> EnvironmentLightModelSpec<S> {
+        EnvironmentLightModelSpec {
+            source: source,
+            intensity: intensity,
+        }
+    }
+}
+
+/// The illumination model of an environment light. Sources are shared
+/// between lights, and between a light and its specification.
+#[derive(Clone, Debug)]
+pub struct EnvironmentLightModel<S> {
+    pub source: Arc<dyn EnvironmentSource<S>>,
+    /// A multiplier on the radiance of the source.
+    pub intensity: S,
+}
+
+impl_illumination_model!(EnvironmentLightModel, EnvironmentLightModelSpec, [source, intensity]);
+
+impl_dimmable!(EnvironmentLightModel, [intensity]);
+
+pub type EnvironmentLight<S> = Light<S, EnvironmentLightModel<S>>;
+
+impl<S> EnvironmentLight<S> where S: ScalarFloat {
+    /// The radiance arriving from the unit direction `direction` in world
+    /// space. The direction is rotated into the light's local frame before
+    /// looking up the source, so turning the light turns the environment.
+    #[inline]
+    pub fn radiance(&self, direction: &Vector3<S>) -> Vector3<S> {
+        self.model().source.radiance(&local_direction(self, direction)) * self.model().intensity
+    }
+
+    /// The radiance `Le(p, ω)` the environment emits towards a receiver,
+    /// with `direction` pointing from the receiver towards the environment,
+    /// as in `DiskLight::emitted_radiance`. An environment is infinitely far
+    /// away, so the point is ignored.
+    #[inline]
+    pub fn emitted_radiance(&self, _point: &Vector3<S>, direction: &Vector3<S>) -> Vector3<S> {
+        self.radiance(direction)
+    }
+
+    /// Sample a unit direction in world space towards the environment from
+    /// a uniform sample in the unit square, importance sampling the source
+    /// if it can. The result is the direction and its probability density
+    /// with respect to solid angle, as in `DiskLight::sample`.
+    #[inline]
+    pub fn sample(&self, u: S, v: S) -> Option<(Vector3<S>, S)> {
+        let (direction, pdf) = self.model().source.sample(u, v)?;
+
+        Some((EnvironmentFrame::of_light(self).to_world(&direction), pdf))
+    }
+
+    /// The probability density with respect to solid angle of sampling the
+    /// unit world space direction `direction` with [`sample`](#method.sample).
+    #[inline]
+    pub fn pdf(&self, direction: &Vector3<S>) -> S {
+        self.model().source.pdf(&local_direction(self, direction))
+    }
+}
+
+/// Rotate a world space direction into a light's local coordinate frame.
+#[inline]
+fn local_direction<S, M>(light: &Light<S, M>, direction: &Vector3<S>) -> Vector3<S>
+    where S: ScalarFloat,
+          M: IlluminationModel,
+{
+    EnvironmentFrame::of_light(light).to_local(direction)
+}
+
+/// The world space axes of an environment light, which rotate world space
+/// directions into the environment source's own coordinate frame.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct EnvironmentFrame<S> {
+    /// The light's right axis in world space.
+    pub right: Vector3<S>,
+    /// The light's up axis in world space.
+    pub up: Vector3<S>,
+    /// The light's forward axis in world space.
+    pub forward: Vector3<S>,
+}
+
+impl<S> EnvironmentFrame<S> where S: ScalarFloat {
+    /// The frame of an unrotated environment light, whose source is looked up
+    /// in world space.
+    #[inline]
+    pub fn identity() -> EnvironmentFrame<S> {
+        let zero = S::zero();
+        let one = S::one();
+
+        EnvironmentFrame {
+            right: Vector3::new(one, zero, zero),
+            up: Vector3::new(zero, one, zero),
+            forward: Vector3::new(zero, zero, -one),
+        }
+    }
+
+    /// The frame of a light's current attitude.
+    #[inline]
+    pub fn of_light<M>(light: &Light<S, M>) -> EnvironmentFrame<S> where M: IlluminationModel {
+        EnvironmentFrame {
+            right: light.right_axis(),
+            up: light.up_axis(),
+            forward: light.forward_axis(),
+        }
+    }
+
+    /// Rotate a world space direction into the frame.
+    #[inline]
+    pub fn to_local(&self, direction: &Vector3<S>) -> Vector3<S> {
+        let local = Vector3::new(
+            direction.dot(&self.right),
+            direction.dot(&self.up),
+            -direction.dot(&self.forward),
+        );
+
+        local.normalize()
+    }
+
+    /// Rotate a direction in the frame into world space, the inverse of
+    /// [`to_local`](#method.to_local).
+    #[inline]
+    pub fn to_world(&self, direction: &Vector3<S>) -> Vector3<S> {
+        let world = self.right * direction.x + self.up * direction.y - self.forward * direction.z;
+
+        world.normalize()
+    }
+}
+
+/// A specification describing a dome light, an environment light whose
+/// appearance to the camera and contribution to lighting are controlled
+/// separately, as in offline renderers. A dome can light a scene with an HDR
+/// capture while showing a dimmer background, or none at all.
+#[derive(Clone, Debug)]
+pub struct DomeLightModelSpec<S> {
+    pub source: Arc<dyn EnvironmentSource<S>>,
+    /// A multiplier on the radiance of the source seen directly by the camera.
+    pub camera_intensity: S,
+    /// A multiplier on the radiance of the source lighting the scene.
+    pub lighting_intensity: S,
+}
+
+impl<S> DomeLightModelSpec<S> where S: ScalarFloat {
+    /// Construct a new dome light specification.
+    #[inline]
+    pub fn new(
+        source: Arc<dyn EnvironmentSource<S>>,
+        camera_intensity: S,
+        lighting_intensity: S) -> DomeLightModelSpec<S>
+    {
+        DomeLightModelSpec {
+            source: source,
+            camera_intensity: camera_intensity,
+            lighting_intensity: lighting_intensity,
+        }
+    }
+}
+
+/// The illumination model of a dome light.
+#[derive(Clone, Debug)]
+pub struct DomeLightModel<S> {
+    pub source: Arc<dyn EnvironmentSource<S>>,
+    /// A multiplier on the radiance of the source seen directly by the camera.
+    pub camera_intensity: S,
+    /// A multiplier on the radiance of the source lighting the scene.
+    pub lighting_intensity: S,
+}
+
+impl_illumination_model!(DomeLightModel, DomeLightModelSpec, [source, camera_intensity, lighting_intensity]);
+
+impl_dimmable!(
+    /// Dimming a dome scales its lighting contribution and leaves the
+    /// background seen by the camera unchanged.
+    DomeLightModel, [lighting_intensity]
+);
+
+impl<S> DomeLightModel<S> where S: ScalarFloat {
+    /// The equivalent environment light model for the dome's lighting
+    /// contribution.
+    #[inline]
+    pub fn lighting(&self) -> EnvironmentLightModel<S> {
+        EnvironmentLightModel {
+            source: self.source.clone(),
+            intensity: self.lighting_intensity,
+        }
+    }
+
+    /// Determine whether the camera sees the dome as a background.
+    #[inline]
+    pub fn is_visible_to_camera(&self) -> bool {
+        self.camera_intensity > S::zero()
+    }
+}
+
+pub type DomeLight<S> = Light<S, DomeLightModel<S>>;
+
+impl<S> DomeLight<S> where S: ScalarFloat {
+    /// The radiance lighting the scene from the unit direction `direction`
+    /// in world space.
+    #[inline]
+    pub fn radiance(&self, direction: &Vector3<S>) -> Vector3<S> {
+        self.model().source.radiance(&local_direction(self, direction)) * self.model().lighting_intensity
+    }
+
+    /// The background the camera sees along the unit direction `direction`
+    /// in world space.
+    #[inline]
+    pub fn background(&self, direction: &Vector3<S>) -> Vector3<S> {
+        self.model().source.radiance(&local_direction(self, direction)) * self.model().camera_intensity
+    }
+
+    /// The radiance `Le(p, ω)` lighting a receiver from the dome, with
+    /// `direction` pointing from the receiver towards the dome, as in
+    /// `EnvironmentLight::emitted_radiance`. Rays that hit the dome after a
+    /// bounce light the scene, so this is the lighting radiance rather than
+    /// the background.
+    #[inline]
+    pub fn emitted_radiance(&self, _point: &Vector3<S>, direction: &Vector3<S>) -> Vector3<S> {
+        self.radiance(direction)
+    }
+
+    /// Sample a unit direction in world space towards the dome, as in
+    /// `EnvironmentLight::sample`.
+    #[inline]
+    pub fn sample(&self, u: S, v: S) -> Option<(Vector3<S>, S)> {
+        let (direction, pdf) = self.model().source.sample(u, v)?;
+
+        Some((EnvironmentFrame::of_light(self).to_world(&direction), pdf))
+    }
+
+    /// The probability density with respect to solid angle of sampling the
+    /// unit world space direction `direction` with [`sample`](#method.sample).
+    #[inline]
+    pub fn pdf(&self, direction: &Vector3<S>) -> S {
+        self.model().source.pdf(&local_direction(self, direction))
+    }
+}
+
+/// A specification describing a portal light, a window or doorway rectangle
+/// through which an environment light enters an interior. Sampling the
+/// environment through its portals instead of over the whole sphere of
+/// directions finds the light entering the room far more often.
+///
+/// The rectangle is centered at the light's position, spans the light's right
+/// and up axes, and faces along the light's forward axis into the interior.
+#[derive(Clone, Debug)]
+pub struct PortalLightModelSpec<S> {
+    /// The extent of the rectangle along the light's right axis.
+    pub width: S,
+    /// The extent of the rectangle along the light's up axis.
+    pub height: S,
+    /// The environment seen through the portal.
+    pub environment: EnvironmentLightModel<S>,
+    /// The orientation of the environment light seen through the portal.
+    pub environment_frame: EnvironmentFrame<S>,
+}
+
+impl<S> PortalLightModelSpec<S> where S: ScalarFloat {
+    /// Construct a new portal light specification for an unrotated
+    /// environment.
+    #[inline]
+    pub fn new(width: S, height: S, environment: EnvironmentLightModel<S>) -> PortalLightModelSpec<S> {
+        PortalLightModelSpec {
+            width: width,
+            height: height,
+            environment: environment,
+            environment_frame: EnvironmentFrame::identity(),
+        }
+    }
+
+    /// Construct a new portal light specification looking out onto an
+    /// environment light, including the light's current orientation.
+    #[inline]
+    pub fn from_environment_light(width: S, height: S, environment: &EnvironmentLight<S>) -> PortalLightModelSpec<S> {
+        PortalLightModelSpec {
+            width: width,
+            height: height,
+            environment: environment.model().clone(),
+            environment_frame: EnvironmentFrame::of_light(environment),
+        }
+    }
+}
+
+/// The illumination model of a portal light.
+#[derive(Clone, Debug)]
+pub struct PortalLightModel<S> {
+    /// The extent of the rectangle along the light's right axis.
+    pub width: S,
+    /// The extent of the rectangle along the light's up axis.
+    pub height: S,
+    /// The environment seen through the portal.
+    pub environment: EnvironmentLightModel<S>,
+    /// The orientation of the environment light seen through the portal.
+    pub environment_frame: EnvironmentFrame<S>,
+}
+
+impl_illumination_model!(PortalLightModel, PortalLightModelSpec, [width, height, environment, environment_frame]);
+
+impl<S> Dimmable<S> for PortalLightModel<S> where S: ScalarFloat {
+    #[inline]
+    fn dimmed(&self, factor: S) -> Self {
+        PortalLightModel {
+            environment: self.environment.dimmed(factor),
+            ..self.clone()
+        }
+    }
+}
+
+pub type PortalLight<S> = Light<S, PortalLightModel<S>>;
+
+impl<S> PortalLight<S> where S: ScalarFloat {
+    /// The environment seen through the portal.
+    #[inline]
+    pub fn environment(&self) -> &EnvironmentLightModel<S> {
+        &self.model().environment
+    }
+
+    /// The corners of the portal's rectangle in world space, in
+    /// counterclockwise order as seen from the interior.
+    pub fn quad(&self) -> [Vector3<S>; 4] {
+        let one_half: S = num_traits::cast(0.5).unwrap();
+        let center = self.position();
+        let right = self.right_axis() * (self.model().width * one_half);
+        let up = self.up_axis() * (self.model().height * one_half);
+
+        [
+            center + right - up,
+            center - right - up,
+            center - right + up,
+            center + right + up,
+        ]
+    }
+
+    /// The radiance arriving through the portal from the unit direction
+    /// `direction` in world space, such as a direction from `sample`. The
+    /// direction is rotated into the environment's frame before looking up
+    /// the source, as the environment light itself would.
+    #[inline]
+    pub fn radiance(&self, direction: &Vector3<S>) -> Vector3<S> {
+        let model = self.model();
+        let local = model.environment_frame.to_local(direction);
+
+        model.environment.source.radiance(&local) * model.environment.intensity
+    }
+
+    /// Sample a point on the portal uniformly by area from a uniform sample
+    /// in the unit square, for a shading point `point` in the interior.
+    ///
+    /// The result is the unit direction from the shading point towards the
+    /// sampled point and the probability density of the direction with
+    /// respect to solid angle, or `None` if the shading point sees the
+    /// portal edge on or from outside, or lies on the sampled point.
+    pub fn sample(&self, u: S, v: S, point: &Vector3<S>) -> Option<(Vector3<S>, S)> {
+        let one_half: S = num_traits::cast(0.5).unwrap();
+        let model = self.model();
+        let area = model.width * model.height;
+        if area <= S::zero() {
+            return None;
+        }
+
+        let sampled = self.position()
+            + self.right_axis() * ((u - one_half) * model.width)
+            + self.up_axis() * ((v - one_half) * model.height);
+        let to_sample = sampled - point;
+        let distance_squared = to_sample.magnitude_squared();
+        if distance_squared <= S::zero() {
+            return None;
+        }
+        let direction = to_sample / distance_squared.sqrt();
+        // Light enters the interior along the forward axis, so the shading
+        // point must face the portal's outer side.
+        let cos_portal = -direction.dot(&self.forward_axis());
+        if cos_portal <= S::zero() {
+            return None;
+        }
+
+        Some((direction, distance_squared / (cos_portal * area)))
+    }
+
+    /// Intersect a ray with the portal's rectangle. The result is the
+    /// distance along the ray to the hit, in units of the ray direction's
+    /// length, if the ray hits the rectangle in front of its origin.
+    pub fn intersect(&self, origin: &Vector3<S>, direction: &Vector3<S>) -> Option<S> {
+        let one_half: S = num_traits::cast(0.5).unwrap();
+        let normal = self.forward_axis();
+        let denominator = direction.dot(&normal);
+        if denominator == S::zero() {
+            return None;
+        }
+        let t = (self.position() - origin).dot(&normal) / denominator;
+        if t <= S::zero() {
+            return None;
+        }
+        let offset = origin + direction * t - self.position();
+        let x = offset.dot(&self.right_axis()).abs();
+        let y = offset.dot(&self.up_axis()).abs();
+        let model = self.model();
+
+        if x <= model.width * one_half && y <= model.height * one_half { Some(t) } else { None }
+    }
+
+    /// The radiance `Le(p, ω)` arriving through the point `point` on the
+    /// portal, with `direction` pointing from a receiver in the interior
+    /// towards the point, as in `DiskLight::emitted_radiance`. Light passes
+    /// through the portal only into the interior.
+    #[inline]
+    pub fn emitted_radiance(&self, _point: &Vector3<S>, direction: &Vector3<S>) -> Vector3<S> {
+        if direction.dot(&self.forward_axis()) < S::zero() {
+            self.radiance(direction)
+        } else {
+            Vector3::zero()
+        }
+    }
+}
@@ -0,0 +1,73 @@
+use cglinalg::{
+    ScalarFloat,
+};
+use crate::{
+    IlluminationModel,
+    Light,
+};
+
+
+/// The multiplier on a light's emission for an exposure compensation in
+/// stops. Each stop doubles or halves the light.
+#[inline]
+pub fn stops_to_multiplier<S: ScalarFloat>(stops: S) -> S {
+    (S::one() + S::one()).powf(stops)
+}
+
+/// The exposure value at ISO 100 of a physical camera with an aperture
+/// f-number, a shutter time in seconds, and an ISO sensitivity.
+#[inline]
+pub fn ev100_from_camera<S: ScalarFloat>(aperture: S, shutter_time: S, iso: S) -> S {
+    let hundred: S = num_traits::cast(100).unwrap();
+
+    (aperture * aperture / shutter_time * hundred / iso).log2()
+}
+
+/// The multiplier that maps luminance in candela per square meter to the
+/// unit range of a display for a camera at an exposure value at ISO 100.
+///
+/// This is the saturation based exposure `1 / (1.2 * 2^EV100)`: a luminance
+/// of `1.2 * 2^EV100` saturates the sensor and maps to one. Pre-exposing
+/// light intensities with this multiplier keeps the values a renderer stores
+/// in half precision buffers in range, even for daylight.
+#[inline]
+pub fn ev100_to_exposure<S: ScalarFloat>(ev100: S) -> S {
+    let saturation: S = num_traits::cast(1.2).unwrap();
+
+    S::one() / (saturation * stops_to_multiplier(ev100))
+}
+
+impl<S, M> Light<S, M>
+    where S: ScalarFloat,
+          M: IlluminationModel,
+{
+    /// The light's exposure compensation in stops. Zero leaves the light
+    /// unchanged.
+    #[inline]
+    pub fn exposure_compensation(&self) -> S {
+        self.exposure_compensation
+    }
+
+    /// Set the light's exposure compensation in stops, brightening the light
+    /// for positive values and darkening it for negative values without
+    /// editing its illumination model.
+    #[inline]
+    pub fn set_exposure_compensation(&mut self, stops: S) {
+        self.exposure_compensation = stops;
+    }
+
+    /// The multiplier on the light's emission from its exposure
+    /// compensation.
+    #[inline]
+    pub fn exposure_multiplier(&self) -> S {
+        stops_to_multiplier(self.exposure_compensation)
+    }
+
+    /// The multiplier a renderer applies to the light's emission to pre-expose
+    /// it for a camera at an exposure value at ISO 100, including the light's
+    /// exposure compensation.
+    #[inline]
+    pub fn pre_exposure(&self, ev100: S) -> S {
+        ev100_to_exposure(ev100) * self.exposure_multiplier()
+    }
+}
@@ -0,0 +1,668 @@
+use cglinalg::{
+    Vector3,
+    ScalarFloat,
+};
+use crate::{
+    AmbientLightModel,
+    BeamLightModel,
+    DirectionalLightModel,
+    DiskLightModel,
+    HemisphereLightModel,
+    IlluminationModel,
+    Light,
+    PointLightModel,
+    SphereLightModel,
+    SpotLightModel,
+    TubeLightModel,
+};
+use crate::color::LightColor;
+use crate::environment::{
+    DomeLightModel,
+    EnvironmentLightModel,
+};
+use crate::ies::IesLightModel;
+use crate::mesh::MeshLightModel;
+use crate::pbr::PbrLightModel;
+use crate::polygon::NGonLightModel;
+use std::collections::HashMap;
+use std::error;
+use core::fmt;
+
+
+/// The deepest nesting of operators, parentheses, and function calls an
+/// expression may have. Deeper expressions are rejected by the parser, so
+/// parsing and evaluating an expression cannot overflow the stack.
+pub const MAX_EXPRESSION_DEPTH: usize = 64;
+
+/// A binary arithmetic operator in an expression.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum BinaryOperator {
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+}
+
+/// A built-in function callable from an expression.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Function {
+    /// `sin(x)`, with `x` in radians.
+    Sin,
+    /// `cos(x)`, with `x` in radians.
+    Cos,
+    /// `abs(x)`.
+    Abs,
+    /// `min(a, b)`.
+    Min,
+    /// `max(a, b)`.
+    Max,
+    /// `clamp(x, low, high)`.
+    Clamp,
+    /// `smoothstep(edge0, edge1, x)`.
+    Smoothstep,
+}
+
+impl Function {
+    /// Look up a built-in function by name.
+    fn from_name(name: &str) -> Option<Function> {
+        match name {
+            "sin" => Some(Function::Sin),
+            "cos" => Some(Function::Cos),
+            "abs" => Some(Function::Abs),
+            "min" => Some(Function::Min),
+            "max" => Some(Function::Max),
+            "clamp" => Some(Function::Clamp),
+            "smoothstep" => Some(Function::Smoothstep),
+            _ => None,
+        }
+    }
+
+    /// The number of arguments the function takes.
+    #[inline]
+    pub fn arity(self) -> usize {
+        match self {
+            Function::Sin | Function::Cos | Function::Abs => 1,
+            Function::Min | Function::Max => 2,
+            Function::Clamp | Function::Smoothstep => 3,
+        }
+    }
+
+    fn apply<S: ScalarFloat>(self, args: &[S]) -> S {
+        match self {
+            Function::Sin => args[0].sin(),
+            Function::Cos => args[0].cos(),
+            Function::Abs => args[0].abs(),
+            Function::Min => args[0].min(args[1]),
+            Function::Max => args[0].max(args[1]),
+            Function::Clamp => args[0].max(args[1]).min(args[2]),
+            Function::Smoothstep => {
+                let two: S = num_traits::cast(2).unwrap();
+                let three: S = num_traits::cast(3).unwrap();
+                let width = args[1] - args[0];
+                if width <= S::zero() {
+                    return if args[2] >= args[1] { S::one() } else { S::zero() };
+                }
+                let t = ((args[2] - args[0]) / width).max(S::zero()).min(S::one());
+
+                t * t * (three - two * t)
+            }
+        }
+    }
+}
+
+/// The named scene variables that expressions are evaluated against, such
+/// as `time` or `player_distance`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Variables<S> {
+    values: HashMap<String, S>,
+}
+
+impl<S> Variables<S> where S: ScalarFloat {
+    /// Construct an empty set of variables.
+    #[inline]
+    pub fn new() -> Variables<S> {
+        Variables {
+            values: HashMap::new(),
+        }
+    }
+
+    /// Set the value of a variable, defining it if necessary.
+    #[inline]
+    pub fn set(&mut self, name: &str, value: S) {
+        if let Some(slot) = self.values.get_mut(name) {
+            *slot = value;
+        } else {
+            self.values.insert(name.to_string(), value);
+        }
+    }
+
+    /// Get the value of a variable.
+    #[inline]
+    pub fn get(&self, name: &str) -> Option<S> {
+        self.values.get(name).copied()
+    }
+
+    /// Remove a variable.
+    #[inline]
+    pub fn remove(&mut self, name: &str) -> Option<S> {
+        self.values.remove(name)
+    }
+}
+
+/// An error produced while parsing an expression.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseError {
+    /// The byte offset in the source at which the error occurred.
+    pub position: usize,
+    /// A description of the error.
+    pub message: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "parse error at offset {}: {}", self.position, self.message)
+    }
+}
+
+impl error::Error for ParseError {}
+
+/// An error produced while evaluating an expression.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum EvaluationError {
+    /// The expression refers to a variable that is not defined.
+    UnknownVariable(String),
+}
+
+impl fmt::Display for EvaluationError {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            EvaluationError::UnknownVariable(name) => write!(formatter, "unknown variable `{}`", name),
+        }
+    }
+}
+
+impl error::Error for EvaluationError {}
+
+/// An arithmetic expression over named scene variables.
+///
+/// Expressions are usually parsed from source text such as
+/// `"1 + 0.5 * sin(time * 6.0)"` or
+/// `"clamp(1 - player_distance / 20, 0, 1)"`. The grammar supports numeric
+/// literals, variables, the operators `+`, `-`, `*`, `/`, unary negation,
+/// parentheses, and the functions in [`Function`]. Expressions nest at most
+/// [`MAX_EXPRESSION_DEPTH`] levels deep.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Expression<S> {
+    Constant(S),
+    Variable(String),
+    Negate(Box<Expression<S>>),
+    Binary(BinaryOperator, Box<Expression<S>>, Box<Expression<S>>),
+    Call(Function, Vec<Expression<S>>),
+}
+
+impl<S> Expression<S> where S: ScalarFloat {
+    /// Parse an expression from source text.
+    pub fn parse(source: &str) -> Result<Expression<S>, ParseError> {
+        let mut parser = Parser {
+            source: source,
+            position: 0,
+            depth: 0,
+        };
+        let expression = parser.parse_sum()?;
+        parser.skip_whitespace();
+        if parser.position < source.len() {
+            return Err(parser.error("unexpected trailing input"));
+        }
+
+        Ok(expression)
+    }
+
+    /// Evaluate the expression against a set of variables.
+    pub fn evaluate(&self, variables: &Variables<S>) -> Result<S, EvaluationError> {
+        match self {
+            Expression::Constant(value) => Ok(*value),
+            Expression::Variable(name) => {
+                variables.get(name).ok_or_else(|| EvaluationError::UnknownVariable(name.clone()))
+            }
+            Expression::Negate(operand) => Ok(-operand.evaluate(variables)?),
+            Expression::Binary(operator, left, right) => {
+                let left = left.evaluate(variables)?;
+                let right = right.evaluate(variables)?;
+                let value = match operator {
+                    BinaryOperator::Add => left + right,
+                    BinaryOperator::Subtract => left - right,
+                    BinaryOperator::Multiply => left * right,
+                    BinaryOperator::Divide => left / right,
+                };
+
+                Ok(value)
+            }
+            Expression::Call(function, args) => {
+                let mut values = [S::zero(); 3];
+                for (value, arg) in values.iter_mut().zip(args.iter()) {
+                    *value = arg.evaluate(variables)?;
+                }
+
+                Ok(function.apply(&values[..args.len()]))
+            }
+        }
+    }
+}
+
+/// A recursive descent parser for expressions.
+struct Parser<'a> {
+    source: &'a str,
+    position: usize,
+    /// The nesting depth of the expression being parsed.
+    depth: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn error(&self, message: &str) -> ParseError {
+        ParseError {
+            position: self.position,
+            message: message.to_string(),
+        }
+    }
+
+    /// Descend one nesting level, failing past the maximum depth.
+    fn enter(&mut self) -> Result<(), ParseError> {
+        if self.depth >= MAX_EXPRESSION_DEPTH {
+            return Err(self.error("expression is nested too deeply"));
+        }
+        self.depth += 1;
+
+        Ok(())
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = &self.source[self.position..];
+        self.position += rest.len() - rest.trim_start().len();
+    }
+
+    fn peek(&mut self) -> Option<char> {
+        self.skip_whitespace();
+        self.source[self.position..].chars().next()
+    }
+
+    fn eat(&mut self, expected: char) -> bool {
+        if self.peek() == Some(expected) {
+            self.position += expected.len_utf8();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn parse_sum<S: ScalarFloat>(&mut self) -> Result<Expression<S>, ParseError> {
+        let depth = self.depth;
+        let mut expression = self.parse_product()?;
+        loop {
+            let operator = if self.eat('+') {
+                BinaryOperator::Add
+            } else if self.eat('-') {
+                BinaryOperator::Subtract
+            } else {
+                self.depth = depth;
+                return Ok(expression);
+            };
+            self.enter()?;
+            let right = self.parse_product()?;
+            expression = Expression::Binary(operator, Box::new(expression), Box::new(right));
+        }
+    }
+
+    fn parse_product<S: ScalarFloat>(&mut self) -> Result<Expression<S>, ParseError> {
+        let depth = self.depth;
+        let mut expression = self.parse_unary()?;
+        loop {
+            let operator = if self.eat('*') {
+                BinaryOperator::Multiply
+            } else if self.eat('/') {
+                BinaryOperator::Divide
+            } else {
+                self.depth = depth;
+                return Ok(expression);
+            };
+            self.enter()?;
+            let right = self.parse_unary()?;
+            expression = Expression::Binary(operator, Box::new(expression), Box::new(right));
+        }
+    }
+
+    fn parse_unary<S: ScalarFloat>(&mut self) -> Result<Expression<S>, ParseError> {
+        if self.eat('-') {
+            self.enter()?;
+            let operand = self.parse_unary()?;
+            self.depth -= 1;
+            return Ok(Expression::Negate(Box::new(operand)));
+        }
+
+        self.parse_primary()
+    }
+
+    fn parse_primary<S: ScalarFloat>(&mut self) -> Result<Expression<S>, ParseError> {
+        match self.peek() {
+            Some('(') => {
+                self.position += 1;
+                self.enter()?;
+                let expression = self.parse_sum()?;
+                if !self.eat(')') {
+                    return Err(self.error("expected `)`"));
+                }
+                self.depth -= 1;
+
+                Ok(expression)
+            }
+            Some(c) if c.is_ascii_digit() || c == '.' => self.parse_number(),
+            Some(c) if c.is_alphabetic() || c == '_' => self.parse_identifier(),
+            Some(_) => Err(self.error("expected a number, a variable, or `(`")),
+            None => Err(self.error("unexpected end of input")),
+        }
+    }
+
+    fn parse_number<S: ScalarFloat>(&mut self) -> Result<Expression<S>, ParseError> {
+        let start = self.position;
+        let bytes = self.source.as_bytes();
+        let mut end = start;
+        while end < bytes.len() && (bytes[end].is_ascii_digit() || bytes[end] == b'.') {
+            end += 1;
+        }
+        if end < bytes.len() && (bytes[end] == b'e' || bytes[end] == b'E') {
+            end += 1;
+            if end < bytes.len() && (bytes[end] == b'+' || bytes[end] == b'-') {
+                end += 1;
+            }
+            while end < bytes.len() && bytes[end].is_ascii_digit() {
+                end += 1;
+            }
+        }
+        let value: f64 = match self.source[start..end].parse() {
+            Ok(value) => value,
+            Err(_) => return Err(self.error("invalid number")),
+        };
+        self.position = end;
+
+        Ok(Expression::Constant(num_traits::cast(value).unwrap()))
+    }
+
+    fn parse_identifier<S: ScalarFloat>(&mut self) -> Result<Expression<S>, ParseError> {
+        let start = self.position;
+        let rest = &self.source[start..];
+        let length = rest
+            .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+            .unwrap_or(rest.len());
+        let name = &rest[..length];
+        self.position += length;
+        if !self.eat('(') {
+            return Ok(Expression::Variable(name.to_string()));
+        }
+
+        let function = match Function::from_name(name) {
+            Some(function) => function,
+            None => {
+                self.position = start;
+                return Err(self.error("unknown function"));
+            }
+        };
+        self.enter()?;
+        let mut args = Vec::with_capacity(function.arity());
+        if !self.eat(')') {
+            loop {
+                args.push(self.parse_sum()?);
+                if self.eat(')') {
+                    break;
+                }
+                if !self.eat(',') {
+                    return Err(self.error("expected `,` or `)`"));
+                }
+            }
+        }
+        if args.len() != function.arity() {
+            self.position = start;
+            return Err(self.error("wrong number of arguments"));
+        }
+        self.depth -= 1;
+
+        Ok(Expression::Call(function, args))
+    }
+}
+
+/// A light parameter that can be driven by an expression.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum LightProperty {
+    /// A multiplier on all of the light's colors.
+    Intensity,
+    /// A multiplier on the red channel of the light's colors.
+    Red,
+    /// A multiplier on the green channel of the light's colors.
+    Green,
+    /// A multiplier on the blue channel of the light's colors.
+    Blue,
+    /// The angle in radians between the light's axis and the edge of its outer
+    /// cone. The inner cone keeps its angle relative to the outer cone.
+    ConeAngle,
+}
+
+/// A lighting model whose parameters can be driven by expressions.
+pub trait BindableModel<S> {
+    /// Set a property of the lighting model. Models without the property
+    /// ignore it.
+    fn apply_property(&mut self, property: LightProperty, value: S);
+}
+
+impl<S> BindableModel<S> for AmbientLightModel<S> where S: ScalarFloat {
+    #[inline]
+    fn apply_property(&mut self, property: LightProperty, value: S) {
+        scale_color(&mut self.ambient, property, value);
+    }
+}
+
+impl<S> BindableModel<S> for HemisphereLightModel<S> where S: ScalarFloat {
+    #[inline]
+    fn apply_property(&mut self, property: LightProperty, value: S) {
+        scale_color(&mut self.sky, property, value);
+        scale_color(&mut self.ground, property, value);
+    }
+}
+
+impl<S> BindableModel<S> for DomeLightModel<S> where S: ScalarFloat {
+    #[inline]
+    fn apply_property(&mut self, property: LightProperty, value: S) {
+        if property == LightProperty::Intensity {
+            self.lighting_intensity *= value;
+        }
+    }
+}
+
+impl<S> BindableModel<S> for EnvironmentLightModel<S> where S: ScalarFloat {
+    #[inline]
+    fn apply_property(&mut self, property: LightProperty, value: S) {
+        if property == LightProperty::Intensity {
+            self.intensity *= value;
+        }
+    }
+}
+
+impl<S> BindableModel<S> for IesLightModel<S> where S: ScalarFloat {
+    #[inline]
+    fn apply_property(&mut self, property: LightProperty, value: S) {
+        scale_color(&mut self.color, property, value);
+    }
+}
+
+impl<S> BindableModel<S> for MeshLightModel<S> where S: ScalarFloat {
+    #[inline]
+    fn apply_property(&mut self, property: LightProperty, value: S) {
+        if property == LightProperty::Intensity {
+            self.intensity *= value;
+        }
+    }
+}
+
+impl<S> BindableModel<S> for NGonLightModel<S> where S: ScalarFloat {
+    #[inline]
+    fn apply_property(&mut self, property: LightProperty, value: S) {
+        scale_color(&mut self.radiance, property, value);
+    }
+}
+
+impl<S> BindableModel<S> for BeamLightModel<S> where S: ScalarFloat {
+    #[inline]
+    fn apply_property(&mut self, property: LightProperty, value: S) {
+        scale_color(&mut self.color, property, value);
+    }
+}
+
+impl<S> BindableModel<S> for PbrLightModel<S> where S: ScalarFloat {
+    #[inline]
+    fn apply_property(&mut self, property: LightProperty, value: S) {
+        match property {
+            LightProperty::Intensity => self.intensity *= value,
+            LightProperty::Red | LightProperty::Green | LightProperty::Blue => {
+                // Scaling single channels bakes a temperature into RGB.
+                let mut color = self.color.resolved_color();
+                scale_color(&mut color, property, value);
+                self.color = LightColor::Rgb(color);
+            }
+            LightProperty::ConeAngle => {}
+        }
+    }
+}
+
+impl<S> BindableModel<S> for PointLightModel<S> where S: ScalarFloat {
+    #[inline]
+    fn apply_property(&mut self, property: LightProperty, value: S) {
+        for color in [&mut self.ambient, &mut self.diffuse, &mut self.specular].iter_mut() {
+            scale_color(color, property, value);
+        }
+    }
+}
+
+impl<S> BindableModel<S> for DirectionalLightModel<S> where S: ScalarFloat {
+    #[inline]
+    fn apply_property(&mut self, property: LightProperty, value: S) {
+        for color in [&mut self.ambient, &mut self.diffuse, &mut self.specular].iter_mut() {
+            scale_color(color, property, value);
+        }
+    }
+}
+
+impl<S> BindableModel<S> for DiskLightModel<S> where S: ScalarFloat {
+    #[inline]
+    fn apply_property(&mut self, property: LightProperty, value: S) {
+        for color in [&mut self.ambient, &mut self.diffuse, &mut self.specular].iter_mut() {
+            scale_color(color, property, value);
+        }
+    }
+}
+
+impl<S> BindableModel<S> for SphereLightModel<S> where S: ScalarFloat {
+    #[inline]
+    fn apply_property(&mut self, property: LightProperty, value: S) {
+        scale_color(&mut self.radiance, property, value);
+    }
+}
+
+impl<S> BindableModel<S> for TubeLightModel<S> where S: ScalarFloat {
+    #[inline]
+    fn apply_property(&mut self, property: LightProperty, value: S) {
+        scale_color(&mut self.radiance, property, value);
+    }
+}
+
+impl<S> BindableModel<S> for SpotLightModel<S> where S: ScalarFloat {
+    fn apply_property(&mut self, property: LightProperty, value: S) {
+        if property == LightProperty::ConeAngle {
+            let outer_angle = self.outer_cutoff.acos();
+            let inner_angle = self.cutoff.acos();
+            let ratio = if outer_angle > S::zero() {
+                inner_angle / outer_angle
+            } else {
+                S::one()
+            };
+            self.outer_cutoff = value.cos();
+            self.cutoff = (value * ratio).cos();
+            return;
+        }
+
+        for color in [&mut self.ambient, &mut self.diffuse, &mut self.specular].iter_mut() {
+            scale_color(color, property, value);
+        }
+    }
+}
+
+/// Scale the channels of a color selected by a property. Properties that
+/// are not color properties leave the color unchanged.
+#[inline]
+fn scale_color<S: ScalarFloat>(color: &mut Vector3<S>, property: LightProperty, value: S) {
+    match property {
+        LightProperty::Intensity => *color *= value,
+        LightProperty::Red => color.x *= value,
+        LightProperty::Green => color.y *= value,
+        LightProperty::Blue => color.z *= value,
+        LightProperty::ConeAngle => {}
+    }
+}
+
+/// A set of expressions driving the parameters of a light.
+///
+/// The bindings keep an unmodified base model. Each update evaluates every
+/// expression against the current scene variables and derives the light's
+/// model from the base model, so the bindings never accumulate drift.
+#[derive(Clone, Debug)]
+pub struct LightBindings<S, M> {
+    base_model: M,
+    bindings: Vec<(LightProperty, Expression<S>)>,
+}
+
+impl<S, M> LightBindings<S, M>
+    where S: ScalarFloat,
+          M: IlluminationModel + BindableModel<S> + Clone,
+{
+    /// Construct an empty set of bindings over a base lighting model.
+    #[inline]
+    pub fn new(base_model: M) -> LightBindings<S, M> {
+        LightBindings {
+            base_model: base_model,
+            bindings: Vec::new(),
+        }
+    }
+
+    /// Bind a property to an expression, replacing any existing binding for
+    /// the property.
+    pub fn bind(&mut self, property: LightProperty, expression: Expression<S>) {
+        self.unbind(property);
+        self.bindings.push((property, expression));
+    }
+
+    /// Remove the binding for a property.
+    #[inline]
+    pub fn unbind(&mut self, property: LightProperty) {
+        self.bindings.retain(|(bound, _)| *bound != property);
+    }
+
+    /// Get the base lighting model.
+    #[inline]
+    pub fn base_model(&self) -> &M {
+        &self.base_model
+    }
+
+    /// Replace the base lighting model.
+    #[inline]
+    pub fn set_base_model(&mut self, base_model: M) {
+        self.base_model = base_model;
+    }
+
+    /// Evaluate the bindings and update the light's model. If any expression
+    /// fails to evaluate, the light is left unchanged.
+    pub fn update(&self, variables: &Variables<S>, light: &mut Light<S, M>) -> Result<(), EvaluationError> {
+        let mut model = self.base_model.clone();
+        for (property, expression) in self.bindings.iter() {
+            model.apply_property(*property, expression.evaluate(variables)?);
+        }
+        *light.model_mut() = model;
+
+        Ok(())
+    }
+}
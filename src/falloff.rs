@@ -0,0 +1,250 @@
+use cglinalg::{
+    ScalarFloat,
+};
+use core::fmt;
+use std::error;
+
+
+/// An error from parsing the text form of a falloff curve.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum FalloffCurveError {
+    /// The text has no interpolation line.
+    MissingInterpolation,
+    /// The interpolation line names an unknown interpolation.
+    UnknownInterpolation(String),
+    /// A control point line does not hold exactly a distance and a value.
+    InvalidControlPoint(String),
+    /// A control point coordinate is not a number.
+    InvalidNumber(String),
+    /// The text has no control points.
+    NoControlPoints,
+}
+
+impl fmt::Display for FalloffCurveError {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FalloffCurveError::MissingInterpolation => write!(formatter, "missing interpolation"),
+            FalloffCurveError::UnknownInterpolation(name) => write!(formatter, "unknown interpolation `{}`", name),
+            FalloffCurveError::InvalidControlPoint(line) => write!(formatter, "invalid control point `{}`", line),
+            FalloffCurveError::InvalidNumber(token) => write!(formatter, "invalid number `{}`", token),
+            FalloffCurveError::NoControlPoints => write!(formatter, "no control points"),
+        }
+    }
+}
+
+impl error::Error for FalloffCurveError {}
+
+/// The interpolation between the control points of a falloff curve.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Interpolation {
+    /// Straight lines between consecutive control points.
+    Linear,
+    /// A Catmull-Rom spline through the control points, which has a
+    /// continuous slope but may overshoot between them.
+    CatmullRom,
+}
+
+impl Interpolation {
+    /// The name of the interpolation in the text form of a curve.
+    #[inline]
+    pub fn name(self) -> &'static str {
+        match self {
+            Interpolation::Linear => "linear",
+            Interpolation::CatmullRom => "catmull-rom",
+        }
+    }
+
+    /// Find the interpolation with a name.
+    #[inline]
+    pub fn from_name(name: &str) -> Option<Interpolation> {
+        match name {
+            "linear" => Some(Interpolation::Linear),
+            "catmull-rom" => Some(Interpolation::CatmullRom),
+            _ => None,
+        }
+    }
+}
+
+/// An artist authored curve from the distance to a light to a multiplier on
+/// its emission, for falloffs no analytic attenuation model captures.
+///
+/// The curve passes through its control points and is held constant before
+/// its first and beyond its last control point.
+///
+/// A curve is written as text with the interpolation's name on the first
+/// line followed by one control point per line, as a distance and a value
+/// separated by whitespace:
+/// ```text
+/// catmull-rom
+/// 0 1
+/// 2.5 0.4
+/// 10 0
+/// ```
+/// The `Display` implementation writes this form and
+/// [`parse`](#method.parse) reads it back.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FalloffCurve<S> {
+    interpolation: Interpolation,
+    /// The control points as pairs of distances and values, sorted by
+    /// distance.
+    points: Vec<(S, S)>,
+}
+
+impl<S> FalloffCurve<S> where S: ScalarFloat {
+    /// Construct a curve from control points given as pairs of distances and
+    /// values. The control points need not be sorted. The result is `None`
+    /// if there are no control points.
+    pub fn new(interpolation: Interpolation, points: Vec<(S, S)>) -> Option<FalloffCurve<S>> {
+        if points.is_empty() {
+            return None;
+        }
+
+        let mut points = points;
+        points.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(core::cmp::Ordering::Equal));
+
+        Some(FalloffCurve {
+            interpolation: interpolation,
+            points: points,
+        })
+    }
+
+    /// Parse a curve from its text form.
+    pub fn parse(source: &str) -> Result<FalloffCurve<S>, FalloffCurveError> {
+        let mut lines = source.lines().map(str::trim).filter(|line| !line.is_empty());
+        let name = lines.next().ok_or(FalloffCurveError::MissingInterpolation)?;
+        let interpolation = Interpolation::from_name(name)
+            .ok_or_else(|| FalloffCurveError::UnknownInterpolation(name.to_string()))?;
+
+        let number = |token: &str| -> Result<S, FalloffCurveError> {
+            let value: f64 = token.parse().map_err(|_| FalloffCurveError::InvalidNumber(token.to_string()))?;
+
+            num_traits::cast(value).ok_or_else(|| FalloffCurveError::InvalidNumber(token.to_string()))
+        };
+        let mut points = Vec::new();
+        for line in lines {
+            let tokens: Vec<&str> = line.split_whitespace().collect();
+            if tokens.len() != 2 {
+                return Err(FalloffCurveError::InvalidControlPoint(line.to_string()));
+            }
+            points.push((number(tokens[0])?, number(tokens[1])?));
+        }
+
+        Self::new(interpolation, points).ok_or(FalloffCurveError::NoControlPoints)
+    }
+
+    /// The interpolation between the control points.
+    #[inline]
+    pub fn interpolation(&self) -> Interpolation {
+        self.interpolation
+    }
+
+    /// The control points of the curve, sorted by distance.
+    #[inline]
+    pub fn points(&self) -> &[(S, S)] {
+        &self.points
+    }
+
+    /// Evaluate the curve at a distance from the light.
+    pub fn evaluate(&self, distance: S) -> S {
+        let last = self.points.len() - 1;
+        if distance <= self.points[0].0 {
+            return self.points[0].1;
+        }
+        if distance >= self.points[last].0 {
+            return self.points[last].1;
+        }
+
+        // The first control point beyond the distance.
+        let upper = self.points.iter().position(|point| point.0 > distance).unwrap_or(last);
+        let lower = upper - 1;
+        let (distance0, value0) = self.points[lower];
+        let (distance1, value1) = self.points[upper];
+        let width = distance1 - distance0;
+        if width <= S::zero() {
+            return value1;
+        }
+        let t = (distance - distance0) / width;
+
+        match self.interpolation {
+            Interpolation::Linear => value0 + (value1 - value0) * t,
+            Interpolation::CatmullRom => {
+                // Hermite interpolation with the slopes at each control point
+                // estimated from its neighbors, scaled to the segment's width.
+                let slope = |i: usize| {
+                    let before = self.points[i.saturating_sub(1)];
+                    let after = self.points[(i + 1).min(last)];
+                    let run = after.0 - before.0;
+                    if run <= S::zero() {
+                        S::zero()
+                    } else {
+                        (after.1 - before.1) / run * width
+                    }
+                };
+                let one = S::one();
+                let two = one + one;
+                let three = two + one;
+                let t2 = t * t;
+                let t3 = t2 * t;
+
+                value0 * (two * t3 - three * t2 + one)
+                    + slope(lower) * (t3 - two * t2 + t)
+                    + value1 * (three * t2 - two * t3)
+                    + slope(upper) * (t3 - t2)
+            }
+        }
+    }
+
+    /// The distance of the curve's last control point, beyond which the
+    /// curve is constant.
+    #[inline]
+    pub fn max_distance(&self) -> S {
+        self.points[self.points.len() - 1].0
+    }
+
+    /// Bake the curve into a lookup table of `resolution` entries for
+    /// shading on the GPU. The table is indexed uniformly by distance: entry
+    /// `i` holds the value at `distance = max_distance * i / (resolution - 1)`,
+    /// so the last entry holds the value beyond the last control point.
+    pub fn bake(&self, resolution: usize) -> Vec<S> {
+        if resolution < 2 {
+            return vec![self.evaluate(S::zero()); resolution];
+        }
+
+        let max_distance = self.max_distance();
+        let last: S = num_traits::cast(resolution - 1).unwrap();
+        (0..resolution)
+            .map(|i| {
+                let i_scalar: S = num_traits::cast(i).unwrap();
+                self.evaluate(max_distance * (i_scalar / last))
+            })
+            .collect()
+    }
+
+    /// The distance from the light beyond which the curve stays below
+    /// `threshold`. The curve is infinite in range if its last control point
+    /// is at least `threshold`. Overshoot of a Catmull-Rom curve between
+    /// control points that both lie below `threshold` is ignored.
+    pub fn range(&self, threshold: S) -> S {
+        let last = self.points.len() - 1;
+        if self.points[last].1 >= threshold {
+            return S::infinity();
+        }
+
+        match self.points.iter().rposition(|point| point.1 >= threshold) {
+            Some(index) => self.points[index + 1].0,
+            None => S::zero(),
+        }
+    }
+}
+
+impl<S> fmt::Display for FalloffCurve<S> where S: ScalarFloat + fmt::Display {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(formatter, "{}", self.interpolation.name())?;
+        for (distance, value) in self.points.iter() {
+            writeln!(formatter, "{} {}", distance, value)?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,148 @@
+use cglinalg::{
+    ScalarFloat,
+};
+use crate::{
+    IlluminationModel,
+    Light,
+};
+
+
+/// A fixed capacity array of lights stored inline without heap allocation.
+///
+/// The capacity `N` is a compile time constant, which makes the array a
+/// natural match for fixed size `MAX_LIGHTS` arrays in shaders. Pushing into
+/// a full array fails and hands the light back to the caller instead of
+/// growing the array.
+pub struct FixedLightArray<S, M, const N: usize> {
+    slots: [Option<Light<S, M>>; N],
+    len: usize,
+}
+
+impl<S, M, const N: usize> FixedLightArray<S, M, N>
+    where S: ScalarFloat,
+          M: IlluminationModel,
+{
+    /// The maximum number of lights the array can hold.
+    pub const CAPACITY: usize = N;
+
+    /// Construct a new empty light array.
+    #[inline]
+    pub fn new() -> Self {
+        FixedLightArray {
+            slots: core::array::from_fn(|_| None),
+            len: 0,
+        }
+    }
+
+    /// Append a light to the end of the array. If the array is full, the
+    /// light is returned in the error.
+    #[inline]
+    pub fn try_push(&mut self, light: Light<S, M>) -> Result<(), Light<S, M>> {
+        if self.len >= N {
+            return Err(light);
+        }
+
+        self.slots[self.len] = Some(light);
+        self.len += 1;
+
+        Ok(())
+    }
+
+    /// Remove the last light from the array.
+    #[inline]
+    pub fn pop(&mut self) -> Option<Light<S, M>> {
+        if self.len == 0 {
+            return None;
+        }
+
+        self.len -= 1;
+        self.slots[self.len].take()
+    }
+
+    /// Remove the light at `index`, replacing it with the last light in the
+    /// array. This does not preserve the order of the lights.
+    pub fn swap_remove(&mut self, index: usize) -> Option<Light<S, M>> {
+        if index >= self.len {
+            return None;
+        }
+
+        self.len -= 1;
+        self.slots.swap(index, self.len);
+        self.slots[self.len].take()
+    }
+
+    /// Remove every light from the array.
+    #[inline]
+    pub fn clear(&mut self) {
+        for slot in self.slots[..self.len].iter_mut() {
+            *slot = None;
+        }
+        self.len = 0;
+    }
+
+    /// Get the light at `index`.
+    #[inline]
+    pub fn get(&self, index: usize) -> Option<&Light<S, M>> {
+        if index < self.len {
+            self.slots[index].as_ref()
+        } else {
+            None
+        }
+    }
+
+    /// Get a mutable reference to the light at `index`.
+    #[inline]
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut Light<S, M>> {
+        if index < self.len {
+            self.slots[index].as_mut()
+        } else {
+            None
+        }
+    }
+
+    /// Iterate over the lights in the array.
+    #[inline]
+    pub fn iter(&self) -> impl Iterator<Item = &Light<S, M>> {
+        self.slots[..self.len].iter().filter_map(Option::as_ref)
+    }
+
+    /// Iterate mutably over the lights in the array.
+    #[inline]
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut Light<S, M>> {
+        self.slots[..self.len].iter_mut().filter_map(Option::as_mut)
+    }
+
+    /// The number of lights in the array.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Determine whether the array holds no lights.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Determine whether the array is at capacity.
+    #[inline]
+    pub fn is_full(&self) -> bool {
+        self.len == N
+    }
+
+    /// The maximum number of lights the array can hold.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        N
+    }
+}
+
+impl<S, M, const N: usize> Default for FixedLightArray<S, M, N>
+    where S: ScalarFloat,
+          M: IlluminationModel,
+{
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,293 @@
+use cglinalg::{
+    Magnitude,
+    Vector3,
+    Quaternion,
+    ScalarFloat,
+};
+use crate::{
+    DirectionalLight,
+    DirectionalLightModelSpec,
+    IlluminationModel,
+    Light,
+    LightAttitude,
+    PointLight,
+    PointLightModelSpec,
+    SpotLight,
+    SpotLightModelSpec,
+    ViewMatrixPolicy,
+};
+use crate::attenuation::Attenuation;
+use crate::layers::LightLayers;
+use crate::sampling::LightJitter;
+
+
+/// The default outer cone angle of a glTF spotlight, a quarter of pi.
+#[inline]
+fn default_outer_cone_angle<S: ScalarFloat>() -> S {
+    num_traits::cast(core::f64::consts::FRAC_PI_4).unwrap()
+}
+
+#[cfg(feature = "serde")]
+#[inline]
+fn default_color<S: ScalarFloat>() -> [S; 3] {
+    [S::one(); 3]
+}
+
+#[cfg(feature = "serde")]
+#[inline]
+fn default_intensity<S: ScalarFloat>() -> S {
+    S::one()
+}
+
+/// The cone of a glTF spotlight. The angles are measured in radians from the
+/// spotlight's axis, with `0 <= inner_cone_angle < outer_cone_angle <= pi / 2`.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+#[cfg_attr(feature = "serde", serde(bound(serialize = "S: serde::Serialize", deserialize = "S: ScalarFloat + serde::Deserialize<'de>")))]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct GltfSpot<S> {
+    #[cfg_attr(feature = "serde", serde(default = "num_traits::Zero::zero"))]
+    pub inner_cone_angle: S,
+    #[cfg_attr(feature = "serde", serde(default = "default_outer_cone_angle"))]
+    pub outer_cone_angle: S,
+}
+
+impl<S> Default for GltfSpot<S> where S: ScalarFloat {
+    #[inline]
+    fn default() -> GltfSpot<S> {
+        GltfSpot {
+            inner_cone_angle: S::zero(),
+            outer_cone_angle: default_outer_cone_angle(),
+        }
+    }
+}
+
+/// The type of a glTF punctual light.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "type", rename_all = "lowercase"))]
+#[cfg_attr(feature = "serde", serde(bound(serialize = "S: serde::Serialize", deserialize = "S: ScalarFloat + serde::Deserialize<'de>")))]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum GltfLightKind<S> {
+    Directional,
+    Point,
+    Spot {
+        spot: GltfSpot<S>,
+    },
+}
+
+/// A light of the glTF `KHR_lights_punctual` extension, one entry of the
+/// extension's `lights` array.
+///
+/// glTF describes a light by a linear RGB color and an intensity, in candela
+/// for point lights and spotlights and in lux for directional lights, and
+/// makes positional lights fall off with the inverse square law, windowed to
+/// zero at `range` if the light has one. The light shines down the local
+/// **negative z-axis** of the node it is attached to, which is also the
+/// forward axis of the lights in this crate.
+///
+/// With the `serde` feature the light serializes to and from the JSON of
+/// the extension, with the defaults of the specification for missing
+/// properties.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound(serialize = "S: serde::Serialize", deserialize = "S: ScalarFloat + serde::Deserialize<'de>")))]
+#[derive(Clone, Debug, PartialEq)]
+pub struct GltfLight<S> {
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub name: Option<String>,
+    #[cfg_attr(feature = "serde", serde(default = "default_color"))]
+    pub color: [S; 3],
+    #[cfg_attr(feature = "serde", serde(default = "default_intensity"))]
+    pub intensity: S,
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub kind: GltfLightKind<S>,
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub range: Option<S>,
+}
+
+/// The translation and rotation of the node a glTF light is attached to, in
+/// world space. Lights ignore the node's scale.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct GltfNodeTransform<S> {
+    pub translation: [S; 3],
+    /// The node's unit rotation quaternion in glTF order, `[x, y, z, w]`.
+    pub rotation: [S; 4],
+}
+
+/// A light imported from glTF.
+pub enum GltfImportedLight<S> {
+    Directional(DirectionalLight<S>),
+    Point(PointLight<S>),
+    Spot(SpotLight<S>),
+}
+
+/// Split a color into a glTF color with components in the unit interval and
+/// an intensity.
+fn split_color<S: ScalarFloat>(color: &Vector3<S>) -> ([S; 3], S) {
+    let intensity = color.x.max(color.y).max(color.z).max(S::zero());
+    if intensity > S::zero() {
+        ([color.x / intensity, color.y / intensity, color.z / intensity], intensity)
+    } else {
+        ([S::one(); 3], S::zero())
+    }
+}
+
+/// The glTF range of a light's attenuation, if it has a window.
+#[inline]
+fn attenuation_range<S: ScalarFloat>(attenuation: &Attenuation<S>) -> Option<S> {
+    match *attenuation {
+        Attenuation::Windowed { range, .. } | Attenuation::WindowedInverseSquare { range } => Some(range),
+        _ => None,
+    }
+}
+
+/// The node transform of a light's position and orientation.
+#[inline]
+fn node_transform<S, M>(light: &Light<S, M>) -> GltfNodeTransform<S>
+    where S: ScalarFloat,
+          M: IlluminationModel,
+{
+    let position = light.attitude.position;
+    let orientation = light.attitude.orientation;
+
+    GltfNodeTransform {
+        translation: [position.x, position.y, position.z],
+        rotation: [orientation.v.x, orientation.v.y, orientation.v.z, orientation.s],
+    }
+}
+
+/// Construct a light at a node with default settings.
+fn light_at_node<S, M>(spec: &M::Spec, node: &GltfNodeTransform<S>) -> Light<S, M>
+    where S: ScalarFloat,
+          M: IlluminationModel,
+{
+    let [x, y, z] = node.translation;
+    let [qx, qy, qz, qw] = node.rotation;
+    let orientation = Quaternion::new(qw, qx, qy, qz).normalize();
+
+    Light {
+        model: M::from_spec(spec),
+        attitude: LightAttitude::from_parts(Vector3::new(x, y, z), orientation, ViewMatrixPolicy::Eager),
+        jitter: LightJitter::zero(),
+        exposure_compensation: S::zero(),
+        layers: LightLayers::DEFAULT,
+        diffuse_factor: S::one(),
+        specular_factor: S::one(),
+        shadow_settings: None,
+    }
+}
+
+impl<S> GltfLight<S> where S: ScalarFloat {
+    /// Import the light attached to a node with the given world space
+    /// transform.
+    ///
+    /// The Phong-style models of this crate have no physical units, so the
+    /// light's color times its intensity becomes the diffuse and specular
+    /// colors, one candela or lux per unit of color, with no ambient color.
+    /// Positional lights fall off with the inverse square law, windowed at
+    /// the light's range if it has one. Spotlights blend smoothly between
+    /// their inner and outer cones, which approximates the falloff glTF
+    /// recommends.
+    pub fn to_light(&self, node: &GltfNodeTransform<S>) -> GltfImportedLight<S> {
+        let color = Vector3::new(self.color[0], self.color[1], self.color[2]) * self.intensity;
+        let ambient = Vector3::zero();
+        let attenuation = match self.range {
+            Some(range) => Attenuation::WindowedInverseSquare { range: range },
+            None => Attenuation::InverseSquare,
+        };
+
+        match self.kind {
+            GltfLightKind::Directional => {
+                let spec = DirectionalLightModelSpec::new(ambient, color, color);
+                GltfImportedLight::Directional(light_at_node(&spec, node))
+            }
+            GltfLightKind::Point => {
+                let spec = PointLightModelSpec::new(ambient, color, color).with_attenuation(attenuation);
+                GltfImportedLight::Point(light_at_node(&spec, node))
+            }
+            GltfLightKind::Spot { spot } => {
+                let spec = SpotLightModelSpec::new(
+                    spot.inner_cone_angle.cos(),
+                    spot.outer_cone_angle.cos(),
+                    ambient,
+                    color,
+                    color,
+                    S::one(),
+                    S::zero(),
+                    S::zero(),
+                ).with_attenuation(attenuation);
+                GltfImportedLight::Spot(light_at_node(&spec, node))
+            }
+        }
+    }
+}
+
+impl<S> PointLight<S> where S: ScalarFloat {
+    /// Export the light to glTF, with the transform of the node to attach it
+    /// to. The diffuse color becomes the glTF color and intensity, one
+    /// candela per unit of color. The ambient and specular colors, shadow
+    /// tint, volumetric parameters, and polarity are not represented, and
+    /// attenuation other than the inverse square law, including a falloff
+    /// curve, is approximated by it.
+    pub fn to_gltf(&self) -> (GltfLight<S>, GltfNodeTransform<S>) {
+        let model = self.model();
+        let (color, intensity) = split_color(&model.diffuse);
+        let light = GltfLight {
+            name: None,
+            color: color,
+            intensity: intensity,
+            kind: GltfLightKind::Point,
+            range: attenuation_range(&model.attenuation),
+        };
+
+        (light, node_transform(self))
+    }
+}
+
+impl<S> SpotLight<S> where S: ScalarFloat {
+    /// Export the light to glTF, with the transform of the node to attach it
+    /// to. The cone angles are clamped to the range glTF allows, and a spot
+    /// exponent, falloff curve, or angular profile is approximated by the
+    /// smooth falloff between the cones. Otherwise the light is exported
+    /// like a point light.
+    pub fn to_gltf(&self) -> (GltfLight<S>, GltfNodeTransform<S>) {
+        let model = self.model();
+        let one = S::one();
+        let half_pi: S = num_traits::cast(core::f64::consts::FRAC_PI_2).unwrap();
+        let (color, intensity) = split_color(&model.diffuse);
+        let outer_cone_angle = model.outer_cutoff.max(-one).min(one).acos().min(half_pi);
+        let inner_cone_angle = model.cutoff.max(-one).min(one).acos().min(outer_cone_angle);
+        let light = GltfLight {
+            name: None,
+            color: color,
+            intensity: intensity,
+            kind: GltfLightKind::Spot {
+                spot: GltfSpot {
+                    inner_cone_angle: inner_cone_angle,
+                    outer_cone_angle: outer_cone_angle,
+                },
+            },
+            range: attenuation_range(&model.attenuation),
+        };
+
+        (light, node_transform(self))
+    }
+}
+
+impl<S> DirectionalLight<S> where S: ScalarFloat {
+    /// Export the light to glTF, with the transform of the node to attach it
+    /// to. The diffuse color becomes the glTF color and intensity, one lux
+    /// per unit of color.
+    pub fn to_gltf(&self) -> (GltfLight<S>, GltfNodeTransform<S>) {
+        let (color, intensity) = split_color(&self.model().diffuse);
+        let light = GltfLight {
+            name: None,
+            color: color,
+            intensity: intensity,
+            kind: GltfLightKind::Directional,
+            range: None,
+        };
+
+        (light, node_transform(self))
+    }
+}
@@ -0,0 +1,210 @@
+use cglinalg::{
+    Vector3,
+    ScalarFloat,
+};
+use crate::{
+    ConeFalloff,
+    DirectionalLight,
+    PointLight,
+    SpotLight,
+};
+use crate::attenuation::Attenuation;
+
+
+/// The attenuation kind of a light without distance attenuation.
+pub const ATTENUATION_NONE: u32 = 0;
+/// The attenuation kind of the constant-linear-quadratic attenuation.
+pub const ATTENUATION_POLYNOMIAL: u32 = 1;
+/// The attenuation kind of the inverse square law.
+pub const ATTENUATION_INVERSE_SQUARE: u32 = 2;
+/// The attenuation kind of the constant-linear-quadratic attenuation with a
+/// linear window.
+pub const ATTENUATION_WINDOWED: u32 = 3;
+/// The attenuation kind of the inverse square law with a smooth window.
+pub const ATTENUATION_WINDOWED_INVERSE_SQUARE: u32 = 4;
+
+#[inline]
+fn to_f32<S: ScalarFloat>(value: S) -> f32 {
+    num_traits::cast(value).unwrap()
+}
+
+#[inline]
+fn vector_to_f32<S: ScalarFloat>(vector: &Vector3<S>) -> [f32; 3] {
+    [to_f32(vector.x), to_f32(vector.y), to_f32(vector.z)]
+}
+
+/// The attenuation kind and its parameters: the constant, linear, and
+/// quadratic coefficients and the window range.
+pub(crate) fn attenuation_to_f32<S: ScalarFloat>(attenuation: &Attenuation<S>) -> (u32, [f32; 4]) {
+    match *attenuation {
+        Attenuation::None => (ATTENUATION_NONE, [1.0, 0.0, 0.0, 0.0]),
+        Attenuation::Polynomial(coefficients) => (
+            ATTENUATION_POLYNOMIAL,
+            [to_f32(coefficients.constant), to_f32(coefficients.linear), to_f32(coefficients.quadratic), 0.0],
+        ),
+        Attenuation::InverseSquare => (ATTENUATION_INVERSE_SQUARE, [0.0, 0.0, 1.0, 0.0]),
+        Attenuation::Windowed { coefficients, range } => (
+            ATTENUATION_WINDOWED,
+            [to_f32(coefficients.constant), to_f32(coefficients.linear), to_f32(coefficients.quadratic), to_f32(range)],
+        ),
+        Attenuation::WindowedInverseSquare { range } => (
+            ATTENUATION_WINDOWED_INVERSE_SQUARE,
+            [0.0, 0.0, 1.0, to_f32(range)],
+        ),
+    }
+}
+
+/// A point light in single precision with a fixed memory layout, for copying
+/// into GPU buffers. The fields are tightly packed with no padding. The
+/// colors include the light's exposure compensation, and the diffuse and
+/// specular colors its diffuse and specular factors. Falloff curves are not
+/// represented: the attenuation is the light's base attenuation, and shaders
+/// that need the curve sample a table from `FalloffCurve::bake` instead.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct GpuPointLight {
+    pub position: [f32; 3],
+    pub ambient: [f32; 3],
+    pub diffuse: [f32; 3],
+    pub specular: [f32; 3],
+    pub shadow_tint: [f32; 3],
+    /// The constant, linear, and quadratic attenuation coefficients and the
+    /// window range, as selected by `attenuation_kind`.
+    pub attenuation: [f32; 4],
+    /// One of the `ATTENUATION_*` constants.
+    pub attenuation_kind: u32,
+    /// The sign of the light's contribution: `1` adds light and `-1`
+    /// subtracts it.
+    pub polarity: f32,
+}
+
+/// A spotlight in single precision with a fixed memory layout, for copying
+/// into GPU buffers. The fields are tightly packed with no padding. The
+/// colors include the light's exposure compensation, and the diffuse and
+/// specular colors its diffuse and specular factors. Falloff curves and
+/// angular profiles are not represented: the attenuation is the light's base
+/// attenuation, and shaders that need them sample tables from
+/// `FalloffCurve::bake` and `AngularProfile::bake` instead.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct GpuSpotLight {
+    pub position: [f32; 3],
+    /// The unit direction of the spotlight's axis.
+    pub direction: [f32; 3],
+    pub ambient: [f32; 3],
+    pub diffuse: [f32; 3],
+    pub specular: [f32; 3],
+    pub shadow_tint: [f32; 3],
+    /// The cosine of the angle to the edge of the inner cone.
+    pub cutoff: f32,
+    /// The cosine of the angle to the edge of the outer cone.
+    pub outer_cutoff: f32,
+    /// The spot exponent, or a negative number for the smooth falloff
+    /// between the inner and outer cones.
+    pub cone_exponent: f32,
+    /// The constant, linear, and quadratic attenuation coefficients and the
+    /// window range, as selected by `attenuation_kind`.
+    pub attenuation: [f32; 4],
+    /// One of the `ATTENUATION_*` constants.
+    pub attenuation_kind: u32,
+    /// The sign of the light's contribution: `1` adds light and `-1`
+    /// subtracts it.
+    pub polarity: f32,
+}
+
+/// A directional light in single precision with a fixed memory layout, for
+/// copying into GPU buffers. The fields are tightly packed with no padding.
+/// The colors include the light's exposure compensation, and the diffuse and
+/// specular colors its diffuse and specular factors.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct GpuDirectionalLight {
+    /// The unit direction the light's rays travel in.
+    pub direction: [f32; 3],
+    pub ambient: [f32; 3],
+    pub diffuse: [f32; 3],
+    pub specular: [f32; 3],
+    pub shadow_tint: [f32; 3],
+}
+
+#[cfg(feature = "bytemuck")]
+unsafe impl bytemuck::Zeroable for GpuPointLight {}
+
+#[cfg(feature = "bytemuck")]
+unsafe impl bytemuck::Pod for GpuPointLight {}
+
+#[cfg(feature = "bytemuck")]
+unsafe impl bytemuck::Zeroable for GpuSpotLight {}
+
+#[cfg(feature = "bytemuck")]
+unsafe impl bytemuck::Pod for GpuSpotLight {}
+
+#[cfg(feature = "bytemuck")]
+unsafe impl bytemuck::Zeroable for GpuDirectionalLight {}
+
+#[cfg(feature = "bytemuck")]
+unsafe impl bytemuck::Pod for GpuDirectionalLight {}
+
+impl<S> PointLight<S> where S: ScalarFloat {
+    /// Convert the light to its GPU representation.
+    pub fn to_gpu(&self) -> GpuPointLight {
+        let model = self.model();
+        let exposure = self.exposure_multiplier();
+        let (attenuation_kind, attenuation) = attenuation_to_f32(&model.attenuation);
+
+        GpuPointLight {
+            position: vector_to_f32(&self.position()),
+            ambient: vector_to_f32(&(model.ambient * exposure)),
+            diffuse: vector_to_f32(&(model.diffuse * (exposure * self.diffuse_factor()))),
+            specular: vector_to_f32(&(model.specular * (exposure * self.specular_factor()))),
+            shadow_tint: vector_to_f32(&model.shadow_tint),
+            attenuation: attenuation,
+            attenuation_kind: attenuation_kind,
+            polarity: to_f32(model.polarity.sign::<S>()),
+        }
+    }
+}
+
+impl<S> SpotLight<S> where S: ScalarFloat {
+    /// Convert the light to its GPU representation.
+    pub fn to_gpu(&self) -> GpuSpotLight {
+        let model = self.model();
+        let exposure = self.exposure_multiplier();
+        let (attenuation_kind, attenuation) = attenuation_to_f32(&model.attenuation);
+        let cone_exponent = match model.cone_falloff {
+            ConeFalloff::Smoothstep => -1.0,
+            ConeFalloff::Exponent(exponent) => to_f32(exponent),
+        };
+
+        GpuSpotLight {
+            position: vector_to_f32(&self.position()),
+            direction: vector_to_f32(&self.forward_axis()),
+            ambient: vector_to_f32(&(model.ambient * exposure)),
+            diffuse: vector_to_f32(&(model.diffuse * (exposure * self.diffuse_factor()))),
+            specular: vector_to_f32(&(model.specular * (exposure * self.specular_factor()))),
+            shadow_tint: vector_to_f32(&model.shadow_tint),
+            cutoff: to_f32(model.cutoff),
+            outer_cutoff: to_f32(model.outer_cutoff),
+            cone_exponent: cone_exponent,
+            attenuation: attenuation,
+            attenuation_kind: attenuation_kind,
+            polarity: to_f32(model.polarity.sign::<S>()),
+        }
+    }
+}
+
+impl<S> DirectionalLight<S> where S: ScalarFloat {
+    /// Convert the light to its GPU representation.
+    pub fn to_gpu(&self) -> GpuDirectionalLight {
+        let model = self.model();
+        let exposure = self.exposure_multiplier();
+
+        GpuDirectionalLight {
+            direction: vector_to_f32(&self.direction()),
+            ambient: vector_to_f32(&(model.ambient * exposure)),
+            diffuse: vector_to_f32(&(model.diffuse * (exposure * self.diffuse_factor()))),
+            specular: vector_to_f32(&(model.specular * (exposure * self.specular_factor()))),
+            shadow_tint: vector_to_f32(&model.shadow_tint),
+        }
+    }
+}
@@ -0,0 +1,232 @@
+use cglinalg::{
+    ScalarFloat,
+};
+use crate::{
+    DirectionalLight,
+    PointLight,
+    SpotLight,
+};
+use crate::gpu::{
+    GpuDirectionalLight,
+    GpuPointLight,
+    GpuSpotLight,
+};
+use half::f16;
+use core::mem;
+
+
+#[inline]
+fn to_f16_bits(value: f32) -> u16 {
+    f16::from_f32(value).to_bits()
+}
+
+#[inline]
+fn pack(vector: &[f32; 3], w: f32) -> [u16; 4] {
+    [to_f16_bits(vector[0]), to_f16_bits(vector[1]), to_f16_bits(vector[2]), to_f16_bits(w)]
+}
+
+/// A point light with its colors and attenuation in half precision, for
+/// bandwidth constrained renderers. The position stays in single precision,
+/// since half precision cannot address a scene of any size. Each `[u16; 4]`
+/// holds the bits of four IEEE 754 binary16 values, which shaders unpack
+/// two at a time, e.g. with `unpackHalf2x16` in GLSL. The position is
+/// declared as an array of floats, so the struct needs only eight byte
+/// alignment in a std430 buffer. It matches the GLSL declaration
+///
+/// ```glsl
+/// struct PointLight {
+///     float position[3];
+///     uint attenuation_kind;
+///     uvec2 ambient;      // rgb, polarity
+///     uvec2 diffuse;      // rgb, unused
+///     uvec2 specular;     // rgb, unused
+///     uvec2 shadow_tint;  // rgb, unused
+///     uvec2 attenuation;
+/// };
+/// ```
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct HalfPointLight {
+    pub position: [f32; 3],
+    /// One of the `gpu::ATTENUATION_*` constants.
+    pub attenuation_kind: u32,
+    /// The ambient color, with the polarity in the last component.
+    pub ambient: [u16; 4],
+    pub diffuse: [u16; 4],
+    pub specular: [u16; 4],
+    pub shadow_tint: [u16; 4],
+    /// The constant, linear, and quadratic attenuation coefficients and the
+    /// window range, as selected by `attenuation_kind`.
+    pub attenuation: [u16; 4],
+}
+
+/// A spotlight with its direction, colors, and attenuation in half
+/// precision. The position and the cutoff cosines stay in single
+/// precision: near one, half precision cosines are too coarse to describe
+/// narrow cones. It matches the GLSL declaration
+///
+/// ```glsl
+/// struct SpotLight {
+///     float position[3];
+///     uint attenuation_kind;
+///     uvec2 direction;    // xyz, cone_exponent
+///     float cutoff;
+///     float outer_cutoff;
+///     uvec2 ambient;      // rgb, polarity
+///     uvec2 diffuse;      // rgb, unused
+///     uvec2 specular;     // rgb, unused
+///     uvec2 shadow_tint;  // rgb, unused
+///     uvec2 attenuation;
+/// };
+/// ```
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct HalfSpotLight {
+    pub position: [f32; 3],
+    /// One of the `gpu::ATTENUATION_*` constants.
+    pub attenuation_kind: u32,
+    /// The unit direction of the spotlight's axis, with the cone exponent in
+    /// the last component.
+    pub direction: [u16; 4],
+    /// The cosine of the angle to the edge of the inner cone.
+    pub cutoff: f32,
+    /// The cosine of the angle to the edge of the outer cone.
+    pub outer_cutoff: f32,
+    /// The ambient color, with the polarity in the last component.
+    pub ambient: [u16; 4],
+    pub diffuse: [u16; 4],
+    pub specular: [u16; 4],
+    pub shadow_tint: [u16; 4],
+    /// The constant, linear, and quadratic attenuation coefficients and the
+    /// window range, as selected by `attenuation_kind`.
+    pub attenuation: [u16; 4],
+}
+
+/// A directional light entirely in half precision. It matches the GLSL
+/// declaration
+///
+/// ```glsl
+/// struct DirectionalLight {
+///     uvec2 direction;    // xyz, unused
+///     uvec2 ambient;      // rgb, unused
+///     uvec2 diffuse;      // rgb, unused
+///     uvec2 specular;     // rgb, unused
+///     uvec2 shadow_tint;  // rgb, unused
+/// };
+/// ```
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct HalfDirectionalLight {
+    /// The unit direction the light's rays travel in.
+    pub direction: [u16; 4],
+    pub ambient: [u16; 4],
+    pub diffuse: [u16; 4],
+    pub specular: [u16; 4],
+    pub shadow_tint: [u16; 4],
+}
+
+const _: () = {
+    assert!(mem::offset_of!(HalfPointLight, ambient) == 16);
+    assert!(mem::offset_of!(HalfPointLight, attenuation) == 48);
+    assert!(mem::size_of::<HalfPointLight>() == 56);
+
+    assert!(mem::offset_of!(HalfSpotLight, direction) == 16);
+    assert!(mem::offset_of!(HalfSpotLight, cutoff) == 24);
+    assert!(mem::offset_of!(HalfSpotLight, ambient) == 32);
+    assert!(mem::offset_of!(HalfSpotLight, attenuation) == 64);
+    assert!(mem::size_of::<HalfSpotLight>() == 72);
+
+    assert!(mem::size_of::<HalfDirectionalLight>() == 40);
+};
+
+#[cfg(feature = "bytemuck")]
+unsafe impl bytemuck::Zeroable for HalfPointLight {}
+
+#[cfg(feature = "bytemuck")]
+unsafe impl bytemuck::Pod for HalfPointLight {}
+
+#[cfg(feature = "bytemuck")]
+unsafe impl bytemuck::Zeroable for HalfSpotLight {}
+
+#[cfg(feature = "bytemuck")]
+unsafe impl bytemuck::Pod for HalfSpotLight {}
+
+#[cfg(feature = "bytemuck")]
+unsafe impl bytemuck::Zeroable for HalfDirectionalLight {}
+
+#[cfg(feature = "bytemuck")]
+unsafe impl bytemuck::Pod for HalfDirectionalLight {}
+
+impl From<GpuPointLight> for HalfPointLight {
+    #[inline]
+    fn from(light: GpuPointLight) -> HalfPointLight {
+        let attenuation = light.attenuation;
+
+        HalfPointLight {
+            position: light.position,
+            attenuation_kind: light.attenuation_kind,
+            ambient: pack(&light.ambient, light.polarity),
+            diffuse: pack(&light.diffuse, 0.0),
+            specular: pack(&light.specular, 0.0),
+            shadow_tint: pack(&light.shadow_tint, 0.0),
+            attenuation: pack(&[attenuation[0], attenuation[1], attenuation[2]], attenuation[3]),
+        }
+    }
+}
+
+impl From<GpuSpotLight> for HalfSpotLight {
+    #[inline]
+    fn from(light: GpuSpotLight) -> HalfSpotLight {
+        let attenuation = light.attenuation;
+
+        HalfSpotLight {
+            position: light.position,
+            attenuation_kind: light.attenuation_kind,
+            direction: pack(&light.direction, light.cone_exponent),
+            cutoff: light.cutoff,
+            outer_cutoff: light.outer_cutoff,
+            ambient: pack(&light.ambient, light.polarity),
+            diffuse: pack(&light.diffuse, 0.0),
+            specular: pack(&light.specular, 0.0),
+            shadow_tint: pack(&light.shadow_tint, 0.0),
+            attenuation: pack(&[attenuation[0], attenuation[1], attenuation[2]], attenuation[3]),
+        }
+    }
+}
+
+impl From<GpuDirectionalLight> for HalfDirectionalLight {
+    #[inline]
+    fn from(light: GpuDirectionalLight) -> HalfDirectionalLight {
+        HalfDirectionalLight {
+            direction: pack(&light.direction, 0.0),
+            ambient: pack(&light.ambient, 0.0),
+            diffuse: pack(&light.diffuse, 0.0),
+            specular: pack(&light.specular, 0.0),
+            shadow_tint: pack(&light.shadow_tint, 0.0),
+        }
+    }
+}
+
+impl<S> PointLight<S> where S: ScalarFloat {
+    /// Convert the light to its half precision representation.
+    #[inline]
+    pub fn to_half(&self) -> HalfPointLight {
+        HalfPointLight::from(self.to_gpu())
+    }
+}
+
+impl<S> SpotLight<S> where S: ScalarFloat {
+    /// Convert the light to its half precision representation.
+    #[inline]
+    pub fn to_half(&self) -> HalfSpotLight {
+        HalfSpotLight::from(self.to_gpu())
+    }
+}
+
+impl<S> DirectionalLight<S> where S: ScalarFloat {
+    /// Convert the light to its half precision representation.
+    #[inline]
+    pub fn to_half(&self) -> HalfDirectionalLight {
+        HalfDirectionalLight::from(self.to_gpu())
+    }
+}
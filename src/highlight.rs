@@ -0,0 +1,184 @@
+use cglinalg::{
+    Magnitude,
+    Vector3,
+    Radians,
+    ScalarFloat,
+};
+use crate::{
+    IlluminationModel,
+    Light,
+    PointLightModel,
+    SphereLightModel,
+    SpotLightModel,
+    TubeLightModel,
+};
+use crate::pbr::PbrLightModel;
+use crate::representative_point::{
+    closest_point_on_segment,
+    closest_point_on_sphere,
+};
+
+
+/// The shape of the emitting surface of a light, centered on the light's
+/// position.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum EmitterShape<S> {
+    /// An infinitesimally small emitter.
+    Point,
+    /// A sphere with the given radius.
+    Sphere { radius: S },
+    /// A tube of the given length and radius, lying along the light's
+    /// right axis.
+    Tube { length: S, radius: S },
+}
+
+/// A lighting model with a known emitter shape.
+pub trait EmitterExtent<S> {
+    /// The shape of the light's emitting surface.
+    fn emitter_shape(&self) -> EmitterShape<S>;
+}
+
+impl<S> EmitterExtent<S> for PointLightModel<S> where S: ScalarFloat {
+    #[inline]
+    fn emitter_shape(&self) -> EmitterShape<S> {
+        EmitterShape::Point
+    }
+}
+
+impl<S> EmitterExtent<S> for SphereLightModel<S> where S: ScalarFloat {
+    #[inline]
+    fn emitter_shape(&self) -> EmitterShape<S> {
+        EmitterShape::Sphere {
+            radius: self.radius,
+        }
+    }
+}
+
+impl<S> EmitterExtent<S> for TubeLightModel<S> where S: ScalarFloat {
+    #[inline]
+    fn emitter_shape(&self) -> EmitterShape<S> {
+        EmitterShape::Tube {
+            length: self.length,
+            radius: self.radius,
+        }
+    }
+}
+
+impl<S> EmitterExtent<S> for PbrLightModel<S> where S: ScalarFloat {
+    #[inline]
+    fn emitter_shape(&self) -> EmitterShape<S> {
+        EmitterShape::Point
+    }
+}
+
+impl<S> EmitterExtent<S> for SpotLightModel<S> where S: ScalarFloat {
+    #[inline]
+    fn emitter_shape(&self) -> EmitterShape<S> {
+        EmitterShape::Point
+    }
+}
+
+/// The location of a light's specular highlight as seen from a surface point.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct SpecularHighlight<S> {
+    /// The unit view direction mirrored about the surface normal. A light
+    /// seen along this direction puts the center of its highlight on the
+    /// surface point.
+    pub reflection: Vector3<S>,
+    /// The point on the light's emitter closest to the reflection ray, in
+    /// world space.
+    pub light_point: Vector3<S>,
+    /// The angle between the reflection direction and the direction to
+    /// `light_point`. The highlight center lies on the surface point when
+    /// the angle is zero.
+    pub angular_offset: Radians<S>,
+    /// Whether the reflection ray hits the light's emitter. Punctual lights
+    /// have no extent and never contain the reflection ray.
+    pub within_extent: bool,
+}
+
+impl<S, M> Light<S, M>
+    where S: ScalarFloat,
+          M: IlluminationModel + EmitterExtent<S>,
+{
+    /// Locate the light's specular highlight for a surface point with unit
+    /// normal `normal`, seen along the unit direction `view` from the surface
+    /// point toward the viewer. The result is `None` when the viewer is
+    /// behind the surface.
+    pub fn specular_highlight(
+        &self,
+        position: &Vector3<S>,
+        normal: &Vector3<S>,
+        view: &Vector3<S>) -> Option<SpecularHighlight<S>>
+    {
+        let reflection = reflect(normal, view)?;
+        let to_center = self.position() - position;
+        let (to_point, within_extent) = match self.model().emitter_shape() {
+            EmitterShape::Point => (to_center, false),
+            EmitterShape::Sphere { radius } => {
+                let to_point = closest_point_on_sphere(&to_center, radius, &reflection);
+
+                (to_point, ray_distance(&to_center, &reflection) <= radius)
+            }
+            EmitterShape::Tube { length, radius } => {
+                let two = S::one() + S::one();
+                let half_segment = self.right_axis() * (length / two);
+                let to_start = to_center - half_segment;
+                let segment = half_segment * two;
+                let to_segment_point = closest_point_on_segment(&to_start, &segment, &reflection);
+                let to_point = closest_point_on_sphere(&to_segment_point, radius, &reflection);
+
+                (to_point, ray_distance(&to_segment_point, &reflection) <= radius)
+            }
+        };
+        let distance = to_point.magnitude();
+        let cos_offset = if distance > S::zero() {
+            (to_point.dot(&reflection) / distance).max(-S::one()).min(S::one())
+        } else {
+            S::one()
+        };
+
+        Some(SpecularHighlight {
+            reflection: reflection,
+            light_point: position + to_point,
+            angular_offset: Radians(cos_offset.acos()),
+            within_extent: within_extent,
+        })
+    }
+}
+
+/// Find where to place a light at the given distance from a surface point
+/// so that its specular highlight is centered on the surface point, seen
+/// along the unit direction `view` from the surface point toward the viewer.
+/// The result is `None` when the viewer is behind the surface.
+pub fn highlight_light_position<S>(
+    position: &Vector3<S>,
+    normal: &Vector3<S>,
+    view: &Vector3<S>,
+    distance: S) -> Option<Vector3<S>>
+    where S: ScalarFloat
+{
+    let reflection = reflect(normal, view)?;
+
+    Some(position + reflection * distance)
+}
+
+/// Mirror the view direction about the normal.
+#[inline]
+fn reflect<S: ScalarFloat>(normal: &Vector3<S>, view: &Vector3<S>) -> Option<Vector3<S>> {
+    let n_dot_v = normal.dot(view);
+    if n_dot_v <= S::zero() {
+        return None;
+    }
+    let two = S::one() + S::one();
+
+    Some(normal * (two * n_dot_v) - view)
+}
+
+/// The distance from a point, relative to the ray origin, to a ray.
+#[inline]
+fn ray_distance<S: ScalarFloat>(point: &Vector3<S>, direction: &Vector3<S>) -> S {
+    let t = point.dot(direction).max(S::zero());
+
+    (point - direction * t).magnitude()
+}
@@ -0,0 +1,404 @@
+use cglinalg::{
+    Magnitude,
+    Vector3,
+    ScalarFloat,
+};
+use crate::{
+    Dimmable,
+    IlluminationModel,
+    Light,
+};
+use crate::photometry::Lumens;
+use core::fmt;
+use std::error;
+use std::sync::Arc;
+
+
+/// An error from parsing an IES LM-63 photometric data file.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum IesError {
+    /// The file has no `TILT=` line separating the header from the data.
+    MissingTilt,
+    /// The file uses tilt data, which is not supported.
+    UnsupportedTilt,
+    /// The file ends before all of the values it declares.
+    UnexpectedEnd,
+    /// A value in the data section is not a number.
+    InvalidNumber(String),
+    /// The angles or candela values do not describe a valid photometric web.
+    InvalidWeb,
+}
+
+impl fmt::Display for IesError {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            IesError::MissingTilt => write!(formatter, "missing TILT= line"),
+            IesError::UnsupportedTilt => write!(formatter, "tilt data is not supported"),
+            IesError::UnexpectedEnd => write!(formatter, "unexpected end of photometric data"),
+            IesError::InvalidNumber(token) => write!(formatter, "invalid number `{}`", token),
+            IesError::InvalidWeb => write!(formatter, "invalid photometric web"),
+        }
+    }
+}
+
+impl error::Error for IesError {}
+
+/// The luminous intensity distribution of a luminaire, sampled over a web of
+/// angles, in the type C photometry used by IES files.
+///
+/// The vertical angle is measured from the luminaire's nadir, which is the
+/// light's forward axis, so zero degrees points straight out of the fixture
+/// and 180 degrees points straight back. The horizontal angle is measured
+/// around the forward axis from the light's right axis towards its up axis.
+/// Intensities are interpolated bilinearly between the samples.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PhotometricWeb<S> {
+    /// The vertical angles in degrees, in increasing order.
+    vertical_angles: Vec<S>,
+    /// The horizontal angles in degrees, in increasing order.
+    horizontal_angles: Vec<S>,
+    /// The intensities in candela, for each horizontal angle the intensities
+    /// at every vertical angle.
+    candela: Vec<S>,
+}
+
+impl<S> PhotometricWeb<S> where S: ScalarFloat {
+    /// Construct a photometric web from angles in degrees and intensities in
+    /// candela stored for each horizontal angle in turn. The result is `None`
+    /// if either set of angles is empty or not increasing, or the number of
+    /// intensities does not match the number of angles.
+    ///
+    /// As in IES files, the horizontal angles determine the web's symmetry: a
+    /// single horizontal angle describes a rotationally symmetric luminaire,
+    /// a last angle of 90 or 180 degrees a luminaire symmetric in each
+    /// quadrant or about a plane, and a last angle of 360 degrees a luminaire
+    /// without symmetry.
+    pub fn new(vertical_angles: Vec<S>, horizontal_angles: Vec<S>, candela: Vec<S>) -> Option<PhotometricWeb<S>> {
+        let increasing = |angles: &[S]| angles.windows(2).all(|window| window[0] < window[1]);
+        if vertical_angles.is_empty() || horizontal_angles.is_empty() {
+            return None;
+        }
+        if !increasing(&vertical_angles) || !increasing(&horizontal_angles) {
+            return None;
+        }
+        if candela.len() != vertical_angles.len() * horizontal_angles.len() {
+            return None;
+        }
+
+        Some(PhotometricWeb {
+            vertical_angles: vertical_angles,
+            horizontal_angles: horizontal_angles,
+            candela: candela,
+        })
+    }
+
+    /// Parse the photometric web from the contents of an IES LM-63 file. The
+    /// candela values are scaled by the file's candela multiplier.
+    pub fn parse(source: &str) -> Result<PhotometricWeb<S>, IesError> {
+        let mut lines = source.lines();
+        let tilt = loop {
+            match lines.next() {
+                Some(line) if line.trim_start().starts_with("TILT=") => break line.trim_start(),
+                Some(_) => continue,
+                None => return Err(IesError::MissingTilt),
+            }
+        };
+        if tilt.trim_end() != "TILT=NONE" {
+            return Err(IesError::UnsupportedTilt);
+        }
+
+        let mut tokens = lines.flat_map(|line| line.split(|c: char| c.is_whitespace() || c == ','))
+            .filter(|token| !token.is_empty());
+        let mut next = || -> Result<S, IesError> {
+            let token = tokens.next().ok_or(IesError::UnexpectedEnd)?;
+            let value: f64 = token.parse().map_err(|_| IesError::InvalidNumber(token.to_string()))?;
+
+            num_traits::cast(value).ok_or_else(|| IesError::InvalidNumber(token.to_string()))
+        };
+        let count = |value: S| -> Result<usize, IesError> {
+            if value.fract() != S::zero() {
+                return Err(IesError::InvalidWeb);
+            }
+
+            num_traits::cast(value).ok_or(IesError::InvalidWeb)
+        };
+
+        let _lamp_count = next()?;
+        let _lumens_per_lamp = next()?;
+        let multiplier = next()?;
+        let vertical_count = count(next()?)?;
+        let horizontal_count = count(next()?)?;
+        // Photometric type, units, luminous opening dimensions, ballast
+        // factor, ballast-lamp factor, and input watts.
+        for _ in 0..8 {
+            next()?;
+        }
+        let vertical_angles = (0..vertical_count).map(|_| next()).collect::<Result<Vec<S>, _>>()?;
+        let horizontal_angles = (0..horizontal_count).map(|_| next()).collect::<Result<Vec<S>, _>>()?;
+        let candela_count = vertical_count.checked_mul(horizontal_count).ok_or(IesError::InvalidWeb)?;
+        let candela = (0..candela_count)
+            .map(|_| next().map(|value| value * multiplier))
+            .collect::<Result<Vec<S>, _>>()?;
+
+        PhotometricWeb::new(vertical_angles, horizontal_angles, candela).ok_or(IesError::InvalidWeb)
+    }
+
+    /// The vertical angles of the web in degrees.
+    #[inline]
+    pub fn vertical_angles(&self) -> &[S] {
+        &self.vertical_angles
+    }
+
+    /// The horizontal angles of the web in degrees.
+    #[inline]
+    pub fn horizontal_angles(&self) -> &[S] {
+        &self.horizontal_angles
+    }
+
+    /// The intensities of the web in candela, for each horizontal angle the
+    /// intensities at every vertical angle.
+    #[inline]
+    pub fn candela(&self) -> &[S] {
+        &self.candela
+    }
+
+    /// The luminous flux of the web, the integral of its interpolated
+    /// intensities over the sphere.
+    ///
+    /// The integral is exact for the bilinear interpolation of
+    /// [`evaluate`](#method.evaluate): the mean over the horizontal angles of
+    /// each row of vertical samples is taken over the range the web covers
+    /// by symmetry, and the rows are integrated against the solid angle
+    /// element `sin θ dθ dφ` between adjacent vertical angles.
+    pub fn luminous_flux(&self) -> Lumens<S> {
+        let two_pi: S = num_traits::cast(2_f64 * core::f64::consts::PI).unwrap();
+        let vertical_count = self.vertical_angles.len();
+        let row_mean = |v: usize| self.horizontal_mean(|h| self.candela[h * vertical_count + v]);
+        let flux = (1..vertical_count).fold(S::zero(), |flux, v| {
+            let theta0 = self.vertical_angles[v - 1].to_radians();
+            let theta1 = self.vertical_angles[v].to_radians();
+            let width = theta1 - theta0;
+            // The integrals of `sin θ` weighted by the interpolation
+            // parameter and by its complement across the interval.
+            let upper = (theta1.sin() - theta0.sin()) / width - theta1.cos();
+            let lower = (theta0.cos() - theta1.cos()) - upper;
+
+            flux + row_mean(v - 1) * lower + row_mean(v) * upper
+        });
+
+        Lumens(two_pi * flux)
+    }
+
+    /// The mean over a full turn of the horizontal interpolation of the
+    /// values `value(h)` at each horizontal angle, folded by the web's
+    /// symmetry as in `fold_horizontal`. Angles outside the web's horizontal
+    /// range take the first value, as in `evaluate`.
+    fn horizontal_mean<F: Fn(usize) -> S>(&self, value: F) -> S {
+        let count = self.horizontal_angles.len();
+        if count == 1 {
+            return value(0);
+        }
+
+        let full: S = num_traits::cast(360).unwrap();
+        let half: S = num_traits::cast(180).unwrap();
+        let quarter: S = num_traits::cast(90).unwrap();
+        let one_half: S = num_traits::cast(0.5).unwrap();
+        let first = self.horizontal_angles[0];
+        let last = self.horizontal_angles[count - 1];
+        let range = if last <= quarter { quarter } else if last <= half { half } else { full };
+        let covered = (1..count).fold(S::zero(), |sum, h| {
+            let width = self.horizontal_angles[h] - self.horizontal_angles[h - 1];
+
+            sum + (value(h - 1) + value(h)) * one_half * width
+        });
+        let uncovered = value(0) * (range - (last - first)).max(S::zero());
+
+        (covered + uncovered) / range
+    }
+
+    /// The largest intensity of the web in candela.
+    pub fn max_candela(&self) -> S {
+        self.candela.iter().fold(S::zero(), |max, &value| max.max(value))
+    }
+
+    /// Evaluate the intensity in candela at a vertical and a horizontal angle
+    /// in degrees. Directions outside the vertical range of the web receive
+    /// no light.
+    pub fn evaluate(&self, vertical: S, horizontal: S) -> S {
+        let vertical_count = self.vertical_angles.len();
+        let (v0, v1, tv) = match bracket(&self.vertical_angles, vertical) {
+            Some(bracket) => bracket,
+            None => return S::zero(),
+        };
+        let horizontal = self.fold_horizontal(horizontal);
+        let (h0, h1, th) = bracket(&self.horizontal_angles, horizontal).unwrap_or((0, 0, S::zero()));
+        let candela = |h: usize, v: usize| self.candela[h * vertical_count + v];
+        let lower = candela(h0, v0) * (S::one() - tv) + candela(h0, v1) * tv;
+        let upper = candela(h1, v0) * (S::one() - tv) + candela(h1, v1) * tv;
+
+        lower * (S::one() - th) + upper * th
+    }
+
+    /// Map a horizontal angle in degrees into the range covered by the web
+    /// using the web's symmetry.
+    fn fold_horizontal(&self, horizontal: S) -> S {
+        let full: S = num_traits::cast(360).unwrap();
+        let half: S = num_traits::cast(180).unwrap();
+        let quarter: S = num_traits::cast(90).unwrap();
+        let last = self.horizontal_angles[self.horizontal_angles.len() - 1];
+        let horizontal = horizontal - (horizontal / full).floor() * full;
+        if last <= quarter {
+            let horizontal = if horizontal > half { full - horizontal } else { horizontal };
+
+            if horizontal > quarter { half - horizontal } else { horizontal }
+        } else if last <= half && horizontal > half {
+            full - horizontal
+        } else {
+            horizontal
+        }
+    }
+}
+
+/// Find the samples bracketing a value in a sorted list, and the
+/// interpolation parameter between them. The result is `None` if the value
+/// lies outside the list.
+fn bracket<S: ScalarFloat>(angles: &[S], value: S) -> Option<(usize, usize, S)> {
+    let last = angles.len() - 1;
+    if value < angles[0] || value > angles[last] {
+        return None;
+    }
+    if last == 0 {
+        return Some((0, 0, S::zero()));
+    }
+
+    let upper = angles.iter().position(|&angle| angle >= value).unwrap_or(last).max(1);
+    let lower = upper - 1;
+    let width = angles[upper] - angles[lower];
+
+    Some((lower, upper, (value - angles[lower]) / width))
+}
+
+/// A specification describing an IES light, a point light whose intensity
+/// varies with direction according to a measured photometric web.
+#[derive(Clone, Debug, PartialEq)]
+pub struct IesLightModelSpec<S> {
+    pub web: Arc<PhotometricWeb<S>>,
+    /// The color of the light, which multiplies the web's intensities.
+    pub color: Vector3<S>,
+}
+
+impl<S> IesLightModelSpec<S> where S: ScalarFloat {
+    /// Construct a new IES light specification.
+    #[inline]
+    pub fn new(web: Arc<PhotometricWeb<S>>, color: Vector3<S>) -> IesLightModelSpec<S> {
+        IesLightModelSpec {
+            web: web,
+            color: color,
+        }
+    }
+}
+
+/// The illumination model of an IES light. Photometric webs are shared
+/// between lights, and between a light and its specification.
+#[derive(Clone, Debug, PartialEq)]
+pub struct IesLightModel<S> {
+    pub web: Arc<PhotometricWeb<S>>,
+    /// The color of the light, which multiplies the web's intensities.
+    pub color: Vector3<S>,
+}
+
+impl_illumination_model!(IesLightModel, IesLightModelSpec, [web, color]);
+
+impl_dimmable!(IesLightModel, [color]);
+
+pub type IesLight<S> = Light<S, IesLightModel<S>>;
+
+impl<S> IesLight<S> where S: ScalarFloat {
+    /// The luminous intensity in candela the light emits along the unit
+    /// direction `direction` in world space, before the light's color is
+    /// applied.
+    pub fn intensity_in_direction(&self, direction: &Vector3<S>) -> S {
+        let forward = direction.dot(&self.forward_axis());
+        let right = direction.dot(&self.right_axis());
+        let up = direction.dot(&self.up_axis());
+        let length = direction.magnitude();
+        if length <= S::zero() {
+            return S::zero();
+        }
+        let vertical = (forward / length).max(-S::one()).min(S::one()).acos().to_degrees();
+        let horizontal = up.atan2(right).to_degrees();
+
+        self.model().web.evaluate(vertical, horizontal)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+
+    /// An IES file with a header line, `vertical_count` vertical angles, and
+    /// `horizontal_count` horizontal angles, followed by the given data.
+    fn ies_source(multiplier: &str, vertical_count: &str, horizontal_count: &str, data: &str) -> String {
+        format!(
+            "IESNA:LM-63-2002\n[TEST] cgilluminate\nTILT=NONE\n1 1000 {} {} {} 1 1 0 0 0\n1.0 1.0 100\n{}\n",
+            multiplier, vertical_count, horizontal_count, data
+        )
+    }
+
+    #[test]
+    fn test_parse_scales_candela_by_multiplier() {
+        let source = ies_source("2.0", "3", "2", "0 45 90\n0, 180\n100 80 10\n50 40 5");
+        let web = PhotometricWeb::<f64>::parse(&source).unwrap();
+
+        assert_eq!(web.vertical_angles(), &[0.0, 45.0, 90.0]);
+        assert_eq!(web.horizontal_angles(), &[0.0, 180.0]);
+        assert_eq!(web.candela(), &[200.0, 160.0, 20.0, 100.0, 80.0, 10.0]);
+        assert_eq!(web.evaluate(0.0, 0.0), 200.0);
+        assert_eq!(web.evaluate(22.5, 90.0), 135.0);
+        // Beyond the last vertical angle the luminaire emits no light.
+        assert_eq!(web.evaluate(120.0, 0.0), 0.0);
+    }
+
+    #[test]
+    fn test_parse_rejects_missing_and_unsupported_tilt() {
+        let missing = "IESNA:LM-63-2002\n1 1000 1 2 1 1 1 0 0 0\n";
+        let unsupported = "IESNA:LM-63-2002\nTILT=INCLUDE\n1 1000 1 2 1 1 1 0 0 0\n";
+
+        assert_eq!(PhotometricWeb::<f64>::parse(missing), Err(IesError::MissingTilt));
+        assert_eq!(PhotometricWeb::<f64>::parse(unsupported), Err(IesError::UnsupportedTilt));
+    }
+
+    #[test]
+    fn test_parse_rejects_truncated_and_invalid_data() {
+        let truncated = ies_source("1", "3", "1", "0 45 90\n0\n100 80");
+        let invalid = ies_source("1", "2", "1", "0 90\n0\n100 ten");
+
+        assert_eq!(PhotometricWeb::<f64>::parse(&truncated), Err(IesError::UnexpectedEnd));
+        assert_eq!(
+            PhotometricWeb::<f64>::parse(&invalid),
+            Err(IesError::InvalidNumber(String::from("ten")))
+        );
+    }
+
+    #[test]
+    fn test_parse_rejects_malformed_counts() {
+        let data = "0 90\n0\n100 50";
+        for &(vertical_count, horizontal_count) in &[("2.5", "1"), ("2", "-1"), ("1e30", "1"), ("2", "NaN")] {
+            let source = ies_source("1", vertical_count, horizontal_count, data);
+
+            assert_eq!(
+                PhotometricWeb::<f64>::parse(&source),
+                Err(IesError::InvalidWeb),
+                "counts {} and {}", vertical_count, horizontal_count
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_rejects_decreasing_angles() {
+        let source = ies_source("1", "2", "1", "90 0\n0\n100 50");
+
+        assert_eq!(PhotometricWeb::<f64>::parse(&source), Err(IesError::InvalidWeb));
+    }
+}
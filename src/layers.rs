@@ -0,0 +1,158 @@
+use cglinalg::{
+    ScalarFloat,
+};
+use crate::{
+    IlluminationModel,
+    Light,
+};
+use core::ops;
+
+
+/// A set of up to 32 light layers (also called lighting channels), for
+/// linking lights to the objects they illuminate.
+///
+/// Renderers give each object a set of layers too, and a light illuminates an
+/// object only if their layers intersect. Lights and objects start on layer
+/// zero, so by default every light illuminates every object. A rim light for
+/// characters only goes on its own layer, which the characters add to their
+/// layers.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct LightLayers {
+    bits: u32,
+}
+
+impl LightLayers {
+    /// The number of layers.
+    pub const COUNT: u32 = 32;
+    /// The empty set of layers. A light on no layers illuminates nothing.
+    pub const NONE: LightLayers = LightLayers { bits: 0 };
+    /// The set of every layer.
+    pub const ALL: LightLayers = LightLayers { bits: u32::MAX };
+    /// The default set, holding only layer zero.
+    pub const DEFAULT: LightLayers = LightLayers { bits: 1 };
+
+    /// Construct a set of layers from a bitmask, with bit `i` set for each
+    /// layer `i` in the set.
+    #[inline]
+    pub const fn from_bits(bits: u32) -> LightLayers {
+        LightLayers {
+            bits: bits,
+        }
+    }
+
+    /// The set holding a single layer. The result is `None` if the layer is
+    /// not less than `COUNT`.
+    #[inline]
+    pub fn layer(layer: u32) -> Option<LightLayers> {
+        if layer < Self::COUNT {
+            Some(LightLayers::from_bits(1 << layer))
+        } else {
+            None
+        }
+    }
+
+    /// The bitmask of the set.
+    #[inline]
+    pub const fn bits(self) -> u32 {
+        self.bits
+    }
+
+    /// Determine whether the set holds no layers.
+    #[inline]
+    pub const fn is_empty(self) -> bool {
+        self.bits == 0
+    }
+
+    /// Determine whether the set holds a layer. Layers not less than `COUNT`
+    /// are never held.
+    #[inline]
+    pub fn contains(self, layer: u32) -> bool {
+        layer < Self::COUNT && self.bits & (1 << layer) != 0
+    }
+
+    /// Add a layer to the set. Layers not less than `COUNT` are ignored.
+    #[inline]
+    pub fn insert(&mut self, layer: u32) {
+        if layer < Self::COUNT {
+            self.bits |= 1 << layer;
+        }
+    }
+
+    /// Remove a layer from the set.
+    #[inline]
+    pub fn remove(&mut self, layer: u32) {
+        if layer < Self::COUNT {
+            self.bits &= !(1 << layer);
+        }
+    }
+
+    /// Determine whether two sets share a layer.
+    #[inline]
+    pub const fn intersects(self, other: LightLayers) -> bool {
+        self.bits & other.bits != 0
+    }
+}
+
+impl Default for LightLayers {
+    #[inline]
+    fn default() -> LightLayers {
+        LightLayers::DEFAULT
+    }
+}
+
+impl ops::BitOr for LightLayers {
+    type Output = LightLayers;
+
+    #[inline]
+    fn bitor(self, other: LightLayers) -> LightLayers {
+        LightLayers::from_bits(self.bits | other.bits)
+    }
+}
+
+impl ops::BitAnd for LightLayers {
+    type Output = LightLayers;
+
+    #[inline]
+    fn bitand(self, other: LightLayers) -> LightLayers {
+        LightLayers::from_bits(self.bits & other.bits)
+    }
+}
+
+impl ops::BitOrAssign for LightLayers {
+    #[inline]
+    fn bitor_assign(&mut self, other: LightLayers) {
+        self.bits |= other.bits;
+    }
+}
+
+impl ops::BitAndAssign for LightLayers {
+    #[inline]
+    fn bitand_assign(&mut self, other: LightLayers) {
+        self.bits &= other.bits;
+    }
+}
+
+impl<S, M> Light<S, M>
+    where S: ScalarFloat,
+          M: IlluminationModel,
+{
+    /// The layers the light is on.
+    #[inline]
+    pub fn layers(&self) -> LightLayers {
+        self.layers
+    }
+
+    /// Move the light onto a set of layers.
+    #[inline]
+    pub fn set_layers(&mut self, layers: LightLayers) {
+        self.layers = layers;
+    }
+
+    /// Determine whether the light illuminates an object on a set of layers.
+    #[inline]
+    pub fn illuminates(&self, object_layers: LightLayers) -> bool {
+        self.layers.intersects(object_layers)
+    }
+}
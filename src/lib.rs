@@ -1,6 +1,6 @@
 use cglinalg::{
+    Magnitude,
     Vector3,
-    Vector4,
     Matrix4x4,
     Quaternion,
     Radians,
@@ -8,11 +8,206 @@ use cglinalg::{
     Unit,
 };
 
+use crate::attenuation::{
+    Attenuation,
+    AttenuationCoefficients,
+};
+use crate::color::{
+    ColorSpace,
+    ColorTemperature,
+    StandardIlluminant,
+};
+use crate::falloff::FalloffCurve;
+use crate::layers::LightLayers;
+use crate::normalization::AreaEmitter;
+use crate::polarity::Polarity;
+use crate::profile::AngularProfile;
+use crate::sampling::LightJitter;
+use crate::shadow::ShadowSettings;
+use crate::stats::Counter;
+use crate::volumetric::VolumetricParameters;
 use core::fmt;
+use std::sync::Arc;
+
+/// Implement the conversions between a lighting model and its specification
+/// in terms of the `IlluminationModel` trait.
+macro_rules! impl_illumination_model_conversions {
+    ($Model:ident, $Spec:ident) => {
+        impl<S> From<&$Spec<S>> for $Model<S> where S: ScalarFloat {
+            #[inline]
+            fn from(spec: &$Spec<S>) -> $Model<S> {
+                <$Model<S> as IlluminationModel>::from_spec(spec)
+            }
+        }
+
+        impl<S> From<$Spec<S>> for $Model<S> where S: ScalarFloat {
+            #[inline]
+            fn from(spec: $Spec<S>) -> $Model<S> {
+                <$Model<S> as IlluminationModel>::from_spec(&spec)
+            }
+        }
+
+        impl<S> From<&$Model<S>> for $Spec<S> where S: ScalarFloat {
+            #[inline]
+            fn from(model: &$Model<S>) -> $Spec<S> {
+                model.to_spec()
+            }
+        }
+
+        impl<S> From<$Model<S>> for $Spec<S> where S: ScalarFloat {
+            #[inline]
+            fn from(model: $Model<S>) -> $Spec<S> {
+                model.to_spec()
+            }
+        }
+    }
+}
+
+/// Implement `IlluminationModel` for a lighting model whose fields are copied
+/// verbatim from its specification, along with the conversions between them.
+macro_rules! impl_illumination_model {
+    ($Model:ident, $Spec:ident, [$($field:ident),* $(,)*]) => {
+        impl<S> IlluminationModel for $Model<S> where S: ScalarFloat {
+            type Spec = $Spec<S>;
+
+            #[inline]
+            fn from_spec(spec: &Self::Spec) -> Self {
+                $Model {
+                    $($field: spec.$field.clone(),)*
+                }
+            }
+
+            #[inline]
+            fn to_spec(&self) -> Self::Spec {
+                $Spec {
+                    $($field: self.$field.clone(),)*
+                }
+            }
+        }
+
+        impl_illumination_model_conversions!($Model, $Spec);
+    }
+}
+
+/// Implement `Dimmable` for a lighting model by scaling the given emission
+/// fields.
+macro_rules! impl_dimmable {
+    ($(#[$attr:meta])* $Model:ident, [$($field:ident),* $(,)*]) => {
+        impl<S> Dimmable<S> for $Model<S> where S: ScalarFloat {
+            $(#[$attr])*
+            #[inline]
+            #[allow(clippy::needless_update)]
+            fn dimmed(&self, factor: S) -> Self {
+                $Model {
+                    $($field: self.$field * factor,)*
+                    ..self.clone()
+                }
+            }
+        }
+    }
+}
+
+/// Implement the `with_shadow_tint` builder method for a lighting model
+/// specification. Doc attributes given before the specification replace the
+/// default documentation.
+macro_rules! impl_with_shadow_tint {
+    ($Spec:ident) => {
+        impl_with_shadow_tint!(
+            /// Set the fraction of the light's emission that reaches shadowed
+            /// regions.
+            $Spec
+        );
+    };
+    ($(#[$attr:meta])+ $Spec:ident) => {
+        impl<S> $Spec<S> where S: ScalarFloat {
+            $(#[$attr])+
+            #[inline]
+            pub fn with_shadow_tint(self, shadow_tint: Vector3<S>) -> $Spec<S> {
+                $Spec {
+                    shadow_tint: shadow_tint,
+                    ..self
+                }
+            }
+        }
+    }
+}
+
+mod random;
 
+pub mod aggregate;
+pub mod any_light;
+pub mod animation;
+pub mod attenuation;
+pub mod cache;
+pub mod cascades;
+pub mod cast;
+pub mod clustering;
+pub mod color;
+pub mod cookie;
+pub mod dmx;
+pub mod environment;
+pub mod exposure;
+pub mod expression;
+pub mod falloff;
+pub mod fixed_array;
+pub mod gltf;
+pub mod gpu;
+#[cfg(feature = "half")]
+pub mod half_precision;
+pub mod highlight;
+pub mod ies;
+pub mod layers;
+pub mod lod;
+pub mod ltc;
+pub mod mesh;
+#[cfg(feature = "mint")]
+pub mod mint_interop;
+pub mod normalization;
+pub mod pbr;
+pub mod photometry;
+pub mod picking;
+pub mod polarity;
+pub mod polygon;
+pub mod presets;
+pub mod preview;
+pub mod probes;
+pub mod profile;
+pub mod representative_point;
+pub mod sampling;
+pub mod scene;
+pub mod screen;
+#[cfg(feature = "serde")]
+pub mod serialization;
+pub mod shadergen;
+pub mod shadow;
+pub mod shadow_atlas;
+pub mod shadow_map;
+pub mod sky;
+pub mod soa;
+#[cfg(feature = "spectral")]
+pub mod spectral;
+pub mod spherical_gaussian;
+pub mod std140;
+pub mod std430;
+pub mod streaming;
+pub mod stats;
+pub mod texture;
+pub mod tiled;
+pub mod uniforms;
+pub mod volumetric;
+#[cfg(feature = "wgpu")]
+pub mod wgpu_integration;
 
+
+pub type AmbientLight<S> = Light<S, AmbientLightModel<S>>;
+pub type HemisphereLight<S> = Light<S, HemisphereLightModel<S>>;
 pub type PointLight<S> = Light<S, PointLightModel<S>>;
 pub type SpotLight<S> = Light<S, SpotLightModel<S>>;
+pub type DirectionalLight<S> = Light<S, DirectionalLightModel<S>>;
+pub type DiskLight<S> = Light<S, DiskLightModel<S>>;
+pub type SphereLight<S> = Light<S, SphereLightModel<S>>;
+pub type TubeLight<S> = Light<S, TubeLightModel<S>>;
+pub type BeamLight<S> = Light<S, BeamLightModel<S>>;
 
 /// A type with this trait can be used as a lighting model. 
 ///
@@ -25,20 +220,40 @@ pub trait IlluminationModel {
     /// Construct a camera model from a description of the 
     /// camera model's parameters.
     fn from_spec(spec: &Self::Spec) -> Self;
+
+    /// Extract a description of the lighting model's parameters. Constructing
+    /// a lighting model from the resulting specification yields the same model.
+    fn to_spec(&self) -> Self::Spec;
+}
+
+/// A lighting model whose overall brightness can be scaled uniformly. 
+///
+/// Controllers that animate a light's intensity over time use this trait to
+/// derive the current model from an unmodified base model.
+pub trait Dimmable<S> {
+    /// Construct a copy of the lighting model with its emission scaled by 
+    /// `factor`.
+    fn dimmed(&self, factor: S) -> Self;
 }
 
 /// This type carries all the information describing the change in attitude of
 /// a light in a scene in Euclidean space.
 #[repr(C)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound(serialize = "S: serde::Serialize", deserialize = "S: serde::Deserialize<'de>")))]
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub struct DeltaAttitude<S> {
     /// The change in the position of the light.
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::vector3"))]
     pub delta_position: Vector3<S>,
     /// The change in the orientation of the light about the **negative z-axis**.
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::radians"))]
     pub roll: Radians<S>,
     /// The change in the orientation of the light about the **positive y-axis**.
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::radians"))]
     pub yaw: Radians<S>,
     /// The change in the orientation of the light about the **positive x-axis**.
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::radians"))]
     pub pitch: Radians<S>,
 }
 
@@ -54,146 +269,1160 @@ impl<S> DeltaAttitude<S> where S: ScalarFloat {
         }
     }
 
-    /// Construct zero change in attitude.
+    /// Construct zero change in attitude.
+    #[inline]
+    pub fn zero() -> Self {
+        Self {
+            delta_position: Vector3::zero(),
+            roll: Radians::zero(),
+            yaw: Radians::zero(),
+            pitch: Radians::zero(),
+        }
+    }
+}
+
+impl<S> fmt::Display for DeltaAttitude<S> where S: fmt::Display {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            formatter,
+            "DeltaAttitude [x={}, y={}, z={}, roll={}, yaw={}, pitch={}]",
+            self.delta_position[0], 
+            self.delta_position[1], 
+            self.delta_position[2], 
+            self.roll, 
+            self.yaw, 
+            self.pitch
+        )
+    }
+}
+
+/// A specification describing a global ambient light, which illuminates every
+/// point in the scene equally from every direction.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound(serialize = "S: serde::Serialize", deserialize = "S: serde::Deserialize<'de>")))]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct AmbientLightModelSpec<S> {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::vector3"))]
+    pub ambient: Vector3<S>,
+}
+
+impl<S> AmbientLightModelSpec<S> where S: ScalarFloat {
+    /// Construct a new ambient light specification.
+    #[inline]
+    pub fn new(ambient: Vector3<S>) -> AmbientLightModelSpec<S> {
+        AmbientLightModelSpec {
+            ambient: ambient,
+        }
+    }
+}
+
+/// The illumination model of a global ambient light.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct AmbientLightModel<S> {
+    pub ambient: Vector3<S>,
+}
+
+impl_illumination_model!(AmbientLightModel, AmbientLightModelSpec, [ambient]);
+
+impl_dimmable!(AmbientLightModel, [ambient]);
+
+impl<S> AmbientLight<S> where S: ScalarFloat {
+    /// Construct an ambient light. Ambient lights have no position or
+    /// orientation, so the light is given the identity attitude at the
+    /// origin, which the model ignores.
+    pub fn from_model_spec(model_spec: &AmbientLightModelSpec<S>) -> Self {
+        let zero = S::zero();
+        let one = S::one();
+        let attitude_spec = LightAttitudeSpec::new(
+            Vector3::zero(),
+            Vector3::new(zero, zero, -one),
+            Vector3::new(one, zero, zero),
+            Vector3::new(zero, one, zero),
+            Vector3::new(zero, zero, -one),
+        );
+
+        Light::new(model_spec, &attitude_spec)
+    }
+}
+
+/// A specification describing a hemisphere light, an ambient light that
+/// blends between a sky color above and a ground color below. The sky lies
+/// along the light's up axis.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound(serialize = "S: serde::Serialize", deserialize = "S: serde::Deserialize<'de>")))]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct HemisphereLightModelSpec<S> {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::vector3"))]
+    pub sky: Vector3<S>,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::vector3"))]
+    pub ground: Vector3<S>,
+}
+
+impl<S> HemisphereLightModelSpec<S> where S: ScalarFloat {
+    /// Construct a new hemisphere light specification.
+    #[inline]
+    pub fn new(sky: Vector3<S>, ground: Vector3<S>) -> HemisphereLightModelSpec<S> {
+        HemisphereLightModelSpec {
+            sky: sky,
+            ground: ground,
+        }
+    }
+}
+
+/// The illumination model of a hemisphere light.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct HemisphereLightModel<S> {
+    pub sky: Vector3<S>,
+    pub ground: Vector3<S>,
+}
+
+impl_illumination_model!(HemisphereLightModel, HemisphereLightModelSpec, [sky, ground]);
+
+impl_dimmable!(HemisphereLightModel, [sky, ground]);
+
+impl<S> HemisphereLightModel<S> where S: ScalarFloat {
+    /// Evaluate the light for a surface whose unit normal makes an angle with
+    /// cosine `cos_angle` with the up direction. Surfaces facing straight up
+    /// receive the sky color, surfaces facing straight down the ground color,
+    /// and the colors blend linearly in between.
+    #[inline]
+    pub fn evaluate(&self, cos_angle: S) -> Vector3<S> {
+        let one_half: S = num_traits::cast(0.5).unwrap();
+        let t = (cos_angle * one_half + one_half).max(S::zero()).min(S::one());
+
+        self.ground + (self.sky - self.ground) * t
+    }
+}
+
+impl<S> HemisphereLight<S> where S: ScalarFloat {
+    /// The unit direction towards the sky in world space, i.e. the light's up
+    /// axis.
+    #[inline]
+    pub fn up(&self) -> Vector3<S> {
+        self.up_axis()
+    }
+
+    /// The light reaching a surface with unit normal `normal` in world space.
+    #[inline]
+    pub fn irradiance(&self, normal: &Vector3<S>) -> Vector3<S> {
+        self.model().evaluate(normal.dot(&self.up()))
+    }
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound(serialize = "S: ScalarFloat + serde::Serialize", deserialize = "S: ScalarFloat + serde::Deserialize<'de>")))]
+#[derive(Clone, Debug, PartialEq)]
+pub struct PointLightModelSpec<S> {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::vector3"))]
+    pub ambient: Vector3<S>,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::vector3"))]
+    pub diffuse: Vector3<S>,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::vector3"))]
+    pub specular: Vector3<S>,
+    /// The falloff of the light's emission with distance.
+    pub attenuation: Attenuation<S>,
+    /// The optional falloff curve replacing the distance attenuation.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub falloff: Option<Arc<FalloffCurve<S>>>,
+    /// The fraction of the light's diffuse and specular emission that reaches
+    /// shadowed regions in each color channel. Zero casts black shadows.
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::vector3"))]
+    pub shadow_tint: Vector3<S>,
+    /// The light's optional contribution to participating media.
+    pub volumetric: Option<VolumetricParameters<S>>,
+    /// Whether the light adds or subtracts light.
+    pub polarity: Polarity,
+}
+
+impl<S> PointLightModelSpec<S> where S: ScalarFloat {
+    /// Construct a new point light specification.
+    #[inline]
+    pub fn new(
+        ambient: Vector3<S>, 
+        diffuse: Vector3<S>, 
+        specular: Vector3<S>) -> PointLightModelSpec<S> 
+    {
+        PointLightModelSpec {
+            ambient: ambient,
+            diffuse: diffuse,
+            specular: specular,
+            attenuation: Attenuation::None,
+            falloff: None,
+            shadow_tint: Vector3::zero(),
+            volumetric: None,
+            polarity: Polarity::Additive,
+        }
+    }
+
+    /// Set the falloff of the light's emission with distance. Point lights
+    /// have no distance attenuation by default.
+    #[inline]
+    pub fn with_attenuation(self, attenuation: Attenuation<S>) -> PointLightModelSpec<S> {
+        PointLightModelSpec {
+            attenuation: attenuation,
+            ..self
+        }
+    }
+
+    /// Replace the light's distance attenuation with an artist authored
+    /// falloff curve.
+    #[inline]
+    pub fn with_falloff(self, falloff: Arc<FalloffCurve<S>>) -> PointLightModelSpec<S> {
+        PointLightModelSpec {
+            falloff: Some(falloff),
+            ..self
+        }
+    }
+
+    /// Make the light illuminate participating media, such as fog or dust,
+    /// with the given volumetric parameters.
+    #[inline]
+    pub fn with_volumetric(self, volumetric: VolumetricParameters<S>) -> PointLightModelSpec<S> {
+        PointLightModelSpec {
+            volumetric: Some(volumetric),
+            ..self
+        }
+    }
+
+    /// Make the light subtract its contribution from the scene, darkening the
+    /// region it would otherwise light.
+    #[inline]
+    pub fn subtractive(self) -> PointLightModelSpec<S> {
+        PointLightModelSpec {
+            polarity: Polarity::Subtractive,
+            ..self
+        }
+    }
+
+    /// Construct a point light specification emitting the color of a standard
+    /// illuminant in the given working color space. The diffuse and specular
+    /// colors are the illuminant's color scaled by `intensity`, and the light
+    /// has no ambient contribution.
+    #[inline]
+    pub fn from_illuminant(
+        illuminant: StandardIlluminant,
+        color_space: ColorSpace,
+        intensity: S) -> PointLightModelSpec<S>
+    {
+        let color = illuminant.color::<S>(color_space) * intensity;
+
+        PointLightModelSpec {
+            ambient: Vector3::zero(),
+            diffuse: color,
+            specular: color,
+            attenuation: Attenuation::None,
+            falloff: None,
+            shadow_tint: Vector3::zero(),
+            volumetric: None,
+            polarity: Polarity::Additive,
+        }
+    }
+
+    /// Construct a point light specification emitting the color of a black
+    /// body at the given temperature in a working color space, the way
+    /// lighting fixtures are rated. The diffuse and specular colors are the
+    /// black body's color at unit luminance scaled by `intensity`, and the
+    /// light has no ambient contribution.
+    #[inline]
+    pub fn from_temperature(
+        temperature: ColorTemperature<S>,
+        color_space: ColorSpace,
+        intensity: S) -> PointLightModelSpec<S>
+    {
+        let color = temperature.to_rgb(color_space) * intensity;
+
+        PointLightModelSpec {
+            ambient: Vector3::zero(),
+            diffuse: color,
+            specular: color,
+            attenuation: Attenuation::None,
+            falloff: None,
+            shadow_tint: Vector3::zero(),
+            volumetric: None,
+            polarity: Polarity::Additive,
+        }
+    }
+}
+
+impl_with_shadow_tint!(
+    /// Set the fraction of the light's emission that reaches shadowed regions,
+    /// e.g. for light filtered through stained glass or foliage.
+    PointLightModelSpec
+);
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct PointLightModel<S> {
+    pub ambient: Vector3<S>,
+    pub diffuse: Vector3<S>,
+    pub specular: Vector3<S>,
+    /// The falloff of the light's emission with distance.
+    pub attenuation: Attenuation<S>,
+    /// The optional falloff curve replacing the distance attenuation. Curves
+    /// are shared between lights, and between a light and its specification.
+    pub falloff: Option<Arc<FalloffCurve<S>>>,
+    /// The fraction of the light's diffuse and specular emission that reaches
+    /// shadowed regions in each color channel. Zero casts black shadows.
+    pub shadow_tint: Vector3<S>,
+    /// The light's optional contribution to participating media.
+    pub volumetric: Option<VolumetricParameters<S>>,
+    /// Whether the light adds or subtracts light.
+    pub polarity: Polarity,
+}
+
+impl_illumination_model!(PointLightModel, PointLightModelSpec, [ambient, diffuse, specular, attenuation, falloff, shadow_tint, volumetric, polarity]);
+
+impl_dimmable!(PointLightModel, [ambient, diffuse, specular]);
+
+impl<S> PointLightModel<S> where S: ScalarFloat {
+    /// Evaluate the distance attenuation of the light at a distance from the
+    /// light. A falloff curve takes precedence over the attenuation model.
+    #[inline]
+    pub fn attenuation(&self, distance: S) -> S {
+        match self.falloff {
+            Some(ref falloff) => falloff.evaluate(distance),
+            None => self.attenuation.evaluate(distance),
+        }
+    }
+
+    /// The distance from the light beyond which its distance attenuation
+    /// falls below `threshold`.
+    #[inline]
+    pub fn range(&self, threshold: S) -> S {
+        match self.falloff {
+            Some(ref falloff) => falloff.range(threshold),
+            None => self.attenuation.range(threshold),
+        }
+    }
+}
+
+/// The shape of a spotlight's angular falloff across its cone.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum ConeFalloff<S> {
+    /// Full intensity inside the inner cone, blending smoothly to zero at the
+    /// outer cone.
+    Smoothstep,
+    /// The fixed-function OpenGL spotlight: the cosine of the angle off the
+    /// axis raised to the spot exponent, cut off sharply at the outer cone.
+    /// The inner cone is ignored.
+    Exponent(S),
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound(serialize = "S: ScalarFloat + serde::Serialize", deserialize = "S: ScalarFloat + serde::Deserialize<'de>")))]
+#[derive(Clone, Debug, PartialEq)]
+pub struct SpotLightModelSpec<S> {
+    cutoff: S,
+    outer_cutoff: S,
+    /// The spotlight illumination parameters.
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::vector3"))]
+    ambient: Vector3<S>,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::vector3"))]
+    diffuse: Vector3<S>,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::vector3"))]
+    specular: Vector3<S>,
+    /// The shape of the spotlight's falloff across its cone.
+    cone_falloff: ConeFalloff<S>,
+    /// The falloff of the spotlight's emission with distance.
+    attenuation: Attenuation<S>,
+    /// The optional falloff curve replacing the distance attenuation.
+    falloff: Option<Arc<FalloffCurve<S>>>,
+    /// The optional angular intensity profile of the spotlight.
+    profile: Option<Arc<AngularProfile<S>>>,
+    /// The fraction of the spotlight's emission that reaches shadowed regions.
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::vector3"))]
+    shadow_tint: Vector3<S>,
+    /// The spotlight's optional contribution to participating media.
+    volumetric: Option<VolumetricParameters<S>>,
+    /// Whether the spotlight adds or subtracts light.
+    polarity: Polarity,
+}
+
+impl<S> SpotLightModelSpec<S> where S: ScalarFloat {
+    #[inline]
+    pub fn new(
+        cutoff: S,
+        outer_cutoff: S,
+        ambient: Vector3<S>,
+        diffuse: Vector3<S>,
+        specular: Vector3<S>,
+        constant: S,
+        linear: S,
+        quadratic: S) -> SpotLightModelSpec<S> 
+    {
+        SpotLightModelSpec { 
+            cutoff: cutoff,
+            outer_cutoff: outer_cutoff,
+            ambient: ambient,
+            diffuse: diffuse,
+            specular: specular,
+            cone_falloff: ConeFalloff::Smoothstep,
+            attenuation: Attenuation::Polynomial(AttenuationCoefficients::new(constant, linear, quadratic)),
+            falloff: None,
+            profile: None,
+            shadow_tint: Vector3::zero(),
+            volumetric: None,
+            polarity: Polarity::Additive,
+        }
+    }
+
+    /// Use the fixed-function OpenGL falloff across the spotlight's cone, the
+    /// cosine of the angle off the axis raised to `exponent` with a sharp
+    /// cutoff at the outer cone, for content ported from legacy engines.
+    #[inline]
+    pub fn with_spot_exponent(self, exponent: S) -> SpotLightModelSpec<S> {
+        SpotLightModelSpec {
+            cone_falloff: ConeFalloff::Exponent(exponent),
+            ..self
+        }
+    }
+
+    /// Replace the spotlight's distance attenuation, e.g. with the inverse
+    /// square law.
+    #[inline]
+    pub fn with_attenuation(self, attenuation: Attenuation<S>) -> SpotLightModelSpec<S> {
+        SpotLightModelSpec {
+            attenuation: attenuation,
+            ..self
+        }
+    }
+
+    /// Replace the spotlight's distance attenuation with an artist authored
+    /// falloff curve.
+    #[inline]
+    pub fn with_falloff(self, falloff: Arc<FalloffCurve<S>>) -> SpotLightModelSpec<S> {
+        SpotLightModelSpec {
+            falloff: Some(falloff),
+            ..self
+        }
+    }
+
+    /// Attach an angular intensity profile to the spotlight, which multiplies
+    /// the falloff between the inner and outer cones.
+    #[inline]
+    pub fn with_profile(self, profile: Arc<AngularProfile<S>>) -> SpotLightModelSpec<S> {
+        SpotLightModelSpec {
+            profile: Some(profile),
+            ..self
+        }
+    }
+
+    /// Make the spotlight illuminate participating media, such as fog or
+    /// dust, with the given volumetric parameters.
+    #[inline]
+    pub fn with_volumetric(self, volumetric: VolumetricParameters<S>) -> SpotLightModelSpec<S> {
+        SpotLightModelSpec {
+            volumetric: Some(volumetric),
+            ..self
+        }
+    }
+
+    /// Make the spotlight subtract its contribution from the scene, darkening
+    /// the region inside its cone.
+    #[inline]
+    pub fn subtractive(self) -> SpotLightModelSpec<S> {
+        SpotLightModelSpec {
+            polarity: Polarity::Subtractive,
+            ..self
+        }
+    }
+}
+
+impl_with_shadow_tint!(SpotLightModelSpec);
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct SpotLightModel<S> {
+    /// The cosine of the angle between the spotlight's axis and the edge of
+    /// its fully lit inner cone.
+    pub cutoff: S,
+    /// The cosine of the angle between the spotlight's axis and the edge of
+    /// its outer cone, beyond which the spotlight emits no light.
+    pub outer_cutoff: S,
+    /// The spotlight illumination parameters.
+    pub ambient: Vector3<S>,
+    pub diffuse: Vector3<S>,
+    pub specular: Vector3<S>,
+    /// The shape of the spotlight's falloff across its cone.
+    pub cone_falloff: ConeFalloff<S>,
+    /// The falloff of the spotlight's emission with distance.
+    pub attenuation: Attenuation<S>,
+    /// The optional falloff curve replacing the distance attenuation. Curves
+    /// are shared between lights, and between a light and its specification.
+    pub falloff: Option<Arc<FalloffCurve<S>>>,
+    /// The optional angular intensity profile of the spotlight. Profiles are
+    /// shared between lights, and between a light and its specification.
+    pub profile: Option<Arc<AngularProfile<S>>>,
+    /// The fraction of the spotlight's diffuse and specular emission that
+    /// reaches shadowed regions in each color channel. Zero casts black
+    /// shadows.
+    pub shadow_tint: Vector3<S>,
+    /// The spotlight's optional contribution to participating media.
+    pub volumetric: Option<VolumetricParameters<S>>,
+    /// Whether the spotlight adds or subtracts light.
+    pub polarity: Polarity,
+}
+
+impl_illumination_model!(SpotLightModel, SpotLightModelSpec, [cutoff, outer_cutoff, ambient, diffuse, specular, cone_falloff, attenuation, falloff, profile, shadow_tint, volumetric, polarity]);
+
+impl<S> SpotLightModel<S> where S: ScalarFloat {
+    /// Evaluate the distance attenuation of the spotlight at a distance 
+    /// from the light. A falloff curve takes precedence over the
+    /// attenuation model.
+    #[inline]
+    pub fn attenuation(&self, distance: S) -> S {
+        match self.falloff {
+            Some(ref falloff) => falloff.evaluate(distance),
+            None => self.attenuation.evaluate(distance),
+        }
+    }
+
+    /// The distance from the spotlight beyond which its distance attenuation
+    /// falls below `threshold`.
+    #[inline]
+    pub fn range(&self, threshold: S) -> S {
+        match self.falloff {
+            Some(ref falloff) => falloff.range(threshold),
+            None => self.attenuation.range(threshold),
+        }
+    }
+
+    /// Evaluate the angular falloff of the spotlight for a direction making
+    /// an angle with cosine `cos_angle` with the spotlight's axis. The falloff
+    /// is zero outside the outer cone. Inside it, the falloff is either one
+    /// inside the inner cone and blends smoothly to the outer cone, or the
+    /// spot exponent falloff, depending on the spotlight's cone falloff. If
+    /// the spotlight has an angular profile, the falloff is multiplied by the
+    /// profile.
+    #[inline]
+    pub fn cone_factor(&self, cos_angle: S) -> S {
+        let profile = match self.profile {
+            Some(ref profile) => profile.evaluate_cos(cos_angle),
+            None => S::one(),
+        };
+
+        let falloff = match self.cone_falloff {
+            ConeFalloff::Smoothstep => self.cone_smoothstep(cos_angle),
+            ConeFalloff::Exponent(exponent) => self.cone_exponent(cos_angle, exponent),
+        };
+
+        falloff * profile
+    }
+
+    /// The solid angle in steradians of a uniform cone emitting as much as
+    /// the spotlight, the integral of its cone factor over the sphere. The
+    /// spotlight's emitted power is its on-axis intensity times this solid
+    /// angle.
+    pub fn beam_solid_angle(&self) -> S {
+        // Integrate over the cosine of the angle off the axis, where the solid
+        // angle element is `2π d(cos θ)`, from the edge of the cone where the
+        // falloff starts so Simpson's rule never straddles its discontinuity.
+        let intervals = 256;
+        let two_pi: S = num_traits::cast(2_f64 * core::f64::consts::PI).unwrap();
+        let two: S = num_traits::cast(2).unwrap();
+        let four: S = num_traits::cast(4).unwrap();
+        let three: S = num_traits::cast(3).unwrap();
+        let lower = match self.cone_falloff {
+            ConeFalloff::Smoothstep => self.cutoff.min(self.outer_cutoff),
+            ConeFalloff::Exponent(_) => self.outer_cutoff.max(S::zero()),
+        };
+        let lower = lower.max(-S::one()).min(S::one());
+        let step = (S::one() - lower) / num_traits::cast(intervals).unwrap();
+        let sum = (0..=intervals).fold(S::zero(), |sum, i| {
+            let weight = if i == 0 || i == intervals { S::one() } else if i % 2 == 1 { four } else { two };
+            let i_scalar: S = num_traits::cast(i).unwrap();
+
+            sum + self.cone_factor(lower + step * i_scalar) * weight
+        });
+
+        two_pi * sum * step / three
+    }
+
+    /// Evaluate the fixed-function OpenGL spotlight falloff.
+    #[inline]
+    fn cone_exponent(&self, cos_angle: S, exponent: S) -> S {
+        if cos_angle < self.outer_cutoff || cos_angle <= S::zero() {
+            return S::zero();
+        }
+
+        cos_angle.min(S::one()).powf(exponent)
+    }
+
+    /// Evaluate the smooth falloff between the inner and outer cones.
+    #[inline]
+    fn cone_smoothstep(&self, cos_angle: S) -> S {
+        let two: S = num_traits::cast(2).unwrap();
+        let three: S = num_traits::cast(3).unwrap();
+        let epsilon = self.cutoff - self.outer_cutoff;
+        if epsilon <= S::zero() {
+            return if cos_angle >= self.cutoff { S::one() } else { S::zero() };
+        }
+        let t = ((cos_angle - self.outer_cutoff) / epsilon).max(S::zero()).min(S::one());
+
+        t * t * (three - two * t)
+    }
+}
+
+impl_dimmable!(SpotLightModel, [ambient, diffuse, specular]);
+
+/// A specification describing a directional light: a light infinitely far
+/// away, such as the sun, whose rays all travel along the light's forward
+/// axis.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound(serialize = "S: serde::Serialize", deserialize = "S: serde::Deserialize<'de>")))]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct DirectionalLightModelSpec<S> {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::vector3"))]
+    pub ambient: Vector3<S>,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::vector3"))]
+    pub diffuse: Vector3<S>,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::vector3"))]
+    pub specular: Vector3<S>,
+    /// The fraction of the light's diffuse and specular emission that reaches
+    /// shadowed regions in each color channel. Zero casts black shadows.
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::vector3"))]
+    pub shadow_tint: Vector3<S>,
+}
+
+impl<S> DirectionalLightModelSpec<S> where S: ScalarFloat {
+    /// Construct a new directional light specification.
+    #[inline]
+    pub fn new(
+        ambient: Vector3<S>,
+        diffuse: Vector3<S>,
+        specular: Vector3<S>) -> DirectionalLightModelSpec<S>
+    {
+        DirectionalLightModelSpec {
+            ambient: ambient,
+            diffuse: diffuse,
+            specular: specular,
+            shadow_tint: Vector3::zero(),
+        }
+    }
+}
+
+impl_with_shadow_tint!(DirectionalLightModelSpec);
+
+/// The illumination model of a directional light. Directional lights have
+/// no distance attenuation: every point in the scene receives the same light.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct DirectionalLightModel<S> {
+    pub ambient: Vector3<S>,
+    pub diffuse: Vector3<S>,
+    pub specular: Vector3<S>,
+    /// The fraction of the light's diffuse and specular emission that reaches
+    /// shadowed regions in each color channel. Zero casts black shadows.
+    pub shadow_tint: Vector3<S>,
+}
+
+impl_illumination_model!(DirectionalLightModel, DirectionalLightModelSpec, [ambient, diffuse, specular, shadow_tint]);
+
+impl_dimmable!(DirectionalLightModel, [ambient, diffuse, specular]);
+
+impl<S> DirectionalLight<S> where S: ScalarFloat {
+    /// The unit direction the light's rays travel in world space, i.e. the
+    /// light's forward axis. Shaders use the negated direction as the
+    /// direction towards the light.
+    #[inline]
+    pub fn direction(&self) -> Vector3<S> {
+        self.forward_axis()
+    }
+}
+
+/// A specification describing a disk shaped area light, such as a studio
+/// softbox. The disk is centered at the light's position and emits from its
+/// front face along the light's forward axis.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct DiskLightModelSpec<S> {
+    /// The radius of the disk in world units.
+    pub radius: S,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::vector3"))]
+    pub ambient: Vector3<S>,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::vector3"))]
+    pub diffuse: Vector3<S>,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::vector3"))]
+    pub specular: Vector3<S>,
+    /// The fraction of the light's diffuse and specular emission that reaches
+    /// shadowed regions in each color channel. Zero casts black shadows.
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::vector3"))]
+    pub shadow_tint: Vector3<S>,
+}
+
+impl<S> DiskLightModelSpec<S> where S: ScalarFloat {
+    /// Construct a new disk light specification.
+    #[inline]
+    pub fn new(
+        radius: S,
+        ambient: Vector3<S>,
+        diffuse: Vector3<S>,
+        specular: Vector3<S>) -> DiskLightModelSpec<S>
+    {
+        DiskLightModelSpec {
+            radius: radius,
+            ambient: ambient,
+            diffuse: diffuse,
+            specular: specular,
+            shadow_tint: Vector3::zero(),
+        }
+    }
+}
+
+impl_with_shadow_tint!(DiskLightModelSpec);
+
+/// The illumination model of a one-sided disk shaped area light.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct DiskLightModel<S> {
+    /// The radius of the disk in world units.
+    pub radius: S,
+    pub ambient: Vector3<S>,
+    pub diffuse: Vector3<S>,
+    pub specular: Vector3<S>,
+    /// The fraction of the light's diffuse and specular emission that reaches
+    /// shadowed regions in each color channel. Zero casts black shadows.
+    pub shadow_tint: Vector3<S>,
+}
+
+impl_illumination_model!(DiskLightModel, DiskLightModelSpec, [radius, ambient, diffuse, specular, shadow_tint]);
+
+impl_dimmable!(DiskLightModel, [ambient, diffuse, specular]);
+
+/// A disk in world space.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct DiskPlane<S> {
+    pub center: Vector3<S>,
+    /// The unit normal of the disk's emitting face.
+    pub normal: Vector3<S>,
+    pub radius: S,
+}
+
+impl<S> DiskLight<S> where S: ScalarFloat {
+    /// The disk the light emits from in world space. The disk faces along the
+    /// light's forward axis.
+    #[inline]
+    pub fn disk_plane(&self) -> DiskPlane<S> {
+        DiskPlane {
+            center: self.position(),
+            normal: self.forward_axis(),
+            radius: self.model().radius,
+        }
+    }
+
+    /// Sample a point on the disk uniformly by area from a uniform sample in
+    /// the unit square, for a shading point `point` in front of the disk.
+    ///
+    /// The result is the unit direction from the shading point towards the
+    /// sampled point and the probability density of the direction with
+    /// respect to solid angle, or `None` if the shading point sees the disk
+    /// edge on or from behind.
+    pub fn sample(&self, u: S, v: S, point: &Vector3<S>) -> Option<(Vector3<S>, S)> {
+        let pi: S = num_traits::cast(core::f64::consts::PI).unwrap();
+        let radius = self.model().radius;
+        let area = pi * radius * radius;
+        if area <= S::zero() {
+            return None;
+        }
+
+        let sample_radius = radius * u.sqrt();
+        let angle = (pi + pi) * v;
+        let sampled = self.position()
+            + self.right_axis() * (sample_radius * angle.cos())
+            + self.up_axis() * (sample_radius * angle.sin());
+        let to_sample = sampled - point;
+        let distance_squared = to_sample.magnitude_squared();
+        if distance_squared <= S::zero() {
+            return None;
+        }
+
+        let direction = to_sample / distance_squared.sqrt();
+        let cos_disk = -direction.dot(&self.forward_axis());
+        if cos_disk <= S::zero() {
+            return None;
+        }
+
+        Some((direction, distance_squared / (cos_disk * area)))
+    }
+
+    /// Intersect a ray with the disk. The result is the distance along the
+    /// ray to the hit, in units of the ray direction's length, if the ray
+    /// hits either face of the disk in front of its origin.
+    pub fn intersect(&self, origin: &Vector3<S>, direction: &Vector3<S>) -> Option<S> {
+        let normal = self.forward_axis();
+        let denominator = direction.dot(&normal);
+        if denominator == S::zero() {
+            return None;
+        }
+        let t = (self.position() - origin).dot(&normal) / denominator;
+        if t <= S::zero() {
+            return None;
+        }
+        let radius = self.model().radius;
+        let offset = origin + direction * t - self.position();
+
+        if offset.magnitude_squared() <= radius * radius { Some(t) } else { None }
+    }
+
+    /// The radiance `Le(p, ω)` the disk emits from the point `point` on its
+    /// surface towards a receiver, where `direction` is the unit direction
+    /// from the receiver towards the point, such as a direction from
+    /// `sample` or the direction of a ray that hit the disk. The disk emits
+    /// only from its front face.
+    #[inline]
+    pub fn emitted_radiance(&self, _point: &Vector3<S>, direction: &Vector3<S>) -> Vector3<S> {
+        if direction.dot(&self.forward_axis()) < S::zero() {
+            self.model().emitted_radiance()
+        } else {
+            Vector3::zero()
+        }
+    }
+}
+
+/// A specification describing a spherical area light centered at the
+/// light's position. Unlike a point light, a sphere light has a physical
+/// size, which softens its highlights and shadows.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct SphereLightModelSpec<S> {
+    /// The radius of the sphere in world units.
+    pub radius: S,
+    /// The radiance emitted from every point of the sphere's surface.
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::vector3"))]
+    pub radiance: Vector3<S>,
+    /// The fraction of the light's emission that reaches shadowed regions in
+    /// each color channel. Zero casts black shadows.
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::vector3"))]
+    pub shadow_tint: Vector3<S>,
+}
+
+impl<S> SphereLightModelSpec<S> where S: ScalarFloat {
+    /// Construct a new sphere light specification.
+    #[inline]
+    pub fn new(radius: S, radiance: Vector3<S>) -> SphereLightModelSpec<S> {
+        SphereLightModelSpec {
+            radius: radius,
+            radiance: radiance,
+            shadow_tint: Vector3::zero(),
+        }
+    }
+}
+
+impl_with_shadow_tint!(SphereLightModelSpec);
+
+/// The illumination model of a spherical area light with uniform emitted
+/// radiance.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct SphereLightModel<S> {
+    /// The radius of the sphere in world units.
+    pub radius: S,
+    /// The radiance emitted from every point of the sphere's surface.
+    pub radiance: Vector3<S>,
+    /// The fraction of the light's emission that reaches shadowed regions in
+    /// each color channel. Zero casts black shadows.
+    pub shadow_tint: Vector3<S>,
+}
+
+impl_illumination_model!(SphereLightModel, SphereLightModelSpec, [radius, radiance, shadow_tint]);
+
+impl_dimmable!(SphereLightModel, [radiance]);
+
+impl<S> SphereLightModel<S> where S: ScalarFloat {
+    /// The sine squared of the half angle the sphere subtends from a point
+    /// at `distance` from its center. The value is one inside the sphere.
+    #[inline]
+    pub fn sin_squared_half_angle(&self, distance: S) -> S {
+        if distance <= self.radius {
+            S::one()
+        } else {
+            let ratio = self.radius / distance;
+
+            ratio * ratio
+        }
+    }
+}
+
+impl<S> SphereLight<S> where S: ScalarFloat {
+    /// The solid angle in steradians the sphere subtends from `point`.
+    ///
+    /// A sphere of radius `r` at distance `d` from the point subtends the
+    /// cone of half angle `α` with `sin(α) = r / d`, whose solid angle is
+    /// `2π(1 - cos(α))`. Points inside the sphere see it in every direction.
+    pub fn solid_angle(&self, point: &Vector3<S>) -> S {
+        let two_pi: S = num_traits::cast(2_f64 * core::f64::consts::PI).unwrap();
+        let distance = (self.position() - point).magnitude();
+        if distance <= self.model().radius {
+            return two_pi + two_pi;
+        }
+
+        let sin_squared = self.model().sin_squared_half_angle(distance);
+        let cos_half_angle = (S::one() - sin_squared).sqrt();
+
+        two_pi * (S::one() - cos_half_angle)
+    }
+
+    /// Sample a direction uniformly within the cone the sphere subtends from
+    /// `point`, from a uniform sample in the unit square.
+    ///
+    /// The result is the unit direction from the shading point towards the
+    /// sphere and the probability density of the direction with respect to
+    /// solid angle, or `None` if the shading point is inside the sphere.
+    pub fn sample(&self, u: S, v: S, point: &Vector3<S>) -> Option<(Vector3<S>, S)> {
+        let two_pi: S = num_traits::cast(2_f64 * core::f64::consts::PI).unwrap();
+        let to_center = self.position() - point;
+        let distance = to_center.magnitude();
+        if distance <= self.model().radius {
+            return None;
+        }
+
+        let axis = to_center / distance;
+        let sin_squared = self.model().sin_squared_half_angle(distance);
+        let cos_half_angle = (S::one() - sin_squared).sqrt();
+        let cos_theta = S::one() - u * (S::one() - cos_half_angle);
+        let sin_theta = (S::one() - cos_theta * cos_theta).max(S::zero()).sqrt();
+        let phi = two_pi * v;
+        // Build an orthonormal frame around the axis towards the center.
+        let helper = if axis.x.abs() < num_traits::cast(0.9).unwrap() {
+            Vector3::unit_x()
+        } else {
+            Vector3::unit_y()
+        };
+        let tangent = axis.cross(&helper).normalize();
+        let bitangent = axis.cross(&tangent);
+        let direction = tangent * (sin_theta * phi.cos())
+            + bitangent * (sin_theta * phi.sin())
+            + axis * cos_theta;
+
+        Some((direction, S::one() / (two_pi * (S::one() - cos_half_angle))))
+    }
+
+    /// Intersect a ray with the sphere. The result is the distance along the
+    /// ray to the nearest hit in front of its origin, in units of the ray
+    /// direction's length, if the ray hits the sphere.
+    pub fn intersect(&self, origin: &Vector3<S>, direction: &Vector3<S>) -> Option<S> {
+        let radius = self.model().radius;
+        let offset = origin - self.position();
+        let a = direction.dot(direction);
+        let half_b = offset.dot(direction);
+        let c = offset.dot(&offset) - radius * radius;
+        let discriminant = half_b * half_b - a * c;
+        if a == S::zero() || discriminant < S::zero() {
+            return None;
+        }
+        let root = discriminant.sqrt();
+        let near = (-half_b - root) / a;
+        let far = (-half_b + root) / a;
+
+        if near > S::zero() {
+            Some(near)
+        } else if far > S::zero() {
+            Some(far)
+        } else {
+            None
+        }
+    }
+
+    /// The radiance `Le(p, ω)` the sphere emits from the point `point` on its
+    /// surface, with `direction` pointing from the receiver towards the
+    /// point, as in [`DiskLight::emitted_radiance`].
     #[inline]
-    pub fn zero() -> Self {
-        Self {
-            delta_position: Vector3::zero(),
-            roll: Radians::zero(),
-            yaw: Radians::zero(),
-            pitch: Radians::zero(),
+    pub fn emitted_radiance(&self, point: &Vector3<S>, direction: &Vector3<S>) -> Vector3<S> {
+        if direction.dot(&(point - self.position())) < S::zero() {
+            self.model().emitted_radiance()
+        } else {
+            Vector3::zero()
         }
     }
 }
 
-impl<S> fmt::Display for DeltaAttitude<S> where S: fmt::Display {
-    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
-        write!(
-            formatter,
-            "DeltaAttitude [x={}, y={}, z={}, roll={}, yaw={}, pitch={}]",
-            self.delta_position[0], 
-            self.delta_position[1], 
-            self.delta_position[2], 
-            self.roll, 
-            self.yaw, 
-            self.pitch
-        )
+/// A specification describing a tube shaped area light, such as a
+/// fluorescent tube: a capsule around a line segment centered at the light's
+/// position and lying along the light's right axis.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct TubeLightModelSpec<S> {
+    /// The length of the tube's center line in world units, excluding its
+    /// rounded caps.
+    pub length: S,
+    /// The radius of the tube in world units.
+    pub radius: S,
+    /// The radiance emitted from every point of the tube's surface.
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::vector3"))]
+    pub radiance: Vector3<S>,
+    /// The fraction of the light's emission that reaches shadowed regions in
+    /// each color channel. Zero casts black shadows.
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::vector3"))]
+    pub shadow_tint: Vector3<S>,
+}
+
+impl<S> TubeLightModelSpec<S> where S: ScalarFloat {
+    /// Construct a new tube light specification.
+    #[inline]
+    pub fn new(length: S, radius: S, radiance: Vector3<S>) -> TubeLightModelSpec<S> {
+        TubeLightModelSpec {
+            length: length,
+            radius: radius,
+            radiance: radiance,
+            shadow_tint: Vector3::zero(),
+        }
     }
 }
 
-#[derive(Clone,)]
-pub struct PointLightModelSpec<S> {
-    pub ambient: Vector3<S>,
-    pub diffuse: Vector3<S>,
-    pub specular: Vector3<S>,
+impl_with_shadow_tint!(TubeLightModelSpec);
+
+/// The illumination model of a tube shaped area light with uniform emitted
+/// radiance.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct TubeLightModel<S> {
+    /// The length of the tube's center line in world units, excluding its
+    /// rounded caps.
+    pub length: S,
+    /// The radius of the tube in world units.
+    pub radius: S,
+    /// The radiance emitted from every point of the tube's surface.
+    pub radiance: Vector3<S>,
+    /// The fraction of the light's emission that reaches shadowed regions in
+    /// each color channel. Zero casts black shadows.
+    pub shadow_tint: Vector3<S>,
 }
 
-impl<S> PointLightModelSpec<S> where S: ScalarFloat {
-    /// Construct a new point light specification.
+impl_illumination_model!(TubeLightModel, TubeLightModelSpec, [length, radius, radiance, shadow_tint]);
+
+impl_dimmable!(TubeLightModel, [radiance]);
+
+impl<S> TubeLightModel<S> where S: ScalarFloat {
+    /// The largest area of the tube's silhouette, seen broadside: the
+    /// rectangle along its center line plus the disk of its two caps.
     #[inline]
-    pub fn new(
-        ambient: Vector3<S>, 
-        diffuse: Vector3<S>, 
-        specular: Vector3<S>) -> PointLightModelSpec<S> 
-    {
-        PointLightModelSpec {
-            ambient: ambient,
-            diffuse: diffuse,
-            specular: specular,
+    pub fn projected_area(&self) -> S {
+        let pi: S = num_traits::cast(core::f64::consts::PI).unwrap();
+        let two = S::one() + S::one();
+
+        two * self.radius * self.length + pi * self.radius * self.radius
+    }
+}
+
+impl<S> TubeLight<S> where S: ScalarFloat {
+    /// The endpoints of the tube's center line in world space. The tube runs
+    /// along the light's right axis from the first endpoint to the second.
+    #[inline]
+    pub fn endpoints(&self) -> (Vector3<S>, Vector3<S>) {
+        let two = S::one() + S::one();
+        let half_segment = self.right_axis() * (self.model().length / two);
+        let center = self.position();
+
+        (center - half_segment, center + half_segment)
+    }
+
+    /// The radiance `Le(p, ω)` the tube emits from the point `point` on its
+    /// surface, with `direction` pointing from the receiver towards the
+    /// point, as in [`DiskLight::emitted_radiance`].
+    pub fn emitted_radiance(&self, point: &Vector3<S>, direction: &Vector3<S>) -> Vector3<S> {
+        let two = S::one() + S::one();
+        let half_length = self.model().length / two;
+        let axis = self.right_axis();
+        let along = (point - self.position()).dot(&axis).max(-half_length).min(half_length);
+        let normal = point - (self.position() + axis * along);
+        if direction.dot(&normal) < S::zero() {
+            self.model().emitted_radiance()
+        } else {
+            Vector3::zero()
         }
     }
 }
 
+/// A specification describing a beam light, such as a laser: a cylinder of
+/// light of constant radius starting at the light's position and running
+/// along its forward axis for a fixed length, without angular or distance
+/// falloff.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Copy, Clone, Debug, PartialEq)]
-pub struct PointLightModel<S> {
-    pub ambient: Vector3<S>,
-    pub diffuse: Vector3<S>,
-    pub specular: Vector3<S>,
+pub struct BeamLightModelSpec<S> {
+    /// The radius of the beam in world units.
+    pub radius: S,
+    /// The length of the beam in world units.
+    pub length: S,
+    /// The color of the light carried by the beam.
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::vector3"))]
+    pub color: Vector3<S>,
 }
 
-impl<S> IlluminationModel for PointLightModel<S> 
-    where S: ScalarFloat
-{
-    type Spec = PointLightModelSpec<S>;
-
+impl<S> BeamLightModelSpec<S> where S: ScalarFloat {
+    /// Construct a new beam light specification.
     #[inline]
-    fn from_spec(spec: &Self::Spec) -> Self {
-        PointLightModel {
-            ambient: spec.ambient,
-            diffuse: spec.diffuse,
-            specular: spec.specular,
+    pub fn new(radius: S, length: S, color: Vector3<S>) -> BeamLightModelSpec<S> {
+        BeamLightModelSpec {
+            radius: radius,
+            length: length,
+            color: color,
         }
     }
 }
 
+/// The illumination model of a beam light.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct BeamLightModel<S> {
+    /// The radius of the beam in world units.
+    pub radius: S,
+    /// The length of the beam in world units.
+    pub length: S,
+    /// The color of the light carried by the beam.
+    pub color: Vector3<S>,
+}
+
+impl_illumination_model!(BeamLightModel, BeamLightModelSpec, [radius, length, color]);
 
+impl_dimmable!(BeamLightModel, [color]);
+
+/// A finite cylinder in world space.
 #[derive(Copy, Clone, Debug, PartialEq)]
-pub struct SpotLightModelSpec<S> {
-    cutoff: S,
-    outer_cutoff: S,
-    /// The spotlight illumination parameters.
-    ambient: Vector3<S>,
-    diffuse: Vector3<S>,
-    specular: Vector3<S>,
-    /// The spotlight attenuation parameters.
-    constant: S,
-    linear: S,
-    quadratic: S,
+pub struct Cylinder<S> {
+    /// The center of the cylinder's starting cap.
+    pub start: Vector3<S>,
+    /// The unit direction of the cylinder's axis.
+    pub axis: Vector3<S>,
+    pub length: S,
+    pub radius: S,
 }
 
-impl<S> SpotLightModelSpec<S> where S: ScalarFloat {
+impl<S> Cylinder<S> where S: ScalarFloat {
+    /// Determine whether a point lies inside the cylinder.
     #[inline]
-    pub fn new(
-        cutoff: S,
-        outer_cutoff: S,
-        ambient: Vector3<S>,
-        diffuse: Vector3<S>,
-        specular: Vector3<S>,
-        constant: S,
-        linear: S,
-        quadratic: S) -> SpotLightModelSpec<S> 
-    {
-        SpotLightModelSpec { 
-            cutoff: cutoff,
-            outer_cutoff: outer_cutoff,
-            ambient: ambient,
-            diffuse: diffuse,
-            specular: specular,
-            constant: constant,
-            linear: linear,
-            quadratic: quadratic,
+    pub fn contains(&self, point: &Vector3<S>) -> bool {
+        let to_point = point - self.start;
+        let axial = to_point.dot(&self.axis);
+        if axial < S::zero() || axial > self.length {
+            return false;
         }
-    }   
-}
+        let radial_squared = to_point.magnitude_squared() - axial * axial;
 
-#[derive(Copy, Clone, Debug, PartialEq)]
-pub struct SpotLightModel<S> {
-    pub cutoff: S,
-    pub outer_cutoff: S,
-    /// The spotlight illumination parameters.
-    pub ambient: Vector3<S>,
-    pub diffuse: Vector3<S>,
-    pub specular: Vector3<S>,
-    /// The spotlight attenuation parameters.
-    pub constant: S,
-    pub linear: S,
-    pub quadratic: S,
+        radial_squared <= self.radius * self.radius
+    }
+
+    /// The center of the cylinder's ending cap.
+    #[inline]
+    pub fn end(&self) -> Vector3<S> {
+        self.start + self.axis * self.length
+    }
 }
 
-impl<S> IlluminationModel for SpotLightModel<S> where S: ScalarFloat {
-    type Spec = SpotLightModelSpec<S>;
+impl<S> BeamLight<S> where S: ScalarFloat {
+    /// The cylinder the beam fills in world space.
+    #[inline]
+    pub fn cylinder(&self) -> Cylinder<S> {
+        Cylinder {
+            start: self.position(),
+            axis: self.forward_axis(),
+            length: self.model().length,
+            radius: self.model().radius,
+        }
+    }
 
+    /// The light the beam delivers to a point in world space, which is the
+    /// beam's color inside its cylinder and zero elsewhere.
     #[inline]
-    fn from_spec(spec: &Self::Spec) -> Self {
-        SpotLightModel {
-            cutoff: spec.cutoff,
-            outer_cutoff: spec.outer_cutoff,
-            ambient: spec.ambient,
-            diffuse: spec.diffuse,
-            specular: spec.specular,
-            constant: spec.constant,
-            linear: spec.linear,
-            quadratic: spec.quadratic,
+    pub fn illumination(&self, point: &Vector3<S>) -> Vector3<S> {
+        if self.cylinder().contains(point) {
+            self.model().color
+        } else {
+            Vector3::zero()
         }
     }
 }
@@ -202,24 +1431,34 @@ impl<S> IlluminationModel for SpotLightModel<S> where S: ScalarFloat {
 /// (position and orientation) of a spotlight. The spec describes the location, 
 /// local coordinate system, and rotation axis for the light in world space.
 /// The coordinate transformation is right-handed orthonormal transformation.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound(serialize = "S: serde::Serialize", deserialize = "S: serde::Deserialize<'de>")))]
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
 pub struct LightAttitudeSpec<S> {
     /// The location of the light in world space.
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::vector3"))]
     position: Vector3<S>,
     /// The direction of the **negative z-axis** (forward axis) of the light.
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::vector3"))]
     forward: Vector3<S>,
     /// The direction of the **positive x-axis** (right axis) of the light.
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::vector3"))]
     right: Vector3<S>,
     /// The direction of the **positive y-axis** (up axis) of the light.
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::vector3"))]
     up: Vector3<S>,
-    /// The **axis of rotation** of the light. It is not necessary that 
-    /// the axis of rotation of the light be the same as one of the coordinate
-    /// axes.
+    /// The **axis of rotation** of the light. The forward, right, and up axes
+    /// determine the light's orientation on their own, so lights ignore this
+    /// field. It is kept so existing specifications and serialized data stay
+    /// valid, and `Light::rotation_axis` derives the axis from the
+    /// orientation instead.
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::vector3"))]
     axis: Vector3<S>,
 }
 
 impl<S> LightAttitudeSpec<S> where S: ScalarFloat {
-    /// Construct a new camera attitude specification.
+    /// Construct a new camera attitude specification. The rotation `axis` is
+    /// stored but not used by lights; see the field's documentation.
     #[inline]
     pub fn new(
         position: Vector3<S>,
@@ -248,6 +1487,25 @@ impl<S> fmt::Display for LightAttitudeSpec<S> where S: fmt::Display {
     }
 }
 
+/// The policy controlling when a light's view matrix is computed.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ViewMatrixPolicy {
+    /// Recompute the view matrix every time the light moves, so
+    /// `Light::view_matrix` is always up to date. This suits a few lights
+    /// that move every frame and whose view matrix is always read, such as
+    /// shadow casting lights.
+    Eager,
+    /// Recompute the view matrix in `Light::refresh_view_matrix` if the light
+    /// moved since the last refresh. A light moved several times per frame
+    /// and refreshed once computes its view matrix once. This suits many
+    /// mostly static lights whose view matrix is read occasionally.
+    Lazy,
+    /// Never cache the view matrix, and compute it on every read. This suits
+    /// lights whose view matrix is rarely read at all.
+    OnDemand,
+}
+
 /// This type contains all the data for tracking the position and orientation
 /// of a light in world space. The light attitude here uses a right-handed 
 /// coordinate system facing along the light's **negative z-axis**.
@@ -258,50 +1516,193 @@ impl<S> fmt::Display for LightAttitudeSpec<S> where S: fmt::Display {
 struct LightAttitude<S> {
     /// The world space position of the light.
     position: Vector3<S>,
-    /// The distance from the light perpendicular to the light's **xy-plane**.
-    forward: Vector4<S>,
-    /// The horizontal axis of the light's coordinate system.
-    right: Vector4<S>,
-    /// The vertical axis of the light's coordinate system.
-    up: Vector4<S>,
-    /// The **axis of rotation** of the light. It is not necessary that 
-    /// the axis of rotation of the light be the same as one of the coordinate
-    /// axes.
-    axis: Quaternion<S>,
-    /// The translation matrix mapping objects from the world space coordinate
-    /// frame to the coordinate frame centered at the eye position of the camera.
-    translation_matrix: Matrix4x4<S>,
-    /// The rotation matrix rotating the a vector in world space to the coordinate
-    /// system of the camera's view space.
-    rotation_matrix: Matrix4x4<S>,
-    /// The viewing matrix of the camera mapping the complete translation + rotation
-    /// of the camera.
+    /// The orientation of the light as a unit quaternion. Rotating the
+    /// light's local coordinate axes by the orientation yields the light's
+    /// axes in world space. The axes and the rotation and translation parts
+    /// of the viewing transformation are computed from the position and
+    /// orientation on demand, which keeps the per-light memory footprint small.
+    orientation: Quaternion<S>,
+    /// When the viewing matrix is computed.
+    policy: ViewMatrixPolicy,
+    /// The viewing matrix of the light mapping the complete translation
+    /// + rotation of the light, as of the last time it was computed.
+    ///
+    /// Under the on-demand policy the matrix is scratch space for the raw
+    /// accessors, and is never read without recomputing it.
     view_matrix: Matrix4x4<S>,
+    /// Whether the light moved since the viewing matrix was computed.
+    view_matrix_stale: bool,
 }
 
 impl<S> LightAttitude<S> where S: ScalarFloat {
-    /// Construct the camera's viewing transformation from its specification. 
+    /// Construct the light's viewing transformation from its specification.
     #[inline]
-    fn from_spec(spec: &LightAttitudeSpec<S>) -> Self {
-        let axis = Quaternion::from_parts(S::zero(), spec.axis);
-        let translation_matrix = Matrix4x4::from_affine_translation(
-            &(-spec.position)
-        );
-        let rotation_matrix = Matrix4x4::from(&axis);
-        let view_matrix = rotation_matrix * translation_matrix;
+    fn from_spec(spec: &LightAttitudeSpec<S>, policy: ViewMatrixPolicy) -> Self {
+        let orientation = Self::orientation_from_basis(&spec.forward, &spec.right, &spec.up);
+
+        Self::from_parts(spec.position, orientation, policy)
+    }
 
+    /// Construct an attitude from a position and a unit orientation. The
+    /// viewing matrix is computed up front under every policy.
+    #[inline]
+    fn from_parts(position: Vector3<S>, orientation: Quaternion<S>, policy: ViewMatrixPolicy) -> Self {
         Self {
-            position: spec.position,
-            forward: spec.forward.to_homogeneous(),
-            right: spec.right.to_homogeneous(),
-            up: spec.up.to_homogeneous(),
-            axis: axis,
-            translation_matrix: translation_matrix,
-            rotation_matrix: rotation_matrix,
-            view_matrix: view_matrix,
+            position: position,
+            orientation: orientation,
+            policy: policy,
+            view_matrix: Self::compute_view_matrix(&position, &orientation),
+            view_matrix_stale: false,
+        }
+    }
+
+    /// Compute the unit orientation rotating the light's local axes onto a
+    /// world space basis.
+    ///
+    /// The basis is orthonormalized first. The forward axis is kept, the
+    /// right axis is made perpendicular to it, and the up axis completes a
+    /// right-handed frame. A degenerate basis is repaired: a zero forward axis
+    /// becomes the **negative z-axis**, and a right axis that is zero or
+    /// parallel to the forward axis is derived from the up axis instead.
+    fn orientation_from_basis(forward: &Vector3<S>, right: &Vector3<S>, up: &Vector3<S>) -> Quaternion<S> {
+        let zero = S::zero();
+        let one = S::one();
+        let quarter: S = num_traits::cast(0.25).unwrap();
+        let magnitude_squared = forward.magnitude_squared();
+        let forward = if magnitude_squared > S::epsilon() && magnitude_squared.is_finite() {
+            forward.normalize()
+        } else {
+            Vector3::new(zero, zero, -one)
+        };
+        let candidates = [
+            *right,
+            forward.cross(up),
+            forward.cross(&Vector3::new(zero, one, zero)),
+            forward.cross(&Vector3::new(one, zero, zero)),
+        ];
+        let right = candidates.iter()
+            .map(|candidate| candidate - forward * candidate.dot(&forward))
+            .find(|candidate| candidate.magnitude_squared() > S::epsilon())
+            .unwrap()
+            .normalize();
+        let up = right.cross(&forward);
+
+        // The rotation matrix has the columns `right`, `up`, and `-forward`.
+        let (m00, m10, m20) = (right.x, right.y, right.z);
+        let (m01, m11, m21) = (up.x, up.y, up.z);
+        let (m02, m12, m22) = (-forward.x, -forward.y, -forward.z);
+        let trace = m00 + m11 + m22;
+        let (s, x, y, z) = if trace > zero {
+            let k = (trace + one).sqrt() * (one + one);
+            (k * quarter, (m21 - m12) / k, (m02 - m20) / k, (m10 - m01) / k)
+        } else if m00 > m11 && m00 > m22 {
+            let k = (one + m00 - m11 - m22).sqrt() * (one + one);
+            ((m21 - m12) / k, k * quarter, (m01 + m10) / k, (m02 + m20) / k)
+        } else if m11 > m22 {
+            let k = (one + m11 - m00 - m22).sqrt() * (one + one);
+            ((m02 - m20) / k, (m01 + m10) / k, k * quarter, (m12 + m21) / k)
+        } else {
+            let k = (one + m22 - m00 - m11).sqrt() * (one + one);
+            ((m10 - m01) / k, (m02 + m20) / k, (m12 + m21) / k, k * quarter)
+        };
+
+        Quaternion::new(s, x, y, z).normalize()
+    }
+
+    /// Mark the viewing matrix as out of date after the attitude changes. 
+    /// Under the eager policy the matrix is recomputed immediately.
+    #[inline]
+    fn invalidate_view_matrix(&mut self) {
+        match self.policy {
+            ViewMatrixPolicy::Eager => {
+                self.view_matrix = Self::compute_view_matrix(&self.position, &self.orientation);
+                self.view_matrix_stale = false;
+            }
+            ViewMatrixPolicy::Lazy | ViewMatrixPolicy::OnDemand => {
+                self.view_matrix_stale = true;
+            }
+        }
+    }
+
+    /// Whether the stored viewing matrix can be used without recomputing it.
+    #[inline]
+    fn has_current_view_matrix(&self) -> bool {
+        self.policy != ViewMatrixPolicy::OnDemand && !self.view_matrix_stale
+    }
+
+    /// Bring the stored viewing matrix up to date and get a reference to it.
+    /// Under the on-demand policy the matrix is recomputed on every call.
+    #[inline]
+    fn refresh_view_matrix(&mut self) -> &Matrix4x4<S> {
+        if !self.has_current_view_matrix() {
+            self.view_matrix = Self::compute_view_matrix(&self.position, &self.orientation);
+            self.view_matrix_stale = self.policy == ViewMatrixPolicy::OnDemand;
+        }
+
+        &self.view_matrix
+    }
+
+    /// Get the light's current viewing matrix, using the cached matrix if the
+    /// policy allows it and it is up to date, and computing it otherwise.
+    #[inline]
+    fn view_matrix(&self) -> Matrix4x4<S> {
+        if self.has_current_view_matrix() {
+            self.view_matrix
+        } else {
+            Self::compute_view_matrix(&self.position, &self.orientation)
         }
     }
 
+    /// Change when the viewing matrix is computed.
+    #[inline]
+    fn set_policy(&mut self, policy: ViewMatrixPolicy) {
+        self.policy = policy;
+        if self.view_matrix_stale {
+            self.invalidate_view_matrix();
+        }
+    }
+
+    /// Compute the viewing matrix mapping world space to the light's local
+    /// coordinate frame.
+    #[inline]
+    fn compute_view_matrix(position: &Vector3<S>, orientation: &Quaternion<S>) -> Matrix4x4<S> {
+        stats::record(Counter::ViewMatricesRebuilt, 1);
+        let translation_matrix = Matrix4x4::from_affine_translation(&(-position));
+        let rotation_matrix = Matrix4x4::from(&orientation.conjugate());
+
+        rotation_matrix * translation_matrix
+    }
+
+    /// Rotate a vector in the light's local coordinate frame into world space.
+    #[inline]
+    fn rotate(&self, vector: &Vector3<S>) -> Vector3<S> {
+        let two = S::one() + S::one();
+        let s = self.orientation.s;
+        let u = self.orientation.v;
+        let uv = u.cross(vector);
+        let uuv = u.cross(&uv);
+
+        vector + uv * (two * s) + uuv * two
+    }
+
+    /// Get the light's forward axis in world space.
+    #[inline]
+    fn forward_axis(&self) -> Vector3<S> {
+        self.rotate(&self.forward_axis_eye())
+    }
+
+    /// Get the light's right axis in world space.
+    #[inline]
+    fn right_axis(&self) -> Vector3<S> {
+        self.rotate(&self.right_axis_eye())
+    }
+
+    /// Get the light's up axis in world space.
+    #[inline]
+    fn up_axis(&self) -> Vector3<S> {
+        self.rotate(&self.up_axis_eye())
+    }
+
     /// Get the camera's up direction in camera space.
     #[inline]
     fn up_axis_eye(&self) -> Vector3<S> {
@@ -333,52 +1734,38 @@ impl<S> LightAttitude<S> where S: ScalarFloat {
     /// attitude.
     #[inline]
     fn update_position_eye(&mut self, delta_attitude: &DeltaAttitude<S>) {
-        self.position += self.forward.contract() * -delta_attitude.delta_position.z;
-        self.position += self.up.contract()      *  delta_attitude.delta_position.y;
-        self.position += self.right.contract()   *  delta_attitude.delta_position.x;
-
-        let translation_inv = Matrix4x4::from_affine_translation(
-            &self.position
-        );
-        self.translation_matrix = translation_inv.inverse().unwrap();
+        self.position += self.forward_axis() * -delta_attitude.delta_position.z;
+        self.position += self.up_axis()      *  delta_attitude.delta_position.y;
+        self.position += self.right_axis()   *  delta_attitude.delta_position.x;
     }
 
     /// Update the light axes so we can rotate the camera about the new rotation axes.
     #[inline]
     fn update_orientation_eye(&mut self, delta_attitude: &DeltaAttitude<S>) {
-        let axis_yaw = Unit::from_value(self.up.contract());
+        let axis_yaw = Unit::from_value(self.up_axis());
         let q_yaw = Quaternion::from_axis_angle(
             &axis_yaw, delta_attitude.yaw
         );
-        self.axis = q_yaw * self.axis;
+        self.orientation = q_yaw * self.orientation;
 
-        let axis_pitch = Unit::from_value(self.right.contract());
+        let axis_pitch = Unit::from_value(self.right_axis());
         let q_pitch = Quaternion::from_axis_angle(
             &axis_pitch, delta_attitude.pitch
         );
-        self.axis = q_pitch * self.axis;
+        self.orientation = q_pitch * self.orientation;
 
-        let axis_roll = Unit::from_value(self.forward.contract());
+        let axis_roll = Unit::from_value(self.forward_axis());
         let q_roll = Quaternion::from_axis_angle(
             &axis_roll, delta_attitude.roll, 
         );
-        self.axis = q_roll * self.axis;
-
-        let rotation_matrix_inv = Matrix4x4::from(&self.axis);
-        self.forward = rotation_matrix_inv * Vector4::new(S::zero(), S::zero(), -S::one(), S::zero());
-        self.right   = rotation_matrix_inv * Vector4::new(S::one(), S::zero(), S::zero(), S::zero());
-        self.up      = rotation_matrix_inv * Vector4::new(S::zero(), S::one(), S::zero(), S::zero());
-        self.rotation_matrix = rotation_matrix_inv.inverse().unwrap();
+        // Renormalize to keep rounding errors from accumulating over many updates.
+        self.orientation = (q_roll * self.orientation).normalize();
     }
 
     #[inline]
     fn update_position_world(&mut self, new_position: &Vector3<S>) {
-        self.position = new_position.clone();
-        let translation_inv = Matrix4x4::from_affine_translation(
-            &self.position
-        );
-        self.translation_matrix = translation_inv.inverse().unwrap();
-        self.view_matrix = self.rotation_matrix * self.translation_matrix;
+        self.position = *new_position;
+        self.invalidate_view_matrix();
     }
 
     /// Update the light's attitude based on the input change in light 
@@ -387,15 +1774,48 @@ impl<S> LightAttitude<S> where S: ScalarFloat {
     fn update(&mut self, delta_attitude: &DeltaAttitude<S>) {
         self.update_orientation_eye(delta_attitude);
         self.update_position_eye(delta_attitude);
-        self.view_matrix = self.rotation_matrix * self.translation_matrix;
+        self.invalidate_view_matrix();
     }
-}
 
+    /// Blend two attitudes, linearly interpolating the positions and
+    /// spherically interpolating the orientations along the shortest arc.
+    /// The result uses the current attitude's view matrix policy.
+    fn interpolate(previous: &Self, current: &Self, alpha: S) -> Self {
+        let position = previous.position + (current.position - previous.position) * alpha;
+        let q0 = previous.orientation;
+        let mut q1 = current.orientation;
+        let mut cos_theta = q0.s * q1.s + q0.v.dot(&q1.v);
+        if cos_theta < S::zero() {
+            q1 = Quaternion::from_parts(-q1.s, -q1.v);
+            cos_theta = -cos_theta;
+        }
+        // Nearly parallel orientations are blended linearly to avoid dividing
+        // by a vanishing sine.
+        let threshold: S = num_traits::cast(0.9995).unwrap();
+        let (w0, w1) = if cos_theta > threshold {
+            (S::one() - alpha, alpha)
+        } else {
+            let theta = cos_theta.acos();
+            let sin_theta = theta.sin();
+
+            (((S::one() - alpha) * theta).sin() / sin_theta, (alpha * theta).sin() / sin_theta)
+        };
+        let orientation = Quaternion::from_parts(q0.s * w0 + q1.s * w1, q0.v * w0 + q1.v * w1).normalize();
 
+        Self::from_parts(position, orientation, current.policy)
+    }
+}
 
+#[derive(Clone, Debug)]
 pub struct Light<S, M> {
     model: M,
     attitude: LightAttitude<S>,
+    jitter: LightJitter<S>,
+    exposure_compensation: S,
+    layers: LightLayers,
+    diffuse_factor: S,
+    specular_factor: S,
+    shadow_settings: Option<ShadowSettings<S>>,
 }
 
 impl<S, M> Light<S, M>
@@ -406,9 +1826,25 @@ impl<S, M> Light<S, M>
         model_spec: &M::Spec, 
         attitude_spec: &LightAttitudeSpec<S>) -> Self {
 
+        Self::with_view_matrix_policy(model_spec, attitude_spec, ViewMatrixPolicy::Eager)
+    }
+
+    /// Construct a new light whose view matrix is computed according to the
+    /// given policy.
+    pub fn with_view_matrix_policy(
+        model_spec: &M::Spec,
+        attitude_spec: &LightAttitudeSpec<S>,
+        policy: ViewMatrixPolicy) -> Self {
+
         Light {
             model: M::from_spec(model_spec),
-            attitude: LightAttitude::from_spec(attitude_spec),
+            attitude: LightAttitude::from_spec(attitude_spec, policy),
+            jitter: LightJitter::zero(),
+            exposure_compensation: S::zero(),
+            layers: LightLayers::DEFAULT,
+            diffuse_factor: S::one(),
+            specular_factor: S::one(),
+            shadow_settings: None,
         }
     }
 
@@ -416,11 +1852,13 @@ impl<S, M> Light<S, M>
     /// the light's local coordinate frame.
     #[inline]
     pub fn update_attitude_eye(&mut self, delta_attitude: &DeltaAttitude<S>) {
+        stats::record(Counter::LightsUpdated, 1);
         self.attitude.update(delta_attitude);
     }
 
     #[inline]
     pub fn update_position_world(&mut self, new_position: &Vector3<S>) {
+        stats::record(Counter::LightsUpdated, 1);
         self.attitude.update_position_world(new_position);
     }
 
@@ -429,28 +1867,90 @@ impl<S, M> Light<S, M>
         &self.model
     }
 
+    /// Get a mutable reference to the light's illumination model so its
+    /// parameters can be edited in place.
+    #[inline]
+    pub fn model_mut(&mut self) -> &mut M {
+        &mut self.model
+    }
+
+    /// Extract the specification describing the light's current illumination
+    /// model.
+    #[inline]
+    pub fn model_spec(&self) -> M::Spec {
+        self.model.to_spec()
+    }
+
     /// Get the camera's position in world space.
     #[inline]
     pub fn position(&self) -> Vector3<S> { 
         self.attitude.position
     }
 
+    /// Get the parameters of the light's per-frame position jitter.
+    #[inline]
+    pub fn jitter(&self) -> &LightJitter<S> {
+        &self.jitter
+    }
+
+    /// Set the parameters of the light's per-frame position jitter.
+    #[inline]
+    pub fn set_jitter(&mut self, jitter: LightJitter<S>) {
+        self.jitter = jitter;
+    }
+
+    /// Get the multiplier on the light's diffuse contribution.
+    #[inline]
+    pub fn diffuse_factor(&self) -> S {
+        self.diffuse_factor
+    }
+
+    /// Set the multiplier on the light's diffuse contribution. A factor of
+    /// zero makes a specular-only light, such as a rim light that should
+    /// only add highlights.
+    #[inline]
+    pub fn set_diffuse_factor(&mut self, factor: S) {
+        self.diffuse_factor = factor;
+    }
+
+    /// Get the multiplier on the light's specular contribution.
+    #[inline]
+    pub fn specular_factor(&self) -> S {
+        self.specular_factor
+    }
+
+    /// Set the multiplier on the light's specular contribution. A factor of
+    /// zero makes a diffuse-only light, such as a fill light that should not
+    /// add highlights.
+    #[inline]
+    pub fn set_specular_factor(&mut self, factor: S) {
+        self.specular_factor = factor;
+    }
+
+    /// Get the light's sample position for a frame, offset within the light's
+    /// source radius. Sampling shadows from the jittered position each frame
+    /// and accumulating them temporally produces soft shadows.
+    #[inline]
+    pub fn jittered_position(&self, frame_index: u32) -> Vector3<S> {
+        self.attitude.position + self.jitter.offset(frame_index)
+    }
+
     /// Get the camera's up direction in world space.
     #[inline]
     pub fn up_axis(&self) -> Vector3<S> {
-        self.attitude.up.contract()
+        self.attitude.up_axis()
     }
         
     /// Get the camera's right axis in world space.
     #[inline]
     pub fn right_axis(&self) -> Vector3<S> {
-        self.attitude.right.contract()
+        self.attitude.right_axis()
     }
         
     /// Get the camera's forward axis in world space.
     #[inline]
     pub fn forward_axis(&self) -> Vector3<S> {
-        self.attitude.forward.contract()
+        self.attitude.forward_axis()
     }
 
     /// Get the camera's **vertical y-axis** in camera view space.
@@ -471,20 +1971,221 @@ impl<S, M> Light<S, M>
         self.attitude.forward_axis_eye()
     }
         
-    /// Get the camera's axis of rotation.
+    /// Get the unit axis of the rotation taking the light's local axes to its
+    /// world space axes. An unrotated light has no unique axis, and reports
+    /// the **negative z-axis**, its forward axis.
     #[inline]
     pub fn rotation_axis(&self) -> Vector3<S> {
-        self.attitude.axis.v
+        self.rotation_axis_angle().0
+    }
+
+    /// Get the rotation taking the light's local axes to its world space axes
+    /// as a unit axis and an angle in `[0, 2π)` about it, as in
+    /// [`rotation_axis`](#method.rotation_axis).
+    pub fn rotation_axis_angle(&self) -> (Vector3<S>, Radians<S>) {
+        let two = S::one() + S::one();
+        let orientation = &self.attitude.orientation;
+        let sin_half_angle = orientation.v.magnitude();
+        if sin_half_angle <= S::epsilon() {
+            return (Vector3::new(S::zero(), S::zero(), -S::one()), Radians(S::zero()));
+        }
+        let angle = two * sin_half_angle.atan2(orientation.s);
+
+        (orientation.v / sin_half_angle, Radians(angle))
+    }
+
+    /// Get the light's current view matrix. Under the eager policy, and
+    /// under the lazy policy for a light refreshed since it last moved, this
+    /// copies the cached matrix. Otherwise the matrix is computed without
+    /// writing the cache.
+    #[inline]
+    pub fn view_matrix(&self) -> Matrix4x4<S> {
+        self.attitude.view_matrix()
+    }
+
+    /// Bring the light's stored view matrix up to date, and get a reference
+    /// to it. Under the lazy policy the matrix is recomputed only if the
+    /// light moved since the last refresh, and under the on-demand policy it
+    /// is recomputed on every call.
+    #[inline]
+    pub fn refresh_view_matrix(&mut self) -> &Matrix4x4<S> {
+        self.attitude.refresh_view_matrix()
+    }
+
+    /// Get the light's current view matrix as 16 floats in column-major
+    /// order, for passing to graphics APIs such as `glUniformMatrix4fv`
+    /// without a copy. The matrix is refreshed first, as in
+    /// [`refresh_view_matrix`](#method.refresh_view_matrix).
+    #[inline]
+    pub fn view_matrix_as_slice(&mut self) -> &[S; 16] {
+        self.refresh_view_matrix().as_ref()
+    }
+
+    /// Get a pointer to the first of the 16 column-major floats of the
+    /// light's current view matrix, for FFI. The matrix is refreshed first,
+    /// and the pointer is valid until the light is next mutated, moved, or
+    /// dropped.
+    #[inline]
+    pub fn view_matrix_as_ptr(&mut self) -> *const S {
+        self.refresh_view_matrix().as_ptr()
+    }
+
+    /// Get the light's position in world space as three floats.
+    #[inline]
+    pub fn position_as_slice(&self) -> &[S; 3] {
+        self.attitude.position.as_ref()
     }
 
+    /// Get the policy controlling when the light's view matrix is computed.
     #[inline]
-    pub fn view_matrix(&self) -> &Matrix4x4<S> {
-        &self.attitude.view_matrix
+    pub fn view_matrix_policy(&self) -> ViewMatrixPolicy {
+        self.attitude.policy
+    }
+
+    /// Change when the light's view matrix is computed.
+    #[inline]
+    pub fn set_view_matrix_policy(&mut self, policy: ViewMatrixPolicy) {
+        self.attitude.set_policy(policy);
     }
  
+    /// Blend the attitudes of two snapshots of the same light, e.g. from the
+    /// previous and current simulation steps, for rendering at a different
+    /// rate than the simulation. An `alpha` of zero yields the previous
+    /// attitude and one yields the current attitude. The blended light takes
+    /// its illumination model, jitter, exposure compensation, layers,
+    /// contribution factors, shadow settings, and view matrix policy from
+    /// `current`.
+    pub fn interpolate(previous: &Self, current: &Self, alpha: S) -> Self
+        where M: Clone
+    {
+        Light {
+            model: current.model.clone(),
+            attitude: LightAttitude::interpolate(&previous.attitude, &current.attitude, alpha),
+            jitter: current.jitter,
+            exposure_compensation: current.exposure_compensation,
+            layers: current.layers,
+            diffuse_factor: current.diffuse_factor,
+            specular_factor: current.specular_factor,
+            shadow_settings: current.shadow_settings,
+        }
+    }
+
     #[inline]
     pub fn model_matrix(&self) -> Matrix4x4<S> {
         Matrix4x4::from_affine_translation(&self.position())
     }
 }
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+
+    const POLICIES: [ViewMatrixPolicy; 3] = [ViewMatrixPolicy::Eager, ViewMatrixPolicy::Lazy, ViewMatrixPolicy::OnDemand];
+
+    fn attitude_spec() -> LightAttitudeSpec<f64> {
+        LightAttitudeSpec::new(
+            Vector3::new(1.0, 2.0, 3.0),
+            Vector3::new(0.0, 0.0, -1.0),
+            Vector3::new(1.0, 0.0, 0.0),
+            Vector3::new(0.0, 1.0, 0.0),
+            Vector3::new(0.0, 0.0, 1.0),
+        )
+    }
+
+    fn point_light(policy: ViewMatrixPolicy) -> PointLight<f64> {
+        let model_spec = PointLightModelSpec::new(
+            Vector3::new(0.1, 0.1, 0.1),
+            Vector3::new(0.8, 0.8, 0.8),
+            Vector3::new(1.0, 1.0, 1.0),
+        );
+
+        PointLight::with_view_matrix_policy(&model_spec, &attitude_spec(), policy)
+    }
+
+    /// The view matrix of a light computed from its current attitude.
+    fn expected_view_matrix<M>(light: &Light<f64, M>) -> Matrix4x4<f64> {
+        LightAttitude::compute_view_matrix(&light.attitude.position, &light.attitude.orientation)
+    }
+
+    fn move_light<M: IlluminationModel>(light: &mut Light<f64, M>, step: u32) {
+        let step = step as f64;
+        let delta_attitude = DeltaAttitude::new(
+            Vector3::new(0.5, -0.25, 1.0),
+            Radians(0.1 * step),
+            Radians(0.2),
+            Radians(-0.05 * step),
+        );
+        light.update_attitude_eye(&delta_attitude);
+        light.update_position_world(&Vector3::new(step, 2.0 * step, -step));
+    }
+
+    #[test]
+    fn test_view_matrix_is_current_under_every_policy() {
+        for &policy in POLICIES.iter() {
+            let mut light = point_light(policy);
+            assert_eq!(light.view_matrix(), expected_view_matrix(&light), "{:?}", policy);
+
+            for step in 1..4 {
+                move_light(&mut light, step);
+                let expected = expected_view_matrix(&light);
+
+                assert_eq!(light.view_matrix(), expected, "{:?}", policy);
+                assert_eq!(*light.refresh_view_matrix(), expected, "{:?}", policy);
+                assert_eq!(light.view_matrix(), expected, "{:?}", policy);
+            }
+        }
+    }
+
+    #[test]
+    fn test_view_matrix_cache_follows_policy() {
+        let mut eager = point_light(ViewMatrixPolicy::Eager);
+        let mut lazy = point_light(ViewMatrixPolicy::Lazy);
+        let mut on_demand = point_light(ViewMatrixPolicy::OnDemand);
+        move_light(&mut eager, 1);
+        move_light(&mut lazy, 1);
+        move_light(&mut on_demand, 1);
+
+        // Eager lights recompute the matrix as they move, and lazy lights
+        // when they are refreshed.
+        assert!(eager.attitude.has_current_view_matrix());
+        assert!(!lazy.attitude.has_current_view_matrix());
+        lazy.refresh_view_matrix();
+        assert!(lazy.attitude.has_current_view_matrix());
+        // On-demand lights never read the stored matrix.
+        on_demand.refresh_view_matrix();
+        assert!(!on_demand.attitude.has_current_view_matrix());
+    }
+
+    #[test]
+    fn test_set_view_matrix_policy_keeps_view_matrix_current() {
+        for &from in POLICIES.iter() {
+            for &to in POLICIES.iter() {
+                let mut light = point_light(from);
+                move_light(&mut light, 1);
+                light.set_view_matrix_policy(to);
+
+                assert_eq!(light.view_matrix_policy(), to);
+                assert_eq!(light.view_matrix(), expected_view_matrix(&light), "{:?} to {:?}", from, to);
+                if to == ViewMatrixPolicy::Eager {
+                    assert!(light.attitude.has_current_view_matrix());
+                }
+
+                move_light(&mut light, 2);
+                assert_eq!(light.view_matrix(), expected_view_matrix(&light), "{:?} to {:?}", from, to);
+            }
+        }
+    }
+
+    #[test]
+    fn test_interpolate_takes_current_policy() {
+        let previous = point_light(ViewMatrixPolicy::Eager);
+        let mut current = point_light(ViewMatrixPolicy::Lazy);
+        move_light(&mut current, 1);
+        let light = Light::interpolate(&previous, &current, 1.0);
+
+        assert_eq!(light.view_matrix_policy(), ViewMatrixPolicy::Lazy);
+        assert_eq!(light.view_matrix(), expected_view_matrix(&light));
+    }
+}
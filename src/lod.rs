@@ -0,0 +1,226 @@
+use cglinalg::{
+    Magnitude,
+    Vector3,
+    ScalarFloat,
+};
+use crate::{
+    BeamLightModel,
+    DirectionalLightModel,
+    DiskLightModel,
+    IlluminationModel,
+    Light,
+    PointLightModel,
+    SphereLightModel,
+    SpotLightModel,
+    TubeLightModel,
+};
+use crate::color::luminance;
+use crate::ies::IesLightModel;
+use crate::mesh::MeshLightModel;
+use crate::pbr::PbrLightModel;
+use crate::polygon::NGonLightModel;
+use crate::stats;
+use crate::stats::Counter;
+
+
+/// The representation a light is rendered with at its current level of
+/// detail.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum LightLod {
+    /// The light is shaded with its full representation.
+    Full,
+    /// The light is shaded with a cheaper representation: area lights are
+    /// shaded as point lights, and shadow casting lights are shaded without
+    /// shadows.
+    Reduced,
+    /// The light's contribution is too small to matter and it is skipped.
+    Culled,
+}
+
+/// A lighting model whose visual importance can be estimated for level of
+/// detail selection.
+pub trait Importance<S> {
+    /// The luminance of the light's emission, before distance attenuation.
+    fn brightness(&self) -> S;
+}
+
+impl<S> Importance<S> for PointLightModel<S> where S: ScalarFloat {
+    #[inline]
+    fn brightness(&self) -> S {
+        luminance(&self.diffuse).max(luminance(&self.specular))
+    }
+}
+
+impl<S> Importance<S> for SpotLightModel<S> where S: ScalarFloat {
+    #[inline]
+    fn brightness(&self) -> S {
+        luminance(&self.diffuse).max(luminance(&self.specular))
+    }
+}
+
+impl<S> Importance<S> for DiskLightModel<S> where S: ScalarFloat {
+    #[inline]
+    fn brightness(&self) -> S {
+        luminance(&self.diffuse).max(luminance(&self.specular))
+    }
+}
+
+impl<S> Importance<S> for SphereLightModel<S> where S: ScalarFloat {
+    /// The luminous intensity of the sphere seen from afar, its radiance
+    /// times its projected area `πr^2`.
+    #[inline]
+    fn brightness(&self) -> S {
+        let pi: S = num_traits::cast(core::f64::consts::PI).unwrap();
+
+        luminance(&self.radiance) * pi * self.radius * self.radius
+    }
+}
+
+impl<S> Importance<S> for TubeLightModel<S> where S: ScalarFloat {
+    /// The luminous intensity of the tube seen broadside from afar, its
+    /// radiance times its projected area.
+    #[inline]
+    fn brightness(&self) -> S {
+        luminance(&self.radiance) * self.projected_area()
+    }
+}
+
+impl<S> Importance<S> for IesLightModel<S> where S: ScalarFloat {
+    /// The largest luminous intensity of the light in any direction.
+    #[inline]
+    fn brightness(&self) -> S {
+        luminance(&self.color) * self.web.max_candela()
+    }
+}
+
+impl<S> Importance<S> for NGonLightModel<S> where S: ScalarFloat {
+    /// The luminous intensity of the polygon seen face on, its radiance
+    /// times its area.
+    #[inline]
+    fn brightness(&self) -> S {
+        luminance(&self.radiance) * self.area()
+    }
+}
+
+impl<S> Importance<S> for MeshLightModel<S> where S: ScalarFloat {
+    /// The luminous intensity of the mesh if all of its triangles faced the
+    /// viewer, its power divided by `π`.
+    #[inline]
+    fn brightness(&self) -> S {
+        let pi: S = num_traits::cast(core::f64::consts::PI).unwrap();
+
+        luminance(&self.power()) / pi
+    }
+}
+
+impl<S> Importance<S> for BeamLightModel<S> where S: ScalarFloat {
+    #[inline]
+    fn brightness(&self) -> S {
+        luminance(&self.color)
+    }
+}
+
+impl<S> Importance<S> for PbrLightModel<S> where S: ScalarFloat {
+    #[inline]
+    fn brightness(&self) -> S {
+        luminance(&self.emission())
+    }
+}
+
+impl<S> Importance<S> for DirectionalLightModel<S> where S: ScalarFloat {
+    #[inline]
+    fn brightness(&self) -> S {
+        luminance(&self.diffuse).max(luminance(&self.specular))
+    }
+}
+
+/// A policy selecting each light's level of detail from its distance to the
+/// camera and its estimated contribution.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct LodPolicy<S> {
+    /// The distance beyond which lights use their reduced representation.
+    pub reduce_distance: S,
+    /// The distance beyond which lights are culled.
+    pub cull_distance: S,
+    /// Lights whose brightness divided by the squared distance to the camera
+    /// falls below this threshold are culled regardless of distance.
+    pub cull_threshold: S,
+    /// The fraction of each distance used as a dead band when a light
+    /// changes level, so lights near a boundary do not flicker between
+    /// levels from frame to frame.
+    pub hysteresis: S,
+}
+
+impl<S> LodPolicy<S> where S: ScalarFloat {
+    /// Construct a new level of detail policy.
+    #[inline]
+    pub fn new(reduce_distance: S, cull_distance: S, cull_threshold: S, hysteresis: S) -> LodPolicy<S> {
+        LodPolicy {
+            reduce_distance: reduce_distance,
+            cull_distance: cull_distance,
+            cull_threshold: cull_threshold,
+            hysteresis: hysteresis,
+        }
+    }
+
+    /// Select the level of detail of a light viewed from `camera_position`.
+    #[inline]
+    pub fn select<M>(&self, light: &Light<S, M>, camera_position: &Vector3<S>) -> LightLod
+        where M: IlluminationModel + Importance<S>
+    {
+        self.select_with_previous(light, camera_position, None)
+    }
+
+    /// Select the level of detail of a light viewed from `camera_position`,
+    /// given the level the light had on the previous frame. A light only
+    /// changes level once it is past a boundary by the hysteresis margin.
+    pub fn select_with_previous<M>(
+        &self,
+        light: &Light<S, M>,
+        camera_position: &Vector3<S>,
+        previous: Option<LightLod>) -> LightLod
+        where M: IlluminationModel + Importance<S>
+    {
+        let distance = (light.position() - camera_position).magnitude();
+        // Shift the boundaries away from the light's previous level.
+        let margin = |boundary: S, previous_is_finer: bool| {
+            if previous.is_none() {
+                boundary
+            } else if previous_is_finer {
+                boundary * (S::one() + self.hysteresis)
+            } else {
+                boundary * (S::one() - self.hysteresis)
+            }
+        };
+        let reduce_distance = margin(self.reduce_distance, previous == Some(LightLod::Full));
+        let cull_distance = margin(self.cull_distance, previous != Some(LightLod::Culled));
+        let contribution = light.model().brightness() / (distance * distance).max(S::epsilon());
+
+        if distance > cull_distance || contribution < self.cull_threshold {
+            LightLod::Culled
+        } else if distance > reduce_distance {
+            LightLod::Reduced
+        } else {
+            LightLod::Full
+        }
+    }
+
+    /// Select the level of detail of every light in a slice for the current
+    /// frame. `levels` holds the levels from the previous frame, if any, and
+    /// is overwritten with the new levels.
+    pub fn update<M>(&self, lights: &[Light<S, M>], camera_position: &Vector3<S>, levels: &mut Vec<LightLod>)
+        where M: IlluminationModel + Importance<S>
+    {
+        let has_previous = levels.len() == lights.len();
+        levels.resize(lights.len(), LightLod::Full);
+        for (light, level) in lights.iter().zip(levels.iter_mut()) {
+            let previous = if has_previous { Some(*level) } else { None };
+            *level = self.select_with_previous(light, camera_position, previous);
+            match *level {
+                LightLod::Full => {}
+                LightLod::Reduced => stats::record(Counter::LightsReduced, 1),
+                LightLod::Culled => stats::record(Counter::LightsCulled, 1),
+            }
+        }
+    }
+}
@@ -0,0 +1,827 @@
+use cglinalg::{
+    Magnitude,
+    Vector3,
+    ScalarFloat,
+};
+use crate::DiskLight;
+use crate::polygon::NGonLight;
+
+
+/// The number of rows and columns of the table `LtcTable::fit` builds by
+/// default, as in the published tables.
+pub const LTC_TABLE_SIZE: usize = 64;
+
+/// The number of samples along each axis of the unit square used to
+/// integrate the BRDF and the fitting error for one table entry.
+const FIT_SAMPLES: usize = 24;
+
+/// The smallest GGX roughness the table is fitted for. Smoother surfaces use
+/// the entries of this roughness.
+const MIN_ALPHA: f64 = 1e-4;
+
+/// The largest polar angle of the view direction the table is fitted for,
+/// just short of grazing.
+const MAX_THETA: f64 = 1.57;
+
+/// The number of sides of the regular polygon a disk is shaded as.
+const DISK_SIDES: usize = 32;
+
+/// One entry of an `LtcTable`: a linearly transformed cosine fitted to the
+/// GGX BRDF times the cosine for one roughness and view angle.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct LtcEntry<S> {
+    /// The inverse transformation `[a, b, c, d]`, mapping directions in the
+    /// shading frame to the clamped cosine lobe by the matrix
+    /// `[[a, 0, b], [0, 1, 0], [c, 0, d]]`. The matrix is scaled so its
+    /// middle entry is one, which leaves the normalized directions unchanged.
+    pub inverse: [S; 4],
+    /// The integral of the BRDF times the cosine over the hemisphere, with a
+    /// Fresnel reflectance of one.
+    pub magnitude: S,
+    /// The integral of the BRDF times the cosine times Schlick's Fresnel
+    /// weight `(1 - v·h)^5` over the hemisphere.
+    pub fresnel: S,
+}
+
+/// A table of linearly transformed cosines approximating the GGX BRDF, for
+/// shading polygonal and disk area lights in real time.
+///
+/// A linearly transformed cosine is a clamped cosine distribution with its
+/// directions transformed by a 3x3 matrix. Its integral over a polygon is
+/// the integral of the clamped cosine over the polygon transformed by the
+/// inverse matrix, which has a closed form, so an area light's specular
+/// response costs one table lookup and one pass over the light's edges.
+///
+/// The table is indexed by the square root of the GGX roughness along its
+/// columns and by `sqrt(1 - cos_theta)`, where `theta` is the angle between
+/// the view direction and the normal, along its rows. `fit` builds the table
+/// by fitting each entry to the BRDF, which takes over ten seconds for the
+/// default size in an optimized build, so applications bake it once, e.g.
+/// into a `cache`.
+///
+/// Lights near the reflection direction are shaded to within a few percent
+/// of the BRDF's integral. Like any fit of a single cosine lobe, the fitted
+/// lobes underestimate the BRDF's tail at grazing view angles on smooth
+/// surfaces.
+///
+/// See Heitz et al., *Real-Time Polygonal-Light Shading with Linearly
+/// Transformed Cosines*, SIGGRAPH 2016.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LtcTable<S> {
+    size: usize,
+    entries: Vec<LtcEntry<S>>,
+}
+
+impl<S> LtcTable<S> where S: ScalarFloat {
+    /// Fit a `size` by `size` table to the GGX BRDF. The result is `None` if
+    /// `size` is less than two.
+    pub fn fit(size: usize) -> Option<LtcTable<S>> {
+        if size < 2 {
+            return None;
+        }
+
+        let last: S = num_traits::cast(size - 1).unwrap();
+        let min_alpha: S = num_traits::cast(MIN_ALPHA).unwrap();
+        let max_theta: S = num_traits::cast(MAX_THETA).unwrap();
+        let mut entries = vec![
+            LtcEntry { inverse: [S::one(), S::zero(), S::zero(), S::one()], magnitude: S::one(), fresnel: S::zero() };
+            size * size
+        ];
+        // Fit from the roughest lobes, which are nearly cosines, to the
+        // smoothest, starting each fit from its neighbor's solution.
+        let mut normal_incidence = [S::one(), S::one(), S::zero()];
+        for column in (0..size).rev() {
+            let roughness = num_traits::cast::<usize, S>(column).unwrap() / last;
+            let alpha = (roughness * roughness).max(min_alpha);
+            let mut parameters = normal_incidence;
+            for row in 0..size {
+                let x = num_traits::cast::<usize, S>(row).unwrap() / last;
+                let theta = (S::one() - x * x).acos().min(max_theta);
+                let view = Vector3::new(theta.sin(), S::zero(), theta.cos());
+                let moments = BrdfMoments::new(&view, alpha);
+                // At normal incidence the lobe is symmetric about the normal.
+                let isotropic = row == 0;
+                let axis = if isotropic { Vector3::unit_z() } else { moments.direction };
+                let fitted = nelder_mead(parameters, |parameters| {
+                    let ltc = Ltc::new(&axis, parameters, isotropic, moments.magnitude);
+
+                    ltc.error(&view, alpha)
+                });
+                let ltc = Ltc::new(&axis, &fitted, isotropic, moments.magnitude);
+                parameters = [ltc.scale_x, ltc.scale_y, ltc.skew];
+                if isotropic {
+                    normal_incidence = parameters;
+                }
+                entries[row * size + column] = LtcEntry {
+                    inverse: ltc.normalized_inverse(),
+                    magnitude: moments.magnitude,
+                    fresnel: moments.fresnel,
+                };
+            }
+        }
+
+        Some(LtcTable {
+            size: size,
+            entries: entries,
+        })
+    }
+
+    /// Construct a table from entries fitted earlier, such as a table read
+    /// back from a cache, stored row by row as `entries` returns them. The
+    /// result is `None` unless `size` is at least two and there are
+    /// `size * size` entries.
+    pub fn from_entries(size: usize, entries: Vec<LtcEntry<S>>) -> Option<LtcTable<S>> {
+        if size < 2 || entries.len() != size * size {
+            return None;
+        }
+
+        Some(LtcTable {
+            size: size,
+            entries: entries,
+        })
+    }
+
+    /// The number of rows and columns of the table.
+    #[inline]
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// The entries of the table, row by row.
+    #[inline]
+    pub fn entries(&self) -> &[LtcEntry<S>] {
+        &self.entries
+    }
+
+    /// Look up the entry for a GGX roughness `alpha`, the square of the
+    /// perceptual roughness, and the cosine of the angle between the view
+    /// direction and the normal, interpolating bilinearly between entries.
+    pub fn lookup(&self, alpha: S, cos_theta: S) -> LtcEntry<S> {
+        let last: S = num_traits::cast(self.size - 1).unwrap();
+        let x = alpha.max(S::zero()).min(S::one()).sqrt() * last;
+        let y = (S::one() - cos_theta.max(S::zero()).min(S::one())).sqrt() * last;
+        let (column, s) = split(x, self.size);
+        let (row, t) = split(y, self.size);
+        let entry = |row: usize, column: usize| &self.entries[row * self.size + column];
+        let blend = |field: &dyn Fn(&LtcEntry<S>) -> S| {
+            let top = field(entry(row, column)) * (S::one() - s) + field(entry(row, column + 1)) * s;
+            let bottom = field(entry(row + 1, column)) * (S::one() - s) + field(entry(row + 1, column + 1)) * s;
+
+            top * (S::one() - t) + bottom * t
+        };
+
+        LtcEntry {
+            inverse: [
+                blend(&|entry| entry.inverse[0]),
+                blend(&|entry| entry.inverse[1]),
+                blend(&|entry| entry.inverse[2]),
+                blend(&|entry| entry.inverse[3]),
+            ],
+            magnitude: blend(&|entry| entry.magnitude),
+            fresnel: blend(&|entry| entry.fresnel),
+        }
+    }
+
+    /// The inverse transformations of the table as RGBA texels holding
+    /// `LtcEntry::inverse`, row by row, ready to upload as a `size` by
+    /// `size` texture.
+    pub fn matrix_texels_f32(&self) -> Vec<f32> {
+        self.entries.iter()
+            .flat_map(|entry| entry.inverse.iter().map(|value| to_f32(*value)).collect::<Vec<f32>>())
+            .collect()
+    }
+
+    /// The magnitudes and Fresnel integrals of the table as RG texels, row
+    /// by row, ready to upload as a `size` by `size` texture.
+    pub fn magnitude_texels_f32(&self) -> Vec<f32> {
+        self.entries.iter()
+            .flat_map(|entry| vec![to_f32(entry.magnitude), to_f32(entry.fresnel)])
+            .collect()
+    }
+
+    /// Integrate the GGX BRDF and a Lambertian BRDF over a polygon of
+    /// uniform radiance, seen from the point `point` with unit normal
+    /// `normal` and unit direction towards the viewer `view`. The vertices
+    /// are in world space, and the polygon is visible from both sides.
+    ///
+    /// The result holds the polygon's form factor and its GGX integrals,
+    /// clipped to the hemisphere above the point.
+    pub fn integrate(
+        &self,
+        vertices: &[Vector3<S>],
+        point: &Vector3<S>,
+        normal: &Vector3<S>,
+        view: &Vector3<S>,
+        alpha: S) -> LtcIntegrals<S>
+    {
+        let cos_theta = view.dot(normal);
+        let tangent = view - normal * cos_theta;
+        let tangent = if tangent.magnitude_squared() > S::zero() {
+            tangent.normalize()
+        } else {
+            any_perpendicular(normal)
+        };
+        let bitangent = normal.cross(&tangent);
+        let local = vertices.iter()
+            .map(|vertex| {
+                let offset = vertex - point;
+                Vector3::new(offset.dot(&tangent), offset.dot(&bitangent), offset.dot(normal))
+            })
+            .collect::<Vec<Vector3<S>>>();
+
+        let entry = self.lookup(alpha, cos_theta);
+        let specular = integrate_cosine(&local, &entry.inverse);
+
+        LtcIntegrals {
+            diffuse: integrate_cosine(&local, &[S::one(), S::zero(), S::zero(), S::one()]),
+            specular: specular * entry.magnitude,
+            fresnel: specular * entry.fresnel,
+        }
+    }
+}
+
+/// The integrals of the BRDFs over an area light, from
+/// `LtcTable::integrate`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct LtcIntegrals<S> {
+    /// The form factor of the light, the integral of the cosine over the
+    /// light divided by `π`. A Lambertian surface with albedo `ρ` reflects
+    /// `ρ` times this times the light's radiance.
+    pub diffuse: S,
+    /// The integral of the GGX BRDF times the cosine over the light, with a
+    /// Fresnel reflectance of one.
+    pub specular: S,
+    /// The integral of the GGX BRDF times the cosine times Schlick's Fresnel
+    /// weight `(1 - v·h)^5` over the light.
+    pub fresnel: S,
+}
+
+impl<S> LtcIntegrals<S> where S: ScalarFloat {
+    /// The radiance reflected towards the viewer from a light emitting
+    /// `diffuse_radiance` towards diffuse reflection and `specular_radiance`
+    /// towards specular reflection, for a surface with albedo
+    /// `diffuse_color` and specular reflectance at normal incidence
+    /// `specular_color`. Schlick's approximation gives the Fresnel
+    /// reflectance.
+    pub fn reflected(
+        &self,
+        diffuse_radiance: &Vector3<S>,
+        specular_radiance: &Vector3<S>,
+        diffuse_color: &Vector3<S>,
+        specular_color: &Vector3<S>) -> Vector3<S>
+    {
+        let specular = |f0: S| f0 * self.specular + (S::one() - f0) * self.fresnel;
+
+        Vector3::new(
+            diffuse_radiance.x * diffuse_color.x * self.diffuse + specular_radiance.x * specular(specular_color.x),
+            diffuse_radiance.y * diffuse_color.y * self.diffuse + specular_radiance.y * specular(specular_color.y),
+            diffuse_radiance.z * diffuse_color.z * self.diffuse + specular_radiance.z * specular(specular_color.z),
+        )
+    }
+}
+
+impl<S> LtcIntegrals<S> where S: ScalarFloat {
+    /// The integrals of a light the point does not see.
+    #[inline]
+    fn zero() -> LtcIntegrals<S> {
+        LtcIntegrals {
+            diffuse: S::zero(),
+            specular: S::zero(),
+            fresnel: S::zero(),
+        }
+    }
+}
+
+impl<S> NGonLight<S> where S: ScalarFloat {
+    /// Integrate the BRDFs over the polygon by linearly transformed cosines
+    /// for a point with unit normal `normal`, unit direction towards the
+    /// viewer `view`, and GGX roughness `alpha`, as in
+    /// `LtcTable::integrate`. Points behind the polygon receive nothing.
+    ///
+    /// Shading with the light's `NGonLightModel::mean_radiance` as both
+    /// radiances in `LtcIntegrals::reflected` gives the reflected radiance,
+    /// so a textured light is shaded with the mean of its image.
+    pub fn ltc_integrals(
+        &self,
+        table: &LtcTable<S>,
+        point: &Vector3<S>,
+        normal: &Vector3<S>,
+        view: &Vector3<S>,
+        alpha: S) -> LtcIntegrals<S>
+    {
+        if (point - self.position()).dot(&self.normal()) <= S::zero() {
+            return LtcIntegrals::zero();
+        }
+
+        table.integrate(&self.world_vertices(), point, normal, view, alpha)
+    }
+}
+
+impl<S> DiskLight<S> where S: ScalarFloat {
+    /// Integrate the BRDFs over the disk by linearly transformed cosines, as
+    /// in `NGonLight::ltc_integrals`. Shading with the model's diffuse and
+    /// specular colors as the radiances in `LtcIntegrals::reflected` gives
+    /// the reflected radiance.
+    ///
+    /// The disk is integrated as a regular 32-gon with the disk's area,
+    /// whose form factor differs from the disk's by well under one percent.
+    pub fn ltc_integrals(
+        &self,
+        table: &LtcTable<S>,
+        point: &Vector3<S>,
+        normal: &Vector3<S>,
+        view: &Vector3<S>,
+        alpha: S) -> LtcIntegrals<S>
+    {
+        if (point - self.position()).dot(&self.forward_axis()) <= S::zero() {
+            return LtcIntegrals::zero();
+        }
+
+        table.integrate(&self.disk_polygon(), point, normal, view, alpha)
+    }
+
+    /// The vertices of the regular polygon with the disk's area that
+    /// `ltc_integrals` integrates in place of the disk.
+    fn disk_polygon(&self) -> Vec<Vector3<S>> {
+        let pi: S = num_traits::cast(core::f64::consts::PI).unwrap();
+        let sides: S = num_traits::cast(DISK_SIDES).unwrap();
+        let step = (pi + pi) / sides;
+        // A regular polygon with circumradius `r` has area
+        // `n r^2 sin(2π / n) / 2`.
+        let radius = self.model().radius * ((pi + pi) / (sides * step.sin())).sqrt();
+
+        (0..DISK_SIDES)
+            .map(|i| {
+                let angle = step * num_traits::cast(i).unwrap();
+                self.position()
+                    + self.right_axis() * (radius * angle.cos())
+                    + self.up_axis() * (radius * angle.sin())
+            })
+            .collect()
+    }
+}
+
+#[inline]
+fn to_f32<S: ScalarFloat>(value: S) -> f32 {
+    num_traits::cast(value).unwrap()
+}
+
+/// Split a table coordinate into the index of the entry below it and the
+/// fraction of the way to the next entry.
+#[inline]
+fn split<S: ScalarFloat>(coordinate: S, size: usize) -> (usize, S) {
+    let index = num_traits::cast::<S, usize>(coordinate.floor()).unwrap_or(0).min(size - 2);
+    let index_scalar: S = num_traits::cast(index).unwrap();
+
+    (index, (coordinate - index_scalar).max(S::zero()).min(S::one()))
+}
+
+/// A unit vector perpendicular to a unit vector.
+#[inline]
+fn any_perpendicular<S: ScalarFloat>(vector: &Vector3<S>) -> Vector3<S> {
+    let helper = if vector.x.abs() < num_traits::cast(0.9).unwrap() {
+        Vector3::unit_x()
+    } else {
+        Vector3::unit_y()
+    };
+
+    vector.cross(&helper).normalize()
+}
+
+/// Integrate the clamped cosine distribution over a polygon with vertices
+/// relative to the shading point in the shading frame, after transforming
+/// them by the inverse transformation `[a, b, c, d]` of an `LtcEntry`.
+fn integrate_cosine<S: ScalarFloat>(vertices: &[Vector3<S>], inverse: &[S; 4]) -> S {
+    let two_pi: S = num_traits::cast(2_f64 * core::f64::consts::PI).unwrap();
+    let [a, b, c, d] = *inverse;
+    let transformed = vertices.iter()
+        .map(|vertex| Vector3::new(a * vertex.x + b * vertex.z, vertex.y, c * vertex.x + d * vertex.z));
+    let clipped = clip_to_horizon(transformed);
+    if clipped.len() < 3 {
+        return S::zero();
+    }
+
+    let directions = clipped.iter().map(|vertex| vertex.normalize()).collect::<Vec<Vector3<S>>>();
+    let mut sum = S::zero();
+    for (i, start) in directions.iter().enumerate() {
+        let end = &directions[(i + 1) % directions.len()];
+        let cos_angle = start.dot(end).max(-S::one()).min(S::one());
+        let angle = cos_angle.acos();
+        let sin_angle = angle.sin();
+        let factor = if sin_angle > num_traits::cast(1e-6).unwrap() { angle / sin_angle } else { S::one() };
+        sum += start.cross(end).z * factor;
+    }
+
+    sum.abs() / two_pi
+}
+
+/// Clip a polygon to the upper hemisphere `z >= 0`.
+fn clip_to_horizon<S, I>(vertices: I) -> Vec<Vector3<S>>
+    where S: ScalarFloat,
+          I: Iterator<Item = Vector3<S>>,
+{
+    let vertices = vertices.collect::<Vec<Vector3<S>>>();
+    let mut clipped = Vec::with_capacity(vertices.len() + 1);
+    for (i, current) in vertices.iter().enumerate() {
+        let next = &vertices[(i + 1) % vertices.len()];
+        if current.z >= S::zero() {
+            clipped.push(*current);
+        }
+        if (current.z >= S::zero()) != (next.z >= S::zero()) {
+            let t = current.z / (current.z - next.z);
+            let mut crossing = current + (next - current) * t;
+            crossing.z = S::zero();
+            clipped.push(crossing);
+        }
+    }
+
+    clipped
+}
+
+/// The GGX BRDF times the cosine for a unit view direction and a unit light
+/// direction in the shading frame, with a Fresnel reflectance of one, and
+/// the probability density of sampling the light direction by
+/// `sample_ggx`.
+fn evaluate_ggx<S: ScalarFloat>(view: &Vector3<S>, light: &Vector3<S>, alpha: S) -> (S, S) {
+    let pi: S = num_traits::cast(core::f64::consts::PI).unwrap();
+    let four: S = num_traits::cast(4).unwrap();
+    if view.z <= S::zero() {
+        return (S::zero(), S::zero());
+    }
+    let half_vector = view + light;
+    if half_vector.z <= S::zero() {
+        return (S::zero(), S::zero());
+    }
+    let half_vector = half_vector.normalize();
+
+    let alpha_squared = alpha * alpha;
+    let slope_squared = (half_vector.x * half_vector.x + half_vector.y * half_vector.y)
+        / (half_vector.z * half_vector.z);
+    let denominator = S::one() + slope_squared / alpha_squared;
+    let cos_squared = half_vector.z * half_vector.z;
+    let distribution = S::one() / (denominator * denominator * pi * alpha_squared * cos_squared * cos_squared);
+    let pdf = (distribution * half_vector.z / (four * view.dot(&half_vector))).abs();
+    if light.z <= S::zero() {
+        return (S::zero(), pdf);
+    }
+    let masking = S::one() / (S::one() + smith_lambda(view.z, alpha) + smith_lambda(light.z, alpha));
+
+    (distribution * masking / (four * view.z), pdf)
+}
+
+/// Smith's auxiliary function for the GGX distribution.
+#[inline]
+fn smith_lambda<S: ScalarFloat>(cos_theta: S, alpha: S) -> S {
+    let one_half: S = num_traits::cast(0.5).unwrap();
+    if cos_theta >= S::one() {
+        return S::zero();
+    }
+    let tan_squared = (S::one() - cos_theta * cos_theta) / (cos_theta * cos_theta);
+
+    one_half * ((S::one() + alpha * alpha * tan_squared).sqrt() - S::one())
+}
+
+/// Sample a light direction by reflecting the view direction about a
+/// sampled GGX normal.
+fn sample_ggx<S: ScalarFloat>(view: &Vector3<S>, alpha: S, u: S, v: S) -> Vector3<S> {
+    let pi: S = num_traits::cast(core::f64::consts::PI).unwrap();
+    let phi = (pi + pi) * u;
+    let r = alpha * (v / (S::one() - v)).sqrt();
+    let normal = Vector3::new(r * phi.cos(), r * phi.sin(), S::one()).normalize();
+
+    normal * ((S::one() + S::one()) * normal.dot(view)) - view
+}
+
+/// The coordinates of the `i`th sample along one axis of the fitting grid.
+#[inline]
+fn grid_coordinate<S: ScalarFloat>(i: usize) -> S {
+    let one_half: S = num_traits::cast(0.5).unwrap();
+    let samples: S = num_traits::cast(FIT_SAMPLES).unwrap();
+
+    (num_traits::cast::<usize, S>(i).unwrap() + one_half) / samples
+}
+
+/// The moments of the GGX BRDF times the cosine for one view direction.
+struct BrdfMoments<S> {
+    magnitude: S,
+    fresnel: S,
+    /// The mean direction of the lobe, projected into the plane of the view
+    /// direction and the normal.
+    direction: Vector3<S>,
+}
+
+impl<S> BrdfMoments<S> where S: ScalarFloat {
+    fn new(view: &Vector3<S>, alpha: S) -> BrdfMoments<S> {
+        let count: S = num_traits::cast(FIT_SAMPLES * FIT_SAMPLES).unwrap();
+        let mut magnitude = S::zero();
+        let mut fresnel = S::zero();
+        let mut direction = Vector3::zero();
+        for j in 0..FIT_SAMPLES {
+            for i in 0..FIT_SAMPLES {
+                let light = sample_ggx(view, alpha, grid_coordinate(i), grid_coordinate(j));
+                let (value, pdf) = evaluate_ggx(view, &light, alpha);
+                if pdf <= S::zero() {
+                    continue;
+                }
+                let weight = value / pdf;
+                let cos_half = (view + light).normalize().dot(view).max(S::zero());
+                magnitude += weight;
+                fresnel += weight * (S::one() - cos_half).powi(5);
+                direction += light * weight;
+            }
+        }
+        direction.y = S::zero();
+        let direction = if direction.magnitude_squared() > S::zero() {
+            direction.normalize()
+        } else {
+            Vector3::unit_z()
+        };
+
+        BrdfMoments {
+            magnitude: magnitude / count,
+            fresnel: fresnel / count,
+            direction: direction,
+        }
+    }
+}
+
+/// A linearly transformed cosine in the shading frame, with its lobe
+/// centered on a unit axis in the plane of the view direction and the
+/// normal. The transformation scales the clamped cosine by `scale_x` and
+/// `scale_y`, skews it by `skew`, and rotates it onto the axis.
+struct Ltc<S> {
+    scale_x: S,
+    scale_y: S,
+    skew: S,
+    magnitude: S,
+    /// The transformation restricted to the plane of the view direction and
+    /// the normal, `[[m00, m02], [m20, m22]]`.
+    matrix: [S; 4],
+    /// The inverse of `matrix`.
+    inverse: [S; 4],
+}
+
+impl<S> Ltc<S> where S: ScalarFloat {
+    fn new(axis: &Vector3<S>, parameters: &[S; 3], isotropic: bool, magnitude: S) -> Ltc<S> {
+        let min_scale: S = num_traits::cast(1e-7).unwrap();
+        let scale_x = parameters[0].max(min_scale);
+        let (scale_y, skew) = if isotropic {
+            (scale_x, S::zero())
+        } else {
+            (parameters[1].max(min_scale), parameters[2])
+        };
+        // The columns of the transformation are `scale_x * tangent`,
+        // `scale_y * y`, and `skew * tangent + axis`.
+        let tangent = Vector3::new(axis.z, S::zero(), -axis.x);
+        let matrix = [
+            scale_x * tangent.x, skew * tangent.x + axis.x,
+            scale_x * tangent.z, skew * tangent.z + axis.z,
+        ];
+        let determinant = matrix[0] * matrix[3] - matrix[1] * matrix[2];
+        let inverse = [
+            matrix[3] / determinant, -matrix[1] / determinant,
+            -matrix[2] / determinant, matrix[0] / determinant,
+        ];
+
+        Ltc {
+            scale_x: scale_x,
+            scale_y: scale_y,
+            skew: skew,
+            magnitude: magnitude,
+            matrix: matrix,
+            inverse: inverse,
+        }
+    }
+
+    /// The inverse transformation scaled so its middle entry is one.
+    fn normalized_inverse(&self) -> [S; 4] {
+        [
+            self.inverse[0] * self.scale_y,
+            self.inverse[1] * self.scale_y,
+            self.inverse[2] * self.scale_y,
+            self.inverse[3] * self.scale_y,
+        ]
+    }
+
+    /// The density of the lobe, times its magnitude, in a unit direction.
+    fn evaluate(&self, direction: &Vector3<S>) -> S {
+        let pi: S = num_traits::cast(core::f64::consts::PI).unwrap();
+        let original = Vector3::new(
+            self.inverse[0] * direction.x + self.inverse[1] * direction.z,
+            direction.y / self.scale_y,
+            self.inverse[2] * direction.x + self.inverse[3] * direction.z,
+        );
+        let length = original.magnitude();
+        let cosine = (original.z / length).max(S::zero()) / pi;
+        // The Jacobian of the normalized inverse transformation.
+        let determinant = S::one() / (self.scale_x * self.scale_y);
+        let jacobian = determinant / (length * length * length);
+
+        self.magnitude * cosine * jacobian
+    }
+
+    /// Sample the lobe by transforming a cosine distributed direction.
+    fn sample(&self, u: S, v: S) -> Vector3<S> {
+        let pi: S = num_traits::cast(core::f64::consts::PI).unwrap();
+        let radius = u.sqrt();
+        let phi = (pi + pi) * v;
+        let original = Vector3::new(
+            radius * phi.cos(),
+            radius * phi.sin(),
+            (S::one() - u).max(S::zero()).sqrt(),
+        );
+
+        Vector3::new(
+            self.matrix[0] * original.x + self.matrix[1] * original.z,
+            self.scale_y * original.y,
+            self.matrix[2] * original.x + self.matrix[3] * original.z,
+        ).normalize()
+    }
+
+    /// The error of the lobe against the GGX BRDF times the cosine, the
+    /// integral of the cubed difference estimated with samples of both
+    /// distributions.
+    fn error(&self, view: &Vector3<S>, alpha: S) -> S {
+        let count: S = num_traits::cast(FIT_SAMPLES * FIT_SAMPLES).unwrap();
+        let mut error = S::zero();
+        let mut accumulate = |direction: &Vector3<S>| {
+            let (brdf, brdf_pdf) = evaluate_ggx(view, direction, alpha);
+            let ltc = self.evaluate(direction);
+            let ltc_pdf = ltc / self.magnitude;
+            let pdf = brdf_pdf + ltc_pdf;
+            if pdf > S::zero() {
+                let difference = (brdf - ltc).abs();
+                error += difference * difference * difference / pdf;
+            }
+        };
+        for j in 0..FIT_SAMPLES {
+            for i in 0..FIT_SAMPLES {
+                let (u, v) = (grid_coordinate(i), grid_coordinate(j));
+                accumulate(&self.sample(u, v));
+                accumulate(&sample_ggx(view, alpha, u, v));
+            }
+        }
+
+        error / count
+    }
+}
+
+/// Minimize a function of three parameters with the Nelder-Mead simplex
+/// method, starting from `start`.
+fn nelder_mead<S, F>(start: [S; 3], mut function: F) -> [S; 3]
+    where S: ScalarFloat,
+          F: FnMut(&[S; 3]) -> S,
+{
+    let step: S = num_traits::cast(0.05).unwrap();
+    let tolerance: S = num_traits::cast(1e-5).unwrap();
+    let one_half: S = num_traits::cast(0.5).unwrap();
+    let two = S::one() + S::one();
+    let max_iterations = 100;
+
+    let mut points = [start; 4];
+    for (i, point) in points.iter_mut().skip(1).enumerate() {
+        point[i] += step;
+    }
+    let mut values = [S::zero(); 4];
+    for (value, point) in values.iter_mut().zip(points.iter()) {
+        *value = function(point);
+    }
+    let along = |from: &[S; 3], to: &[S; 3], t: S| {
+        [
+            from[0] + (to[0] - from[0]) * t,
+            from[1] + (to[1] - from[1]) * t,
+            from[2] + (to[2] - from[2]) * t,
+        ]
+    };
+
+    for _ in 0..max_iterations {
+        let mut order = [0, 1, 2, 3];
+        order.sort_by(|&i, &j| values[i].partial_cmp(&values[j]).unwrap_or(core::cmp::Ordering::Equal));
+        let (best, second_worst, worst) = (order[0], order[2], order[3]);
+        if (values[worst] - values[best]).abs() < tolerance {
+            break;
+        }
+
+        let mut centroid = [S::zero(); 3];
+        for &i in order.iter().take(3) {
+            for k in 0..3 {
+                centroid[k] += points[i][k] / num_traits::cast(3).unwrap();
+            }
+        }
+
+        let reflected = along(&points[worst], &centroid, two);
+        let reflected_value = function(&reflected);
+        if reflected_value < values[best] {
+            let expanded = along(&points[worst], &centroid, two + S::one());
+            let expanded_value = function(&expanded);
+            if expanded_value < reflected_value {
+                points[worst] = expanded;
+                values[worst] = expanded_value;
+            } else {
+                points[worst] = reflected;
+                values[worst] = reflected_value;
+            }
+        } else if reflected_value < values[second_worst] {
+            points[worst] = reflected;
+            values[worst] = reflected_value;
+        } else {
+            let (contracted, bound) = if reflected_value < values[worst] {
+                (along(&centroid, &reflected, one_half), reflected_value)
+            } else {
+                (along(&centroid, &points[worst], one_half), values[worst])
+            };
+            let contracted_value = function(&contracted);
+            if contracted_value < bound {
+                points[worst] = contracted;
+                values[worst] = contracted_value;
+            } else {
+                let best_point = points[best];
+                for i in 0..4 {
+                    if i != best {
+                        points[i] = along(&best_point, &points[i], one_half);
+                        values[i] = function(&points[i]);
+                    }
+                }
+            }
+        }
+    }
+
+    let mut best = 0;
+    for i in 1..4 {
+        if values[i] < values[best] {
+            best = i;
+        }
+    }
+
+    points[best]
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        DiskLightModelSpec,
+        LightAttitudeSpec,
+    };
+    use crate::polygon::NGonLightModelSpec;
+
+
+    fn attitude_spec() -> LightAttitudeSpec<f64> {
+        LightAttitudeSpec::new(
+            Vector3::new(0.0, 0.0, 2.0),
+            Vector3::new(0.0, 0.0, -1.0),
+            Vector3::new(1.0, 0.0, 0.0),
+            Vector3::new(0.0, -1.0, 0.0),
+            Vector3::new(0.0, 0.0, 1.0),
+        )
+    }
+
+    #[test]
+    fn test_disk_form_factor() {
+        let model_spec = DiskLightModelSpec::new(1.5, Vector3::zero(), Vector3::new(1.0, 1.0, 1.0), Vector3::zero());
+        let disk = DiskLight::new(&model_spec, &attitude_spec());
+        let table = LtcTable::from_entries(2, vec![
+            LtcEntry { inverse: [1.0, 0.0, 0.0, 1.0], magnitude: 1.0, fresnel: 0.0 };
+            4
+        ]).unwrap();
+        let point = Vector3::zero();
+        let normal = Vector3::unit_z();
+        let integrals = disk.ltc_integrals(&table, &point, &normal, &normal, 0.5);
+        // The form factor of a coaxial disk is `r^2 / (r^2 + h^2)`.
+        let expected = 1.5 * 1.5 / (1.5 * 1.5 + 2.0 * 2.0);
+
+        assert!((integrals.diffuse - expected).abs() < 0.005 * expected);
+
+        let behind = Vector3::new(0.0, 0.0, 3.0);
+        assert_eq!(disk.ltc_integrals(&table, &behind, &normal, &normal, 0.5).diffuse, 0.0);
+    }
+
+    #[test]
+    fn test_fitted_table_matches_ggx_near_the_reflection_direction() {
+        let table = LtcTable::<f64>::fit(8).unwrap();
+        let model_spec = NGonLightModelSpec::rectangle(2.0, 1.0, Vector3::new(1.0, 1.0, 1.0)).unwrap();
+        let light = NGonLight::new(&model_spec, &attitude_spec());
+        let point = Vector3::new(-0.5, 0.0, 0.0);
+        let normal = Vector3::unit_z();
+        for &(alpha, theta) in &[(0.3, 0.0), (0.3, 0.4), (0.6, 0.6)] {
+            let view = Vector3::new(-f64::sin(theta), 0.0, f64::cos(theta));
+            let integrals = light.ltc_integrals(&table, &point, &normal, &view, alpha);
+
+            let samples = 200;
+            let mut expected = 0.0;
+            for i in 0..samples {
+                for j in 0..samples {
+                    let u = (i as f64 + 0.5) / samples as f64;
+                    let v = (j as f64 + 0.5) / samples as f64;
+                    if let Some((direction, pdf)) = light.sample(u, v, &point) {
+                        expected += evaluate_ggx(&view, &direction, alpha).0 / pdf;
+                    }
+                }
+            }
+            expected /= (samples * samples) as f64;
+
+            assert!((integrals.specular - expected).abs() < 0.05 * expected, "{} {}", integrals.specular, expected);
+        }
+    }
+}
@@ -0,0 +1,199 @@
+use cglinalg::{
+    Magnitude,
+    Vector3,
+    ScalarFloat,
+};
+use crate::{
+    Dimmable,
+    IlluminationModel,
+    Light,
+};
+use std::sync::Arc;
+
+
+/// A triangle list of emissive geometry, such as a neon sign or a lamp shade.
+///
+/// Every three consecutive positions form a triangle, and every triangle
+/// emits a uniform radiance from its front face. The front face is the side
+/// from which the triangle's vertices appear in counterclockwise order.
+#[derive(Clone, Debug, PartialEq)]
+pub struct EmissiveMesh<S> {
+    positions: Vec<Vector3<S>>,
+    emission: Vec<Vector3<S>>,
+}
+
+impl<S> EmissiveMesh<S> where S: ScalarFloat {
+    /// Construct an emissive mesh from a triangle list of positions and the
+    /// radiance emitted by each triangle. The result is `None` unless there
+    /// are exactly three positions per triangle.
+    pub fn new(positions: Vec<Vector3<S>>, emission: Vec<Vector3<S>>) -> Option<EmissiveMesh<S>> {
+        if positions.len() != 3 * emission.len() {
+            return None;
+        }
+
+        Some(EmissiveMesh {
+            positions: positions,
+            emission: emission,
+        })
+    }
+
+    /// The number of triangles in the mesh.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.emission.len()
+    }
+
+    /// Determine whether the mesh has no triangles.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.emission.is_empty()
+    }
+
+    /// The vertices of a triangle.
+    #[inline]
+    pub fn triangle(&self, index: usize) -> [Vector3<S>; 3] {
+        [
+            self.positions[3 * index],
+            self.positions[3 * index + 1],
+            self.positions[3 * index + 2],
+        ]
+    }
+
+    /// The radiance emitted by a triangle.
+    #[inline]
+    pub fn emission(&self, index: usize) -> Vector3<S> {
+        self.emission[index]
+    }
+
+    /// The area of a triangle.
+    #[inline]
+    pub fn triangle_area(&self, index: usize) -> S {
+        let one_half: S = num_traits::cast(0.5).unwrap();
+        let [a, b, c] = self.triangle(index);
+
+        (b - a).cross(&(c - a)).magnitude() * one_half
+    }
+
+    /// The total surface area of the mesh.
+    pub fn area(&self) -> S {
+        (0..self.len()).fold(S::zero(), |area, index| area + self.triangle_area(index))
+    }
+
+    /// The radiant power the mesh emits in each color channel. A surface of
+    /// area `A` emitting a uniform radiance `L` from one side emits a power
+    /// of `πLA`.
+    pub fn power(&self) -> Vector3<S> {
+        let pi: S = num_traits::cast(core::f64::consts::PI).unwrap();
+
+        (0..self.len()).fold(Vector3::zero(), |power, index| {
+            power + self.emission[index] * (pi * self.triangle_area(index))
+        })
+    }
+}
+
+/// A specification describing a mesh light, which emits light from a
+/// triangle mesh attached to the light. The mesh's positions are in the
+/// light's local coordinate frame.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MeshLightModelSpec<S> {
+    pub mesh: Arc<EmissiveMesh<S>>,
+    /// A multiplier on the emission of every triangle.
+    pub intensity: S,
+}
+
+impl<S> MeshLightModelSpec<S> where S: ScalarFloat {
+    /// Construct a new mesh light specification.
+    #[inline]
+    pub fn new(mesh: Arc<EmissiveMesh<S>>, intensity: S) -> MeshLightModelSpec<S> {
+        MeshLightModelSpec {
+            mesh: mesh,
+            intensity: intensity,
+        }
+    }
+}
+
+/// The illumination model of a mesh light. Meshes are shared between
+/// lights, and between a light and its specification.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MeshLightModel<S> {
+    pub mesh: Arc<EmissiveMesh<S>>,
+    /// A multiplier on the emission of every triangle.
+    pub intensity: S,
+}
+
+impl_illumination_model!(MeshLightModel, MeshLightModelSpec, [mesh, intensity]);
+
+impl_dimmable!(MeshLightModel, [intensity]);
+
+impl<S> MeshLightModel<S> where S: ScalarFloat {
+    /// The radiant power the light emits in each color channel.
+    #[inline]
+    pub fn power(&self) -> Vector3<S> {
+        self.mesh.power() * self.intensity
+    }
+
+    /// The radiance emitted by a triangle.
+    #[inline]
+    pub fn emission(&self, index: usize) -> Vector3<S> {
+        self.mesh.emission(index) * self.intensity
+    }
+}
+
+pub type MeshLight<S> = Light<S, MeshLightModel<S>>;
+
+impl<S> MeshLight<S> where S: ScalarFloat {
+    /// The vertices of a triangle of the light's mesh in world space.
+    pub fn world_triangle(&self, index: usize) -> [Vector3<S>; 3] {
+        let position = self.position();
+        let right = self.right_axis();
+        let up = self.up_axis();
+        let backward = -self.forward_axis();
+        let to_world = |vertex: &Vector3<S>| position + right * vertex.x + up * vertex.y + backward * vertex.z;
+        let [a, b, c] = self.model().mesh.triangle(index);
+
+        [to_world(&a), to_world(&b), to_world(&c)]
+    }
+
+    /// The radiance `Le(p, ω)` the mesh emits from the point `point` on its
+    /// surface, with `direction` pointing from the receiver towards the
+    /// point, as in `DiskLight::emitted_radiance`.
+    ///
+    /// The point is attributed to the triangle containing its projection
+    /// that lies nearest to it, so points a ray hit with some rounding error
+    /// still find their triangle. Each triangle emits only from its front
+    /// face, and points off the mesh emit nothing.
+    pub fn emitted_radiance(&self, point: &Vector3<S>, direction: &Vector3<S>) -> Vector3<S> {
+        let mut nearest: Option<(usize, Vector3<S>, S)> = None;
+        for index in 0..self.model().mesh.len() {
+            let [a, b, c] = self.world_triangle(index);
+            let normal = (b - a).cross(&(c - a));
+            let area_squared = normal.magnitude_squared();
+            if area_squared <= S::zero() {
+                continue;
+            }
+
+            // The barycentric coordinates of the point's projection onto the
+            // triangle's plane.
+            let to_point = point - a;
+            let u = (to_point.cross(&(c - a))).dot(&normal) / area_squared;
+            let v = ((b - a).cross(&to_point)).dot(&normal) / area_squared;
+            if u < S::zero() || v < S::zero() || u + v > S::one() {
+                continue;
+            }
+
+            let distance = to_point.dot(&normal).abs() / area_squared.sqrt();
+            let is_nearer = match nearest {
+                Some((_, _, nearest_distance)) => distance < nearest_distance,
+                None => true,
+            };
+            if is_nearer {
+                nearest = Some((index, normal, distance));
+            }
+        }
+
+        match nearest {
+            Some((index, normal, _)) if direction.dot(&normal) < S::zero() => self.model().emission(index),
+            _ => Vector3::zero(),
+        }
+    }
+}
@@ -0,0 +1,143 @@
+use cglinalg::{
+    Matrix4x4,
+    Quaternion,
+    Vector3,
+    Vector4,
+    ScalarFloat,
+};
+use crate::LightAttitudeSpec;
+
+
+/// Convert a math type of this crate to its `mint` equivalent, for passing
+/// positions, directions, and matrices to glam, nalgebra, ultraviolet, and
+/// other libraries that interoperate through `mint`.
+///
+/// The light accessors return `cglinalg` types, so any of them converts
+/// with a trailing `to_mint()`, e.g. `light.view_matrix().to_mint()`.
+pub trait ToMint {
+    /// The `mint` type.
+    type Output;
+
+    /// Convert the value to its `mint` equivalent.
+    fn to_mint(&self) -> Self::Output;
+}
+
+/// Construct a math type of this crate from a `mint` type.
+pub trait FromMint<T> {
+    /// Convert a `mint` value.
+    fn from_mint(value: T) -> Self;
+}
+
+impl<S> ToMint for Vector3<S> where S: ScalarFloat {
+    type Output = mint::Vector3<S>;
+
+    #[inline]
+    fn to_mint(&self) -> Self::Output {
+        mint::Vector3 { x: self.x, y: self.y, z: self.z }
+    }
+}
+
+impl<S> ToMint for Vector4<S> where S: ScalarFloat {
+    type Output = mint::Vector4<S>;
+
+    #[inline]
+    fn to_mint(&self) -> Self::Output {
+        mint::Vector4 { x: self.x, y: self.y, z: self.z, w: self.w }
+    }
+}
+
+impl<S> ToMint for Quaternion<S> where S: ScalarFloat {
+    type Output = mint::Quaternion<S>;
+
+    #[inline]
+    fn to_mint(&self) -> Self::Output {
+        mint::Quaternion { v: self.v.to_mint(), s: self.s }
+    }
+}
+
+impl<S> ToMint for Matrix4x4<S> where S: ScalarFloat {
+    type Output = mint::ColumnMatrix4<S>;
+
+    #[inline]
+    fn to_mint(&self) -> Self::Output {
+        let columns: &[[S; 4]; 4] = self.as_ref();
+        let column = |c: usize| mint::Vector4 {
+            x: columns[c][0],
+            y: columns[c][1],
+            z: columns[c][2],
+            w: columns[c][3],
+        };
+
+        mint::ColumnMatrix4 { x: column(0), y: column(1), z: column(2), w: column(3) }
+    }
+}
+
+impl<S> FromMint<mint::Vector3<S>> for Vector3<S> where S: ScalarFloat {
+    #[inline]
+    fn from_mint(value: mint::Vector3<S>) -> Self {
+        Vector3::new(value.x, value.y, value.z)
+    }
+}
+
+impl<S> FromMint<mint::Point3<S>> for Vector3<S> where S: ScalarFloat {
+    #[inline]
+    fn from_mint(value: mint::Point3<S>) -> Self {
+        Vector3::new(value.x, value.y, value.z)
+    }
+}
+
+impl<S> FromMint<mint::Vector4<S>> for Vector4<S> where S: ScalarFloat {
+    #[inline]
+    fn from_mint(value: mint::Vector4<S>) -> Self {
+        Vector4::new(value.x, value.y, value.z, value.w)
+    }
+}
+
+impl<S> FromMint<mint::Quaternion<S>> for Quaternion<S> where S: ScalarFloat {
+    #[inline]
+    fn from_mint(value: mint::Quaternion<S>) -> Self {
+        Quaternion::new(value.s, value.v.x, value.v.y, value.v.z)
+    }
+}
+
+impl<S> FromMint<mint::ColumnMatrix4<S>> for Matrix4x4<S> where S: ScalarFloat {
+    #[inline]
+    fn from_mint(value: mint::ColumnMatrix4<S>) -> Self {
+        Matrix4x4::from_columns(
+            Vector4::from_mint(value.x),
+            Vector4::from_mint(value.y),
+            Vector4::from_mint(value.z),
+            Vector4::from_mint(value.w),
+        )
+    }
+}
+
+impl<S> FromMint<mint::RowMatrix4<S>> for Matrix4x4<S> where S: ScalarFloat {
+    #[inline]
+    fn from_mint(value: mint::RowMatrix4<S>) -> Self {
+        Matrix4x4::from_columns(
+            Vector4::new(value.x.x, value.y.x, value.z.x, value.w.x),
+            Vector4::new(value.x.y, value.y.y, value.z.y, value.w.y),
+            Vector4::new(value.x.z, value.y.z, value.z.z, value.w.z),
+            Vector4::new(value.x.w, value.y.w, value.z.w, value.w.w),
+        )
+    }
+}
+
+impl<S> LightAttitudeSpec<S> where S: ScalarFloat {
+    /// Construct a new light attitude specification from any math types that
+    /// convert to `mint`, such as glam's or nalgebra's vectors.
+    #[inline]
+    pub fn from_mint<P, V>(position: P, forward: V, right: V, up: V, axis: V) -> Self
+        where P: Into<mint::Point3<S>>,
+              V: Into<mint::Vector3<S>>,
+    {
+        LightAttitudeSpec::new(
+            Vector3::from_mint(position.into()),
+            Vector3::from_mint(forward.into()),
+            Vector3::from_mint(right.into()),
+            Vector3::from_mint(up.into()),
+            Vector3::from_mint(axis.into()),
+        )
+    }
+}
@@ -0,0 +1,135 @@
+use cglinalg::{
+    Vector3,
+    ScalarFloat,
+};
+use crate::{
+    Dimmable,
+    DiskLightModel,
+    SphereLightModel,
+    TubeLightModel,
+};
+use crate::polygon::NGonLightModel;
+
+
+/// What an area light holds constant when its size changes.
+///
+/// Offline renderers offer this as a toggle. In radiance mode a larger light
+/// looks equally bright but emits more power, like adding more of the same
+/// panel. In power mode a larger light emits the same power spread over more
+/// area, like a diffuser over the same bulb, so resizing it changes the
+/// softness of its shadows without changing the exposure of the scene.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum EmissionMode {
+    /// The emitted radiance stays constant, and the power scales with area.
+    Radiance,
+    /// The emitted power stays constant, and the radiance scales inversely
+    /// with area.
+    Power,
+}
+
+/// An area light emitting uniform Lambertian radiance from its surface.
+pub trait AreaEmitter<S>: Dimmable<S> {
+    /// The area of the light's emitting surface.
+    fn emitting_area(&self) -> S;
+
+    /// The radiance emitted from every point of the light's surface.
+    fn emitted_radiance(&self) -> Vector3<S>;
+}
+
+/// The power an area light emits, in the units of its radiance times area.
+/// Each point of a Lambertian emitter emits `π` times its radiance.
+#[inline]
+pub fn emitted_power<S, M>(model: &M) -> Vector3<S>
+    where S: ScalarFloat,
+          M: AreaEmitter<S>,
+{
+    let pi: S = num_traits::cast(core::f64::consts::PI).unwrap();
+
+    model.emitted_radiance() * (pi * model.emitting_area())
+}
+
+/// The radiance a Lambertian emitter with the given area needs to emit a
+/// given power.
+#[inline]
+pub fn radiance_for_power<S: ScalarFloat>(power: &Vector3<S>, area: S) -> Vector3<S> {
+    let pi: S = num_traits::cast(core::f64::consts::PI).unwrap();
+
+    power / (pi * area)
+}
+
+/// Change the size of an area light with `resize`, which edits the light's
+/// dimensions, then rescale its emission according to the emission mode. In
+/// power mode the light emits the same power after resizing as before.
+pub fn resize<S, M, F>(model: &mut M, mode: EmissionMode, resize: F)
+    where S: ScalarFloat,
+          M: AreaEmitter<S>,
+          F: FnOnce(&mut M),
+{
+    let old_area = model.emitting_area();
+    resize(model);
+    if mode == EmissionMode::Radiance {
+        return;
+    }
+
+    let new_area = model.emitting_area();
+    if old_area > S::zero() && new_area > S::zero() {
+        *model = model.dimmed(old_area / new_area);
+    }
+}
+
+impl<S> AreaEmitter<S> for DiskLightModel<S> where S: ScalarFloat {
+    #[inline]
+    fn emitting_area(&self) -> S {
+        let pi: S = num_traits::cast(core::f64::consts::PI).unwrap();
+
+        pi * self.radius * self.radius
+    }
+
+    #[inline]
+    fn emitted_radiance(&self) -> Vector3<S> {
+        self.diffuse
+    }
+}
+
+impl<S> AreaEmitter<S> for SphereLightModel<S> where S: ScalarFloat {
+    #[inline]
+    fn emitting_area(&self) -> S {
+        let four_pi: S = num_traits::cast(4_f64 * core::f64::consts::PI).unwrap();
+
+        four_pi * self.radius * self.radius
+    }
+
+    #[inline]
+    fn emitted_radiance(&self) -> Vector3<S> {
+        self.radiance
+    }
+}
+
+impl<S> AreaEmitter<S> for TubeLightModel<S> where S: ScalarFloat {
+    /// The area of the tube's cylindrical side and its two hemispherical
+    /// caps.
+    #[inline]
+    fn emitting_area(&self) -> S {
+        let two_pi: S = num_traits::cast(2_f64 * core::f64::consts::PI).unwrap();
+        let two = S::one() + S::one();
+
+        two_pi * self.radius * (self.length + two * self.radius)
+    }
+
+    #[inline]
+    fn emitted_radiance(&self) -> Vector3<S> {
+        self.radiance
+    }
+}
+
+impl<S> AreaEmitter<S> for NGonLightModel<S> where S: ScalarFloat {
+    #[inline]
+    fn emitting_area(&self) -> S {
+        self.area()
+    }
+
+    #[inline]
+    fn emitted_radiance(&self) -> Vector3<S> {
+        self.radiance
+    }
+}
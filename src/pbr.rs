@@ -0,0 +1,159 @@
+use cglinalg::{
+    Vector3,
+    ScalarFloat,
+};
+use crate::{
+    Dimmable,
+    IlluminationModel,
+    Light,
+};
+use crate::attenuation::Attenuation;
+use crate::color::{
+    ColorSpace,
+    ColorTemperature,
+    LightColor,
+};
+use crate::polarity::Polarity;
+
+
+/// A specification describing a physically based punctual light.
+///
+/// Physically based pipelines describe a light by a single color and an
+/// intensity in physical units, rather than the separate ambient, diffuse and
+/// specular colors of the Phong-style models.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct PbrLightModelSpec<S> {
+    /// The color of the light, as a linear RGB color conventionally with
+    /// components in the unit interval or as a temperature and tint.
+    pub color: LightColor<S>,
+    /// The luminous intensity of the light in candela.
+    pub intensity: S,
+    /// The falloff of the light's emission with distance.
+    pub attenuation: Attenuation<S>,
+    /// The fraction of the light's emission that reaches shadowed regions in
+    /// each color channel. Zero casts black shadows.
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::vector3"))]
+    pub shadow_tint: Vector3<S>,
+    /// Whether the light adds or subtracts light.
+    pub polarity: Polarity,
+}
+
+impl<S> PbrLightModelSpec<S> where S: ScalarFloat {
+    /// Construct a new physically based light specification. The light falls
+    /// off with the inverse square law.
+    #[inline]
+    pub fn new(color: Vector3<S>, intensity: S) -> PbrLightModelSpec<S> {
+        PbrLightModelSpec {
+            color: LightColor::Rgb(color),
+            intensity: intensity,
+            attenuation: Attenuation::InverseSquare,
+            shadow_tint: Vector3::zero(),
+            polarity: Polarity::Additive,
+        }
+    }
+
+    /// Construct a physically based light specification emitting the color of
+    /// a black body at the given temperature in a working color space, with
+    /// a luminous intensity in candela. The light keeps the temperature and
+    /// resolves it to RGB when shading.
+    #[inline]
+    pub fn from_temperature(
+        temperature: ColorTemperature<S>,
+        color_space: ColorSpace,
+        intensity: S) -> PbrLightModelSpec<S>
+    {
+        PbrLightModelSpec {
+            color: LightColor::from_temperature(temperature.kelvin, S::zero(), color_space),
+            intensity: intensity,
+            attenuation: Attenuation::InverseSquare,
+            shadow_tint: Vector3::zero(),
+            polarity: Polarity::Additive,
+        }
+    }
+
+    /// Replace the light's color, e.g. with a temperature and tint that stay
+    /// editable instead of being baked into RGB.
+    #[inline]
+    pub fn with_color(self, color: LightColor<S>) -> PbrLightModelSpec<S> {
+        PbrLightModelSpec {
+            color: color,
+            ..self
+        }
+    }
+
+    /// Replace the light's distance attenuation.
+    #[inline]
+    pub fn with_attenuation(self, attenuation: Attenuation<S>) -> PbrLightModelSpec<S> {
+        PbrLightModelSpec {
+            attenuation: attenuation,
+            ..self
+        }
+    }
+
+    /// Make the light subtract its contribution from the scene, darkening the
+    /// region it would otherwise light.
+    #[inline]
+    pub fn subtractive(self) -> PbrLightModelSpec<S> {
+        PbrLightModelSpec {
+            polarity: Polarity::Subtractive,
+            ..self
+        }
+    }
+}
+
+impl_with_shadow_tint!(PbrLightModelSpec);
+
+/// The illumination model of a physically based punctual light, emitting
+/// equally in all directions.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct PbrLightModel<S> {
+    /// The color of the light, as a linear RGB color or as a temperature and
+    /// tint.
+    pub color: LightColor<S>,
+    /// The luminous intensity of the light in candela.
+    pub intensity: S,
+    /// The falloff of the light's emission with distance.
+    pub attenuation: Attenuation<S>,
+    /// The fraction of the light's emission that reaches shadowed regions in
+    /// each color channel. Zero casts black shadows.
+    pub shadow_tint: Vector3<S>,
+    /// Whether the light adds or subtracts light.
+    pub polarity: Polarity,
+}
+
+impl_illumination_model!(PbrLightModel, PbrLightModelSpec, [color, intensity, attenuation, shadow_tint, polarity]);
+
+impl_dimmable!(PbrLightModel, [intensity]);
+
+impl<S> PbrLightModel<S> where S: ScalarFloat {
+    /// The light's color resolved to linear RGB.
+    #[inline]
+    pub fn resolved_color(&self) -> Vector3<S> {
+        self.color.resolved_color()
+    }
+
+    /// The light's resolved color scaled by its intensity, the value shaders
+    /// multiply by the distance attenuation and the BRDF.
+    #[inline]
+    pub fn emission(&self) -> Vector3<S> {
+        self.resolved_color() * self.intensity
+    }
+
+    /// The light's emission with the sign of its polarity, negative for
+    /// subtractive lights.
+    #[inline]
+    pub fn signed_emission(&self) -> Vector3<S> {
+        self.emission() * self.polarity.sign()
+    }
+
+    /// The illuminance the light delivers in each color channel to a surface
+    /// facing it at a distance from it. The illuminance of a subtractive light
+    /// is negative.
+    #[inline]
+    pub fn illuminance(&self, distance: S) -> Vector3<S> {
+        self.signed_emission() * self.attenuation.evaluate(distance)
+    }
+}
+
+pub type PbrLight<S> = Light<S, PbrLightModel<S>>;
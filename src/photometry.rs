@@ -0,0 +1,442 @@
+use cglinalg::{
+    Magnitude,
+    Vector3,
+    ScalarFloat,
+};
+use crate::{
+    DiskLightModel,
+    IlluminationModel,
+    Light,
+    PointLightModel,
+    SphereLightModel,
+    SpotLightModel,
+    TubeLightModel,
+};
+use crate::attenuation::Attenuation;
+use crate::color::luminance;
+use crate::ies::{
+    IesLight,
+    IesLightModel,
+};
+use crate::mesh::MeshLightModel;
+use crate::normalization::emitted_power;
+use crate::pbr::{
+    PbrLight,
+    PbrLightModel,
+    PbrLightModelSpec,
+};
+use crate::polygon::NGonLightModel;
+use crate::scene::{
+    FrozenLightScene,
+    LightScene,
+};
+#[cfg(feature = "spectral")]
+use crate::spectral::SpectralLightModel;
+
+
+/// A luminous flux, the total visible power emitted by a light, in lumens.
+#[derive(Copy, Clone, Debug, PartialEq, PartialOrd)]
+pub struct Lumens<S>(pub S);
+
+/// A luminous intensity, the visible power a light emits per unit solid angle
+/// in a direction, in candela.
+#[derive(Copy, Clone, Debug, PartialEq, PartialOrd)]
+pub struct Candela<S>(pub S);
+
+/// An illuminance, the visible power arriving per unit area of a surface, in
+/// lux.
+#[derive(Copy, Clone, Debug, PartialEq, PartialOrd)]
+pub struct Lux<S>(pub S);
+
+/// A power in watts, such as the radiant flux of a light or the electrical
+/// power drawn by a lamp.
+#[derive(Copy, Clone, Debug, PartialEq, PartialOrd)]
+pub struct Watts<S>(pub S);
+
+/// The luminous efficacy of monochromatic light at 555 nanometers, the
+/// wavelength the eye is most sensitive to, in lumens per watt. No light
+/// converts watts into more lumens.
+pub const MAX_LUMINOUS_EFFICACY: f64 = 683.0;
+
+/// The solid angle of a cone with the given half angle in radians.
+#[inline]
+pub fn cone_solid_angle<S: ScalarFloat>(half_angle: S) -> S {
+    let two_pi: S = num_traits::cast(2_f64 * core::f64::consts::PI).unwrap();
+
+    two_pi * (S::one() - half_angle.cos())
+}
+
+impl<S> Lumens<S> where S: ScalarFloat {
+    /// The luminous intensity of a light emitting this flux equally in all
+    /// directions.
+    #[inline]
+    pub fn to_candela(self) -> Candela<S> {
+        let four_pi: S = num_traits::cast(4_f64 * core::f64::consts::PI).unwrap();
+
+        Candela(self.0 / four_pi)
+    }
+
+    /// The luminous intensity of a light emitting this flux equally in all
+    /// directions inside a cone with the given half angle in radians, such as
+    /// a spotlight. Lights that distribute their flux unevenly across the
+    /// cone are brighter on their axis.
+    #[inline]
+    pub fn to_candela_in_cone(self, half_angle: S) -> Candela<S> {
+        Candela(self.0 / cone_solid_angle(half_angle))
+    }
+
+    /// The illuminance this flux produces spread evenly over an area in
+    /// square meters.
+    #[inline]
+    pub fn over_area(self, area: S) -> Lux<S> {
+        Lux(self.0 / area)
+    }
+
+    /// The power in watts that produces this flux at a luminous efficacy in
+    /// lumens per watt. The radiant flux of visible light follows from the
+    /// efficacy of its spectrum, at most `MAX_LUMINOUS_EFFICACY`, and the
+    /// electrical power of a lamp from the lamp's rated efficacy, such as
+    /// about 15 lumens per watt for an incandescent bulb.
+    #[inline]
+    pub fn to_watts(self, efficacy: S) -> Watts<S> {
+        Watts(self.0 / efficacy)
+    }
+}
+
+impl<S> Watts<S> where S: ScalarFloat {
+    /// The luminous flux this power produces at a luminous efficacy in
+    /// lumens per watt.
+    #[inline]
+    pub fn to_lumens(self, efficacy: S) -> Lumens<S> {
+        Lumens(self.0 * efficacy)
+    }
+}
+
+impl<S> Candela<S> where S: ScalarFloat {
+    /// The luminous flux of a light with this intensity in all directions.
+    #[inline]
+    pub fn to_lumens(self) -> Lumens<S> {
+        let four_pi: S = num_traits::cast(4_f64 * core::f64::consts::PI).unwrap();
+
+        Lumens(self.0 * four_pi)
+    }
+
+    /// The luminous flux of a light with this intensity in every direction
+    /// inside a cone with the given half angle in radians, and no emission
+    /// outside it.
+    #[inline]
+    pub fn to_lumens_in_cone(self, half_angle: S) -> Lumens<S> {
+        Lumens(self.0 * cone_solid_angle(half_angle))
+    }
+
+    /// The illuminance this intensity produces on a surface facing the light
+    /// at a distance in meters, by the inverse square law.
+    #[inline]
+    pub fn illuminance_at(self, distance: S) -> Lux<S> {
+        Lux(self.0 / (distance * distance))
+    }
+}
+
+impl<S> Lux<S> where S: ScalarFloat {
+    /// The luminous intensity a light needs to produce this illuminance on a
+    /// surface facing it at a distance in meters.
+    #[inline]
+    pub fn intensity_at(self, distance: S) -> Candela<S> {
+        Candela(self.0 * distance * distance)
+    }
+
+    /// The luminous flux arriving on an area in square meters under this
+    /// illuminance.
+    #[inline]
+    pub fn over_area(self, area: S) -> Lumens<S> {
+        Lumens(self.0 * area)
+    }
+}
+
+impl<S> PbrLightModelSpec<S> where S: ScalarFloat {
+    /// Construct a physically based light specification with a luminous
+    /// intensity.
+    #[inline]
+    pub fn from_candela(color: Vector3<S>, intensity: Candela<S>) -> PbrLightModelSpec<S> {
+        PbrLightModelSpec::new(color, intensity.0)
+    }
+
+    /// Construct a physically based light specification emitting a luminous
+    /// flux equally in all directions, such as the rated output of a bulb.
+    #[inline]
+    pub fn from_lumens(color: Vector3<S>, flux: Lumens<S>) -> PbrLightModelSpec<S> {
+        PbrLightModelSpec::new(color, flux.to_candela().0)
+    }
+}
+
+impl<S> PbrLightModel<S> where S: ScalarFloat {
+    /// The luminous intensity of the light.
+    #[inline]
+    pub fn luminous_intensity(&self) -> Candela<S> {
+        Candela(self.intensity)
+    }
+
+    /// The luminous flux of the light.
+    #[inline]
+    pub fn luminous_flux(&self) -> Lumens<S> {
+        self.luminous_intensity().to_lumens()
+    }
+}
+
+/// An illumination model emitting a finite total power.
+///
+/// The power is the luminous flux the light emits in all directions, which
+/// converts to watts with [`Lumens::to_watts`]. Photometric models measure
+/// their intensities in candela, and the area models are read as emitting a
+/// luminance in candela per square meter, as physically based pipelines
+/// specify them.
+///
+/// The Phong-style models store colors without physical units. The point and
+/// spot lights report their power by reading the luminance of the diffuse
+/// color as an intensity in candela, so their power compares them with each
+/// other but not with the photometric models. The ambient, hemisphere and
+/// directional lights, the environment lights, and beams illuminate all of
+/// space or have no intensity to integrate, and do not implement this trait.
+pub trait PowerEmitter<S> {
+    /// The luminous flux the light emits.
+    fn emitted_power(&self) -> Lumens<S>;
+}
+
+impl<S> PowerEmitter<S> for PbrLightModel<S> where S: ScalarFloat {
+    #[inline]
+    fn emitted_power(&self) -> Lumens<S> {
+        self.luminous_flux()
+    }
+}
+
+#[cfg(feature = "spectral")]
+impl<S> PowerEmitter<S> for SpectralLightModel<S> where S: ScalarFloat {
+    /// The luminous flux of the spectrum emitted equally in all directions,
+    /// with the light's intensity read as the luminous intensity in candela
+    /// of a spectrum of unit luminance.
+    #[inline]
+    fn emitted_power(&self) -> Lumens<S> {
+        Candela(self.intensity * self.spectrum.to_xyz().y).to_lumens()
+    }
+}
+
+impl<S> PowerEmitter<S> for IesLightModel<S> where S: ScalarFloat {
+    /// The luminous flux of the photometric web, scaled by the luminance of
+    /// the light's color.
+    #[inline]
+    fn emitted_power(&self) -> Lumens<S> {
+        Lumens(self.web.luminous_flux().0 * luminance(&self.color))
+    }
+}
+
+impl<S> PowerEmitter<S> for PointLightModel<S> where S: ScalarFloat {
+    /// The flux of the diffuse color's luminance read as an intensity in
+    /// candela, emitted equally in all directions.
+    #[inline]
+    fn emitted_power(&self) -> Lumens<S> {
+        Candela(luminance(&self.diffuse)).to_lumens()
+    }
+}
+
+impl<S> PowerEmitter<S> for SpotLightModel<S> where S: ScalarFloat {
+    /// The flux of the diffuse color's luminance read as the on-axis
+    /// intensity in candela, integrated over the spotlight's cone.
+    #[inline]
+    fn emitted_power(&self) -> Lumens<S> {
+        Lumens(luminance(&self.diffuse) * self.beam_solid_angle())
+    }
+}
+
+impl<S> PowerEmitter<S> for DiskLightModel<S> where S: ScalarFloat {
+    /// The flux of the diffuse color read as the disk's luminance.
+    #[inline]
+    fn emitted_power(&self) -> Lumens<S> {
+        Lumens(luminance(&emitted_power(self)))
+    }
+}
+
+impl<S> PowerEmitter<S> for SphereLightModel<S> where S: ScalarFloat {
+    #[inline]
+    fn emitted_power(&self) -> Lumens<S> {
+        Lumens(luminance(&emitted_power(self)))
+    }
+}
+
+impl<S> PowerEmitter<S> for TubeLightModel<S> where S: ScalarFloat {
+    #[inline]
+    fn emitted_power(&self) -> Lumens<S> {
+        Lumens(luminance(&emitted_power(self)))
+    }
+}
+
+impl<S> PowerEmitter<S> for NGonLightModel<S> where S: ScalarFloat {
+    /// The flux of the polygon's radiance, modulated by the mean of its
+    /// emission texture if it has one.
+    fn emitted_power(&self) -> Lumens<S> {
+        let pi: S = num_traits::cast(core::f64::consts::PI).unwrap();
+
+        Lumens(luminance(&self.mean_radiance()) * pi * self.area())
+    }
+}
+
+impl<S> PowerEmitter<S> for MeshLightModel<S> where S: ScalarFloat {
+    #[inline]
+    fn emitted_power(&self) -> Lumens<S> {
+        Lumens(luminance(&self.power()))
+    }
+}
+
+impl<S, M> LightScene<S, M>
+    where S: ScalarFloat,
+          M: IlluminationModel + PowerEmitter<S>,
+{
+    /// The total luminous flux the scene's lights emit, including each
+    /// light's exposure compensation, e.g. for building a power based light
+    /// sampling distribution or auditing the energy of a scene.
+    pub fn total_power(&self) -> Lumens<S> {
+        let power = self.lights().iter().fold(S::zero(), |power, light| {
+            power + light.model().emitted_power().0 * light.exposure_multiplier()
+        });
+
+        Lumens(power)
+    }
+}
+
+/// A punctual illumination model whose emission is a luminous intensity in
+/// candela, so the illuminance it produces is measured in lux.
+///
+/// Only the photometric models implement this trait. The Phong-style models
+/// store colors without physical units, and have no illuminance in lux.
+pub trait LuminousSource<S>: IlluminationModel + Sized {
+    /// The luminous intensity a light with this model emits along the unit
+    /// direction `direction` in world space, pointing away from the light.
+    /// Subtractive lights have a negative intensity.
+    fn luminous_intensity_toward(light: &Light<S, Self>, direction: &Vector3<S>) -> Candela<S>;
+
+    /// The falloff of the model's emission at a distance from the light,
+    /// which is the inverse square law for a physical light.
+    fn distance_attenuation(&self, distance: S) -> S;
+}
+
+impl<S> LuminousSource<S> for PbrLightModel<S> where S: ScalarFloat {
+    #[inline]
+    fn luminous_intensity_toward(light: &PbrLight<S>, _direction: &Vector3<S>) -> Candela<S> {
+        let model = light.model();
+
+        Candela(model.intensity * model.polarity.sign())
+    }
+
+    #[inline]
+    fn distance_attenuation(&self, distance: S) -> S {
+        self.attenuation.evaluate(distance)
+    }
+}
+
+impl<S> LuminousSource<S> for IesLightModel<S> where S: ScalarFloat {
+    /// The intensity of the photometric web in the direction, scaled by the
+    /// luminance of the light's color.
+    #[inline]
+    fn luminous_intensity_toward(light: &IesLight<S>, direction: &Vector3<S>) -> Candela<S> {
+        Candela(light.intensity_in_direction(direction) * luminance(&light.model().color))
+    }
+
+    #[inline]
+    fn distance_attenuation(&self, distance: S) -> S {
+        Attenuation::InverseSquare.evaluate(distance)
+    }
+}
+
+impl<S, M> Light<S, M>
+    where S: ScalarFloat,
+          M: LuminousSource<S>,
+{
+    /// The unit direction from a point towards the light and the distance
+    /// between them, or `None` if the point lies at the light's position.
+    #[inline]
+    pub(crate) fn direction_from(&self, point: &Vector3<S>) -> Option<(Vector3<S>, S)> {
+        let to_light = self.position() - point;
+        let distance = to_light.magnitude();
+        if distance <= S::zero() {
+            return None;
+        }
+
+        Some((to_light / distance, distance))
+    }
+
+    /// The illuminance the light produces at a point on a surface with unit
+    /// normal `normal`, ignoring occlusion and including the light's exposure
+    /// compensation. Surfaces facing away from the light receive none.
+    pub fn illuminance_at(&self, point: &Vector3<S>, normal: &Vector3<S>) -> Lux<S> {
+        let (direction, distance) = match self.direction_from(point) {
+            Some(to_light) => to_light,
+            None => return Lux(S::zero()),
+        };
+        let cos_angle = normal.dot(&direction);
+        if cos_angle <= S::zero() {
+            return Lux(S::zero());
+        }
+        let intensity = M::luminous_intensity_toward(self, &(-direction)).0;
+        let attenuation = self.model().distance_attenuation(distance);
+
+        Lux(intensity * attenuation * cos_angle * self.exposure_multiplier())
+    }
+}
+
+/// The illuminance a set of lights produces at a point on a surface with unit
+/// normal `normal`, as a virtual lux meter would read it, e.g. to check that a
+/// desk receives 500 lux or to drive a light level game mechanic.
+///
+/// The callback `visible(origin, direction, t_max)` reports whether the ray
+/// from `origin` along the unit vector `direction` travels the distance
+/// `t_max` without hitting an occluder, so callers can answer shadow queries
+/// with their own ray tracer. Each light is tested with one ray from the
+/// point towards it; a callback that always returns `true` ignores
+/// occlusion.
+pub fn illuminance_lux<S, M, F>(lights: &[Light<S, M>], point: &Vector3<S>, normal: &Vector3<S>, visible: F) -> Lux<S>
+    where S: ScalarFloat,
+          M: LuminousSource<S>,
+          F: Fn(&Vector3<S>, &Vector3<S>, S) -> bool,
+{
+    let illuminance = lights.iter().fold(S::zero(), |illuminance, light| {
+        let lux = light.illuminance_at(point, normal).0;
+        if lux == S::zero() {
+            return illuminance;
+        }
+        match light.direction_from(point) {
+            Some((direction, distance)) if visible(point, &direction, distance) => illuminance + lux,
+            _ => illuminance,
+        }
+    });
+
+    Lux(illuminance)
+}
+
+impl<S, M> LightScene<S, M>
+    where S: ScalarFloat,
+          M: LuminousSource<S>,
+{
+    /// The illuminance the scene's lights produce at a point on a surface,
+    /// as [`illuminance_lux`](fn.illuminance_lux.html) computes it for a
+    /// slice of lights.
+    #[inline]
+    pub fn illuminance_lux<F>(&self, point: &Vector3<S>, normal: &Vector3<S>, visible: F) -> Lux<S>
+        where F: Fn(&Vector3<S>, &Vector3<S>, S) -> bool
+    {
+        illuminance_lux(self.lights(), point, normal, visible)
+    }
+}
+
+impl<S, M> FrozenLightScene<S, M>
+    where S: ScalarFloat,
+          M: LuminousSource<S>,
+{
+    /// The illuminance the snapshot's lights produce at a point on a
+    /// surface, as [`illuminance_lux`](fn.illuminance_lux.html) computes it
+    /// for a slice of lights.
+    #[inline]
+    pub fn illuminance_lux<F>(&self, point: &Vector3<S>, normal: &Vector3<S>, visible: F) -> Lux<S>
+        where F: Fn(&Vector3<S>, &Vector3<S>, S) -> bool
+    {
+        illuminance_lux(self.lights(), point, normal, visible)
+    }
+}
@@ -0,0 +1,286 @@
+use cglinalg::{
+    Magnitude,
+    Vector3,
+    ScalarFloat,
+};
+use crate::{
+    BeamLightModel,
+    DirectionalLightModel,
+    DiskLightModel,
+    IlluminationModel,
+    Light,
+    PointLightModel,
+    SphereLightModel,
+    SpotLightModel,
+    TubeLightModel,
+};
+use crate::pbr::PbrLightModel;
+use crate::polygon::NGonLightModel;
+use crate::scene::{
+    LightHandle,
+    LightScene,
+};
+
+
+/// The shape of the proxy geometry an editor draws to represent a light.
+/// Gizmos are placed at the light's position and oriented along the light's
+/// forward axis.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum GizmoShape<S> {
+    /// A sphere centered at the light's position.
+    Sphere {
+        radius: S,
+    },
+    /// A cone with its apex at the light's position opening along the light's
+    /// forward axis.
+    Cone {
+        /// The distance from the apex to the base of the cone.
+        length: S,
+        /// The angle between the cone's axis and its surface, in radians.
+        half_angle: S,
+    },
+}
+
+/// A lighting model with a gizmo that can be picked in an editor.
+pub trait Pickable<S> {
+    /// The shape of the light's gizmo, for a gizmo of the given size in
+    /// world units.
+    fn gizmo_shape(&self, gizmo_size: S) -> GizmoShape<S>;
+}
+
+impl<S> Pickable<S> for PointLightModel<S> where S: ScalarFloat {
+    #[inline]
+    fn gizmo_shape(&self, gizmo_size: S) -> GizmoShape<S> {
+        GizmoShape::Sphere {
+            radius: gizmo_size,
+        }
+    }
+}
+
+impl<S> Pickable<S> for DirectionalLightModel<S> where S: ScalarFloat {
+    #[inline]
+    fn gizmo_shape(&self, gizmo_size: S) -> GizmoShape<S> {
+        GizmoShape::Sphere {
+            radius: gizmo_size,
+        }
+    }
+}
+
+impl<S> Pickable<S> for DiskLightModel<S> where S: ScalarFloat {
+    #[inline]
+    fn gizmo_shape(&self, gizmo_size: S) -> GizmoShape<S> {
+        GizmoShape::Sphere {
+            radius: gizmo_size.max(self.radius),
+        }
+    }
+}
+
+impl<S> Pickable<S> for SphereLightModel<S> where S: ScalarFloat {
+    #[inline]
+    fn gizmo_shape(&self, gizmo_size: S) -> GizmoShape<S> {
+        GizmoShape::Sphere {
+            radius: gizmo_size.max(self.radius),
+        }
+    }
+}
+
+impl<S> Pickable<S> for TubeLightModel<S> where S: ScalarFloat {
+    #[inline]
+    fn gizmo_shape(&self, gizmo_size: S) -> GizmoShape<S> {
+        let two = S::one() + S::one();
+
+        GizmoShape::Sphere {
+            radius: gizmo_size.max(self.length / two + self.radius),
+        }
+    }
+}
+
+impl<S> Pickable<S> for NGonLightModel<S> where S: ScalarFloat {
+    #[inline]
+    fn gizmo_shape(&self, gizmo_size: S) -> GizmoShape<S> {
+        GizmoShape::Sphere {
+            radius: gizmo_size.max(self.bounding_radius()),
+        }
+    }
+}
+
+impl<S> Pickable<S> for BeamLightModel<S> where S: ScalarFloat {
+    #[inline]
+    fn gizmo_shape(&self, gizmo_size: S) -> GizmoShape<S> {
+        GizmoShape::Sphere {
+            radius: gizmo_size,
+        }
+    }
+}
+
+impl<S> Pickable<S> for PbrLightModel<S> where S: ScalarFloat {
+    #[inline]
+    fn gizmo_shape(&self, gizmo_size: S) -> GizmoShape<S> {
+        GizmoShape::Sphere {
+            radius: gizmo_size,
+        }
+    }
+}
+
+impl<S> Pickable<S> for SpotLightModel<S> where S: ScalarFloat {
+    #[inline]
+    fn gizmo_shape(&self, gizmo_size: S) -> GizmoShape<S> {
+        GizmoShape::Cone {
+            length: gizmo_size,
+            half_angle: self.outer_cutoff.acos(),
+        }
+    }
+}
+
+impl<S, M> Light<S, M>
+    where S: ScalarFloat,
+          M: IlluminationModel + Pickable<S>,
+{
+    /// Intersect a ray with the light's gizmo. The result is the distance
+    /// along the ray to the nearest intersection, in units of the ray
+    /// direction's length, if the ray hits the gizmo.
+    pub fn intersect_gizmo(
+        &self,
+        ray_origin: &Vector3<S>,
+        ray_direction: &Vector3<S>,
+        gizmo_size: S) -> Option<S>
+    {
+        let position = self.position();
+        match self.model().gizmo_shape(gizmo_size) {
+            GizmoShape::Sphere { radius } => {
+                intersect_sphere(ray_origin, ray_direction, &position, radius)
+            }
+            GizmoShape::Cone { length, half_angle } => {
+                let axis = self.forward_axis().normalize();
+                intersect_cone(ray_origin, ray_direction, &position, &axis, length, half_angle)
+            }
+        }
+    }
+}
+
+/// Find the light whose gizmo is hit first by a ray. The result is the index
+/// of the light in `lights` and the distance along the ray to the hit.
+pub fn pick<S, M>(
+    lights: &[Light<S, M>],
+    ray_origin: &Vector3<S>,
+    ray_direction: &Vector3<S>,
+    gizmo_size: S) -> Option<(usize, S)>
+    where S: ScalarFloat,
+          M: IlluminationModel + Pickable<S>,
+{
+    let mut nearest: Option<(usize, S)> = None;
+    for (i, light) in lights.iter().enumerate() {
+        if let Some(t) = light.intersect_gizmo(ray_origin, ray_direction, gizmo_size) {
+            match nearest {
+                Some((_, nearest_t)) if nearest_t <= t => {}
+                _ => nearest = Some((i, t)),
+            }
+        }
+    }
+
+    nearest
+}
+
+impl<S, M> LightScene<S, M>
+    where S: ScalarFloat,
+          M: IlluminationModel + Pickable<S>,
+{
+    /// Find the light whose gizmo is hit first by a ray, as `pick` does for
+    /// a slice, e.g. for selecting a light with a click in an editor. The
+    /// result is the light's handle and the distance along the ray to the
+    /// hit.
+    #[inline]
+    pub fn pick(&self, ray_origin: &Vector3<S>, ray_direction: &Vector3<S>, gizmo_size: S) -> Option<(LightHandle, S)> {
+        pick(self.lights(), ray_origin, ray_direction, gizmo_size)
+            .map(|(index, t)| (self.handles()[index], t))
+    }
+}
+
+/// Intersect a ray with a sphere, returning the nearest non-negative
+/// ray parameter.
+fn intersect_sphere<S: ScalarFloat>(
+    ray_origin: &Vector3<S>,
+    ray_direction: &Vector3<S>,
+    center: &Vector3<S>,
+    radius: S) -> Option<S>
+{
+    let offset = ray_origin - center;
+    let a = ray_direction.dot(ray_direction);
+    let half_b = offset.dot(ray_direction);
+    let c = offset.dot(&offset) - radius * radius;
+
+    nearest_root(a, half_b, c)
+}
+
+/// Intersect a ray with a solid cone capped by a disk at its base, returning
+/// the nearest non-negative ray parameter.
+fn intersect_cone<S: ScalarFloat>(
+    ray_origin: &Vector3<S>,
+    ray_direction: &Vector3<S>,
+    apex: &Vector3<S>,
+    axis: &Vector3<S>,
+    length: S,
+    half_angle: S) -> Option<S>
+{
+    let cos_squared = half_angle.cos() * half_angle.cos();
+    let offset = ray_origin - apex;
+    let d_dot_axis = ray_direction.dot(axis);
+    let o_dot_axis = offset.dot(axis);
+    let a = d_dot_axis * d_dot_axis - ray_direction.dot(ray_direction) * cos_squared;
+    let half_b = d_dot_axis * o_dot_axis - ray_direction.dot(&offset) * cos_squared;
+    let c = o_dot_axis * o_dot_axis - offset.dot(&offset) * cos_squared;
+
+    let mut nearest: Option<S> = None;
+    let mut consider = |t: S| {
+        if t >= S::zero() {
+            match nearest {
+                Some(nearest_t) if nearest_t <= t => {}
+                _ => nearest = Some(t),
+            }
+        }
+    };
+
+    // The quadratic describes a double cone, so only keep the hits on the
+    // forward nappe between the apex and the base.
+    let discriminant = half_b * half_b - a * c;
+    if a != S::zero() && discriminant >= S::zero() {
+        let root = discriminant.sqrt();
+        for &t in [(-half_b - root) / a, (-half_b + root) / a].iter() {
+            let height = o_dot_axis + t * d_dot_axis;
+            if height >= S::zero() && height <= length {
+                consider(t);
+            }
+        }
+    }
+
+    if d_dot_axis != S::zero() {
+        let t = (length - o_dot_axis) / d_dot_axis;
+        let base_radius = length * half_angle.tan();
+        let hit = ray_origin + ray_direction * t;
+        let base_center = apex + axis * length;
+        if (hit - base_center).magnitude_squared() <= base_radius * base_radius {
+            consider(t);
+        }
+    }
+
+    nearest
+}
+
+/// Find the smallest non-negative root of `a t^2 + 2 half_b t + c = 0`.
+fn nearest_root<S: ScalarFloat>(a: S, half_b: S, c: S) -> Option<S> {
+    let discriminant = half_b * half_b - a * c;
+    if a == S::zero() || discriminant < S::zero() {
+        return None;
+    }
+
+    let root = discriminant.sqrt();
+    let near = (-half_b - root) / a;
+    let far = (-half_b + root) / a;
+    if near >= S::zero() {
+        Some(near)
+    } else if far >= S::zero() {
+        Some(far)
+    } else {
+        None
+    }
+}
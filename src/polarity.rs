@@ -0,0 +1,94 @@
+use cglinalg::{
+    ScalarFloat,
+};
+use crate::{
+    IlluminationModel,
+    Light,
+    PointLightModel,
+    SpotLightModel,
+};
+use crate::pbr::PbrLightModel;
+
+
+/// Whether a light adds light to a scene or takes it away.
+///
+/// Subtractive (or negative) lights are a film lighting tool for darkening
+/// part of a scene locally, e.g. to deepen the shadow under a table without
+/// relighting the rest of the set. A subtractive light's colors and intensity
+/// stay positive; the polarity flips the sign of its contribution.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Polarity {
+    /// The light adds its contribution to the scene.
+    Additive,
+    /// The light subtracts its contribution from the scene.
+    Subtractive,
+}
+
+impl Polarity {
+    /// The sign a light's contribution is multiplied by.
+    #[inline]
+    pub fn sign<S: ScalarFloat>(self) -> S {
+        match self {
+            Polarity::Additive => S::one(),
+            Polarity::Subtractive => -S::one(),
+        }
+    }
+
+    /// Determine whether the polarity subtracts light.
+    #[inline]
+    pub fn is_subtractive(self) -> bool {
+        self == Polarity::Subtractive
+    }
+}
+
+impl Default for Polarity {
+    #[inline]
+    fn default() -> Polarity {
+        Polarity::Additive
+    }
+}
+
+/// A lighting model that can add or subtract light.
+pub trait Polarized {
+    /// The polarity of the model's contribution.
+    fn polarity(&self) -> Polarity;
+}
+
+impl<S> Polarized for PointLightModel<S> where S: ScalarFloat {
+    #[inline]
+    fn polarity(&self) -> Polarity {
+        self.polarity
+    }
+}
+
+impl<S> Polarized for SpotLightModel<S> where S: ScalarFloat {
+    #[inline]
+    fn polarity(&self) -> Polarity {
+        self.polarity
+    }
+}
+
+impl<S> Polarized for PbrLightModel<S> where S: ScalarFloat {
+    #[inline]
+    fn polarity(&self) -> Polarity {
+        self.polarity
+    }
+}
+
+impl<S, M> Light<S, M>
+    where S: ScalarFloat,
+          M: IlluminationModel + Polarized,
+{
+    /// The polarity of the light's contribution.
+    #[inline]
+    pub fn polarity(&self) -> Polarity {
+        self.model().polarity()
+    }
+
+    /// Determine whether the light subtracts light from the scene.
+    #[inline]
+    pub fn is_subtractive(&self) -> bool {
+        self.polarity().is_subtractive()
+    }
+}
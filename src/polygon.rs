@@ -0,0 +1,384 @@
+use cglinalg::{
+    Magnitude,
+    Vector2,
+    Vector3,
+    ScalarFloat,
+};
+use crate::{
+    Dimmable,
+    IlluminationModel,
+    Light,
+};
+use crate::texture::EmissionTexture;
+use std::sync::Arc;
+
+
+/// A specification describing a polygonal area light, such as a window pane
+/// or an LED panel. The polygon lies in the plane spanned by the light's right
+/// and up axes through its position, and emits from its front face along the
+/// light's forward axis.
+#[derive(Clone, Debug, PartialEq)]
+pub struct NGonLightModelSpec<S> {
+    vertices: Vec<Vector2<S>>,
+    radiance: Vector3<S>,
+    emission: Option<Arc<EmissionTexture<S>>>,
+    shadow_tint: Vector3<S>,
+}
+
+impl<S> NGonLightModelSpec<S> where S: ScalarFloat {
+    /// Construct a new polygonal light specification from the vertices of a
+    /// convex polygon in the light's local coordinate frame, with the first
+    /// coordinate along the right axis and the second along the up axis.
+    ///
+    /// The vertices are stored in counterclockwise order with respect to the
+    /// right and up axes, reversing them if necessary. The result is `None`
+    /// if there are fewer than three vertices or the polygon is not strictly
+    /// convex.
+    pub fn new(vertices: Vec<Vector2<S>>, radiance: Vector3<S>) -> Option<NGonLightModelSpec<S>> {
+        let mut vertices = vertices;
+        let count = vertices.len();
+        if count < 3 {
+            return None;
+        }
+
+        let turn = |i: usize| {
+            let a = vertices[i];
+            let b = vertices[(i + 1) % count];
+            let c = vertices[(i + 2) % count];
+
+            (b - a).x * (c - b).y - (b - a).y * (c - b).x
+        };
+        let clockwise = turn(0) < S::zero();
+        for i in 0..count {
+            let turn = turn(i);
+            if turn == S::zero() || (turn < S::zero()) != clockwise {
+                return None;
+            }
+        }
+        if clockwise {
+            vertices.reverse();
+        }
+
+        Some(NGonLightModelSpec {
+            vertices: vertices,
+            radiance: radiance,
+            emission: None,
+            shadow_tint: Vector3::zero(),
+        })
+    }
+
+    /// Construct a new rectangular light specification, a rectangle of
+    /// `width` along the right axis by `height` along the up axis centered
+    /// at the light's position. The result is `None` if the rectangle is
+    /// empty.
+    pub fn rectangle(width: S, height: S, radiance: Vector3<S>) -> Option<NGonLightModelSpec<S>> {
+        let one_half: S = num_traits::cast(0.5).unwrap();
+        let (x, y) = (width * one_half, height * one_half);
+        let vertices = vec![
+            Vector2::new(-x, -y),
+            Vector2::new(x, -y),
+            Vector2::new(x, y),
+            Vector2::new(-x, y),
+        ];
+
+        Self::new(vertices, radiance)
+    }
+
+    /// Modulate the emission of a rectangular light with an image, such as
+    /// the picture on a television. The image covers the rectangle with its
+    /// first row along the top edge, and multiplies the light's radiance.
+    /// The result is `None` unless the polygon is a rectangle aligned with
+    /// the light's right and up axes, as made by
+    /// [`rectangle`](#method.rectangle).
+    pub fn with_emission(self, emission: Arc<EmissionTexture<S>>) -> Option<NGonLightModelSpec<S>> {
+        if !is_rectangle(&self.vertices) {
+            return None;
+        }
+
+        Some(NGonLightModelSpec {
+            emission: Some(emission),
+            ..self
+        })
+    }
+}
+
+/// Determine whether a convex polygon is a rectangle aligned with the axes.
+fn is_rectangle<S: ScalarFloat>(vertices: &[Vector2<S>]) -> bool {
+    if vertices.len() != 4 {
+        return false;
+    }
+    let (min, max) = bounds(vertices);
+
+    vertices.iter().all(|vertex| {
+        (vertex.x == min.x || vertex.x == max.x) && (vertex.y == min.y || vertex.y == max.y)
+    })
+}
+
+/// The corners of the bounding rectangle of a set of points.
+fn bounds<S: ScalarFloat>(vertices: &[Vector2<S>]) -> (Vector2<S>, Vector2<S>) {
+    vertices.iter().skip(1).fold((vertices[0], vertices[0]), |(min, max), vertex| {
+        (
+            Vector2::new(min.x.min(vertex.x), min.y.min(vertex.y)),
+            Vector2::new(max.x.max(vertex.x), max.y.max(vertex.y)),
+        )
+    })
+}
+
+impl_with_shadow_tint!(NGonLightModelSpec);
+
+/// The illumination model of a one-sided convex polygonal area light with
+/// uniform emitted radiance, or a rectangular light with emission modulated
+/// by an image.
+#[derive(Clone, Debug, PartialEq)]
+pub struct NGonLightModel<S> {
+    /// The vertices of the polygon in the light's local coordinate frame, in
+    /// counterclockwise order with respect to the right and up axes.
+    vertices: Vec<Vector2<S>>,
+    /// The radiance emitted from every point of the polygon's front face.
+    pub radiance: Vector3<S>,
+    /// The optional image modulating the radiance of a rectangular light.
+    /// Textures are shared between lights, and between a light and its
+    /// specification.
+    emission: Option<Arc<EmissionTexture<S>>>,
+    /// The fraction of the light's emission that reaches shadowed regions in
+    /// each color channel. Zero casts black shadows.
+    pub shadow_tint: Vector3<S>,
+}
+
+impl_illumination_model!(NGonLightModel, NGonLightModelSpec, [vertices, radiance, emission, shadow_tint]);
+
+impl_dimmable!(NGonLightModel, [radiance]);
+
+impl<S> NGonLightModel<S> where S: ScalarFloat {
+    /// The vertices of the polygon in the light's local coordinate frame, in
+    /// counterclockwise order with respect to the right and up axes.
+    #[inline]
+    pub fn vertices(&self) -> &[Vector2<S>] {
+        &self.vertices
+    }
+
+    /// The image modulating the light's radiance, if any.
+    #[inline]
+    pub fn emission(&self) -> Option<&Arc<EmissionTexture<S>>> {
+        self.emission.as_ref()
+    }
+
+    /// The radiance emitted from a point in the plane of the polygon, given
+    /// in the light's local coordinate frame.
+    pub fn radiance_at(&self, point: &Vector2<S>) -> Vector3<S> {
+        match self.emission {
+            Some(ref emission) => {
+                let texel = emission.lookup(&self.texture_coordinate(point));
+
+                Vector3::new(self.radiance.x * texel.x, self.radiance.y * texel.y, self.radiance.z * texel.z)
+            }
+            None => self.radiance,
+        }
+    }
+
+    /// The mean radiance emitted over the polygon, the radiance modulated by
+    /// the mean of the emission texture if the light has one.
+    pub fn mean_radiance(&self) -> Vector3<S> {
+        match self.emission {
+            Some(ref emission) => {
+                let texels = emission.texels();
+                let count: S = num_traits::cast(texels.len()).unwrap();
+                let mean = texels.iter().fold(Vector3::zero(), |sum, texel| sum + texel) / count;
+
+                Vector3::new(self.radiance.x * mean.x, self.radiance.y * mean.y, self.radiance.z * mean.z)
+            }
+            None => self.radiance,
+        }
+    }
+
+    /// The texture coordinate of a point in the plane of the polygon, with
+    /// the unit square spanning the polygon's bounding rectangle and the
+    /// first row of the image along its top edge.
+    fn texture_coordinate(&self, point: &Vector2<S>) -> Vector2<S> {
+        let (min, max) = bounds(&self.vertices);
+
+        Vector2::new((point.x - min.x) / (max.x - min.x), (max.y - point.y) / (max.y - min.y))
+    }
+
+    /// Sample a point on the polygon from a uniform sample in the unit
+    /// square, returning the point in the light's local coordinate frame and
+    /// its probability density with respect to area. A textured light
+    /// samples its image's luminance, and other lights sample uniformly by
+    /// area.
+    pub fn sample_point(&self, u: S, v: S) -> (Vector2<S>, S) {
+        let area = self.area();
+        if let Some(ref emission) = self.emission {
+            let (uv, pdf) = emission.sample(u, v);
+            let (min, max) = bounds(&self.vertices);
+            let point = Vector2::new(min.x + uv.x * (max.x - min.x), max.y - uv.y * (max.y - min.y));
+
+            return (point, pdf / area);
+        }
+
+        // Choose a triangle of the fan around the first vertex in proportion
+        // to its area, then reuse the rescaled sample to pick a point in it
+        // uniformly.
+        let origin = self.vertices[0];
+        let count = self.vertices.len();
+        let one_half: S = num_traits::cast(0.5).unwrap();
+        let target = u * area;
+        let mut accumulated = S::zero();
+        let mut chosen = (self.vertices[1], self.vertices[2], S::one());
+        for i in 1..count - 1 {
+            let (a, b) = (self.vertices[i], self.vertices[i + 1]);
+            let triangle = cross(&(a - origin), &(b - origin)) * one_half;
+            chosen = (a, b, (target - accumulated) / triangle);
+            accumulated += triangle;
+            if target < accumulated {
+                break;
+            }
+        }
+        let (a, b, u) = chosen;
+        let u = u.max(S::zero()).min(S::one());
+        let root = u.sqrt();
+        let point = origin * (S::one() - root) + a * (root * (S::one() - v)) + b * (root * v);
+
+        (point, S::one() / area)
+    }
+
+    /// The area of the polygon.
+    pub fn area(&self) -> S {
+        let one_half: S = num_traits::cast(0.5).unwrap();
+
+        self.edges().fold(S::zero(), |area, (a, b)| area + cross(&a, &b)) * one_half
+    }
+
+    /// The centroid of the polygon in the light's local coordinate frame.
+    pub fn centroid(&self) -> Vector2<S> {
+        let three: S = num_traits::cast(3).unwrap();
+        let (sum, twice_area) = self.edges().fold((Vector2::zero(), S::zero()), |(sum, twice_area), (a, b)| {
+            let weight = cross(&a, &b);
+
+            (sum + (a + b) * weight, twice_area + weight)
+        });
+
+        sum / (three * twice_area)
+    }
+
+    /// The distance from the light's position to the farthest vertex.
+    pub fn bounding_radius(&self) -> S {
+        self.vertices.iter().fold(S::zero(), |radius, vertex| {
+            radius.max((vertex.x * vertex.x + vertex.y * vertex.y).sqrt())
+        })
+    }
+
+    /// Determine whether a point in the light's local coordinate frame lies
+    /// inside the polygon or on its boundary.
+    pub fn contains(&self, point: &Vector2<S>) -> bool {
+        self.edges().all(|(a, b)| cross(&(b - a), &(point - a)) >= S::zero())
+    }
+
+    /// The edges of the polygon as pairs of consecutive vertices.
+    fn edges(&self) -> impl Iterator<Item = (Vector2<S>, Vector2<S>)> + '_ {
+        let count = self.vertices.len();
+
+        (0..count).map(move |i| (self.vertices[i], self.vertices[(i + 1) % count]))
+    }
+}
+
+#[inline]
+fn cross<S: ScalarFloat>(a: &Vector2<S>, b: &Vector2<S>) -> S {
+    a.x * b.y - a.y * b.x
+}
+
+pub type NGonLight<S> = Light<S, NGonLightModel<S>>;
+
+impl<S> NGonLight<S> where S: ScalarFloat {
+    /// Map a point in the plane of the polygon to world space.
+    #[inline]
+    fn to_world(&self, point: &Vector2<S>) -> Vector3<S> {
+        self.position() + self.right_axis() * point.x + self.up_axis() * point.y
+    }
+
+    /// The vertices of the polygon in world space, in the same order as the
+    /// model's vertices.
+    pub fn world_vertices(&self) -> Vec<Vector3<S>> {
+        self.model().vertices.iter().map(|vertex| self.to_world(vertex)).collect()
+    }
+
+    /// The centroid of the polygon in world space.
+    #[inline]
+    pub fn world_centroid(&self) -> Vector3<S> {
+        self.to_world(&self.model().centroid())
+    }
+
+    /// The unit normal of the polygon's emitting face in world space.
+    #[inline]
+    pub fn normal(&self) -> Vector3<S> {
+        self.forward_axis()
+    }
+
+    /// Map a point in world space to the plane of the polygon.
+    #[inline]
+    fn to_local(&self, point: &Vector3<S>) -> Vector2<S> {
+        let offset = point - self.position();
+
+        Vector2::new(offset.dot(&self.right_axis()), offset.dot(&self.up_axis()))
+    }
+
+    /// Sample a point on the polygon from a uniform sample in the unit
+    /// square, as in `NGonLightModel::sample_point`, for a shading point
+    /// `point` in front of the polygon.
+    ///
+    /// The result is the unit direction from the shading point towards the
+    /// sampled point and the probability density of the direction with
+    /// respect to solid angle, or `None` if the shading point sees the
+    /// polygon edge on or from behind.
+    pub fn sample(&self, u: S, v: S, point: &Vector3<S>) -> Option<(Vector3<S>, S)> {
+        let (sampled, pdf) = self.model().sample_point(u, v);
+        if pdf <= S::zero() {
+            return None;
+        }
+
+        let to_sample = self.to_world(&sampled) - point;
+        let distance_squared = to_sample.magnitude_squared();
+        if distance_squared <= S::zero() {
+            return None;
+        }
+
+        let direction = to_sample / distance_squared.sqrt();
+        let cos_polygon = -direction.dot(&self.normal());
+        if cos_polygon <= S::zero() {
+            return None;
+        }
+
+        Some((direction, pdf * distance_squared / cos_polygon))
+    }
+
+    /// Intersect a ray with the polygon. The result is the distance along the
+    /// ray to the hit, in units of the ray direction's length, if the ray
+    /// hits either face of the polygon in front of its origin.
+    pub fn intersect(&self, origin: &Vector3<S>, direction: &Vector3<S>) -> Option<S> {
+        let normal = self.normal();
+        let denominator = direction.dot(&normal);
+        if denominator == S::zero() {
+            return None;
+        }
+        let t = (self.position() - origin).dot(&normal) / denominator;
+        if t <= S::zero() {
+            return None;
+        }
+        let hit = origin + direction * t;
+
+        if self.model().contains(&self.to_local(&hit)) { Some(t) } else { None }
+    }
+
+    /// The radiance `Le(p, ω)` the polygon emits from the point `point` on
+    /// its surface, with `direction` pointing from the receiver towards the
+    /// point, as in `DiskLight::emitted_radiance`. The polygon emits only
+    /// from its front face, and a textured light looks up its image at the
+    /// point.
+    #[inline]
+    pub fn emitted_radiance(&self, point: &Vector3<S>, direction: &Vector3<S>) -> Vector3<S> {
+        if direction.dot(&self.normal()) < S::zero() {
+            self.model().radiance_at(&self.to_local(point))
+        } else {
+            Vector3::zero()
+        }
+    }
+}
@@ -0,0 +1,206 @@
+use cglinalg::{
+    Magnitude,
+    Vector3,
+    ScalarFloat,
+};
+use crate::{
+    Light,
+    LightAttitudeSpec,
+    PointLight,
+    PointLightModelSpec,
+};
+use crate::random::Random;
+
+
+/// The per-instance variation applied to each light in a string of lights.
+///
+/// Real strings of lights are never perfectly regular. The jitter perturbs
+/// each light's position and brightness by a bounded random amount so that
+/// large arrays of lights do not look mechanically placed.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct StringLightsJitter<S> {
+    /// The maximum distance along each coordinate axis that a light may be
+    /// displaced from its position along the path.
+    pub position: S,
+    /// The maximum relative change in brightness of a light. A value of
+    /// `0.25` scales each light's colors by a factor in `[0.75, 1.25]`.
+    pub intensity: S,
+    /// The seed for the random number generator. The same seed always
+    /// produces the same jitter.
+    pub seed: u64,
+}
+
+impl<S> StringLightsJitter<S> where S: ScalarFloat {
+    /// Construct a new jitter specification.
+    #[inline]
+    pub fn new(position: S, intensity: S, seed: u64) -> StringLightsJitter<S> {
+        StringLightsJitter {
+            position: position,
+            intensity: intensity,
+            seed: seed,
+        }
+    }
+
+    /// Construct a jitter specification that leaves every light unchanged.
+    #[inline]
+    pub fn zero() -> StringLightsJitter<S> {
+        StringLightsJitter {
+            position: S::zero(),
+            intensity: S::zero(),
+            seed: 0,
+        }
+    }
+}
+
+/// Instantiate `count` point lights evenly spaced by arc length along the
+/// polyline `path`, sharing the same illumination model parameters.
+///
+/// The first and last lights sit on the endpoints of the path. A single
+/// light is placed at the midpoint of the path. To place lights along a
+/// smooth curve, tessellate the curve first, e.g. with [`catmull_rom_path`].
+pub fn string_lights<S>(
+    path: &[Vector3<S>],
+    count: usize,
+    model_spec: &PointLightModelSpec<S>) -> Vec<PointLight<S>>
+    where S: ScalarFloat
+{
+    string_lights_with_jitter(path, count, model_spec, &StringLightsJitter::zero())
+}
+
+/// Instantiate `count` point lights evenly spaced by arc length along the
+/// polyline `path`, perturbing each light's position and brightness by the
+/// jitter specification.
+pub fn string_lights_with_jitter<S>(
+    path: &[Vector3<S>],
+    count: usize,
+    model_spec: &PointLightModelSpec<S>,
+    jitter: &StringLightsJitter<S>) -> Vec<PointLight<S>>
+    where S: ScalarFloat
+{
+    if path.is_empty() || count == 0 {
+        return Vec::new();
+    }
+
+    let total_length = path.windows(2).fold(S::zero(), |length, segment| {
+        length + (segment[1] - segment[0]).magnitude()
+    });
+    let mut random = Random::new(jitter.seed);
+    let mut lights = Vec::with_capacity(count);
+    for i in 0..count {
+        let distance = if count == 1 {
+            total_length / (S::one() + S::one())
+        } else {
+            let i_scalar: S = num_traits::cast(i).unwrap();
+            let last: S = num_traits::cast(count - 1).unwrap();
+            total_length * (i_scalar / last)
+        };
+        let offset = Vector3::new(
+            random.next_signed_scalar::<S>() * jitter.position,
+            random.next_signed_scalar::<S>() * jitter.position,
+            random.next_signed_scalar::<S>() * jitter.position,
+        );
+        let brightness = S::one() + random.next_signed_scalar::<S>() * jitter.intensity;
+        let position = point_at_distance(path, distance) + offset;
+        let instance_spec = PointLightModelSpec {
+            ambient: model_spec.ambient * brightness,
+            diffuse: model_spec.diffuse * brightness,
+            specular: model_spec.specular * brightness,
+            ..model_spec.clone()
+        };
+        let attitude_spec = default_attitude_spec(position);
+
+        lights.push(Light::new(&instance_spec, &attitude_spec));
+    }
+
+    lights
+}
+
+/// Tessellate a uniform Catmull-Rom spline through the control points into
+/// a polyline with `samples_per_segment` line segments between each pair of
+/// consecutive control points.
+///
+/// The curve passes through every control point. The resulting polyline can
+/// be passed directly to [`string_lights`].
+pub fn catmull_rom_path<S>(control_points: &[Vector3<S>], samples_per_segment: usize) -> Vec<Vector3<S>>
+    where S: ScalarFloat
+{
+    if control_points.len() < 2 || samples_per_segment == 0 {
+        return control_points.to_vec();
+    }
+
+    let last = control_points.len() - 1;
+    let samples: S = num_traits::cast(samples_per_segment).unwrap();
+    let mut path = Vec::with_capacity(last * samples_per_segment + 1);
+    for i in 0..last {
+        // Duplicate the endpoints so the curve starts and ends on the control points.
+        let p0 = control_points[if i == 0 { 0 } else { i - 1 }];
+        let p1 = control_points[i];
+        let p2 = control_points[i + 1];
+        let p3 = control_points[if i + 2 > last { last } else { i + 2 }];
+        for j in 0..samples_per_segment {
+            let j_scalar: S = num_traits::cast(j).unwrap();
+            path.push(catmull_rom(&p0, &p1, &p2, &p3, j_scalar / samples));
+        }
+    }
+    path.push(control_points[last]);
+
+    path
+}
+
+/// Evaluate a uniform Catmull-Rom segment between `p1` and `p2` at parameter `t`.
+#[inline]
+fn catmull_rom<S: ScalarFloat>(
+    p0: &Vector3<S>,
+    p1: &Vector3<S>,
+    p2: &Vector3<S>,
+    p3: &Vector3<S>,
+    t: S) -> Vector3<S>
+{
+    let one_half: S = num_traits::cast(0.5).unwrap();
+    let two: S = num_traits::cast(2).unwrap();
+    let three: S = num_traits::cast(3).unwrap();
+    let four: S = num_traits::cast(4).unwrap();
+    let five: S = num_traits::cast(5).unwrap();
+    let t2 = t * t;
+    let t3 = t2 * t;
+
+    (p1 * two
+        + (p2 - p0) * t
+        + (p0 * two - p1 * five + p2 * four - p3) * t2
+        + (p1 * three - p0 - p2 * three + p3) * t3
+    ) * one_half
+}
+
+/// Find the point at the given arc length along a polyline.
+fn point_at_distance<S: ScalarFloat>(path: &[Vector3<S>], distance: S) -> Vector3<S> {
+    let mut remaining = distance;
+    for segment in path.windows(2) {
+        let length = (segment[1] - segment[0]).magnitude();
+        if remaining <= length {
+            if length <= S::zero() {
+                return segment[0];
+            }
+
+            return segment[0] + (segment[1] - segment[0]) * (remaining / length);
+        }
+        remaining -= length;
+    }
+
+    path[path.len() - 1]
+}
+
+/// The attitude of a light at the given position facing down the
+/// **negative z-axis** of world space.
+#[inline]
+pub(crate) fn default_attitude_spec<S: ScalarFloat>(position: Vector3<S>) -> LightAttitudeSpec<S> {
+    let zero = S::zero();
+    let one = S::one();
+
+    LightAttitudeSpec::new(
+        position,
+        Vector3::new(zero, zero, -one),
+        Vector3::new(one, zero, zero),
+        Vector3::new(zero, one, zero),
+        Vector3::new(zero, zero, -one),
+    )
+}
@@ -0,0 +1,530 @@
+use cglinalg::{
+    Magnitude,
+    Vector3,
+    Vector4,
+    Matrix4x4,
+    ScalarFloat,
+};
+use crate::{
+    AmbientLightModel,
+    DirectionalLightModel,
+    DiskLightModel,
+    IlluminationModel,
+    Light,
+    PointLightModel,
+    SphereLightModel,
+    SpotLightModel,
+    TubeLightModel,
+};
+use crate::pbr::PbrLightModel;
+use crate::scene::LightScene;
+
+
+/// The Phong light terms a light delivers to a point, after distance and
+/// angular attenuation.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct IncidentLight<S> {
+    pub ambient: Vector3<S>,
+    pub diffuse: Vector3<S>,
+    pub specular: Vector3<S>,
+    /// The fraction of the diffuse and specular terms that reaches the point
+    /// when it is in shadow.
+    pub shadow_tint: Vector3<S>,
+}
+
+/// A lighting model that can be evaluated at a point in space.
+pub trait Illuminate<S> {
+    /// The light terms reaching `point` from a light at `light_position`
+    /// facing along the unit vector `light_forward`.
+    fn incident(
+        &self,
+        light_position: &Vector3<S>,
+        light_forward: &Vector3<S>,
+        point: &Vector3<S>) -> IncidentLight<S>;
+
+    /// The unit direction from `point` towards the light and the distance to
+    /// it, or `None` if the point coincides with the light. By default the
+    /// light is a point at `light_position`.
+    #[inline]
+    fn to_light(
+        &self,
+        light_position: &Vector3<S>,
+        _light_forward: &Vector3<S>,
+        point: &Vector3<S>) -> Option<(Vector3<S>, S)>
+        where S: ScalarFloat
+    {
+        let to_light = light_position - point;
+        let distance = to_light.magnitude();
+        if distance <= S::zero() {
+            return None;
+        }
+
+        Some((to_light / distance, distance))
+    }
+}
+
+impl<S> Illuminate<S> for AmbientLightModel<S> where S: ScalarFloat {
+    #[inline]
+    fn incident(
+        &self,
+        _light_position: &Vector3<S>,
+        _light_forward: &Vector3<S>,
+        _point: &Vector3<S>) -> IncidentLight<S>
+    {
+        IncidentLight {
+            ambient: self.ambient,
+            diffuse: Vector3::zero(),
+            specular: Vector3::zero(),
+            shadow_tint: Vector3::zero(),
+        }
+    }
+
+    /// Ambient light arrives from no particular direction.
+    #[inline]
+    fn to_light(
+        &self,
+        _light_position: &Vector3<S>,
+        _light_forward: &Vector3<S>,
+        _point: &Vector3<S>) -> Option<(Vector3<S>, S)>
+    {
+        None
+    }
+}
+
+impl<S> Illuminate<S> for PointLightModel<S> where S: ScalarFloat {
+    #[inline]
+    fn incident(
+        &self,
+        light_position: &Vector3<S>,
+        _light_forward: &Vector3<S>,
+        point: &Vector3<S>) -> IncidentLight<S>
+    {
+        let attenuation = self.attenuation((point - light_position).magnitude())
+            * self.polarity.sign();
+
+        IncidentLight {
+            ambient: self.ambient * attenuation,
+            diffuse: self.diffuse * attenuation,
+            specular: self.specular * attenuation,
+            shadow_tint: self.shadow_tint,
+        }
+    }
+}
+
+impl<S> Illuminate<S> for SpotLightModel<S> where S: ScalarFloat {
+    fn incident(
+        &self,
+        light_position: &Vector3<S>,
+        light_forward: &Vector3<S>,
+        point: &Vector3<S>) -> IncidentLight<S>
+    {
+        let to_point = point - light_position;
+        let distance = to_point.magnitude();
+        let cos_angle = if distance > S::zero() {
+            to_point.dot(light_forward) / distance
+        } else {
+            S::one()
+        };
+        let attenuation = self.attenuation(distance) * self.polarity.sign();
+        let intensity = attenuation * self.cone_factor(cos_angle);
+
+        IncidentLight {
+            ambient: self.ambient * attenuation,
+            diffuse: self.diffuse * intensity,
+            specular: self.specular * intensity,
+            shadow_tint: self.shadow_tint,
+        }
+    }
+}
+
+impl<S> Illuminate<S> for DirectionalLightModel<S> where S: ScalarFloat {
+    #[inline]
+    fn incident(
+        &self,
+        _light_position: &Vector3<S>,
+        _light_forward: &Vector3<S>,
+        _point: &Vector3<S>) -> IncidentLight<S>
+    {
+        IncidentLight {
+            ambient: self.ambient,
+            diffuse: self.diffuse,
+            specular: self.specular,
+            shadow_tint: self.shadow_tint,
+        }
+    }
+
+    #[inline]
+    fn to_light(
+        &self,
+        _light_position: &Vector3<S>,
+        light_forward: &Vector3<S>,
+        _point: &Vector3<S>) -> Option<(Vector3<S>, S)>
+    {
+        Some((-light_forward, S::infinity()))
+    }
+}
+
+impl<S> Illuminate<S> for DiskLightModel<S> where S: ScalarFloat {
+    fn incident(
+        &self,
+        light_position: &Vector3<S>,
+        light_forward: &Vector3<S>,
+        point: &Vector3<S>) -> IncidentLight<S>
+    {
+        // The disk emits from its front face only, with a Lambertian falloff
+        // away from its normal.
+        let to_point = point - light_position;
+        let distance = to_point.magnitude();
+        let cos_angle = if distance > S::zero() {
+            to_point.dot(light_forward) / distance
+        } else {
+            S::one()
+        };
+        let intensity = cos_angle.max(S::zero());
+
+        IncidentLight {
+            ambient: self.ambient,
+            diffuse: self.diffuse * intensity,
+            specular: self.specular * intensity,
+            shadow_tint: self.shadow_tint,
+        }
+    }
+}
+
+impl<S> Illuminate<S> for SphereLightModel<S> where S: ScalarFloat {
+    fn incident(
+        &self,
+        light_position: &Vector3<S>,
+        _light_forward: &Vector3<S>,
+        point: &Vector3<S>) -> IncidentLight<S>
+    {
+        // The irradiance from a uniformly emitting sphere facing the point is
+        // its radiance times π sin^2(α), for the half angle α it subtends.
+        let pi: S = num_traits::cast(core::f64::consts::PI).unwrap();
+        let distance = (point - light_position).magnitude();
+        let irradiance = self.radiance * (pi * self.sin_squared_half_angle(distance));
+
+        IncidentLight {
+            ambient: Vector3::zero(),
+            diffuse: irradiance,
+            specular: irradiance,
+            shadow_tint: self.shadow_tint,
+        }
+    }
+}
+
+impl<S> Illuminate<S> for TubeLightModel<S> where S: ScalarFloat {
+    fn incident(
+        &self,
+        light_position: &Vector3<S>,
+        _light_forward: &Vector3<S>,
+        point: &Vector3<S>) -> IncidentLight<S>
+    {
+        // Approximate the tube by a sphere with the same projected area,
+        // which is accurate far from the tube and adequate for a preview.
+        let pi: S = num_traits::cast(core::f64::consts::PI).unwrap();
+        let distance = (point - light_position).magnitude();
+        let projected_solid_angle = (self.projected_area() / (distance * distance)).min(pi);
+        let irradiance = self.radiance * projected_solid_angle;
+
+        IncidentLight {
+            ambient: Vector3::zero(),
+            diffuse: irradiance,
+            specular: irradiance,
+            shadow_tint: self.shadow_tint,
+        }
+    }
+}
+
+impl<S> Illuminate<S> for PbrLightModel<S> where S: ScalarFloat {
+    #[inline]
+    fn incident(
+        &self,
+        light_position: &Vector3<S>,
+        _light_forward: &Vector3<S>,
+        point: &Vector3<S>) -> IncidentLight<S>
+    {
+        let illuminance = self.illuminance((point - light_position).magnitude());
+
+        IncidentLight {
+            ambient: Vector3::zero(),
+            diffuse: illuminance,
+            specular: illuminance,
+            shadow_tint: self.shadow_tint,
+        }
+    }
+}
+
+/// The shape of a preview object.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum PreviewShape<S> {
+    /// An infinite plane through a point with a unit normal.
+    Plane { point: Vector3<S>, normal: Vector3<S> },
+    Sphere { center: Vector3<S>, radius: S },
+}
+
+/// A simple object rendered by the preview renderer with a Blinn-Phong
+/// material.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct PreviewObject<S> {
+    pub shape: PreviewShape<S>,
+    /// The diffuse reflectance of the object.
+    pub albedo: Vector3<S>,
+    /// The specular exponent of the object.
+    pub shininess: S,
+}
+
+impl<S> PreviewObject<S> where S: ScalarFloat {
+    /// Construct a new preview object.
+    #[inline]
+    pub fn new(shape: PreviewShape<S>, albedo: Vector3<S>, shininess: S) -> PreviewObject<S> {
+        PreviewObject {
+            shape: shape,
+            albedo: albedo,
+            shininess: shininess,
+        }
+    }
+
+    /// Intersect a ray with a unit direction with the object, returning the
+    /// distance to the nearest hit beyond `t_min`.
+    fn intersect(&self, origin: &Vector3<S>, direction: &Vector3<S>, t_min: S) -> Option<S> {
+        match self.shape {
+            PreviewShape::Plane { point, normal } => {
+                let denominator = direction.dot(&normal);
+                if denominator == S::zero() {
+                    return None;
+                }
+                let t = (point - origin).dot(&normal) / denominator;
+
+                if t > t_min { Some(t) } else { None }
+            }
+            PreviewShape::Sphere { center, radius } => {
+                let to_origin = origin - center;
+                let b = to_origin.dot(direction);
+                let c = to_origin.magnitude_squared() - radius * radius;
+                let discriminant = b * b - c;
+                if discriminant < S::zero() {
+                    return None;
+                }
+                let root = discriminant.sqrt();
+                if -b - root > t_min {
+                    Some(-b - root)
+                } else if -b + root > t_min {
+                    Some(-b + root)
+                } else {
+                    None
+                }
+            }
+        }
+    }
+
+    /// The unit surface normal at a point on the object, facing the ray.
+    fn normal(&self, point: &Vector3<S>, direction: &Vector3<S>) -> Vector3<S> {
+        let normal = match self.shape {
+            PreviewShape::Plane { normal, .. } => normal,
+            PreviewShape::Sphere { center, .. } => (point - center).normalize(),
+        };
+
+        if normal.dot(direction) > S::zero() { -normal } else { normal }
+    }
+}
+
+/// A linear RGB image produced by the preview renderer, stored in row-major
+/// order from the top left pixel.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PreviewImage<S> {
+    width: usize,
+    height: usize,
+    pixels: Vec<Vector3<S>>,
+}
+
+impl<S> PreviewImage<S> where S: ScalarFloat {
+    /// Construct a black image.
+    #[inline]
+    pub fn new(width: usize, height: usize) -> PreviewImage<S> {
+        PreviewImage {
+            width: width,
+            height: height,
+            pixels: vec![Vector3::zero(); width * height],
+        }
+    }
+
+    #[inline]
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    #[inline]
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Get the linear RGB pixels of the image.
+    #[inline]
+    pub fn pixels(&self) -> &[Vector3<S>] {
+        &self.pixels
+    }
+
+    /// Get a pixel of the image.
+    #[inline]
+    pub fn pixel(&self, x: usize, y: usize) -> Vector3<S> {
+        self.pixels[y * self.width + x]
+    }
+
+    /// Convert the image to 8 bit sRGB, clamping values outside `[0, 1]`,
+    /// with three bytes per pixel. The result is stable across runs, which
+    /// makes it suitable for golden image tests.
+    pub fn to_srgb8(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(self.pixels.len() * 3);
+        for pixel in self.pixels.iter() {
+            for &channel in [pixel.x, pixel.y, pixel.z].iter() {
+                bytes.push(encode_srgb8(channel));
+            }
+        }
+
+        bytes
+    }
+}
+
+/// Encode a linear channel value as an 8 bit sRGB value.
+fn encode_srgb8<S: ScalarFloat>(linear: S) -> u8 {
+    let linear: f64 = num_traits::cast(linear.max(S::zero()).min(S::one())).unwrap_or(0.0);
+    let encoded = if linear <= 0.003_130_8 {
+        linear * 12.92
+    } else {
+        1.055 * linear.powf(1.0 / 2.4) - 0.055
+    };
+
+    (encoded * 255.0).round() as u8
+}
+
+/// A small software renderer for previewing light rigs.
+///
+/// The renderer ray casts a handful of caller-supplied planes and spheres
+/// with one ray per pixel, and shades the visible surfaces with the lights'
+/// own illumination models using a Blinn-Phong material and hard shadows
+/// from the same objects. It is meant for validating rigs and for headless
+/// golden image tests, not for production rendering.
+#[derive(Clone, Debug)]
+pub struct PreviewRenderer<S> {
+    inverse_view_projection: Matrix4x4<S>,
+    objects: Vec<PreviewObject<S>>,
+}
+
+impl<S> PreviewRenderer<S> where S: ScalarFloat {
+    /// Construct a renderer for a camera's view-projection matrix, using
+    /// OpenGL clip space conventions. The result is `None` if the matrix is
+    /// not invertible.
+    pub fn new(view_projection: &Matrix4x4<S>, objects: Vec<PreviewObject<S>>) -> Option<PreviewRenderer<S>> {
+        let inverse_view_projection = view_projection.inverse()?;
+
+        Some(PreviewRenderer {
+            inverse_view_projection: inverse_view_projection,
+            objects: objects,
+        })
+    }
+
+    /// Add the contribution of every light in a scene to an image, as
+    /// [`render`](#method.render) does for a slice of lights.
+    #[inline]
+    pub fn render_scene<M>(&self, scene: &LightScene<S, M>, image: &mut PreviewImage<S>)
+        where M: IlluminationModel + Illuminate<S>
+    {
+        self.render(scene.lights(), image)
+    }
+
+    /// Add the contribution of a set of lights to an image. Render each
+    /// group of lights with the same illumination model into the same image
+    /// to preview a rig with several kinds of lights. Each light's exposure
+    /// compensation and diffuse and specular factors scale its contribution.
+    pub fn render<M>(&self, lights: &[Light<S, M>], image: &mut PreviewImage<S>)
+        where M: IlluminationModel + Illuminate<S>
+    {
+        let one_half: S = num_traits::cast(0.5).unwrap();
+        let epsilon: S = num_traits::cast(1e-4).unwrap();
+        let width: S = num_traits::cast(image.width).unwrap();
+        let height: S = num_traits::cast(image.height).unwrap();
+        for y in 0..image.height {
+            for x in 0..image.width {
+                let x_scalar: S = num_traits::cast(x).unwrap();
+                let y_scalar: S = num_traits::cast(y).unwrap();
+                let ndc_x = (x_scalar + one_half) / width * (S::one() + S::one()) - S::one();
+                let ndc_y = S::one() - (y_scalar + one_half) / height * (S::one() + S::one());
+                let near = self.unproject(ndc_x, ndc_y, -S::one());
+                let far = self.unproject(ndc_x, ndc_y, S::one());
+                let direction = (far - near).normalize();
+                let (object, t) = match self.nearest_hit(&near, &direction, S::zero()) {
+                    Some(hit) => hit,
+                    None => continue,
+                };
+
+                let point = near + direction * t;
+                let normal = object.normal(&point, &direction);
+                let view = -direction;
+                let mut color = Vector3::zero();
+                for light in lights.iter() {
+                    let incident = light.model().incident(&light.position(), &light.forward_axis(), &point);
+                    let exposure = light.exposure_multiplier();
+                    let ambient = component_product(&incident.ambient, &object.albedo) * exposure;
+                    color += ambient;
+
+                    let (light_direction, distance) = match light.model().to_light(&light.position(), &light.forward_axis(), &point) {
+                        Some(to_light) => to_light,
+                        None => continue,
+                    };
+                    let n_dot_l = normal.dot(&light_direction);
+                    if n_dot_l <= S::zero() {
+                        continue;
+                    }
+                    let shadow_origin = point + normal * epsilon;
+                    let shadowed = self.objects.iter().any(|occluder| {
+                        match occluder.intersect(&shadow_origin, &light_direction, S::zero()) {
+                            Some(t) => t < distance,
+                            None => false,
+                        }
+                    });
+                    let half_vector = (light_direction + view).normalize();
+                    let n_dot_h = normal.dot(&half_vector).max(S::zero());
+                    let diffuse = component_product(&incident.diffuse, &object.albedo)
+                        * (n_dot_l * exposure * light.diffuse_factor());
+                    let specular = incident.specular * (n_dot_h.powf(object.shininess) * exposure * light.specular_factor());
+                    let lit = diffuse + specular;
+                    if shadowed {
+                        color += component_product(&lit, &incident.shadow_tint);
+                    } else {
+                        color += lit;
+                    }
+                }
+                image.pixels[y * image.width + x] += color;
+            }
+        }
+    }
+
+    /// Map a point in normalized device coordinates back to world space.
+    #[inline]
+    fn unproject(&self, x: S, y: S, z: S) -> Vector3<S> {
+        let point = self.inverse_view_projection * Vector4::new(x, y, z, S::one());
+
+        Vector3::new(point.x / point.w, point.y / point.w, point.z / point.w)
+    }
+
+    /// Find the nearest object hit by a ray.
+    fn nearest_hit(&self, origin: &Vector3<S>, direction: &Vector3<S>, t_min: S) -> Option<(&PreviewObject<S>, S)> {
+        let mut nearest = None;
+        for object in self.objects.iter() {
+            if let Some(t) = object.intersect(origin, direction, t_min) {
+                match nearest {
+                    Some((_, nearest_t)) if nearest_t <= t => {}
+                    _ => nearest = Some((object, t)),
+                }
+            }
+        }
+
+        nearest
+    }
+}
+
+#[inline]
+fn component_product<S: ScalarFloat>(a: &Vector3<S>, b: &Vector3<S>) -> Vector3<S> {
+    Vector3::new(a.x * b.x, a.y * b.y, a.z * b.z)
+}
@@ -0,0 +1,237 @@
+use cglinalg::{
+    Magnitude,
+    Vector3,
+    ScalarFloat,
+};
+use crate::{
+    IlluminationModel,
+    Light,
+};
+use crate::any_light::LightModelInfo;
+use crate::lod::Importance;
+use crate::scene::LightScene;
+use std::collections::HashSet;
+
+
+/// An axis aligned box bounding a level.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct ProbeBounds<S> {
+    pub min: Vector3<S>,
+    pub max: Vector3<S>,
+}
+
+impl<S> ProbeBounds<S> where S: ScalarFloat {
+    /// Construct a new bounding box.
+    #[inline]
+    pub fn new(min: Vector3<S>, max: Vector3<S>) -> ProbeBounds<S> {
+        ProbeBounds {
+            min: min,
+            max: max,
+        }
+    }
+}
+
+/// The influence of a light on the probe placement.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct ProbeSource<S> {
+    pub position: Vector3<S>,
+    /// The distance beyond which the light contributes nothing. Lights
+    /// without distance attenuation have an infinite radius.
+    pub radius: S,
+    /// The luminance of the light's emission.
+    pub brightness: S,
+}
+
+impl<S> ProbeSource<S> where S: ScalarFloat {
+    /// Construct a new probe source.
+    #[inline]
+    pub fn new(position: Vector3<S>, radius: S, brightness: S) -> ProbeSource<S> {
+        ProbeSource {
+            position: position,
+            radius: radius,
+            brightness: brightness,
+        }
+    }
+
+    /// Construct a probe source from a light, bounding its influence by the
+    /// distance at which its attenuation falls below `threshold`.
+    pub fn from_light<M>(light: &Light<S, M>, threshold: S) -> ProbeSource<S>
+        where M: IlluminationModel + LightModelInfo<S> + Importance<S>
+    {
+        ProbeSource {
+            position: light.position(),
+            radius: light.model().influence_radius(threshold),
+            brightness: light.model().brightness(),
+        }
+    }
+
+    /// Estimate the light's contribution at a point with an inverse square
+    /// falloff that is softened near the light and windowed to zero at its
+    /// radius.
+    fn contribution(&self, point: &Vector3<S>) -> S {
+        let distance_squared = (point - self.position).magnitude_squared();
+        let window = if self.radius.is_finite() {
+            let ratio_squared = distance_squared / (self.radius * self.radius);
+            let falloff = (S::one() - ratio_squared * ratio_squared).max(S::zero());
+
+            falloff * falloff
+        } else {
+            S::one()
+        };
+
+        self.brightness * window / (S::one() + distance_squared)
+    }
+}
+
+/// A policy suggesting where to place the probes of an irradiance volume.
+///
+/// The policy subdivides the level's bounding box as an octree. A cell is
+/// split while the estimated lighting at its corners and center varies by
+/// more than `gradient_threshold` relative to its brightest sample, so
+/// probes concentrate where lighting changes quickly, such as around the
+/// edges of light influence volumes, and stay sparse in evenly lit or dark
+/// regions. Probes are suggested at the corners of the leaf cells, shared
+/// between neighboring cells.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct ProbePlacement<S> {
+    /// The subdivision depth every cell is split to regardless of lighting.
+    pub min_depth: u32,
+    /// The deepest subdivision, which bounds the probe density.
+    pub max_depth: u32,
+    /// The relative variation in lighting across a cell above which the
+    /// cell is split.
+    pub gradient_threshold: S,
+}
+
+impl<S> ProbePlacement<S> where S: ScalarFloat {
+    /// Construct a new placement policy. Depths beyond ten are clamped to ten.
+    #[inline]
+    pub fn new(min_depth: u32, max_depth: u32, gradient_threshold: S) -> ProbePlacement<S> {
+        let max_depth = max_depth.min(10);
+
+        ProbePlacement {
+            min_depth: min_depth.min(max_depth),
+            max_depth: max_depth,
+            gradient_threshold: gradient_threshold,
+        }
+    }
+
+    /// Suggest probe positions for the lights of a scene, bounding each
+    /// light's influence by the distance at which its attenuation falls below
+    /// `threshold`, as `ProbeSource::from_light` does.
+    pub fn suggest_for_scene<M>(
+        &self,
+        scene: &LightScene<S, M>,
+        threshold: S,
+        bounds: Option<&ProbeBounds<S>>) -> Vec<Vector3<S>>
+        where M: IlluminationModel + LightModelInfo<S> + Importance<S>
+    {
+        let sources = scene.lights()
+            .iter()
+            .map(|light| ProbeSource::from_light(light, threshold))
+            .collect::<Vec<_>>();
+
+        self.suggest(&sources, bounds)
+    }
+
+    /// Suggest probe positions for a set of lights. Without level bounds, the
+    /// bounds enclose the influence volumes of the lights with finite radii.
+    /// The result is empty if there is nothing to bound.
+    pub fn suggest(&self, sources: &[ProbeSource<S>], bounds: Option<&ProbeBounds<S>>) -> Vec<Vector3<S>> {
+        let bounds = match bounds.copied().or_else(|| Self::source_bounds(sources)) {
+            Some(bounds) => bounds,
+            None => return Vec::new(),
+        };
+
+        let resolution = 1_u32 << self.max_depth;
+        let mut corners = HashSet::new();
+        self.subdivide(sources, &bounds, [0, 0, 0], resolution, 0, &mut corners);
+
+        let mut corners: Vec<[u32; 3]> = corners.into_iter().collect();
+        corners.sort_unstable();
+        corners.iter().map(|corner| Self::lattice_point(&bounds, resolution, corner)).collect()
+    }
+
+    /// The bounds of the finite influence volumes of the lights.
+    fn source_bounds(sources: &[ProbeSource<S>]) -> Option<ProbeBounds<S>> {
+        let mut bounds: Option<ProbeBounds<S>> = None;
+        for source in sources.iter().filter(|source| source.radius.is_finite()) {
+            let extent = Vector3::new(source.radius, source.radius, source.radius);
+            let min = source.position - extent;
+            let max = source.position + extent;
+            bounds = Some(match bounds {
+                Some(bounds) => ProbeBounds::new(
+                    Vector3::new(bounds.min.x.min(min.x), bounds.min.y.min(min.y), bounds.min.z.min(min.z)),
+                    Vector3::new(bounds.max.x.max(max.x), bounds.max.y.max(max.y), bounds.max.z.max(max.z)),
+                ),
+                None => ProbeBounds::new(min, max),
+            });
+        }
+
+        bounds
+    }
+
+    /// Map a point of the finest subdivision lattice into world space.
+    fn lattice_point(bounds: &ProbeBounds<S>, resolution: u32, corner: &[u32; 3]) -> Vector3<S> {
+        let resolution: S = num_traits::cast(resolution).unwrap();
+        let extent = bounds.max - bounds.min;
+        let x: S = num_traits::cast(corner[0]).unwrap();
+        let y: S = num_traits::cast(corner[1]).unwrap();
+        let z: S = num_traits::cast(corner[2]).unwrap();
+
+        Vector3::new(
+            bounds.min.x + extent.x * (x / resolution),
+            bounds.min.y + extent.y * (y / resolution),
+            bounds.min.z + extent.z * (z / resolution),
+        )
+    }
+
+    /// Subdivide a cell given by its lowest corner and size on the lattice.
+    fn subdivide(
+        &self,
+        sources: &[ProbeSource<S>],
+        bounds: &ProbeBounds<S>,
+        origin: [u32; 3],
+        size: u32,
+        depth: u32,
+        corners: &mut HashSet<[u32; 3]>)
+    {
+        let resolution = 1_u32 << self.max_depth;
+        let cell_corners: Vec<[u32; 3]> = (0..8)
+            .map(|i| [
+                origin[0] + size * (i & 1),
+                origin[1] + size * ((i >> 1) & 1),
+                origin[2] + size * ((i >> 2) & 1),
+            ])
+            .collect();
+
+        let split = size > 1 && (depth < self.min_depth || {
+            let half = size / 2;
+            let center = [origin[0] + half, origin[1] + half, origin[2] + half];
+            let mut min = S::infinity();
+            let mut max = S::neg_infinity();
+            for corner in cell_corners.iter().chain(core::iter::once(&center)) {
+                let point = Self::lattice_point(bounds, resolution, corner);
+                let value = sources.iter().fold(S::zero(), |sum, source| sum + source.contribution(&point));
+                min = min.min(value);
+                max = max.max(value);
+            }
+
+            max > S::zero() && (max - min) / max > self.gradient_threshold
+        });
+
+        if split {
+            let half = size / 2;
+            for i in 0..8 {
+                let child = [
+                    origin[0] + half * (i & 1),
+                    origin[1] + half * ((i >> 1) & 1),
+                    origin[2] + half * ((i >> 2) & 1),
+                ];
+                self.subdivide(sources, bounds, child, half, depth + 1, corners);
+            }
+        } else {
+            corners.extend(cell_corners);
+        }
+    }
+}
@@ -0,0 +1,105 @@
+use cglinalg::{
+    Radians,
+    ScalarFloat,
+};
+
+
+/// An angular intensity profile for a spotlight, given as a sampled curve
+/// from the angle off the spotlight's axis to an intensity multiplier.
+///
+/// Profiles describe fixtures whose beams are not smooth, such as a bright
+/// hot spot surrounded by a dimmer ring, without the full photometric data
+/// of an IES file. The curve is interpolated linearly between its samples
+/// and held constant beyond its first and last samples.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AngularProfile<S> {
+    /// The samples of the curve as pairs of angles in radians and
+    /// multipliers, sorted by angle.
+    samples: Vec<(S, S)>,
+}
+
+impl<S> AngularProfile<S> where S: ScalarFloat {
+    /// Construct a profile from samples of angles off the spotlight's axis
+    /// and the intensity multipliers at those angles. The samples need not be
+    /// sorted. The result is `None` if there are no samples.
+    pub fn new<A: Into<Radians<S>>>(samples: Vec<(A, S)>) -> Option<AngularProfile<S>> {
+        if samples.is_empty() {
+            return None;
+        }
+
+        let mut samples: Vec<(S, S)> = samples
+            .into_iter()
+            .map(|(angle, multiplier)| (angle.into().0, multiplier))
+            .collect();
+        samples.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(core::cmp::Ordering::Equal));
+
+        Some(AngularProfile {
+            samples: samples,
+        })
+    }
+
+    /// The samples of the curve, sorted by angle.
+    #[inline]
+    pub fn samples(&self) -> &[(S, S)] {
+        &self.samples
+    }
+
+    /// The largest angle off the axis that the profile has a sample for.
+    #[inline]
+    pub fn max_angle(&self) -> Radians<S> {
+        Radians(self.samples[self.samples.len() - 1].0)
+    }
+
+    /// Evaluate the intensity multiplier at an angle off the spotlight's axis.
+    pub fn evaluate(&self, angle: Radians<S>) -> S {
+        let angle = angle.0;
+        let first = self.samples[0];
+        if angle <= first.0 {
+            return first.1;
+        }
+
+        for window in self.samples.windows(2) {
+            let (angle0, multiplier0) = window[0];
+            let (angle1, multiplier1) = window[1];
+            if angle <= angle1 {
+                let width = angle1 - angle0;
+                if width <= S::zero() {
+                    return multiplier1;
+                }
+                let t = (angle - angle0) / width;
+
+                return multiplier0 + (multiplier1 - multiplier0) * t;
+            }
+        }
+
+        self.samples[self.samples.len() - 1].1
+    }
+
+    /// Evaluate the intensity multiplier for a direction making an angle
+    /// with cosine `cos_angle` with the spotlight's axis.
+    #[inline]
+    pub fn evaluate_cos(&self, cos_angle: S) -> S {
+        self.evaluate(Radians(cos_angle.max(-S::one()).min(S::one()).acos()))
+    }
+
+    /// Bake the profile into a lookup table of `resolution` entries for
+    /// shading on the GPU. The table is indexed uniformly by the cosine of
+    /// the angle off the axis: entry `i` holds the multiplier at
+    /// `cos_angle = cos_max + (1 - cos_max) * i / (resolution - 1)`, where
+    /// `cos_max` is the cosine of the profile's largest angle, so the last
+    /// entry lies on the spotlight's axis.
+    pub fn bake(&self, resolution: usize) -> Vec<S> {
+        if resolution < 2 {
+            return vec![self.samples[0].1; resolution];
+        }
+
+        let cos_max = self.max_angle().0.cos();
+        let last: S = num_traits::cast(resolution - 1).unwrap();
+        (0..resolution)
+            .map(|i| {
+                let i_scalar: S = num_traits::cast(i).unwrap();
+                self.evaluate_cos(cos_max + (S::one() - cos_max) * (i_scalar / last))
+            })
+            .collect()
+    }
+}
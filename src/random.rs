@@ -0,0 +1,52 @@
+use cglinalg::{
+    ScalarFloat,
+};
+
+
+/// A small, seedable pseudorandom number generator based on the
+/// SplitMix64 algorithm.
+///
+/// The generator is deterministic: the same seed always produces the same
+/// sequence, which keeps procedurally placed lights stable from run to run.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub(crate) struct Random {
+    state: u64,
+}
+
+impl Random {
+    /// Construct a new generator from a seed.
+    #[inline]
+    pub(crate) fn new(seed: u64) -> Random {
+        Random {
+            state: seed,
+        }
+    }
+
+    /// Generate the next 64 bit value in the sequence.
+    #[inline]
+    pub(crate) fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+
+        z ^ (z >> 31)
+    }
+
+    /// Generate a uniformly distributed scalar in the interval `[0, 1)`.
+    #[inline]
+    pub(crate) fn next_scalar<S: ScalarFloat>(&mut self) -> S {
+        // Use the upper 53 bits so the value is exactly representable as an `f64`.
+        let value = (self.next_u64() >> 11) as f64 * (1.0 / ((1_u64 << 53) as f64));
+
+        num_traits::cast(value).unwrap()
+    }
+
+    /// Generate a uniformly distributed scalar in the interval `[-1, 1)`.
+    #[inline]
+    pub(crate) fn next_signed_scalar<S: ScalarFloat>(&mut self) -> S {
+        let two = S::one() + S::one();
+
+        self.next_scalar::<S>() * two - S::one()
+    }
+}
@@ -0,0 +1,148 @@
+use cglinalg::{
+    Magnitude,
+    Vector3,
+    ScalarFloat,
+};
+
+
+/// The representative point of an area light for specular shading.
+///
+/// Shading an area light's specular response with a single light direction
+/// works well when the direction points at the spot on the light closest to
+/// the reflection ray. Because the resulting highlight is brighter and
+/// wider than a punctual light's, the specular term is scaled by an energy
+/// normalization factor to keep the total reflected energy plausible.
+///
+/// See Karis, *Real Shading in Unreal Engine 4*, SIGGRAPH 2013.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct RepresentativePoint<S> {
+    /// The unit direction from the surface point to the representative point.
+    pub direction: Vector3<S>,
+    /// The distance from the surface point to the representative point.
+    pub distance: S,
+    /// The factor to scale the specular distribution by.
+    pub normalization: S,
+}
+
+/// Compute the representative point of a sphere light.
+///
+/// The `reflection` vector is the unit view direction reflected about the
+/// surface normal, and `alpha` is the GGX roughness parameter, i.e. the
+/// square of the perceptual roughness.
+pub fn sphere_representative_point<S>(
+    center: &Vector3<S>,
+    radius: S,
+    position: &Vector3<S>,
+    reflection: &Vector3<S>,
+    alpha: S) -> RepresentativePoint<S>
+    where S: ScalarFloat
+{
+    let to_center = center - position;
+    let distance = to_center.magnitude();
+    let to_point = closest_point_on_sphere(&to_center, radius, reflection);
+    let widened_alpha = widen(alpha, radius, distance);
+    let ratio = alpha / widened_alpha;
+
+    representative_point(&to_point, ratio * ratio)
+}
+
+/// Compute the representative point of a tube light, a line segment from
+/// `start` to `end` thickened by `radius`.
+///
+/// The `reflection` vector is the unit view direction reflected about the
+/// surface normal, and `alpha` is the GGX roughness parameter, i.e. the
+/// square of the perceptual roughness.
+pub fn tube_representative_point<S>(
+    start: &Vector3<S>,
+    end: &Vector3<S>,
+    radius: S,
+    position: &Vector3<S>,
+    reflection: &Vector3<S>,
+    alpha: S) -> RepresentativePoint<S>
+    where S: ScalarFloat
+{
+    let to_start = start - position;
+    let to_end = end - position;
+    let segment = to_end - to_start;
+    let length = segment.magnitude();
+
+    let to_segment_point = closest_point_on_segment(&to_start, &segment, reflection);
+    let to_point = closest_point_on_sphere(&to_segment_point, radius, reflection);
+    let two = S::one() + S::one();
+    let distance = (to_start + segment / two).magnitude();
+    // The segment widens the lobe along one dimension and the radius along both.
+    let line_alpha = widen(alpha, length / two, distance);
+    let sphere_alpha = widen(alpha, radius, distance);
+    let sphere_ratio = alpha / sphere_alpha;
+    let normalization = (alpha / line_alpha) * sphere_ratio * sphere_ratio;
+
+    representative_point(&to_point, normalization)
+}
+
+/// Find the point on a line segment, relative to the shading point, closest
+/// to the reflection ray.
+#[inline]
+pub(crate) fn closest_point_on_segment<S: ScalarFloat>(
+    to_start: &Vector3<S>,
+    segment: &Vector3<S>,
+    reflection: &Vector3<S>) -> Vector3<S>
+{
+    let r_dot_start = reflection.dot(to_start);
+    let r_dot_segment = reflection.dot(segment);
+    let denominator = segment.magnitude_squared() - r_dot_segment * r_dot_segment;
+    let t = if denominator > S::zero() {
+        (r_dot_start * r_dot_segment - to_start.dot(segment)) / denominator
+    } else {
+        S::zero()
+    };
+    let t = t.max(S::zero()).min(S::one());
+
+    to_start + segment * t
+}
+
+/// Find the point on a sphere, relative to the shading point, closest to
+/// the reflection ray.
+#[inline]
+pub(crate) fn closest_point_on_sphere<S: ScalarFloat>(
+    to_center: &Vector3<S>,
+    radius: S,
+    reflection: &Vector3<S>) -> Vector3<S>
+{
+    let center_to_ray = reflection * to_center.dot(reflection) - to_center;
+    let center_to_ray_distance = center_to_ray.magnitude();
+    if center_to_ray_distance <= S::zero() {
+        return *to_center;
+    }
+    let t = (radius / center_to_ray_distance).min(S::one());
+
+    to_center + center_to_ray * t
+}
+
+/// Widen a GGX lobe by half of the angle subtended by a light of the given
+/// extent at the given distance.
+#[inline]
+fn widen<S: ScalarFloat>(alpha: S, extent: S, distance: S) -> S {
+    if distance <= S::zero() {
+        return S::one();
+    }
+    let two = S::one() + S::one();
+
+    (alpha + extent / (two * distance)).min(S::one())
+}
+
+#[inline]
+fn representative_point<S: ScalarFloat>(to_point: &Vector3<S>, normalization: S) -> RepresentativePoint<S> {
+    let distance = to_point.magnitude();
+    let direction = if distance > S::zero() {
+        to_point / distance
+    } else {
+        Vector3::zero()
+    };
+    let normalization = if normalization.is_finite() { normalization } else { S::one() };
+
+    RepresentativePoint {
+        direction: direction,
+        distance: distance,
+        normalization: normalization,
+    }
+}
@@ -0,0 +1,695 @@
+use cglinalg::{
+    Vector2,
+    Vector3,
+    ScalarFloat,
+};
+
+
+/// The first few prime numbers, used as the bases of the Halton sequence.
+const PRIMES: [u32; 16] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47, 53];
+
+/// Compute the radical inverse of `index` in the given base, reflecting the
+/// digits of the index about the radix point.
+pub fn radical_inverse(base: u32, index: u32) -> f64 {
+    let inv_base = 1.0 / base as f64;
+    let mut index = index;
+    let mut reversed = 0.0;
+    let mut inv_base_power = 1.0;
+    while index > 0 {
+        let digit = index % base;
+        inv_base_power *= inv_base;
+        reversed += digit as f64 * inv_base_power;
+        index /= base;
+    }
+
+    reversed
+}
+
+/// Compute a dimension of the `index`-th point of the Halton sequence. 
+/// Each dimension uses the radical inverse in a different prime base.
+///
+/// ## Panics
+/// Panics if `dimension` is 16 or larger.
+#[inline]
+pub fn halton<S: ScalarFloat>(index: u32, dimension: usize) -> S {
+    num_traits::cast(radical_inverse(PRIMES[dimension], index)).unwrap()
+}
+
+/// The number of dimensions of the Sobol sequence with built-in direction
+/// numbers.
+const SOBOL_DIMENSIONS: usize = 8;
+
+/// The degrees, polynomial coefficients, and initial direction numbers of the
+/// primitive polynomials generating the Sobol sequence beyond its first
+/// dimension, from Joe and Kuo's `new-joe-kuo-6.21201` table.
+const SOBOL_POLYNOMIALS: [(u32, u32, [u32; 5]); SOBOL_DIMENSIONS - 1] = [
+    (1, 0, [1, 0, 0, 0, 0]),
+    (2, 1, [1, 3, 0, 0, 0]),
+    (3, 1, [1, 3, 1, 0, 0]),
+    (3, 2, [1, 1, 1, 0, 0]),
+    (4, 1, [1, 1, 3, 3, 0]),
+    (4, 4, [1, 3, 5, 13, 0]),
+    (5, 2, [1, 1, 5, 5, 17]),
+];
+
+/// The direction numbers of the first dimensions of the Sobol sequence.
+static SOBOL_DIRECTIONS: [[u32; 32]; SOBOL_DIMENSIONS] = sobol_directions();
+
+/// Compute the direction numbers of the Sobol sequence.
+const fn sobol_directions() -> [[u32; 32]; SOBOL_DIMENSIONS] {
+    let mut directions = [[0_u32; 32]; SOBOL_DIMENSIONS];
+    // The first dimension is the van der Corput sequence in base two.
+    let mut k = 0;
+    while k < 32 {
+        directions[0][k] = 1 << (31 - k);
+        k += 1;
+    }
+
+    let mut dimension = 1;
+    while dimension < SOBOL_DIMENSIONS {
+        let (degree, coefficients, initial) = SOBOL_POLYNOMIALS[dimension - 1];
+        let degree = degree as usize;
+        let mut k = 0;
+        while k < 32 {
+            directions[dimension][k] = if k < degree {
+                initial[k] << (31 - k)
+            } else {
+                let mut value = directions[dimension][k - degree];
+                value ^= value >> degree;
+                let mut j = 1;
+                while j < degree {
+                    if (coefficients >> (degree - 1 - j)) & 1 == 1 {
+                        value ^= directions[dimension][k - j];
+                    }
+                    j += 1;
+                }
+
+                value
+            };
+            k += 1;
+        }
+        dimension += 1;
+    }
+
+    directions
+}
+
+/// Compute a dimension of the `index`-th point of the Sobol sequence as a
+/// 32 bit fixed point fraction.
+#[inline]
+fn sobol_bits(index: u32, dimension: usize) -> u32 {
+    let directions = &SOBOL_DIRECTIONS[dimension];
+    let mut index = index;
+    let mut result = 0;
+    let mut k = 0;
+    while index != 0 {
+        if index & 1 == 1 {
+            result ^= directions[k];
+        }
+        index >>= 1;
+        k += 1;
+    }
+
+    result
+}
+
+/// Compute a dimension of the `index`-th point of the Sobol sequence.
+///
+/// ## Panics
+/// Panics if `dimension` is 8 or larger.
+#[inline]
+pub fn sobol<S: ScalarFloat>(index: u32, dimension: usize) -> S {
+    to_unit_interval(sobol_bits(index, dimension))
+}
+
+/// Apply a nested uniform (Owen) scramble to a 32 bit fixed point fraction
+/// with a hash based permutation.
+///
+/// See Burley, *Practical Hash-based Owen Scrambling*, JCGT 2020.
+#[inline]
+fn owen_scramble(value: u32, seed: u32) -> u32 {
+    let mut x = value.reverse_bits();
+    x = x.wrapping_add(seed);
+    x ^= x.wrapping_mul(0x6C50_B47C);
+    x ^= x.wrapping_mul(0xB82F_1E52);
+    x ^= x.wrapping_mul(0xC7AF_E638);
+    x ^= x.wrapping_mul(0x8D22_F6E6);
+
+    x.reverse_bits()
+}
+
+/// Convert a 32 bit fixed point fraction to a scalar in `[0, 1)`.
+#[inline]
+fn to_unit_interval<S: ScalarFloat>(bits: u32) -> S {
+    num_traits::cast(bits as f64 / 4_294_967_296.0).unwrap()
+}
+
+/// A source of sample points for Monte Carlo and quasi-Monte Carlo
+/// integration.
+///
+/// A sampler produces a sequence of sample points, each consisting of as
+/// many dimensions as the integrand consumes. The light samplers in this
+/// crate take their random numbers from a sampler's dimensions, so swapping
+/// a pseudorandom sampler for a low-discrepancy one improves convergence
+/// without changing the sampling code.
+pub trait Sampler<S> {
+    /// Begin the sample point with the given index, restarting at its first
+    /// dimension.
+    fn start_sample(&mut self, index: u32);
+
+    /// Get the next dimension of the current sample point, in `[0, 1)`.
+    fn next_1d(&mut self) -> S;
+
+    /// Get the next two dimensions of the current sample point, in the unit
+    /// square.
+    #[inline]
+    fn next_2d(&mut self) -> Vector2<S> {
+        let u = self.next_1d();
+        let v = self.next_1d();
+
+        Vector2::new(u, v)
+    }
+}
+
+/// A sampler drawing points from the Halton sequence, randomized per seed
+/// and per dimension with a Cranley-Patterson rotation.
+///
+/// Dimensions beyond the sixteenth reuse the sequence's dimensions with
+/// independent rotations.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct HaltonSampler {
+    seed: u32,
+    index: u32,
+    dimension: usize,
+}
+
+impl HaltonSampler {
+    /// Construct a new sampler. Different seeds, e.g. one per light, produce
+    /// decorrelated sequences.
+    #[inline]
+    pub fn new(seed: u32) -> HaltonSampler {
+        HaltonSampler {
+            seed: seed,
+            index: 0,
+            dimension: 0,
+        }
+    }
+}
+
+impl<S> Sampler<S> for HaltonSampler where S: ScalarFloat {
+    #[inline]
+    fn start_sample(&mut self, index: u32) {
+        self.index = index;
+        self.dimension = 0;
+    }
+
+    #[inline]
+    fn next_1d(&mut self) -> S {
+        let base_dimension = self.dimension % PRIMES.len();
+        let cycle = (self.dimension / PRIMES.len()) as u32;
+        let seed = if cycle == 0 { self.seed } else { self.seed ^ hash_u32(cycle) };
+        let sample = rotate(halton::<S>(self.index, base_dimension), scramble_offset(seed, base_dimension));
+        self.dimension += 1;
+
+        sample
+    }
+}
+
+/// A sampler drawing points from the Sobol sequence, randomized per seed
+/// and per dimension with a nested uniform (Owen) scramble.
+///
+/// Owen scrambling keeps the sequence's stratification while removing the
+/// structured artifacts of the unscrambled sequence. Dimensions beyond the
+/// eighth reuse the sequence's dimensions with independent scrambles.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct SobolSampler {
+    seed: u32,
+    index: u32,
+    dimension: usize,
+}
+
+impl SobolSampler {
+    /// Construct a new sampler. Different seeds, e.g. one per light, produce
+    /// decorrelated sequences.
+    #[inline]
+    pub fn new(seed: u32) -> SobolSampler {
+        SobolSampler {
+            seed: seed,
+            index: 0,
+            dimension: 0,
+        }
+    }
+}
+
+impl<S> Sampler<S> for SobolSampler where S: ScalarFloat {
+    #[inline]
+    fn start_sample(&mut self, index: u32) {
+        self.index = index;
+        self.dimension = 0;
+    }
+
+    #[inline]
+    fn next_1d(&mut self) -> S {
+        let base_dimension = self.dimension % SOBOL_DIMENSIONS;
+        let seed = hash_u32(self.seed ^ hash_u32(self.dimension as u32));
+        let bits = owen_scramble(sobol_bits(self.index, base_dimension), seed);
+        self.dimension += 1;
+
+        to_unit_interval(bits)
+    }
+}
+
+/// Hash a 32 bit integer into a well mixed 32 bit integer.
+#[inline]
+pub(crate) fn hash_u32(value: u32) -> u32 {
+    let mut x = value;
+    x ^= x >> 16;
+    x = x.wrapping_mul(0x7FEB_352D);
+    x ^= x >> 15;
+    x = x.wrapping_mul(0x846C_A68B);
+    x ^= x >> 16;
+
+    x
+}
+
+/// Compute a per-seed, per-dimension offset in `[0, 1)` for randomizing a
+/// low-discrepancy sequence with a Cranley-Patterson rotation.
+#[inline]
+pub(crate) fn scramble_offset<S: ScalarFloat>(seed: u32, dimension: usize) -> S {
+    let hash = hash_u32(seed ^ hash_u32(dimension as u32));
+
+    num_traits::cast(hash as f64 / 4_294_967_296.0).unwrap()
+}
+
+/// Shift a sample in `[0, 1)` by an offset, wrapping around to stay in `[0, 1)`.
+#[inline]
+pub(crate) fn rotate<S: ScalarFloat>(sample: S, offset: S) -> S {
+    let shifted = sample + offset;
+
+    shifted - shifted.floor()
+}
+
+/// Map a point in the unit cube to a uniformly distributed point inside
+/// the ball of the given radius centered at the origin.
+pub(crate) fn uniform_ball<S: ScalarFloat>(u: S, v: S, w: S, radius: S) -> Vector3<S> {
+    let two = S::one() + S::one();
+    let three = two + S::one();
+    let two_pi: S = num_traits::cast(2.0 * core::f64::consts::PI).unwrap();
+    let cos_theta = S::one() - two * v;
+    let sin_theta = (S::one() - cos_theta * cos_theta).max(S::zero()).sqrt();
+    let phi = two_pi * w;
+    let r = radius * u.powf(S::one() / three);
+
+    Vector3::new(r * sin_theta * phi.cos(), r * sin_theta * phi.sin(), r * cos_theta)
+}
+
+/// The parameters of the per-frame jitter of a light's sample position.
+///
+/// Jittering the light's position within its source radius from frame to
+/// frame lets temporal antialiasing accumulate soft shadows from hard shadow
+/// maps.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct LightJitter<S> {
+    /// The radius of the light source. A radius of zero disables jitter.
+    pub radius: S,
+    /// The seed decorrelating this light's jitter sequence from other lights.
+    pub seed: u32,
+}
+
+impl<S> LightJitter<S> where S: ScalarFloat {
+    /// Construct a new jitter specification.
+    #[inline]
+    pub fn new(radius: S, seed: u32) -> LightJitter<S> {
+        LightJitter {
+            radius: radius,
+            seed: seed,
+        }
+    }
+
+    /// Construct a jitter specification that leaves the light in place.
+    #[inline]
+    pub fn zero() -> LightJitter<S> {
+        LightJitter {
+            radius: S::zero(),
+            seed: 0,
+        }
+    }
+
+    /// Compute the jitter offset for a frame. The offsets follow a randomized
+    /// Halton sequence, so any window of consecutive frames covers the source
+    /// evenly, and the same frame always produces the same offset.
+    pub fn offset(&self, frame_index: u32) -> Vector3<S> {
+        let mut sampler = HaltonSampler::new(self.seed);
+        Sampler::<S>::start_sample(&mut sampler, frame_index);
+
+        self.offset_with(&mut sampler)
+    }
+
+    /// Compute a jitter offset from the next three dimensions of a sampler's
+    /// current sample point.
+    pub fn offset_with<T: Sampler<S>>(&self, sampler: &mut T) -> Vector3<S> {
+        if self.radius <= S::zero() {
+            return Vector3::zero();
+        }
+
+        let u = sampler.next_1d();
+        let v = sampler.next_1d();
+        let w = sampler.next_1d();
+
+        uniform_ball(u, v, w, self.radius)
+    }
+}
+
+/// A piecewise constant probability distribution over `[0, 1)`.
+#[derive(Clone, Debug, PartialEq)]
+struct Distribution1D<S> {
+    weights: Vec<S>,
+    cdf: Vec<S>,
+    integral: S,
+}
+
+impl<S> Distribution1D<S> where S: ScalarFloat {
+    fn new(weights: &[S]) -> Distribution1D<S> {
+        let count: S = num_traits::cast(weights.len()).unwrap();
+        let mut cdf = Vec::with_capacity(weights.len() + 1);
+        cdf.push(S::zero());
+        for (i, weight) in weights.iter().enumerate() {
+            cdf.push(cdf[i] + weight.abs() / count);
+        }
+        let integral = cdf[weights.len()];
+        for (i, value) in cdf.iter_mut().enumerate() {
+            // Fall back to a uniform distribution when every weight is zero.
+            *value = if integral > S::zero() {
+                *value / integral
+            } else {
+                num_traits::cast::<usize, S>(i).unwrap() / count
+            };
+        }
+
+        Distribution1D {
+            weights: weights.iter().map(|weight| weight.abs()).collect(),
+            cdf: cdf,
+            integral: integral,
+        }
+    }
+
+    /// The probability density of the piece with the given index.
+    #[inline]
+    fn pdf(&self, index: usize) -> S {
+        if self.integral > S::zero() {
+            self.weights[index] / self.integral
+        } else {
+            S::one()
+        }
+    }
+
+    /// Map a uniform sample in `[0, 1)` to a sample of the distribution,
+    /// returning the sample, its density, and the index of its piece.
+    fn sample(&self, u: S) -> (S, S, usize) {
+        let count = self.weights.len();
+        // Find the last piece whose cumulative distribution does not exceed `u`.
+        let index = self.cdf
+            .partition_point(|&value| value <= u)
+            .saturating_sub(1)
+            .min(count - 1);
+        let width = self.cdf[index + 1] - self.cdf[index];
+        let offset = if width > S::zero() {
+            (u - self.cdf[index]) / width
+        } else {
+            S::zero()
+        };
+        let index_scalar: S = num_traits::cast(index).unwrap();
+        let count_scalar: S = num_traits::cast(count).unwrap();
+
+        ((index_scalar + offset) / count_scalar, self.pdf(index), index)
+    }
+}
+
+/// A piecewise constant probability distribution over the unit square,
+/// proportional to a grid of non-negative weights such as the luminance of
+/// an image.
+///
+/// Samples are drawn by first choosing a row from the marginal distribution
+/// of the rows and then a column from the chosen row's conditional
+/// distribution.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Distribution2D<S> {
+    width: usize,
+    height: usize,
+    conditionals: Vec<Distribution1D<S>>,
+    marginal: Distribution1D<S>,
+}
+
+impl<S> Distribution2D<S> where S: ScalarFloat {
+    /// Construct a distribution from a `width` by `height` grid of weights
+    /// stored in row-major order.
+    ///
+    /// ## Panics
+    /// Panics if the grid is empty or `weights` does not contain exactly
+    /// `width * height` values.
+    pub fn new(width: usize, height: usize, weights: &[S]) -> Distribution2D<S> {
+        assert!(width > 0 && height > 0, "the distribution's grid must not be empty");
+        assert_eq!(weights.len(), width * height, "the weights must fill the distribution's grid");
+
+        let conditionals: Vec<Distribution1D<S>> = weights
+            .chunks(width)
+            .map(Distribution1D::new)
+            .collect();
+        let row_weights: Vec<S> = conditionals.iter().map(|row| row.integral).collect();
+        let marginal = Distribution1D::new(&row_weights);
+
+        Distribution2D {
+            width: width,
+            height: height,
+            conditionals: conditionals,
+            marginal: marginal,
+        }
+    }
+
+    /// Map a uniform sample in the unit square to a sample of the
+    /// distribution, returning the sample and its probability density with
+    /// respect to area in the unit square.
+    pub fn sample(&self, u: S, v: S) -> (Vector2<S>, S) {
+        let (y, row_pdf, row) = self.marginal.sample(v);
+        let (x, column_pdf, _) = self.conditionals[row].sample(u);
+
+        (Vector2::new(x, y), row_pdf * column_pdf)
+    }
+
+    /// The probability density of a point in the unit square with respect
+    /// to area in the unit square.
+    pub fn pdf(&self, point: &Vector2<S>) -> S {
+        let column = grid_index(point.x, self.width);
+        let row = grid_index(point.y, self.height);
+
+        self.marginal.pdf(row) * self.conditionals[row].pdf(column)
+    }
+
+    /// The number of columns in the distribution's grid.
+    #[inline]
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// The number of rows in the distribution's grid.
+    #[inline]
+    pub fn height(&self) -> usize {
+        self.height
+    }
+}
+
+/// Find the index of the grid cell containing a coordinate in `[0, 1)`.
+#[inline]
+pub(crate) fn grid_index<S: ScalarFloat>(coordinate: S, size: usize) -> usize {
+    let size_scalar: S = num_traits::cast(size).unwrap();
+    let index: usize = num_traits::cast((coordinate * size_scalar).floor().max(S::zero())).unwrap_or(0);
+
+    index.min(size - 1)
+}
+
+/// A probability distribution over the unit square, proportional to a square
+/// grid of non-negative weights, sampled by hierarchical sample warping.
+///
+/// The distribution keeps a mip pyramid of the weights. Sampling descends
+/// the pyramid from its coarsest level, choosing a quadrant of each 2x2
+/// block in proportion to its weight and rescaling the sample to reuse it at
+/// the next level. Unlike inverting a marginal and conditional CDF, the
+/// warp is continuous across the whole square, so stratified sample sets
+/// stay well stratified, and the descent needs no binary searches, which
+/// suits shader-side sampling.
+///
+/// See Clarberg et al., *Wavelet Importance Sampling*, SIGGRAPH 2005.
+#[derive(Clone, Debug, PartialEq)]
+pub struct HierarchicalWarp<S> {
+    levels: Vec<Vec<S>>,
+}
+
+impl<S> HierarchicalWarp<S> where S: ScalarFloat {
+    /// Construct a distribution from a `size` by `size` grid of weights
+    /// stored in row-major order. The result is `None` unless `size` is a
+    /// power of two and `weights` contains exactly `size * size` values.
+    pub fn new(size: usize, weights: &[S]) -> Option<HierarchicalWarp<S>> {
+        if !size.is_power_of_two() || weights.len() != size * size {
+            return None;
+        }
+
+        let mut levels = vec![weights.iter().map(|weight| weight.abs()).collect::<Vec<S>>()];
+        let mut level_size = size;
+        while level_size > 1 {
+            let finer = &levels[levels.len() - 1];
+            let coarser_size = level_size / 2;
+            let mut coarser = Vec::with_capacity(coarser_size * coarser_size);
+            for row in 0..coarser_size {
+                for column in 0..coarser_size {
+                    let top = 2 * row * level_size + 2 * column;
+                    let bottom = top + level_size;
+                    coarser.push(finer[top] + finer[top + 1] + finer[bottom] + finer[bottom + 1]);
+                }
+            }
+            levels.push(coarser);
+            level_size = coarser_size;
+        }
+        levels.reverse();
+
+        Some(HierarchicalWarp {
+            levels: levels,
+        })
+    }
+
+    /// Construct a distribution from a mip pyramid of weights laid out as
+    /// [`levels`](#method.levels) returns it, such as one read back from a
+    /// cache. The result is `None` unless there is at least one level, level
+    /// `i` holds `4^i` weights, and every weight is non-negative. The
+    /// coarser levels are taken as given rather than recomputed from the
+    /// finest.
+    pub fn from_levels(levels: Vec<Vec<S>>) -> Option<HierarchicalWarp<S>> {
+        if levels.is_empty() {
+            return None;
+        }
+        for (i, level) in levels.iter().enumerate() {
+            if level.len() != 1 << (2 * i) || level.iter().any(|weight| weight.is_nan() || *weight < S::zero()) {
+                return None;
+            }
+        }
+
+        Some(HierarchicalWarp {
+            levels: levels,
+        })
+    }
+
+    /// The mip pyramid of weights, from the single total weight at level zero
+    /// to the full resolution grid. Level `i` is a `2^i` by `2^i` grid in
+    /// row-major order, ready to upload for shader-side sampling.
+    #[inline]
+    pub fn levels(&self) -> &[Vec<S>] {
+        &self.levels
+    }
+
+    /// The number of rows and columns in the full resolution grid.
+    #[inline]
+    pub fn size(&self) -> usize {
+        1 << (self.levels.len() - 1)
+    }
+
+    /// Map a uniform sample in the unit square to a sample of the
+    /// distribution, returning the sample and its probability density with
+    /// respect to area in the unit square.
+    pub fn sample(&self, u: S, v: S) -> (Vector2<S>, S) {
+        let mut u = u;
+        let mut v = v;
+        let mut column = 0;
+        let mut row = 0;
+        for (level, weights) in self.levels.iter().enumerate().skip(1) {
+            let level_size = 1 << level;
+            let top = 2 * row * level_size + 2 * column;
+            let bottom = top + level_size;
+            let (top_left, top_right) = (weights[top], weights[top + 1]);
+            let (bottom_left, bottom_right) = (weights[bottom], weights[bottom + 1]);
+
+            let (right, u_warped) = warp(u, top_left + bottom_left, top_right + bottom_right);
+            let (lower, v_warped) = if right {
+                warp(v, top_right, bottom_right)
+            } else {
+                warp(v, top_left, bottom_left)
+            };
+            u = u_warped;
+            v = v_warped;
+            column = 2 * column + right as usize;
+            row = 2 * row + lower as usize;
+        }
+
+        let size = self.size();
+        let size_scalar: S = num_traits::cast(size).unwrap();
+        let column_scalar: S = num_traits::cast(column).unwrap();
+        let row_scalar: S = num_traits::cast(row).unwrap();
+        let point = Vector2::new((column_scalar + u) / size_scalar, (row_scalar + v) / size_scalar);
+
+        (point, self.cell_pdf(column, row))
+    }
+
+    /// The probability density of a point in the unit square with respect
+    /// to area in the unit square.
+    pub fn pdf(&self, point: &Vector2<S>) -> S {
+        let size = self.size();
+
+        self.cell_pdf(grid_index(point.x, size), grid_index(point.y, size))
+    }
+
+    #[inline]
+    fn cell_pdf(&self, column: usize, row: usize) -> S {
+        let size = self.size();
+        let total = self.levels[0][0];
+        if total <= S::zero() {
+            return S::one();
+        }
+        let cell_count: S = num_traits::cast(size * size).unwrap();
+
+        self.levels[self.levels.len() - 1][row * size + column] * cell_count / total
+    }
+}
+
+/// Choose between two halves of an interval in proportion to their weights,
+/// returning whether the second half was chosen and the sample rescaled to
+/// `[0, 1)` within the chosen half.
+#[inline]
+fn warp<S: ScalarFloat>(sample: S, first: S, second: S) -> (bool, S) {
+    let total = first + second;
+    let one_half: S = num_traits::cast(0.5).unwrap();
+    let split = if total > S::zero() { first / total } else { one_half };
+    if sample < split {
+        (false, sample / split)
+    } else {
+        let rescaled = (sample - split) / (S::one() - split);
+
+        (true, rescaled.min(S::one()))
+    }
+}
+
+/// Map a point in the unit square to a unit direction by the
+/// equirectangular (latitude-longitude) parameterization. The first
+/// coordinate sweeps the azimuth about the **positive y-axis** starting at
+/// the **positive x-axis**, and the second sweeps from the positive y-axis
+/// down to the negative y-axis.
+pub fn equirectangular_direction<S: ScalarFloat>(point: &Vector2<S>) -> Vector3<S> {
+    let pi: S = num_traits::cast(core::f64::consts::PI).unwrap();
+    let two_pi = pi + pi;
+    let phi = point.x * two_pi;
+    let theta = point.y * pi;
+    let sin_theta = theta.sin();
+
+    Vector3::new(sin_theta * phi.cos(), theta.cos(), sin_theta * phi.sin())
+}
+
+/// Convert a probability density with respect to area in the unit square
+/// into a density with respect to solid angle under the equirectangular
+/// parameterization.
+pub fn equirectangular_pdf<S: ScalarFloat>(point: &Vector2<S>, pdf: S) -> S {
+    let pi: S = num_traits::cast(core::f64::consts::PI).unwrap();
+    let sin_theta = (point.y * pi).sin();
+    if sin_theta <= S::zero() {
+        return S::zero();
+    }
+
+    pdf / (pi * pi * (S::one() + S::one()) * sin_theta)
+}
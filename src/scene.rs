@@ -0,0 +1,332 @@
+use cglinalg::{
+    ScalarFloat,
+};
+use crate::{
+    Dimmable,
+    IlluminationModel,
+    Light,
+    ViewMatrixPolicy,
+};
+use crate::animation::{
+    TransientDecay,
+    TransientLight,
+};
+use std::sync::Arc;
+
+
+/// A handle identifying a light in a `LightScene`.
+///
+/// Handles stay valid while the light stays in the scene, regardless of the
+/// lights inserted or removed around it. Once the light is removed, its
+/// handle never refers to another light.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct LightHandle {
+    index: u32,
+    generation: u32,
+}
+
+/// An entry in the table mapping handles to lights.
+#[derive(Copy, Clone, Debug)]
+struct Slot {
+    /// The generation of the handle currently issued for the slot.
+    generation: u32,
+    /// The position of the slot's light in the dense light storage, if the
+    /// slot holds a light.
+    position: Option<usize>,
+}
+
+/// Find the position of the light a handle refers to.
+#[inline]
+fn position_of(slots: &[Slot], handle: LightHandle) -> Option<usize> {
+    slots.get(handle.index as usize)
+        .filter(|slot| slot.generation == handle.generation)
+        .and_then(|slot| slot.position)
+}
+
+/// A collection of lights with one illumination model, addressed by stable
+/// handles.
+///
+/// The lights are stored contiguously, so the slice functions of the crate,
+/// such as `picking::pick` and `preview::PreviewRenderer::render`, apply to
+/// [`lights`](#method.lights) directly. Removing a light moves the last light
+/// into its place, so positions in the slice change while handles do not.
+///
+/// Transient lights spawned into the scene decay as the scene is
+/// [`update`](#method.update)d, and are removed once they expire.
+#[derive(Clone, Debug)]
+pub struct LightScene<S, M> {
+    lights: Vec<Light<S, M>>,
+    handles: Vec<LightHandle>,
+    slots: Vec<Slot>,
+    free: Vec<u32>,
+    transients: Vec<(LightHandle, TransientDecay<S, M>)>,
+}
+
+impl<S, M> LightScene<S, M>
+    where S: ScalarFloat,
+          M: IlluminationModel,
+{
+    /// Construct an empty scene.
+    pub fn new() -> LightScene<S, M> {
+        LightScene {
+            lights: Vec::new(),
+            handles: Vec::new(),
+            slots: Vec::new(),
+            free: Vec::new(),
+            transients: Vec::new(),
+        }
+    }
+
+    /// Add a light to the scene, returning its handle.
+    pub fn insert(&mut self, light: Light<S, M>) -> LightHandle {
+        let position = self.lights.len();
+        let handle = match self.free.pop() {
+            Some(index) => {
+                let slot = &mut self.slots[index as usize];
+                slot.position = Some(position);
+
+                LightHandle { index: index, generation: slot.generation }
+            }
+            None => {
+                let index = self.slots.len() as u32;
+                self.slots.push(Slot { generation: 0, position: Some(position) });
+
+                LightHandle { index: index, generation: 0 }
+            }
+        };
+        self.lights.push(light);
+        self.handles.push(handle);
+
+        handle
+    }
+
+    /// Remove a light from the scene, returning it if the handle refers to a
+    /// light in the scene.
+    pub fn remove(&mut self, handle: LightHandle) -> Option<Light<S, M>> {
+        let position = position_of(&self.slots, handle)?;
+        let slot = &mut self.slots[handle.index as usize];
+        slot.position = None;
+        slot.generation = slot.generation.wrapping_add(1);
+        self.free.push(handle.index);
+
+        let light = self.lights.swap_remove(position);
+        self.handles.swap_remove(position);
+        if let Some(moved) = self.handles.get(position) {
+            self.slots[moved.index as usize].position = Some(position);
+        }
+
+        Some(light)
+    }
+
+    /// Determine whether a handle refers to a light in the scene.
+    #[inline]
+    pub fn contains(&self, handle: LightHandle) -> bool {
+        position_of(&self.slots, handle).is_some()
+    }
+
+    /// Get the light a handle refers to.
+    #[inline]
+    pub fn get(&self, handle: LightHandle) -> Option<&Light<S, M>> {
+        position_of(&self.slots, handle).map(move |position| &self.lights[position])
+    }
+
+    /// Get the light a handle refers to mutably.
+    #[inline]
+    pub fn get_mut(&mut self, handle: LightHandle) -> Option<&mut Light<S, M>> {
+        let position = position_of(&self.slots, handle)?;
+
+        Some(&mut self.lights[position])
+    }
+
+    /// The number of lights in the scene.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.lights.len()
+    }
+
+    /// Determine whether the scene has no lights.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.lights.is_empty()
+    }
+
+    /// The lights of the scene in storage order.
+    #[inline]
+    pub fn lights(&self) -> &[Light<S, M>] {
+        &self.lights
+    }
+
+    /// The lights of the scene in storage order, mutably.
+    #[inline]
+    pub fn lights_mut(&mut self) -> &mut [Light<S, M>] {
+        &mut self.lights
+    }
+
+    /// The handles of the scene's lights, in the same order as
+    /// [`lights`](#method.lights).
+    #[inline]
+    pub fn handles(&self) -> &[LightHandle] {
+        &self.handles
+    }
+
+    /// Iterate over the scene's lights and their handles.
+    #[inline]
+    pub fn iter(&self) -> impl Iterator<Item = (LightHandle, &Light<S, M>)> {
+        self.handles.iter().copied().zip(self.lights.iter())
+    }
+
+    /// Take an immutable snapshot of the scene for reading on other threads,
+    /// such as culling, ray tracing, or audio occlusion workers, while the
+    /// live scene keeps changing.
+    ///
+    /// Every light in the snapshot has its view matrix computed and cached,
+    /// so reading it never recomputes the matrix. The snapshot is `Send` and
+    /// `Sync` whenever the scalar and model types are.
+    pub fn freeze(&self) -> Arc<FrozenLightScene<S, M>>
+        where M: Clone
+    {
+        Arc::new(FrozenLightScene::new(self.lights.clone(), self.handles.clone(), self.slots.clone()))
+    }
+}
+
+impl<S, M> LightScene<S, M>
+    where S: ScalarFloat,
+          M: IlluminationModel + Dimmable<S>,
+{
+    /// Add a transient light to the scene, returning its handle. The light
+    /// decays as the scene is updated, and is removed from the scene when it
+    /// expires.
+    pub fn spawn_transient(&mut self, transient: TransientLight<S, M>) -> LightHandle {
+        let (light, decay) = transient.into_parts();
+        let handle = self.insert(light);
+        self.transients.push((handle, decay));
+
+        handle
+    }
+
+    /// Advance the scene's transient lights by `elapsed` seconds, as
+    /// `animation::update_transients` does, and remove the lights that have
+    /// expired.
+    pub fn update(&mut self, elapsed: S) {
+        let mut i = 0;
+        while i < self.transients.len() {
+            let handle = self.transients[i].0;
+            // Transients whose lights were removed from the scene are
+            // forgotten along with the expired ones.
+            let position = match position_of(&self.slots, handle) {
+                Some(position) => position,
+                None => {
+                    self.transients.swap_remove(i);
+                    continue;
+                }
+            };
+            let decay = &mut self.transients[i].1;
+            decay.update(elapsed, &mut self.lights[position]);
+            if decay.is_expired() {
+                self.transients.swap_remove(i);
+                self.remove(handle);
+                continue;
+            }
+            i += 1;
+        }
+    }
+}
+
+impl<S, M> Default for LightScene<S, M>
+    where S: ScalarFloat,
+          M: IlluminationModel,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// An immutable snapshot of a `LightScene`, as produced by
+/// [`LightScene::freeze`](struct.LightScene.html#method.freeze).
+#[derive(Clone, Debug)]
+pub struct FrozenLightScene<S, M> {
+    lights: Vec<Light<S, M>>,
+    handles: Vec<LightHandle>,
+    slots: Vec<Slot>,
+}
+
+impl<S, M> FrozenLightScene<S, M>
+    where S: ScalarFloat,
+          M: IlluminationModel,
+{
+    /// Construct a snapshot, caching the view matrix of every light.
+    fn new(mut lights: Vec<Light<S, M>>, handles: Vec<LightHandle>, slots: Vec<Slot>) -> FrozenLightScene<S, M> {
+        for light in lights.iter_mut() {
+            light.set_view_matrix_policy(ViewMatrixPolicy::Eager);
+            light.refresh_view_matrix();
+        }
+
+        FrozenLightScene {
+            lights: lights,
+            handles: handles,
+            slots: slots,
+        }
+    }
+
+    /// Blend two snapshots of the same scene, e.g. from the previous and
+    /// current simulation steps, for rendering at a different rate than the
+    /// simulation. Each light in `current` is blended with the light with the
+    /// same handle in `previous` by `Light::interpolate`, and lights missing
+    /// from `previous` are taken from `current` unchanged. An `alpha` of zero
+    /// yields the previous attitudes and one yields the current attitudes.
+    pub fn interpolate(previous: &Self, current: &Self, alpha: S) -> Arc<FrozenLightScene<S, M>>
+        where M: Clone
+    {
+        let lights = current.iter()
+            .map(|(handle, light)| match previous.get(handle) {
+                Some(previous_light) => Light::interpolate(previous_light, light, alpha),
+                None => light.clone(),
+            })
+            .collect();
+
+        Arc::new(FrozenLightScene::new(lights, current.handles.clone(), current.slots.clone()))
+    }
+
+    /// Determine whether a handle refers to a light in the snapshot.
+    #[inline]
+    pub fn contains(&self, handle: LightHandle) -> bool {
+        position_of(&self.slots, handle).is_some()
+    }
+
+    /// Get the light a handle refers to.
+    #[inline]
+    pub fn get(&self, handle: LightHandle) -> Option<&Light<S, M>> {
+        position_of(&self.slots, handle).map(move |position| &self.lights[position])
+    }
+
+    /// The number of lights in the snapshot.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.lights.len()
+    }
+
+    /// Determine whether the snapshot has no lights.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.lights.is_empty()
+    }
+
+    /// The lights of the snapshot in the scene's storage order.
+    #[inline]
+    pub fn lights(&self) -> &[Light<S, M>] {
+        &self.lights
+    }
+
+    /// The handles of the snapshot's lights, in the same order as
+    /// [`lights`](#method.lights).
+    #[inline]
+    pub fn handles(&self) -> &[LightHandle] {
+        &self.handles
+    }
+
+    /// Iterate over the snapshot's lights and their handles.
+    #[inline]
+    pub fn iter(&self) -> impl Iterator<Item = (LightHandle, &Light<S, M>)> {
+        self.handles.iter().copied().zip(self.lights.iter())
+    }
+}
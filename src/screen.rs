@@ -0,0 +1,85 @@
+use cglinalg::{
+    Vector2,
+    Vector3,
+    Matrix4x4,
+    ScalarFloat,
+};
+use crate::{
+    IlluminationModel,
+    Light,
+};
+
+
+/// A rectangular region of the render target, in pixels, with its origin at
+/// the **top left** corner.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Viewport<S> {
+    pub x: S,
+    pub y: S,
+    pub width: S,
+    pub height: S,
+}
+
+impl<S> Viewport<S> where S: ScalarFloat {
+    /// Construct a new viewport.
+    #[inline]
+    pub fn new(x: S, y: S, width: S, height: S) -> Viewport<S> {
+        Viewport {
+            x: x,
+            y: y,
+            width: width,
+            height: height,
+        }
+    }
+}
+
+/// The screen space anchor of a light, as used by lens flare and bloom
+/// sprite passes.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct ScreenPosition<S> {
+    /// The light's position in normalized device coordinates.
+    pub ndc: Vector3<S>,
+    /// The light's position in pixels, relative to the viewport's top left
+    /// corner.
+    pub pixel: Vector2<S>,
+    /// An estimate in `[0, 1]` of how visible the light is. The estimate is
+    /// one well inside the viewport and fades linearly to zero across the
+    /// outermost tenth of normalized device coordinates, so flares fade out
+    /// instead of popping at the screen's edge. It does not account for
+    /// occlusion by scene geometry.
+    pub visibility: S,
+}
+
+impl<S, M> Light<S, M>
+    where S: ScalarFloat,
+          M: IlluminationModel,
+{
+    /// Project the light's position onto the screen. The result is `None`
+    /// when the light is behind the camera.
+    pub fn screen_position(
+        &self,
+        view_projection: &Matrix4x4<S>,
+        viewport: &Viewport<S>) -> Option<ScreenPosition<S>>
+    {
+        let clip = view_projection * self.position().extend(S::one());
+        if clip.w <= S::zero() {
+            return None;
+        }
+
+        let one_half: S = num_traits::cast(0.5).unwrap();
+        let border: S = num_traits::cast(0.1).unwrap();
+        let ndc = Vector3::new(clip.x / clip.w, clip.y / clip.w, clip.z / clip.w);
+        let pixel = Vector2::new(
+            viewport.x + (ndc.x + S::one()) * one_half * viewport.width,
+            viewport.y + (S::one() - ndc.y) * one_half * viewport.height,
+        );
+        let edge_distance = S::one() - ndc.x.abs().max(ndc.y.abs());
+        let visibility = (edge_distance / border).max(S::zero()).min(S::one());
+
+        Some(ScreenPosition {
+            ndc: ndc,
+            pixel: pixel,
+            visibility: visibility,
+        })
+    }
+}
@@ -0,0 +1,293 @@
+use cglinalg::{
+    Vector3,
+    Quaternion,
+    Radians,
+    ScalarFloat,
+};
+use crate::{
+    IlluminationModel,
+    Light,
+    LightAttitude,
+    ViewMatrixPolicy,
+};
+use crate::falloff::{
+    FalloffCurve,
+    Interpolation,
+};
+use crate::layers::LightLayers;
+use crate::profile::AngularProfile;
+use crate::sampling::LightJitter;
+use crate::shadow::ShadowSettings;
+use serde::{
+    Deserialize,
+    Deserializer,
+    Serialize,
+    Serializer,
+};
+use serde::de;
+
+
+/// Serialize a vector as an array of its three components.
+pub(crate) mod vector3 {
+    use cglinalg::Vector3;
+    use serde::{
+        Deserialize,
+        Deserializer,
+        Serialize,
+        Serializer,
+    };
+
+    pub(crate) fn serialize<S, Ser>(vector: &Vector3<S>, serializer: Ser) -> Result<Ser::Ok, Ser::Error>
+        where S: Serialize,
+              Ser: Serializer,
+    {
+        [&vector.x, &vector.y, &vector.z].serialize(serializer)
+    }
+
+    pub(crate) fn deserialize<'de, S, De>(deserializer: De) -> Result<Vector3<S>, De::Error>
+        where S: Deserialize<'de>,
+              De: Deserializer<'de>,
+    {
+        let [x, y, z] = <[S; 3]>::deserialize(deserializer)?;
+
+        Ok(Vector3::new(x, y, z))
+    }
+}
+
+/// Serialize an angle as its value in radians.
+pub(crate) mod radians {
+    use cglinalg::Radians;
+    use serde::{
+        Deserialize,
+        Deserializer,
+        Serialize,
+        Serializer,
+    };
+
+    pub(crate) fn serialize<S, Ser>(angle: &Radians<S>, serializer: Ser) -> Result<Ser::Ok, Ser::Error>
+        where S: Serialize,
+              Ser: Serializer,
+    {
+        angle.0.serialize(serializer)
+    }
+
+    pub(crate) fn deserialize<'de, S, De>(deserializer: De) -> Result<Radians<S>, De::Error>
+        where S: Deserialize<'de>,
+              De: Deserializer<'de>,
+    {
+        S::deserialize(deserializer).map(Radians)
+    }
+}
+
+/// Serialize a quaternion as the array `[s, x, y, z]`.
+mod quaternion {
+    use cglinalg::{
+        Quaternion,
+        Vector3,
+    };
+    use serde::{
+        Deserialize,
+        Deserializer,
+        Serialize,
+        Serializer,
+    };
+
+    pub(crate) fn serialize<S, Ser>(quaternion: &Quaternion<S>, serializer: Ser) -> Result<Ser::Ok, Ser::Error>
+        where S: Serialize,
+              Ser: Serializer,
+    {
+        [&quaternion.s, &quaternion.v.x, &quaternion.v.y, &quaternion.v.z].serialize(serializer)
+    }
+
+    pub(crate) fn deserialize<'de, S, De>(deserializer: De) -> Result<Quaternion<S>, De::Error>
+        where S: Deserialize<'de>,
+              De: Deserializer<'de>,
+    {
+        let [s, x, y, z] = <[S; 4]>::deserialize(deserializer)?;
+
+        Ok(Quaternion { s: s, v: Vector3::new(x, y, z) })
+    }
+}
+
+/// A falloff curve is serialized as its interpolation and control points,
+/// and validated by `FalloffCurve::new` when deserialized.
+#[derive(Serialize, Deserialize)]
+struct FalloffCurveRepr<S> {
+    interpolation: Interpolation,
+    points: Vec<(S, S)>,
+}
+
+impl<S> Serialize for FalloffCurve<S> where S: ScalarFloat + Serialize {
+    fn serialize<Ser: Serializer>(&self, serializer: Ser) -> Result<Ser::Ok, Ser::Error> {
+        let repr = FalloffCurveRepr {
+            interpolation: self.interpolation(),
+            points: self.points().to_vec(),
+        };
+
+        repr.serialize(serializer)
+    }
+}
+
+impl<'de, S> Deserialize<'de> for FalloffCurve<S> where S: ScalarFloat + Deserialize<'de> {
+    fn deserialize<De: Deserializer<'de>>(deserializer: De) -> Result<Self, De::Error> {
+        let repr = FalloffCurveRepr::deserialize(deserializer)?;
+
+        FalloffCurve::new(repr.interpolation, repr.points)
+            .ok_or_else(|| de::Error::custom("a falloff curve needs at least one control point"))
+    }
+}
+
+/// An angular profile is serialized as its samples, with angles in
+/// radians, and validated by `AngularProfile::new` when deserialized.
+impl<S> Serialize for AngularProfile<S> where S: ScalarFloat + Serialize {
+    fn serialize<Ser: Serializer>(&self, serializer: Ser) -> Result<Ser::Ok, Ser::Error> {
+        self.samples().serialize(serializer)
+    }
+}
+
+impl<'de, S> Deserialize<'de> for AngularProfile<S> where S: ScalarFloat + Deserialize<'de> {
+    fn deserialize<De: Deserializer<'de>>(deserializer: De) -> Result<Self, De::Error> {
+        let samples: Vec<(S, S)> = Vec::deserialize(deserializer)?;
+        let samples = samples.into_iter().map(|(angle, value)| (Radians(angle), value)).collect();
+
+        AngularProfile::new(samples)
+            .ok_or_else(|| de::Error::custom("an angular profile needs at least one sample"))
+    }
+}
+
+/// A serializable snapshot of a light, for saving and loading scenes.
+///
+/// The snapshot stores the light's model as its specification and its
+/// attitude as its position and unit orientation, so restoring a snapshot
+/// reproduces the light exactly. The view matrix is not stored; restored
+/// lights recompute it.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct LightSnapshot<S, Spec> {
+    /// The specification of the light's illumination model.
+    pub model: Spec,
+    /// The light's position in world space.
+    #[serde(with = "vector3")]
+    pub position: Vector3<S>,
+    /// The light's orientation as a unit quaternion, stored as `[s, x, y, z]`.
+    #[serde(with = "quaternion")]
+    pub orientation: Quaternion<S>,
+    pub view_matrix_policy: ViewMatrixPolicy,
+    pub jitter: LightJitter<S>,
+    pub exposure_compensation: S,
+    pub layers: LightLayers,
+    pub diffuse_factor: S,
+    pub specular_factor: S,
+    pub shadow_settings: Option<ShadowSettings<S>>,
+}
+
+impl<S, M> Light<S, M>
+    where S: ScalarFloat,
+          M: IlluminationModel,
+{
+    /// Take a snapshot of the light for serialization.
+    pub fn snapshot(&self) -> LightSnapshot<S, M::Spec> {
+        LightSnapshot {
+            model: self.model.to_spec(),
+            position: self.attitude.position,
+            orientation: self.attitude.orientation,
+            view_matrix_policy: self.attitude.policy,
+            jitter: self.jitter,
+            exposure_compensation: self.exposure_compensation,
+            layers: self.layers,
+            diffuse_factor: self.diffuse_factor,
+            specular_factor: self.specular_factor,
+            shadow_settings: self.shadow_settings,
+        }
+    }
+
+    /// Restore a light from a snapshot.
+    pub fn from_snapshot(snapshot: &LightSnapshot<S, M::Spec>) -> Self {
+        Light {
+            model: M::from_spec(&snapshot.model),
+            attitude: LightAttitude::from_parts(snapshot.position, snapshot.orientation, snapshot.view_matrix_policy),
+            jitter: snapshot.jitter,
+            exposure_compensation: snapshot.exposure_compensation,
+            layers: snapshot.layers,
+            diffuse_factor: snapshot.diffuse_factor,
+            specular_factor: snapshot.specular_factor,
+            shadow_settings: snapshot.shadow_settings,
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        LightAttitudeSpec,
+        PointLight,
+        PointLightModelSpec,
+    };
+    use crate::attenuation::Attenuation;
+    use crate::shadow::ShadowFilter;
+    use std::sync::Arc;
+
+
+    fn point_light() -> PointLight<f64> {
+        let falloff = FalloffCurve::new(Interpolation::CatmullRom, vec![(0.0, 1.0), (2.5, 0.4), (10.0, 0.0)]).unwrap();
+        let model_spec = PointLightModelSpec::new(
+            Vector3::new(0.1, 0.1, 0.1),
+            Vector3::new(0.8, 0.7, 0.6),
+            Vector3::new(1.0, 1.0, 1.0),
+        )
+        .with_attenuation(Attenuation::WindowedInverseSquare { range: 12.0 })
+        .with_falloff(Arc::new(falloff));
+        let attitude_spec = LightAttitudeSpec::new(
+            Vector3::new(1.0, 2.0, 3.0),
+            Vector3::new(0.0, 0.0, -1.0),
+            Vector3::new(1.0, 0.0, 0.0),
+            Vector3::new(0.0, 1.0, 0.0),
+            Vector3::new(0.0, 0.0, 1.0),
+        );
+        let mut light = PointLight::with_view_matrix_policy(&model_spec, &attitude_spec, ViewMatrixPolicy::Lazy);
+        let shadow_settings = ShadowSettings::new(0.05, 40.0)
+            .with_depth_bias(0.002, 1.5)
+            .with_resolution_hint(1024)
+            .with_filter(ShadowFilter::Variance { min_variance: 1e-4, light_bleeding_reduction: 0.2 });
+        light.set_jitter(LightJitter::new(0.125, 3));
+        light.set_exposure_compensation(0.5);
+        light.set_layers(LightLayers::from_bits(0b1010));
+        light.set_diffuse_factor(0.75);
+        light.set_specular_factor(1.25);
+        light.set_shadow_settings(Some(shadow_settings));
+
+        light
+    }
+
+    #[test]
+    fn test_light_snapshot_json_round_trip() {
+        let light = point_light();
+        let snapshot = light.snapshot();
+        let json = serde_json::to_string(&snapshot).unwrap();
+        let restored: LightSnapshot<f64, PointLightModelSpec<f64>> = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(restored, snapshot);
+
+        let restored_light = PointLight::from_snapshot(&restored);
+        assert_eq!(restored_light.snapshot(), snapshot);
+        assert_eq!(restored_light.view_matrix(), light.view_matrix());
+    }
+
+    #[test]
+    fn test_falloff_curve_and_profile_json_round_trip() {
+        let falloff = FalloffCurve::new(Interpolation::Linear, vec![(0.0, 1.0), (5.0, 0.5), (8.0, 0.0)]).unwrap();
+        let json = serde_json::to_string(&falloff).unwrap();
+        assert_eq!(serde_json::from_str::<FalloffCurve<f64>>(&json).unwrap(), falloff);
+
+        let profile = AngularProfile::new(vec![(Radians(0.0), 1.0), (Radians(0.3), 0.8), (Radians(0.6), 0.0)]).unwrap();
+        let json = serde_json::to_string(&profile).unwrap();
+        assert_eq!(serde_json::from_str::<AngularProfile<f64>>(&json).unwrap(), profile);
+    }
+
+    #[test]
+    fn test_deserialization_validates_curves() {
+        assert!(serde_json::from_str::<FalloffCurve<f64>>(r#"{"interpolation":"Linear","points":[]}"#).is_err());
+        assert!(serde_json::from_str::<AngularProfile<f64>>("[]").is_err());
+    }
+}
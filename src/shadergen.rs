@@ -0,0 +1,777 @@
+use cglinalg::ScalarFloat;
+use crate::attenuation::MIN_DISTANCE;
+use crate::clustering::ClusterGrid;
+use crate::gpu::{
+    ATTENUATION_INVERSE_SQUARE,
+    ATTENUATION_NONE,
+    ATTENUATION_POLYNOMIAL,
+    ATTENUATION_WINDOWED,
+    ATTENUATION_WINDOWED_INVERSE_SQUARE,
+};
+use crate::std140::{
+    Std140DirectionalLight,
+    Std140PointLight,
+    Std140SpotLight,
+};
+use crate::std430::STD430_HEADER_SIZE;
+use core::fmt::Write;
+use core::mem;
+
+
+/// The type of a member of a generated light struct.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum MemberType {
+    Float,
+    Uint,
+    Vec3,
+    Vec4,
+}
+
+impl MemberType {
+    /// The alignment of the type in bytes under the std140 rules.
+    #[inline]
+    pub const fn alignment(self) -> usize {
+        match self {
+            MemberType::Float | MemberType::Uint => 4,
+            MemberType::Vec3 | MemberType::Vec4 => 16,
+        }
+    }
+
+    /// The size of the type in bytes.
+    #[inline]
+    pub const fn size(self) -> usize {
+        match self {
+            MemberType::Float | MemberType::Uint => 4,
+            MemberType::Vec3 => 12,
+            MemberType::Vec4 => 16,
+        }
+    }
+
+    /// The name of the type in GLSL.
+    #[inline]
+    pub fn glsl_name(self) -> &'static str {
+        match self {
+            MemberType::Float => "float",
+            MemberType::Uint => "uint",
+            MemberType::Vec3 => "vec3",
+            MemberType::Vec4 => "vec4",
+        }
+    }
+
+    /// The name of the type in HLSL.
+    #[inline]
+    pub fn hlsl_name(self) -> &'static str {
+        match self {
+            MemberType::Float => "float",
+            MemberType::Uint => "uint",
+            MemberType::Vec3 => "float3",
+            MemberType::Vec4 => "float4",
+        }
+    }
+
+    /// The name of the type in WGSL.
+    #[cfg(feature = "shadergen-wgsl")]
+    #[inline]
+    pub fn wgsl_name(self) -> &'static str {
+        match self {
+            MemberType::Float => "f32",
+            MemberType::Uint => "u32",
+            MemberType::Vec3 => "vec3<f32>",
+            MemberType::Vec4 => "vec4<f32>",
+        }
+    }
+}
+
+/// A member of a generated light struct.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Member {
+    pub name: &'static str,
+    pub ty: MemberType,
+}
+
+const fn member(name: &'static str, ty: MemberType) -> Member {
+    Member {
+        name: name,
+        ty: ty,
+    }
+}
+
+/// The definition of a light struct shared by the CPU and the GPU. The
+/// members are listed in the order of the crate's std140 structs, and the
+/// generated declarations lay them out at the same offsets.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct StructDefinition {
+    pub name: &'static str,
+    pub members: &'static [Member],
+}
+
+impl StructDefinition {
+    /// The offset in bytes of the member at `index` under the std140 rules.
+    /// The WGSL layout rules for the uniform and storage address spaces
+    /// give the same offsets for these types.
+    pub const fn offset(&self, index: usize) -> usize {
+        let mut offset: usize = 0;
+        let mut i = 0;
+        while i <= index {
+            let alignment = self.members[i].ty.alignment();
+            offset = offset.next_multiple_of(alignment);
+            if i < index {
+                offset += self.members[i].ty.size();
+            }
+            i += 1;
+        }
+
+        offset
+    }
+
+    /// The offset in bytes of the member named `name` under the std140
+    /// rules. Panics if the struct has no such member, which fails the
+    /// build when evaluated in a constant.
+    pub const fn offset_of(&self, name: &str) -> usize {
+        let mut i = 0;
+        while i < self.members.len() {
+            if str_eq(self.members[i].name, name) {
+                return self.offset(i);
+            }
+            i += 1;
+        }
+
+        panic!("no member with the given name")
+    }
+
+    /// The size in bytes of the struct under the std140 rules, which is
+    /// also its array stride.
+    pub const fn size(&self) -> usize {
+        let last = self.members.len() - 1;
+        let end = self.offset(last) + self.members[last].ty.size();
+
+        end.next_multiple_of(16)
+    }
+}
+
+/// Compare two strings in a constant context.
+const fn str_eq(a: &str, b: &str) -> bool {
+    let a = a.as_bytes();
+    let b = b.as_bytes();
+    if a.len() != b.len() {
+        return false;
+    }
+    let mut i = 0;
+    while i < a.len() {
+        if a[i] != b[i] {
+            return false;
+        }
+        i += 1;
+    }
+
+    true
+}
+
+/// Assert at compile time that a struct definition lays out exactly the
+/// given members of a `#[repr(C)]` struct at the struct's offsets, and has
+/// the struct's size.
+macro_rules! assert_definition_layout {
+    ($definition:expr, $Struct:ty, [$($field:ident),* $(,)*]) => {
+        const _: () = {
+            assert!($definition.size() == mem::size_of::<$Struct>());
+            assert!($definition.members.len() == [$(stringify!($field)),*].len());
+            $(assert!($definition.offset_of(stringify!($field)) == mem::offset_of!($Struct, $field));)*
+        };
+    }
+}
+
+/// The definition of `Std140PointLight`.
+pub const POINT_LIGHT: StructDefinition = StructDefinition {
+    name: "PointLight",
+    members: &[
+        member("position", MemberType::Vec3),
+        member("attenuation_kind", MemberType::Uint),
+        member("ambient", MemberType::Vec3),
+        member("polarity", MemberType::Float),
+        member("diffuse", MemberType::Vec3),
+        member("specular", MemberType::Vec3),
+        member("shadow_tint", MemberType::Vec3),
+        member("attenuation", MemberType::Vec4),
+    ],
+};
+
+/// The definition of `Std140SpotLight`.
+pub const SPOT_LIGHT: StructDefinition = StructDefinition {
+    name: "SpotLight",
+    members: &[
+        member("position", MemberType::Vec3),
+        member("cutoff", MemberType::Float),
+        member("direction", MemberType::Vec3),
+        member("outer_cutoff", MemberType::Float),
+        member("ambient", MemberType::Vec3),
+        member("cone_exponent", MemberType::Float),
+        member("diffuse", MemberType::Vec3),
+        member("polarity", MemberType::Float),
+        member("specular", MemberType::Vec3),
+        member("attenuation_kind", MemberType::Uint),
+        member("shadow_tint", MemberType::Vec3),
+        member("attenuation", MemberType::Vec4),
+    ],
+};
+
+/// The definition of `Std140DirectionalLight`.
+pub const DIRECTIONAL_LIGHT: StructDefinition = StructDefinition {
+    name: "DirectionalLight",
+    members: &[
+        member("direction", MemberType::Vec3),
+        member("ambient", MemberType::Vec3),
+        member("diffuse", MemberType::Vec3),
+        member("specular", MemberType::Vec3),
+        member("shadow_tint", MemberType::Vec3),
+    ],
+};
+
+/// Every light struct definition, in the order they are generated.
+pub const STRUCTS: [StructDefinition; 3] = [POINT_LIGHT, SPOT_LIGHT, DIRECTIONAL_LIGHT];
+
+// Laying out the definitions by the std140 rules must reproduce the offset
+// of every member of the std140 structs, and their sizes.
+assert_definition_layout!(POINT_LIGHT, Std140PointLight, [
+    position,
+    attenuation_kind,
+    ambient,
+    polarity,
+    diffuse,
+    specular,
+    shadow_tint,
+    attenuation,
+]);
+
+assert_definition_layout!(SPOT_LIGHT, Std140SpotLight, [
+    position,
+    cutoff,
+    direction,
+    outer_cutoff,
+    ambient,
+    cone_exponent,
+    diffuse,
+    polarity,
+    specular,
+    attenuation_kind,
+    shadow_tint,
+    attenuation,
+]);
+
+assert_definition_layout!(DIRECTIONAL_LIGHT, Std140DirectionalLight, [
+    direction,
+    ambient,
+    diffuse,
+    specular,
+    shadow_tint,
+]);
+
+/// The attenuation kind constants, by name.
+const ATTENUATION_KINDS: [(&str, u32); 5] = [
+    ("ATTENUATION_NONE", ATTENUATION_NONE),
+    ("ATTENUATION_POLYNOMIAL", ATTENUATION_POLYNOMIAL),
+    ("ATTENUATION_INVERSE_SQUARE", ATTENUATION_INVERSE_SQUARE),
+    ("ATTENUATION_WINDOWED", ATTENUATION_WINDOWED),
+    ("ATTENUATION_WINDOWED_INVERSE_SQUARE", ATTENUATION_WINDOWED_INVERSE_SQUARE),
+];
+
+/// The renderer configuration a generated shader header is specialized to,
+/// so the shader's array capacities and cluster layout come from the same
+/// values as the CPU side packing.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ShaderConfig {
+    /// The capacity of the point light array.
+    pub max_point_lights: u32,
+    /// The capacity of the spotlight array.
+    pub max_spot_lights: u32,
+    /// The capacity of the directional light array.
+    pub max_directional_lights: u32,
+    /// The number of clusters along the screen's width, its height, and
+    /// depth, as in `ClusterGrid::dimensions`.
+    pub cluster_dimensions: [u32; 3],
+    /// Whether the lights are packed into std430 storage buffers with
+    /// `std430::pack_std430` rather than into std140 uniform arrays.
+    pub std430: bool,
+}
+
+impl ShaderConfig {
+    /// Construct a configuration for the clusters of `grid`, with room for
+    /// `max_lights` lights of each kind in std430 storage buffers.
+    pub fn new<S: ScalarFloat>(grid: &ClusterGrid<S>, max_lights: u32) -> ShaderConfig {
+        ShaderConfig {
+            max_point_lights: max_lights,
+            max_spot_lights: max_lights,
+            max_directional_lights: max_lights,
+            cluster_dimensions: grid.dimensions(),
+            std430: true,
+        }
+    }
+
+    /// The named constants of the configuration, in the order they are
+    /// generated. Flags are encoded as zero or one.
+    fn constants(&self) -> Vec<(String, u32)> {
+        let [width, height, depth] = self.cluster_dimensions;
+        let mut constants = vec![
+            (String::from("MAX_POINT_LIGHTS"), self.max_point_lights),
+            (String::from("MAX_SPOT_LIGHTS"), self.max_spot_lights),
+            (String::from("MAX_DIRECTIONAL_LIGHTS"), self.max_directional_lights),
+            (String::from("CLUSTER_DIMENSIONS_X"), width),
+            (String::from("CLUSTER_DIMENSIONS_Y"), height),
+            (String::from("CLUSTER_DIMENSIONS_Z"), depth),
+            (String::from("CLUSTER_COUNT"), width * height * depth),
+            (String::from("LIGHT_BUFFERS_STD430"), self.std430 as u32),
+            (String::from("STD430_HEADER_SIZE"), STD430_HEADER_SIZE as u32),
+        ];
+        for definition in STRUCTS.iter() {
+            constants.push((format!("{}_SIZE", constant_case(definition.name)), definition.size() as u32));
+        }
+
+        constants
+    }
+}
+
+/// Convert a struct name such as `PointLight` into a constant name prefix
+/// such as `POINT_LIGHT`.
+fn constant_case(name: &str) -> String {
+    let mut result = String::new();
+    for (i, character) in name.chars().enumerate() {
+        if i > 0 && character.is_uppercase() {
+            result.push('_');
+        }
+        result.push(character.to_ascii_uppercase());
+    }
+
+    result
+}
+
+/// The first line of every generated source.
+const GENERATED_BANNER: &str = "// Generated by cgilluminate. Do not edit.\n";
+
+/// The GLSL reference implementations of the crate's attenuation and
+/// spotlight cone falloff, matching `Attenuation::evaluate` and
+/// `SpotLightModel::cone_factor` without angular profiles.
+const GLSL_FUNCTIONS: &str = r#"float smooth_window(float light_distance, float range) {
+    if (range <= 0.0) {
+        return 0.0;
+    }
+    float ratio = light_distance / range;
+    float ratio_squared = ratio * ratio;
+    float window = max(1.0 - ratio_squared * ratio_squared, 0.0);
+    return window * window;
+}
+
+float inverse_square(float light_distance) {
+    float d = max(light_distance, MIN_ATTENUATION_DISTANCE);
+    return 1.0 / (d * d);
+}
+
+float polynomial_attenuation(vec4 attenuation, float light_distance) {
+    return 1.0 / (attenuation.x + attenuation.y * light_distance + attenuation.z * light_distance * light_distance);
+}
+
+float evaluate_attenuation(uint kind, vec4 attenuation, float light_distance) {
+    if (kind == ATTENUATION_POLYNOMIAL) {
+        return polynomial_attenuation(attenuation, light_distance);
+    } else if (kind == ATTENUATION_INVERSE_SQUARE) {
+        return inverse_square(light_distance);
+    } else if (kind == ATTENUATION_WINDOWED) {
+        if (attenuation.w <= 0.0) {
+            return 0.0;
+        }
+        float window = max(1.0 - light_distance / attenuation.w, 0.0);
+        return polynomial_attenuation(attenuation, light_distance) * window;
+    } else if (kind == ATTENUATION_WINDOWED_INVERSE_SQUARE) {
+        return inverse_square(light_distance) * smooth_window(light_distance, attenuation.w);
+    }
+    return 1.0;
+}
+
+float spot_cone_factor(float cos_angle, float cutoff, float outer_cutoff, float cone_exponent) {
+    if (cone_exponent >= 0.0) {
+        if (cos_angle < outer_cutoff || cos_angle <= 0.0) {
+            return 0.0;
+        }
+        return pow(min(cos_angle, 1.0), cone_exponent);
+    }
+    float epsilon = cutoff - outer_cutoff;
+    if (epsilon <= 0.0) {
+        return cos_angle >= cutoff ? 1.0 : 0.0;
+    }
+    float t = clamp((cos_angle - outer_cutoff) / epsilon, 0.0, 1.0);
+    return t * t * (3.0 - 2.0 * t);
+}
+"#;
+
+/// The WGSL reference implementations of the crate's attenuation and
+/// spotlight cone falloff, matching the GLSL ones.
+#[cfg(feature = "shadergen-wgsl")]
+const WGSL_FUNCTIONS: &str = r#"fn smooth_window(light_distance: f32, range: f32) -> f32 {
+    if (range <= 0.0) {
+        return 0.0;
+    }
+    let ratio = light_distance / range;
+    let ratio_squared = ratio * ratio;
+    let window = max(1.0 - ratio_squared * ratio_squared, 0.0);
+    return window * window;
+}
+
+fn inverse_square(light_distance: f32) -> f32 {
+    let d = max(light_distance, MIN_ATTENUATION_DISTANCE);
+    return 1.0 / (d * d);
+}
+
+fn polynomial_attenuation(attenuation: vec4<f32>, light_distance: f32) -> f32 {
+    return 1.0 / (attenuation.x + attenuation.y * light_distance + attenuation.z * light_distance * light_distance);
+}
+
+fn evaluate_attenuation(kind: u32, attenuation: vec4<f32>, light_distance: f32) -> f32 {
+    if (kind == ATTENUATION_POLYNOMIAL) {
+        return polynomial_attenuation(attenuation, light_distance);
+    } else if (kind == ATTENUATION_INVERSE_SQUARE) {
+        return inverse_square(light_distance);
+    } else if (kind == ATTENUATION_WINDOWED) {
+        if (attenuation.w <= 0.0) {
+            return 0.0;
+        }
+        let window = max(1.0 - light_distance / attenuation.w, 0.0);
+        return polynomial_attenuation(attenuation, light_distance) * window;
+    } else if (kind == ATTENUATION_WINDOWED_INVERSE_SQUARE) {
+        return inverse_square(light_distance) * smooth_window(light_distance, attenuation.w);
+    }
+    return 1.0;
+}
+
+fn spot_cone_factor(cos_angle: f32, cutoff: f32, outer_cutoff: f32, cone_exponent: f32) -> f32 {
+    if (cone_exponent >= 0.0) {
+        if (cos_angle < outer_cutoff || cos_angle <= 0.0) {
+            return 0.0;
+        }
+        return pow(min(cos_angle, 1.0), cone_exponent);
+    }
+    let epsilon = cutoff - outer_cutoff;
+    if (epsilon <= 0.0) {
+        return select(0.0, 1.0, cos_angle >= cutoff);
+    }
+    let t = clamp((cos_angle - outer_cutoff) / epsilon, 0.0, 1.0);
+    return t * t * (3.0 - 2.0 * t);
+}
+"#;
+
+/// Generate the GLSL declaration of a light struct. Each member is
+/// annotated with its std140 offset.
+pub fn glsl_struct(definition: &StructDefinition) -> String {
+    let mut source = String::new();
+    writeln!(source, "struct {} {{", definition.name).unwrap();
+    for (i, member) in definition.members.iter().enumerate() {
+        writeln!(
+            source, "    {} {}; // offset {}",
+            member.ty.glsl_name(), member.name, definition.offset(i)
+        ).unwrap();
+    }
+    writeln!(source, "}};").unwrap();
+
+    source
+}
+
+/// Generate a GLSL source with the attenuation kind constants, the light
+/// struct declarations, and the reference attenuation and spotlight cone
+/// falloff functions, for including in shaders that read the crate's std140
+/// and std430 buffers.
+pub fn glsl() -> String {
+    let mut source = String::from(GENERATED_BANNER);
+    writeln!(source).unwrap();
+    for (name, value) in ATTENUATION_KINDS.iter() {
+        writeln!(source, "const uint {} = {}u;", name, value).unwrap();
+    }
+    writeln!(source, "const float MIN_ATTENUATION_DISTANCE = {:?};", MIN_DISTANCE).unwrap();
+    for definition in STRUCTS.iter() {
+        writeln!(source).unwrap();
+        source.push_str(&glsl_struct(definition));
+    }
+    writeln!(source).unwrap();
+    source.push_str(GLSL_FUNCTIONS);
+
+    source
+}
+
+/// Generate a GLSL header with the constants of a renderer configuration:
+/// the light array capacities, the cluster dimensions, and the buffer
+/// packing, including the stride of each light struct.
+pub fn glsl_config(config: &ShaderConfig) -> String {
+    let mut source = String::from(GENERATED_BANNER);
+    writeln!(source).unwrap();
+    for (name, value) in config.constants().iter() {
+        writeln!(source, "const uint {} = {}u;", name, value).unwrap();
+    }
+
+    source
+}
+
+/// Generate the WGSL declaration of a light struct. Each member is
+/// annotated with its offset, which is the same in the uniform and storage
+/// address spaces.
+#[cfg(feature = "shadergen-wgsl")]
+pub fn wgsl_struct(definition: &StructDefinition) -> String {
+    let mut source = String::new();
+    writeln!(source, "struct {} {{", definition.name).unwrap();
+    for (i, member) in definition.members.iter().enumerate() {
+        writeln!(
+            source, "    {}: {}, // offset {}",
+            member.name, member.ty.wgsl_name(), definition.offset(i)
+        ).unwrap();
+    }
+    writeln!(source, "}}").unwrap();
+
+    source
+}
+
+/// Generate a WGSL source with the attenuation kind constants, the light
+/// struct declarations, and the reference attenuation and spotlight cone
+/// falloff functions, matching the output of [`glsl`].
+#[cfg(feature = "shadergen-wgsl")]
+pub fn wgsl() -> String {
+    let mut source = String::from(GENERATED_BANNER);
+    writeln!(source).unwrap();
+    for (name, value) in ATTENUATION_KINDS.iter() {
+        writeln!(source, "const {}: u32 = {}u;", name, value).unwrap();
+    }
+    writeln!(source, "const MIN_ATTENUATION_DISTANCE: f32 = {:?};", MIN_DISTANCE).unwrap();
+    for definition in STRUCTS.iter() {
+        writeln!(source).unwrap();
+        source.push_str(&wgsl_struct(definition));
+    }
+    writeln!(source).unwrap();
+    source.push_str(WGSL_FUNCTIONS);
+
+    source
+}
+
+/// Generate a WGSL header with the constants of a renderer configuration,
+/// matching the output of [`glsl_config`].
+#[cfg(feature = "shadergen-wgsl")]
+pub fn wgsl_config(config: &ShaderConfig) -> String {
+    let mut source = String::from(GENERATED_BANNER);
+    writeln!(source).unwrap();
+    for (name, value) in config.constants().iter() {
+        writeln!(source, "const {}: u32 = {}u;", name, value).unwrap();
+    }
+
+    source
+}
+
+/// The WGSL reference kernel of the GPU culling pass, with
+/// `WORKGROUP_SIZE` standing for the workgroup size.
+#[cfg(feature = "shadergen-wgsl")]
+const WGSL_CLUSTER_CULLING: &str = r#"struct ClusterParams {
+    position: vec3<f32>,
+    tan_half_fov: f32,
+    right: vec3<f32>,
+    aspect: f32,
+    up: vec3<f32>,
+    near: f32,
+    forward: vec3<f32>,
+    far: f32,
+    dimensions: vec3<u32>,
+    light_count: u32,
+    max_light_indices: u32,
+}
+
+struct LightBounds {
+    center: vec3<f32>,
+    radius: f32,
+}
+
+struct ClusterCounters {
+    light_index_count: atomic<u32>,
+    overflow: atomic<u32>,
+}
+
+@group(0) @binding(0) var<uniform> params: ClusterParams;
+@group(0) @binding(1) var<storage, read> light_bounds: array<LightBounds>;
+@group(0) @binding(2) var<storage, read_write> counters: ClusterCounters;
+@group(0) @binding(3) var<storage, read_write> clusters: array<vec2<u32>>;
+@group(0) @binding(4) var<storage, read_write> light_indices: array<u32>;
+
+fn slice_depth(slice: u32) -> f32 {
+    return params.near * pow(params.far / params.near, f32(slice) / f32(params.dimensions.z));
+}
+
+fn light_reaches(light: u32, froxel_min: vec3<f32>, froxel_max: vec3<f32>) -> bool {
+    let bounds = light_bounds[light];
+    if (bounds.radius < 0.0) {
+        return false;
+    }
+    let offset = bounds.center - params.position;
+    let center = vec3<f32>(dot(params.right, offset), dot(params.up, offset), dot(params.forward, offset));
+    let delta = center - clamp(center, froxel_min, froxel_max);
+    return dot(delta, delta) <= bounds.radius * bounds.radius;
+}
+
+@compute @workgroup_size(WORKGROUP_SIZE)
+fn cull_clusters(@builtin(global_invocation_id) id: vec3<u32>) {
+    let dimensions = params.dimensions;
+    let index = id.x;
+    if (index >= dimensions.x * dimensions.y * dimensions.z) {
+        return;
+    }
+    let x = index % dimensions.x;
+    let y = (index / dimensions.x) % dimensions.y;
+    let z = index / (dimensions.x * dimensions.y);
+
+    let near = slice_depth(z);
+    let far = slice_depth(z + 1u);
+    let half_width = params.tan_half_fov * params.aspect;
+    let ndc_left = 2.0 * f32(x) / f32(dimensions.x) - 1.0;
+    let ndc_right = 2.0 * f32(x + 1u) / f32(dimensions.x) - 1.0;
+    let ndc_bottom = 2.0 * f32(y) / f32(dimensions.y) - 1.0;
+    let ndc_top = 2.0 * f32(y + 1u) / f32(dimensions.y) - 1.0;
+    let froxel_min = vec3<f32>(
+        min(ndc_left * half_width * near, ndc_left * half_width * far),
+        min(ndc_bottom * params.tan_half_fov * near, ndc_bottom * params.tan_half_fov * far),
+        near,
+    );
+    let froxel_max = vec3<f32>(
+        max(ndc_right * half_width * near, ndc_right * half_width * far),
+        max(ndc_top * params.tan_half_fov * near, ndc_top * params.tan_half_fov * far),
+        far,
+    );
+
+    var count = 0u;
+    for (var light = 0u; light < params.light_count; light = light + 1u) {
+        if (light_reaches(light, froxel_min, froxel_max)) {
+            count = count + 1u;
+        }
+    }
+    let offset = atomicAdd(&counters.light_index_count, count);
+    if (offset + count > params.max_light_indices) {
+        atomicStore(&counters.overflow, 1u);
+        clusters[index] = vec2<u32>(offset, 0u);
+        return;
+    }
+
+    var written = 0u;
+    for (var light = 0u; light < params.light_count; light = light + 1u) {
+        if (light_reaches(light, froxel_min, froxel_max)) {
+            light_indices[offset + written] = light;
+            written = written + 1u;
+        }
+    }
+    clusters[index] = vec2<u32>(offset, count);
+}
+"#;
+
+/// Generate the WGSL reference kernel of GPU driven clustered culling, with
+/// `workgroup_size` invocations per workgroup.
+///
+/// The kernel `cull_clusters` reproduces `clustering::ClusterGrid::assign`
+/// with one invocation per cluster. It reads a `clustering::GpuClusterParams`
+/// uniform and the `clustering::gpu_light_bounds` buffer, allocates storage
+/// in the light index buffer with the `clustering::GpuClusterCounters`, and
+/// writes one `[offset, count]` pair per cluster. Each cluster lists its
+/// lights in the same order as on the CPU, but the clusters' lists are laid
+/// out in the order the invocations run. Dispatch it with
+/// `ClusterGrid::dispatch_args`.
+#[cfg(feature = "shadergen-wgsl")]
+pub fn wgsl_cluster_culling(workgroup_size: u32) -> String {
+    let mut source = String::from(GENERATED_BANNER);
+    writeln!(source).unwrap();
+    source.push_str(&WGSL_CLUSTER_CULLING.replace("WORKGROUP_SIZE", &workgroup_size.max(1).to_string()));
+
+    source
+}
+
+/// Generate the HLSL declaration of a light struct. Each member is
+/// annotated with its offset.
+///
+/// Constant buffers pack members into 16 byte registers, which reproduces
+/// the std140 offsets, but structured buffers pack them tightly. The
+/// declaration spells out the std140 padding as explicit members, so both
+/// kinds of buffer lay the struct out like the crate's std140 structs.
+pub fn hlsl_struct(definition: &StructDefinition) -> String {
+    let mut source = String::new();
+    let mut end = 0;
+    let mut padding_count = 0;
+    writeln!(source, "struct {} {{", definition.name).unwrap();
+    for (i, member) in definition.members.iter().enumerate() {
+        let offset = definition.offset(i);
+        while end < offset {
+            writeln!(source, "    float _padding{}; // offset {}", padding_count, end).unwrap();
+            padding_count += 1;
+            end += 4;
+        }
+        writeln!(
+            source, "    {} {}; // offset {}",
+            member.ty.hlsl_name(), member.name, offset
+        ).unwrap();
+        end = offset + member.ty.size();
+    }
+    while end < definition.size() {
+        writeln!(source, "    float _padding{}; // offset {}", padding_count, end).unwrap();
+        padding_count += 1;
+        end += 4;
+    }
+    writeln!(source, "}};").unwrap();
+
+    source
+}
+
+/// Generate the HLSL declaration of a constant buffer holding a light
+/// count and an array of up to `capacity` lights, bound to the constant
+/// buffer register `register`.
+pub fn hlsl_cbuffer(definition: &StructDefinition, name: &str, register: u32, capacity: usize) -> String {
+    let mut source = String::new();
+    writeln!(source, "cbuffer {} : register(b{}) {{", name, register).unwrap();
+    writeln!(source, "    uint {}_count;", name).unwrap();
+    writeln!(source, "    {} {}_lights[{}];", definition.name, name, capacity).unwrap();
+    writeln!(source, "}};").unwrap();
+
+    source
+}
+
+/// Generate the HLSL declaration of a structured buffer of lights bound to
+/// the shader resource register `register`. A structured buffer has no
+/// header, so shaders take the light count from `GetDimensions`, and its
+/// contents are the lights of a `std430::pack_std430` buffer after the
+/// header.
+pub fn hlsl_structured_buffer(definition: &StructDefinition, name: &str, register: u32) -> String {
+    let mut source = String::new();
+    writeln!(source, "StructuredBuffer<{}> {} : register(t{});", definition.name, name, register).unwrap();
+
+    source
+}
+
+/// Generate an HLSL source with the attenuation kind constants, the light
+/// struct declarations, and the reference attenuation and spotlight cone
+/// falloff functions, matching the output of [`glsl`]. Buffer declarations
+/// depend on the application's bindings, so they are generated separately
+/// with [`hlsl_cbuffer`] and [`hlsl_structured_buffer`].
+pub fn hlsl() -> String {
+    let mut source = String::from(GENERATED_BANNER);
+    writeln!(source).unwrap();
+    for (name, value) in ATTENUATION_KINDS.iter() {
+        writeln!(source, "static const uint {} = {}u;", name, value).unwrap();
+    }
+    writeln!(source, "static const float MIN_ATTENUATION_DISTANCE = {:?};", MIN_DISTANCE).unwrap();
+    for definition in STRUCTS.iter() {
+        writeln!(source).unwrap();
+        source.push_str(&hlsl_struct(definition));
+    }
+    writeln!(source).unwrap();
+    // The GLSL functions use only scalar types and `vec4`, and the rest of
+    // their syntax is valid HLSL.
+    source.push_str(&GLSL_FUNCTIONS.replace("vec4", "float4"));
+
+    source
+}
+
+/// Generate an HLSL header with the constants of a renderer configuration,
+/// matching the output of [`glsl_config`]. The light array capacities are
+/// the capacities to pass to [`hlsl_cbuffer`].
+pub fn hlsl_config(config: &ShaderConfig) -> String {
+    let mut source = String::from(GENERATED_BANNER);
+    writeln!(source).unwrap();
+    for (name, value) in config.constants().iter() {
+        writeln!(source, "static const uint {} = {}u;", name, value).unwrap();
+    }
+
+    source
+}
@@ -0,0 +1,656 @@
+use cglinalg::{
+    Magnitude,
+    Vector3,
+    Radians,
+    ScalarFloat,
+};
+use crate::{
+    BeamLightModel,
+    DirectionalLightModel,
+    DiskLightModel,
+    IlluminationModel,
+    Light,
+    PointLightModel,
+    SphereLightModel,
+    SpotLightModel,
+    TubeLightModel,
+};
+use crate::any_light::LightModelInfo;
+use crate::pbr::PbrLightModel;
+use crate::polygon::NGonLightModel;
+#[cfg(feature = "spectral")]
+use crate::spectral::SpectralLightModel;
+use crate::stats;
+use crate::stats::Counter;
+
+
+/// The geometry of a light as seen by a shadow caster.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum ShadowLightGeometry<S> {
+    /// A light infinitely far away shining along a fixed direction.
+    Directional {
+        direction: Vector3<S>,
+    },
+    /// A light at a position in world space, such as a point or spot light.
+    Positional {
+        position: Vector3<S>,
+    },
+}
+
+/// A bounding sphere around a shadow caster.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct CasterBounds<S> {
+    pub center: Vector3<S>,
+    pub radius: S,
+}
+
+impl<S> CasterBounds<S> where S: ScalarFloat {
+    /// Construct a new caster bounding sphere.
+    #[inline]
+    pub fn new(center: Vector3<S>, radius: S) -> CasterBounds<S> {
+        CasterBounds {
+            center: center,
+            radius: radius,
+        }
+    }
+}
+
+/// An axis-aligned bounding box around a shadow caster.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Aabb<S> {
+    pub min: Vector3<S>,
+    pub max: Vector3<S>,
+}
+
+impl<S> Aabb<S> where S: ScalarFloat {
+    /// Construct a new bounding box from its minimum and maximum corners.
+    #[inline]
+    pub fn new(min: Vector3<S>, max: Vector3<S>) -> Aabb<S> {
+        Aabb {
+            min: min,
+            max: max,
+        }
+    }
+
+    /// The center of the box.
+    #[inline]
+    pub fn center(&self) -> Vector3<S> {
+        let one_half: S = num_traits::cast(0.5).unwrap();
+
+        (self.min + self.max) * one_half
+    }
+
+    /// The distance from the center of the box to each of its faces.
+    #[inline]
+    pub fn half_extents(&self) -> Vector3<S> {
+        let one_half: S = num_traits::cast(0.5).unwrap();
+
+        (self.max - self.min) * one_half
+    }
+
+    /// The squared distance from a point to the nearest point of the box,
+    /// which is zero for points inside it.
+    pub fn distance_squared(&self, point: &Vector3<S>) -> S {
+        let clamped = Vector3::new(
+            point.x.max(self.min.x).min(self.max.x),
+            point.y.max(self.min.y).min(self.max.y),
+            point.z.max(self.min.z).min(self.max.z),
+        );
+
+        (point - clamped).magnitude_squared()
+    }
+
+    /// The bounding sphere of the box.
+    #[inline]
+    pub fn bounding_sphere(&self) -> CasterBounds<S> {
+        CasterBounds::new(self.center(), self.half_extents().magnitude())
+    }
+}
+
+/// Statistics describing how steeply a caster's surfaces face away from the
+/// light, measured as angles between the surface normals and the direction
+/// towards the light.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct SlopeStatistics<S> {
+    /// The area weighted mean angle of incidence over the caster's surfaces.
+    pub mean_angle: Radians<S>,
+    /// The largest angle of incidence over the caster's lit surfaces.
+    pub max_angle: Radians<S>,
+}
+
+impl<S> SlopeStatistics<S> where S: ScalarFloat {
+    /// Construct new slope statistics.
+    #[inline]
+    pub fn new<A: Into<Radians<S>>>(mean_angle: A, max_angle: A) -> SlopeStatistics<S> {
+        SlopeStatistics {
+            mean_angle: mean_angle.into(),
+            max_angle: max_angle.into(),
+        }
+    }
+}
+
+/// A recommended set of depth bias parameters for a shadow caster, in world
+/// units.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct BiasRecommendation<S> {
+    /// The depth offset applied to every sample.
+    pub constant_bias: S,
+    /// The depth offset per unit of depth slope.
+    pub slope_scaled_bias: S,
+    /// The offset applied along the surface normal before the shadow lookup.
+    pub normal_offset: S,
+}
+
+/// Recommend depth bias parameters for a specific shadow caster.
+///
+/// A shadow map texel covers `texel_world_size` units at the caster. A
+/// surface tilted away from the light by an angle `θ` changes depth by
+/// `texel_world_size * tan(θ)` across a texel, so the bias must cover half of
+/// that to avoid acne without overshooting into peter-panning. For positional
+/// lights the direction towards the light varies over the caster, which
+/// widens the range of incidence angles by the angle the bounding sphere
+/// subtends from the light.
+pub fn recommend_shadow_bias<S>(
+    light: &ShadowLightGeometry<S>,
+    bounds: &CasterBounds<S>,
+    texel_world_size: S,
+    slopes: &SlopeStatistics<S>) -> BiasRecommendation<S>
+    where S: ScalarFloat
+{
+    let one_half: S = num_traits::cast(0.5).unwrap();
+    // Beyond this angle the slope term grows without bound, and grazing
+    // surfaces are better handled by the normal offset.
+    let max_incidence: S = num_traits::cast(85_f64.to_radians()).unwrap();
+    let spread = match *light {
+        ShadowLightGeometry::Directional { .. } => S::zero(),
+        ShadowLightGeometry::Positional { position } => {
+            let distance = (bounds.center - position).magnitude();
+            if distance <= bounds.radius {
+                max_incidence
+            } else {
+                (bounds.radius / distance).asin()
+            }
+        }
+    };
+    let worst_angle = (slopes.max_angle.0 + spread).min(max_incidence);
+    let mean_angle = (slopes.mean_angle.0 + spread).min(max_incidence);
+
+    BiasRecommendation {
+        constant_bias: texel_world_size * one_half,
+        slope_scaled_bias: texel_world_size * one_half * worst_angle.tan(),
+        normal_offset: texel_world_size * mean_angle.sin(),
+    }
+}
+
+/// How a light's shadow maps store depth and are filtered.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum ShadowFilter<S> {
+    /// The shadow map stores depth, compared against each fragment's depth
+    /// (with percentage-closer filtering, if the renderer uses it).
+    Depth,
+    /// A variance shadow map storing the first two moments of depth, which
+    /// can be blurred and mipmapped like an ordinary texture.
+    Variance {
+        /// The smallest variance used in the Chebyshev bound, which hides
+        /// acne on flat surfaces.
+        min_variance: S,
+        /// The fraction of the Chebyshev bound's low end cut off to reduce
+        /// light bleeding where occluders overlap, in the unit interval.
+        light_bleeding_reduction: S,
+    },
+    /// An exponential shadow map storing the exponential of depth.
+    Exponential {
+        /// The exponent depths are scaled by. Larger exponents sharpen
+        /// contact shadows at the risk of overflowing the texture's format.
+        exponent: S,
+    },
+}
+
+impl<S> ShadowFilter<S> where S: ScalarFloat {
+    /// The visibility of a fragment at `depth` from the blurred moments of
+    /// a variance shadow map, by the one-sided Chebyshev inequality.
+    /// Filters other than `Variance` treat the moments as plain depth.
+    pub fn variance_visibility(&self, mean: S, mean_squared: S, depth: S) -> S {
+        if depth <= mean {
+            return S::one();
+        }
+
+        match *self {
+            ShadowFilter::Variance { min_variance, light_bleeding_reduction } => {
+                let variance = (mean_squared - mean * mean).max(min_variance);
+                let difference = depth - mean;
+                let upper_bound = variance / (variance + difference * difference);
+                let reduction = light_bleeding_reduction.max(S::zero()).min(S::one());
+                if reduction >= S::one() {
+                    return S::zero();
+                }
+
+                ((upper_bound - reduction) / (S::one() - reduction)).max(S::zero()).min(S::one())
+            }
+            _ => S::zero(),
+        }
+    }
+
+    /// The visibility of a fragment at `depth` from the blurred value of an
+    /// exponential shadow map, which stores `exp(exponent * occluder_depth)`.
+    /// Filters other than `Exponential` treat the value as plain depth.
+    pub fn exponential_visibility(&self, occluder: S, depth: S) -> S {
+        match *self {
+            ShadowFilter::Exponential { exponent } => {
+                (occluder * (-exponent * depth).exp()).max(S::zero()).min(S::one())
+            }
+            _ => if depth <= occluder { S::one() } else { S::zero() },
+        }
+    }
+}
+
+impl<S> Default for ShadowFilter<S> {
+    #[inline]
+    fn default() -> ShadowFilter<S> {
+        ShadowFilter::Depth
+    }
+}
+
+/// The shadow configuration of a light, carried with the light so renderers
+/// and tools agree on how its shadow maps are rendered and sampled.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct ShadowSettings<S> {
+    /// The depth offset applied to every sample, in world units.
+    pub constant_bias: S,
+    /// The depth offset per unit of depth slope, in world units.
+    pub slope_scaled_bias: S,
+    /// The offset applied along the surface normal before the shadow lookup,
+    /// in world units.
+    pub normal_offset: S,
+    /// The distance from the light to the shadow map's near plane.
+    pub near: S,
+    /// The distance from the light to the shadow map's far plane.
+    pub far: S,
+    /// The shadow map resolution the light prefers, in texels on a side.
+    /// Renderers managing a shared budget of shadow maps may ignore it.
+    pub resolution_hint: Option<u32>,
+    /// How the shadow maps store depth and are filtered.
+    pub filter: ShadowFilter<S>,
+}
+
+impl<S> ShadowSettings<S> where S: ScalarFloat {
+    /// Construct new shadow settings with the given depth range, no bias, no
+    /// resolution hint, and plain depth comparison.
+    #[inline]
+    pub fn new(near: S, far: S) -> ShadowSettings<S> {
+        ShadowSettings {
+            constant_bias: S::zero(),
+            slope_scaled_bias: S::zero(),
+            normal_offset: S::zero(),
+            near: near,
+            far: far,
+            resolution_hint: None,
+            filter: ShadowFilter::Depth,
+        }
+    }
+
+    /// Set the constant and slope-scaled depth bias.
+    #[inline]
+    pub fn with_depth_bias(self, constant_bias: S, slope_scaled_bias: S) -> ShadowSettings<S> {
+        ShadowSettings {
+            constant_bias: constant_bias,
+            slope_scaled_bias: slope_scaled_bias,
+            ..self
+        }
+    }
+
+    /// Set the offset along the surface normal.
+    #[inline]
+    pub fn with_normal_offset(self, normal_offset: S) -> ShadowSettings<S> {
+        ShadowSettings {
+            normal_offset: normal_offset,
+            ..self
+        }
+    }
+
+    /// Take every bias parameter from a recommendation.
+    #[inline]
+    pub fn with_bias_recommendation(self, recommendation: &BiasRecommendation<S>) -> ShadowSettings<S> {
+        ShadowSettings {
+            constant_bias: recommendation.constant_bias,
+            slope_scaled_bias: recommendation.slope_scaled_bias,
+            normal_offset: recommendation.normal_offset,
+            ..self
+        }
+    }
+
+    /// Set the preferred shadow map resolution.
+    #[inline]
+    pub fn with_resolution_hint(self, resolution: u32) -> ShadowSettings<S> {
+        ShadowSettings {
+            resolution_hint: Some(resolution),
+            ..self
+        }
+    }
+
+    /// Set how the shadow maps store depth and are filtered.
+    #[inline]
+    pub fn with_filter(self, filter: ShadowFilter<S>) -> ShadowSettings<S> {
+        ShadowSettings {
+            filter: filter,
+            ..self
+        }
+    }
+
+    /// The depth bias for a surface whose normal makes an angle with cosine
+    /// `n_dot_l` with the direction towards the light. The slope term grows
+    /// with the tangent of the angle, which is capped so grazing surfaces
+    /// do not receive an unbounded bias.
+    pub fn depth_bias(&self, n_dot_l: S) -> S {
+        let max_slope: S = num_traits::cast(10).unwrap();
+        let cos_angle = n_dot_l.max(S::zero()).min(S::one());
+        let sin_angle = (S::one() - cos_angle * cos_angle).sqrt();
+        let slope = if sin_angle < cos_angle * max_slope {
+            sin_angle / cos_angle
+        } else {
+            max_slope
+        };
+
+        self.constant_bias + self.slope_scaled_bias * slope
+    }
+}
+
+/// The region of space in which a light can cast shadows.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum ShadowVolume<S> {
+    /// A light shining in every direction, bounded by its influence radius.
+    Sphere {
+        center: Vector3<S>,
+        radius: S,
+    },
+    /// A light shining into a cone around its axis, bounded by its influence
+    /// radius.
+    Cone {
+        apex: Vector3<S>,
+        /// The unit direction of the cone's axis.
+        axis: Vector3<S>,
+        /// The cosine of the angle between the cone's axis and its edge.
+        cos_half_angle: S,
+        range: S,
+    },
+}
+
+impl<S> ShadowVolume<S> where S: ScalarFloat {
+    /// Determine whether a caster's bounding sphere intersects the volume.
+    /// The test is conservative: it may report intersections for casters
+    /// just outside the volume, but never misses a caster inside it.
+    pub fn intersects(&self, bounds: &CasterBounds<S>) -> bool {
+        stats::record(Counter::CasterTests, 1);
+        match *self {
+            ShadowVolume::Sphere { center, radius } => {
+                let reach = radius + bounds.radius;
+
+                (bounds.center - center).magnitude_squared() <= reach * reach
+            }
+            ShadowVolume::Cone { apex, axis, cos_half_angle, range } => {
+                let to_center = bounds.center - apex;
+                let distance_squared = to_center.magnitude_squared();
+                let reach = range + bounds.radius;
+                if distance_squared > reach * reach {
+                    return false;
+                }
+
+                let axial_distance = to_center.dot(&axis);
+                if axial_distance < -bounds.radius {
+                    return false;
+                }
+
+                // The signed distance from the sphere's center to the cone's surface.
+                let sin_half_angle = (S::one() - cos_half_angle * cos_half_angle).max(S::zero()).sqrt();
+                let radial_distance = (distance_squared - axial_distance * axial_distance).max(S::zero()).sqrt();
+                let cone_distance = cos_half_angle * radial_distance - sin_half_angle * axial_distance;
+
+                cone_distance <= bounds.radius
+            }
+        }
+    }
+
+    /// Determine whether a caster's bounding box intersects the volume. The
+    /// test is exact against a spherical volume and tests a cone against the
+    /// box's bounding sphere, so it is conservative in the same way as
+    /// [`intersects`](#method.intersects).
+    pub fn intersects_aabb(&self, aabb: &Aabb<S>) -> bool {
+        match *self {
+            ShadowVolume::Sphere { center, radius } => {
+                stats::record(Counter::CasterTests, 1);
+
+                aabb.distance_squared(&center) <= radius * radius
+            }
+            ShadowVolume::Cone { .. } => self.intersects(&aabb.bounding_sphere()),
+        }
+    }
+}
+
+/// A lighting model whose shadows may be restricted to a cone.
+pub trait ShadowCone<S> {
+    /// The cosine of the angle between the light's axis and the edge of the
+    /// cone it casts shadows into, or `None` if it casts shadows in every
+    /// direction.
+    fn shadow_cone(&self) -> Option<S>;
+}
+
+impl<S> ShadowCone<S> for PointLightModel<S> where S: ScalarFloat {
+    #[inline]
+    fn shadow_cone(&self) -> Option<S> {
+        None
+    }
+}
+
+impl<S> ShadowCone<S> for DirectionalLightModel<S> where S: ScalarFloat {
+    #[inline]
+    fn shadow_cone(&self) -> Option<S> {
+        None
+    }
+}
+
+impl<S> ShadowCone<S> for DiskLightModel<S> where S: ScalarFloat {
+    #[inline]
+    fn shadow_cone(&self) -> Option<S> {
+        // The disk only emits forwards, but its rim extends sideways from
+        // its center, so a cone with its apex at the center would miss
+        // casters beside the rim.
+        None
+    }
+}
+
+impl<S> ShadowCone<S> for SphereLightModel<S> where S: ScalarFloat {
+    #[inline]
+    fn shadow_cone(&self) -> Option<S> {
+        None
+    }
+}
+
+impl<S> ShadowCone<S> for TubeLightModel<S> where S: ScalarFloat {
+    #[inline]
+    fn shadow_cone(&self) -> Option<S> {
+        None
+    }
+}
+
+impl<S> ShadowCone<S> for NGonLightModel<S> where S: ScalarFloat {
+    #[inline]
+    fn shadow_cone(&self) -> Option<S> {
+        None
+    }
+}
+
+impl<S> ShadowCone<S> for BeamLightModel<S> where S: ScalarFloat {
+    /// The beam only lights the half space in front of the light, which
+    /// contains its cylinder.
+    #[inline]
+    fn shadow_cone(&self) -> Option<S> {
+        Some(S::zero())
+    }
+}
+
+impl<S> ShadowCone<S> for PbrLightModel<S> where S: ScalarFloat {
+    #[inline]
+    fn shadow_cone(&self) -> Option<S> {
+        None
+    }
+}
+
+#[cfg(feature = "spectral")]
+impl<S> ShadowCone<S> for SpectralLightModel<S> where S: ScalarFloat {
+    #[inline]
+    fn shadow_cone(&self) -> Option<S> {
+        None
+    }
+}
+
+impl<S> ShadowCone<S> for SpotLightModel<S> where S: ScalarFloat {
+    #[inline]
+    fn shadow_cone(&self) -> Option<S> {
+        Some(self.outer_cutoff)
+    }
+}
+
+impl<S, M> Light<S, M>
+    where S: ScalarFloat,
+          M: IlluminationModel + LightModelInfo<S> + ShadowCone<S>,
+{
+    /// The region in which the light casts shadows, bounded by the distance
+    /// at which its attenuation falls below `threshold`.
+    pub fn shadow_volume(&self, threshold: S) -> ShadowVolume<S> {
+        let range = self.model().influence_radius(threshold);
+        match self.model().shadow_cone() {
+            Some(cos_half_angle) => ShadowVolume::Cone {
+                apex: self.position(),
+                axis: self.forward_axis(),
+                cos_half_angle: cos_half_angle,
+                range: range,
+            },
+            None => ShadowVolume::Sphere {
+                center: self.position(),
+                radius: range,
+            },
+        }
+    }
+
+    /// Determine whether the light reaches a sphere with its attenuation
+    /// above `threshold`, for building per-light caster lists. Point lights
+    /// test against their range sphere, spotlights against their cone, and
+    /// lights without distance attenuation, such as directional lights,
+    /// affect everything.
+    #[inline]
+    pub fn affects_sphere(&self, center: &Vector3<S>, radius: S, threshold: S) -> bool {
+        self.shadow_volume(threshold).intersects(&CasterBounds::new(*center, radius))
+    }
+
+    /// Determine whether the light reaches a box with its attenuation above
+    /// `threshold`, as for [`affects_sphere`](#method.affects_sphere).
+    #[inline]
+    pub fn affects_aabb(&self, aabb: &Aabb<S>, threshold: S) -> bool {
+        self.shadow_volume(threshold).intersects_aabb(aabb)
+    }
+}
+
+impl<S, M> Light<S, M>
+    where S: ScalarFloat,
+          M: IlluminationModel,
+{
+    /// The light's shadow settings, or `None` if the light casts no shadows.
+    #[inline]
+    pub fn shadow_settings(&self) -> Option<&ShadowSettings<S>> {
+        self.shadow_settings.as_ref()
+    }
+
+    /// Set the light's shadow settings. Setting `None` marks the light as
+    /// casting no shadows.
+    #[inline]
+    pub fn set_shadow_settings(&mut self, settings: Option<ShadowSettings<S>>) {
+        self.shadow_settings = settings;
+    }
+
+    /// Determine whether the light casts shadows.
+    #[inline]
+    pub fn casts_shadows(&self) -> bool {
+        self.shadow_settings.is_some()
+    }
+}
+
+/// The lists of shadow casters inside each light's shadow volume.
+///
+/// The lists are cached between updates. Each update compares the lights'
+/// volumes and the casters' bounds with the previous update's, rebuilds the
+/// lists of lights whose volumes changed, and patches the remaining lists
+/// for the casters that moved, so mostly static scenes are cheap to update.
+#[derive(Clone, Debug, Default)]
+pub struct ShadowCasterLists<S> {
+    volumes: Vec<ShadowVolume<S>>,
+    bounds: Vec<CasterBounds<S>>,
+    lists: Vec<Vec<usize>>,
+}
+
+impl<S> ShadowCasterLists<S> where S: ScalarFloat {
+    /// Construct an empty set of caster lists.
+    #[inline]
+    pub fn new() -> ShadowCasterLists<S> {
+        ShadowCasterLists {
+            volumes: Vec::new(),
+            bounds: Vec::new(),
+            lists: Vec::new(),
+        }
+    }
+
+    /// Update the caster lists for the current shadow volumes of the lights
+    /// and bounds of the casters. Lights and casters are identified by their
+    /// indices, which should stay stable between updates.
+    pub fn update(&mut self, volumes: &[ShadowVolume<S>], bounds: &[CasterBounds<S>]) {
+        // Removing lights or casters renumbers them, so start over.
+        if volumes.len() < self.volumes.len() || bounds.len() < self.bounds.len() {
+            self.clear();
+        }
+
+        let changed_casters: Vec<usize> = (0..bounds.len())
+            .filter(|&j| self.bounds.get(j) != Some(&bounds[j]))
+            .collect();
+        self.lists.resize_with(volumes.len(), Vec::new);
+        for (i, volume) in volumes.iter().enumerate() {
+            let list = &mut self.lists[i];
+            if self.volumes.get(i) != Some(volume) {
+                list.clear();
+                list.extend((0..bounds.len()).filter(|&j| volume.intersects(&bounds[j])));
+            } else {
+                for &j in changed_casters.iter() {
+                    match (list.binary_search(&j), volume.intersects(&bounds[j])) {
+                        (Ok(position), false) => {
+                            list.remove(position);
+                        }
+                        (Err(position), true) => {
+                            list.insert(position, j);
+                        }
+                        _ => {}
+                    }
+                }
+            }
+        }
+
+        self.volumes.clear();
+        self.volumes.extend_from_slice(volumes);
+        self.bounds.clear();
+        self.bounds.extend_from_slice(bounds);
+    }
+
+    /// The indices of the casters inside a light's shadow volume, in
+    /// increasing order.
+    #[inline]
+    pub fn casters(&self, light: usize) -> &[usize] {
+        self.lists.get(light).map_or(&[], |list| &list[..])
+    }
+
+    /// Forget all cached lists, forcing the next update to rebuild them.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.volumes.clear();
+        self.bounds.clear();
+        self.lists.clear();
+    }
+}
@@ -0,0 +1,245 @@
+use cglinalg::{
+    Matrix4x4,
+    ScalarFloat,
+};
+
+
+/// The largest power of two not greater than `value`, or zero for zero.
+#[inline]
+fn floor_power_of_two(value: u32) -> u32 {
+    if value == 0 {
+        0
+    } else {
+        1 << (31 - value.leading_zeros())
+    }
+}
+
+/// The position of the `index`-th block along a Z-order (Morton) curve,
+/// in blocks.
+fn morton_decode(index: u32) -> (u32, u32) {
+    let compact = |mut bits: u32| {
+        bits &= 0x5555_5555;
+        bits = (bits | (bits >> 1)) & 0x3333_3333;
+        bits = (bits | (bits >> 2)) & 0x0f0f_0f0f;
+        bits = (bits | (bits >> 4)) & 0x00ff_00ff;
+        bits = (bits | (bits >> 8)) & 0x0000_ffff;
+
+        bits
+    };
+
+    (compact(index), compact(index >> 1))
+}
+
+/// A light's request for space in a shadow atlas.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct ShadowAtlasRequest<S> {
+    /// The index of the light in the caller's light list.
+    pub light: usize,
+    /// The light's claim on the atlas, usually the fraction of the screen
+    /// its influence covers, in the unit interval. A light with priority
+    /// `p` asks for a tile whose side is `sqrt(p)` times the atlas's.
+    pub priority: S,
+    /// The largest tile the light wants, in texels on a side, e.g. from its
+    /// shadow settings' resolution hint.
+    pub max_size: Option<u32>,
+}
+
+impl<S> ShadowAtlasRequest<S> where S: ScalarFloat {
+    /// Construct a new request with no size limit.
+    #[inline]
+    pub fn new(light: usize, priority: S) -> ShadowAtlasRequest<S> {
+        ShadowAtlasRequest {
+            light: light,
+            priority: priority,
+            max_size: None,
+        }
+    }
+
+    /// Limit the size of the light's tile.
+    #[inline]
+    pub fn with_max_size(self, max_size: u32) -> ShadowAtlasRequest<S> {
+        ShadowAtlasRequest {
+            max_size: Some(max_size),
+            ..self
+        }
+    }
+}
+
+/// A square region of a shadow atlas, in texels, with its origin at the
+/// **bottom left** corner of the atlas.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct AtlasViewport {
+    pub x: u32,
+    pub y: u32,
+    pub size: u32,
+}
+
+/// The tile of a shadow atlas allocated to a light.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct ShadowAtlasTile<S> {
+    /// The index of the light in the caller's light list.
+    pub light: usize,
+    /// The viewport to render the light's shadow map into.
+    pub viewport: AtlasViewport,
+    /// The matrix mapping the light's shadow clip space to atlas texture
+    /// coordinates inside the tile, and depths to the unit interval. Shaders
+    /// multiply it onto the light's shadow view projection matrix.
+    pub scale_bias: Matrix4x4<S>,
+}
+
+/// A square shadow map atlas shared by many lights.
+///
+/// Each allocation hands out square tiles whose sizes are powers of two,
+/// largest first, along a Z-order curve. Tiles of decreasing size laid out
+/// this way pack the atlas without gaps, so a set of requests fits whenever
+/// its total area does. When the atlas runs out of space, lights of lower
+/// priority receive smaller tiles, down to the minimum tile size, and then
+/// no tile at all.
+#[derive(Clone, Debug)]
+pub struct ShadowAtlas<S> {
+    size: u32,
+    min_tile_size: u32,
+    max_tile_size: u32,
+    tiles: Vec<ShadowAtlasTile<S>>,
+}
+
+impl<S> ShadowAtlas<S> where S: ScalarFloat {
+    /// Construct a new empty atlas with `size` texels on a side. The atlas
+    /// size and tile sizes are rounded down to powers of two, and the
+    /// tiles are at most half the atlas's size so at least four lights
+    /// always fit.
+    pub fn new(size: u32, min_tile_size: u32) -> ShadowAtlas<S> {
+        let size = floor_power_of_two(size.max(1));
+        let max_tile_size = (size / 2).max(1);
+        let min_tile_size = floor_power_of_two(min_tile_size.max(1)).min(max_tile_size);
+
+        ShadowAtlas {
+            size: size,
+            min_tile_size: min_tile_size,
+            max_tile_size: max_tile_size,
+            tiles: Vec::new(),
+        }
+    }
+
+    /// Limit the largest tile handed out.
+    #[inline]
+    pub fn with_max_tile_size(self, max_tile_size: u32) -> ShadowAtlas<S> {
+        let max_tile_size = floor_power_of_two(max_tile_size.max(1))
+            .max(self.min_tile_size)
+            .min(self.size);
+
+        ShadowAtlas {
+            max_tile_size: max_tile_size,
+            ..self
+        }
+    }
+
+    /// The number of texels on a side of the atlas.
+    #[inline]
+    pub fn size(&self) -> u32 {
+        self.size
+    }
+
+    /// The smallest tile handed out.
+    #[inline]
+    pub fn min_tile_size(&self) -> u32 {
+        self.min_tile_size
+    }
+
+    /// The largest tile handed out.
+    #[inline]
+    pub fn max_tile_size(&self) -> u32 {
+        self.max_tile_size
+    }
+
+    /// The tile size a request asks for before the atlas runs out of space.
+    fn requested_size(&self, request: &ShadowAtlasRequest<S>) -> u32 {
+        let atlas_size: S = num_traits::cast(self.size).unwrap();
+        let priority = request.priority.max(S::zero()).min(S::one());
+        let size = num_traits::cast::<S, u32>((atlas_size * priority.sqrt()).floor()).unwrap_or(0);
+        let max_size = request.max_size.map_or(self.max_tile_size, |max_size| {
+            floor_power_of_two(max_size).min(self.max_tile_size)
+        });
+
+        floor_power_of_two(size).max(self.min_tile_size).min(max_size.max(self.min_tile_size))
+    }
+
+    /// The matrix mapping shadow clip space into a viewport of the atlas.
+    fn scale_bias(&self, viewport: &AtlasViewport) -> Matrix4x4<S> {
+        let zero = S::zero();
+        let one = S::one();
+        let one_half: S = num_traits::cast(0.5).unwrap();
+        let atlas_size: S = num_traits::cast(self.size).unwrap();
+        let size: S = num_traits::cast(viewport.size).unwrap();
+        let x: S = num_traits::cast(viewport.x).unwrap();
+        let y: S = num_traits::cast(viewport.y).unwrap();
+        let scale = one_half * size / atlas_size;
+        let bias_x = (x + one_half * size) / atlas_size;
+        let bias_y = (y + one_half * size) / atlas_size;
+
+        Matrix4x4::new(
+            scale,  zero,   zero,     zero,
+            zero,   scale,  zero,     zero,
+            zero,   zero,   one_half, zero,
+            bias_x, bias_y, one_half, one,
+        )
+    }
+
+    /// Distribute the atlas among a set of lights, replacing the previous
+    /// allocation. Lights with higher priority receive larger tiles. Lights
+    /// that do not fit receive no tile.
+    pub fn allocate(&mut self, requests: &[ShadowAtlasRequest<S>]) -> &[ShadowAtlasTile<S>] {
+        let mut order: Vec<(u32, &ShadowAtlasRequest<S>)> = requests.iter()
+            .map(|request| (self.requested_size(request), request))
+            .collect();
+        order.sort_by(|a, b| {
+            b.0.cmp(&a.0).then_with(|| {
+                b.1.priority.partial_cmp(&a.1.priority).unwrap_or(core::cmp::Ordering::Equal)
+            })
+        });
+
+        let capacity = u64::from(self.size) * u64::from(self.size);
+        let mut used = 0_u64;
+        // Tiles never grow along the curve, which keeps every tile aligned
+        // to a multiple of its size.
+        let mut cap = self.max_tile_size;
+        self.tiles.clear();
+        for (requested_size, request) in order {
+            let mut size = requested_size.min(cap);
+            while size > self.min_tile_size && used + u64::from(size) * u64::from(size) > capacity {
+                size /= 2;
+            }
+            let area = u64::from(size) * u64::from(size);
+            if used + area > capacity {
+                break;
+            }
+
+            let (x, y) = morton_decode((used / area) as u32);
+            let viewport = AtlasViewport {
+                x: x * size,
+                y: y * size,
+                size: size,
+            };
+            self.tiles.push(ShadowAtlasTile {
+                light: request.light,
+                viewport: viewport,
+                scale_bias: self.scale_bias(&viewport),
+            });
+            used += area;
+            cap = size;
+        }
+
+        &self.tiles
+    }
+
+    /// The tiles of the current allocation, in the order they were placed.
+    #[inline]
+    pub fn tiles(&self) -> &[ShadowAtlasTile<S>] {
+        &self.tiles
+    }
+
+    /// The tile allocated to a light, if any.
+    pub fn tile(&self, light: usize) -> Option<&ShadowAtlasTile<S>> {
+        self.tiles.iter().find(|tile| tile.light == light)
+    }
+}
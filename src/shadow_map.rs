@@ -0,0 +1,384 @@
+use cglinalg::{
+    Magnitude,
+    Vector3,
+    Matrix4x4,
+    ScalarFloat,
+};
+use crate::{
+    DirectionalLight,
+    PointLight,
+    SpotLight,
+};
+use crate::environment::CubeFace;
+
+
+/// The view matrix of a camera at `position` looking along the unit vector
+/// `forward` with the unit vector `up` pointing up, mapping world space to a
+/// right-handed view space looking down the **negative z-axis**.
+fn look_along<S: ScalarFloat>(position: &Vector3<S>, forward: &Vector3<S>, up: &Vector3<S>) -> Matrix4x4<S> {
+    let zero = S::zero();
+    let right = forward.cross(up);
+    let back = -forward;
+
+    Matrix4x4::new(
+        right.x,              up.x,              back.x,              zero,
+        right.y,              up.y,              back.y,              zero,
+        right.z,              up.z,              back.z,              zero,
+        -right.dot(position), -up.dot(position), -back.dot(position), S::one(),
+    )
+}
+
+impl CubeFace {
+    /// The unit direction through the center of the face, and the unit up
+    /// direction of the face's image, following the OpenGL cube map
+    /// conventions.
+    pub fn basis<S: ScalarFloat>(self) -> (Vector3<S>, Vector3<S>) {
+        let zero = S::zero();
+        let one = S::one();
+        match self {
+            CubeFace::PositiveX => (Vector3::new(one, zero, zero), Vector3::new(zero, -one, zero)),
+            CubeFace::NegativeX => (Vector3::new(-one, zero, zero), Vector3::new(zero, -one, zero)),
+            CubeFace::PositiveY => (Vector3::new(zero, one, zero), Vector3::new(zero, zero, one)),
+            CubeFace::NegativeY => (Vector3::new(zero, -one, zero), Vector3::new(zero, zero, -one)),
+            CubeFace::PositiveZ => (Vector3::new(zero, zero, one), Vector3::new(zero, -one, zero)),
+            CubeFace::NegativeZ => (Vector3::new(zero, zero, -one), Vector3::new(zero, -one, zero)),
+        }
+    }
+}
+
+impl<S> PointLight<S> where S: ScalarFloat {
+    /// The view matrix for rendering one face of an omnidirectional shadow
+    /// cube map from the light's position. The faces are aligned with the
+    /// world axes regardless of the light's orientation.
+    #[inline]
+    pub fn cube_face_view_matrix(&self, face: CubeFace) -> Matrix4x4<S> {
+        let (forward, up) = face.basis();
+
+        look_along(&self.position(), &forward, &up)
+    }
+
+    /// The view matrices of the six faces of an omnidirectional shadow cube
+    /// map, in the order of `CubeFace::ALL`.
+    pub fn cube_face_view_matrices(&self) -> [Matrix4x4<S>; 6] {
+        let mut matrices = [Matrix4x4::zero(); 6];
+        for (matrix, face) in matrices.iter_mut().zip(CubeFace::ALL.iter()) {
+            *matrix = self.cube_face_view_matrix(*face);
+        }
+
+        matrices
+    }
+
+    /// The projection shared by the six faces of an omnidirectional shadow
+    /// cube map: an OpenGL perspective projection with a 90 degree field of
+    /// view and a square aspect ratio, mapping depths between `near` and
+    /// `far` to the unit depth range.
+    pub fn cube_face_projection(near: S, far: S) -> Matrix4x4<S> {
+        let zero = S::zero();
+        let one = S::one();
+        let two = one + one;
+        let depth = near - far;
+
+        Matrix4x4::new(
+            one,  zero, zero,                       zero,
+            zero, one,  zero,                       zero,
+            zero, zero, (far + near) / depth,       -one,
+            zero, zero, two * far * near / depth,   zero,
+        )
+    }
+}
+
+/// The extents of an orthographic view volume in view space. The view looks
+/// down the **negative z-axis**, so `near` and `far` are distances along the
+/// view direction.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct OrthographicBounds<S> {
+    pub left: S,
+    pub right: S,
+    pub bottom: S,
+    pub top: S,
+    pub near: S,
+    pub far: S,
+}
+
+impl<S> OrthographicBounds<S> where S: ScalarFloat {
+    /// The width of the view volume.
+    #[inline]
+    pub fn width(&self) -> S {
+        self.right - self.left
+    }
+
+    /// The height of the view volume.
+    #[inline]
+    pub fn height(&self) -> S {
+        self.top - self.bottom
+    }
+
+    /// The OpenGL orthographic projection of the view volume, mapping it to
+    /// the cube from `-1` to `1` on each axis.
+    pub fn projection(&self) -> Matrix4x4<S> {
+        let zero = S::zero();
+        let one = S::one();
+        let two = one + one;
+        let width = self.right - self.left;
+        let height = self.top - self.bottom;
+        let depth = self.far - self.near;
+
+        Matrix4x4::new(
+            two / width,                          zero,                                 zero,                              zero,
+            zero,                                 two / height,                         zero,                              zero,
+            zero,                                 zero,                                 -two / depth,                      zero,
+            -(self.right + self.left) / width,    -(self.top + self.bottom) / height,   -(self.far + self.near) / depth,   one,
+        )
+    }
+}
+
+/// An orthographic shadow map projection for a directional light.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct OrthographicShadow<S> {
+    /// The light-space view matrix. Its origin is the world origin and its
+    /// axes are the light's right, up, and backward axes.
+    pub view: Matrix4x4<S>,
+    /// The extents of the shadow map's view volume in light space.
+    pub bounds: OrthographicBounds<S>,
+}
+
+impl<S> OrthographicShadow<S> where S: ScalarFloat {
+    /// The orthographic projection of the shadow map.
+    #[inline]
+    pub fn projection(&self) -> Matrix4x4<S> {
+        self.bounds.projection()
+    }
+
+    /// The matrix mapping world space to the shadow map's clip space.
+    #[inline]
+    pub fn view_projection(&self) -> Matrix4x4<S> {
+        self.projection() * self.view
+    }
+
+    /// Snap the view volume's origin to the texel grid of a shadow map with
+    /// `resolution` texels on a side, keeping its size.
+    ///
+    /// A view volume that follows the camera by fractions of a texel
+    /// rasterizes the casters at shifting offsets, which makes shadow edges
+    /// shimmer as the camera moves. Snapping moves the volume in whole texels
+    /// only, so a static caster always lands on the same texels.
+    pub fn snapped_to_texels(&self, resolution: usize) -> OrthographicShadow<S> {
+        let resolution: S = num_traits::cast(resolution.max(1)).unwrap();
+        let width = self.bounds.width();
+        let height = self.bounds.height();
+        let texel_width = width / resolution;
+        let texel_height = height / resolution;
+        let left = (self.bounds.left / texel_width).floor() * texel_width;
+        let bottom = (self.bounds.bottom / texel_height).floor() * texel_height;
+
+        OrthographicShadow {
+            view: self.view,
+            bounds: OrthographicBounds {
+                left: left,
+                right: left + width,
+                bottom: bottom,
+                top: bottom + height,
+                ..self.bounds
+            },
+        }
+    }
+}
+
+impl<S> DirectionalLight<S> where S: ScalarFloat {
+    /// The light-space coordinates of a point in world space, along the
+    /// light's right, up, and backward axes from the world origin.
+    #[inline]
+    fn to_light_space(&self, point: &Vector3<S>) -> Vector3<S> {
+        Vector3::new(
+            self.right_axis().dot(point),
+            self.up_axis().dot(point),
+            -self.forward_axis().dot(point),
+        )
+    }
+
+    /// The light-space view matrix for the light's shadow maps.
+    #[inline]
+    pub fn shadow_view_matrix(&self) -> Matrix4x4<S> {
+        look_along(&Vector3::zero(), &self.forward_axis(), &self.up_axis())
+    }
+
+    /// Fit an orthographic shadow map projection tightly around a region of
+    /// space, usually the eight corners of a camera's view frustum.
+    ///
+    /// The view volume is widened by `padding` on each side, so filtering
+    /// kernels near its edge stay inside the shadow map. Its near plane is
+    /// pulled `caster_extension` towards the light, so casters between the
+    /// light and the region, outside the region itself, still cast shadows
+    /// into it.
+    pub fn fit_orthographic_shadow(
+        &self,
+        corners: &[Vector3<S>; 8],
+        padding: S,
+        caster_extension: S) -> OrthographicShadow<S>
+    {
+        let first = self.to_light_space(&corners[0]);
+        let (min, max) = corners[1..].iter().fold((first, first), |(min, max), corner| {
+            let point = self.to_light_space(corner);
+            let min = Vector3::new(min.x.min(point.x), min.y.min(point.y), min.z.min(point.z));
+            let max = Vector3::new(max.x.max(point.x), max.y.max(point.y), max.z.max(point.z));
+
+            (min, max)
+        });
+
+        // The view looks down the negative z-axis, so the largest z is
+        // nearest to the light.
+        let bounds = OrthographicBounds {
+            left: min.x - padding,
+            right: max.x + padding,
+            bottom: min.y - padding,
+            top: max.y + padding,
+            near: -max.z - caster_extension,
+            far: -min.z,
+        };
+
+        OrthographicShadow {
+            view: self.shadow_view_matrix(),
+            bounds: bounds,
+        }
+    }
+
+    /// Fit an orthographic shadow map projection around a region of space
+    /// whose size does not depend on the light's or the camera's orientation.
+    ///
+    /// The view volume is a square around the bounding sphere of the corners,
+    /// so its size only changes when the region's shape does. Together with
+    /// [`OrthographicShadow::snapped_to_texels`] this keeps cascades from
+    /// shimmering, at the cost of a looser fit than
+    /// [`fit_orthographic_shadow`](#method.fit_orthographic_shadow).
+    pub fn fit_stable_orthographic_shadow(
+        &self,
+        corners: &[Vector3<S>; 8],
+        padding: S,
+        caster_extension: S) -> OrthographicShadow<S>
+    {
+        let eight: S = num_traits::cast(8).unwrap();
+        let sixteen: S = num_traits::cast(16).unwrap();
+        let center = corners.iter().fold(Vector3::zero(), |sum, corner| sum + corner) / eight;
+        let radius = corners.iter().fold(S::zero(), |radius, corner| {
+            radius.max((corner - center).magnitude())
+        });
+        // Round the radius up to remove floating point noise, which would
+        // otherwise resize the volume slightly from frame to frame.
+        let radius = (radius * sixteen).ceil() / sixteen + padding;
+        let center = self.to_light_space(&center);
+
+        let bounds = OrthographicBounds {
+            left: center.x - radius,
+            right: center.x + radius,
+            bottom: center.y - radius,
+            top: center.y + radius,
+            near: -center.z - radius - caster_extension,
+            far: -center.z + radius,
+        };
+
+        OrthographicShadow {
+            view: self.shadow_view_matrix(),
+            bounds: bounds,
+        }
+    }
+}
+
+/// A plane in world space. Points on the side the normal points to have
+/// positive signed distances.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Plane<S> {
+    /// The unit normal of the plane.
+    pub normal: Vector3<S>,
+    /// The signed distance from the plane to the origin along the normal.
+    pub distance: S,
+}
+
+impl<S> Plane<S> where S: ScalarFloat {
+    /// Construct the plane through a point with a unit normal.
+    #[inline]
+    pub fn from_point_normal(point: &Vector3<S>, normal: &Vector3<S>) -> Plane<S> {
+        Plane {
+            normal: *normal,
+            distance: -normal.dot(point),
+        }
+    }
+
+    /// The signed distance from the plane to a point.
+    #[inline]
+    pub fn signed_distance(&self, point: &Vector3<S>) -> S {
+        self.normal.dot(point) + self.distance
+    }
+}
+
+/// The bounding frustum of a spotlight's cone, for culling the shadow
+/// casters rendered into its shadow map.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct SpotFrustum<S> {
+    /// The near, far, left, right, bottom, and top planes, with normals
+    /// pointing into the frustum.
+    pub planes: [Plane<S>; 6],
+    /// The four corners of the near plane followed by the four corners of
+    /// the far plane, each in the order bottom left, bottom right, top
+    /// right, top left as seen from the light.
+    pub corners: [Vector3<S>; 8],
+}
+
+impl<S> SpotFrustum<S> where S: ScalarFloat {
+    /// Determine whether a point lies inside the frustum.
+    pub fn contains_point(&self, point: &Vector3<S>) -> bool {
+        self.planes.iter().all(|plane| plane.signed_distance(point) >= S::zero())
+    }
+
+    /// Determine whether a sphere may intersect the frustum. The test is
+    /// conservative: some spheres near the frustum's edges and corners pass
+    /// without touching it.
+    pub fn intersects_sphere(&self, center: &Vector3<S>, radius: S) -> bool {
+        self.planes.iter().all(|plane| plane.signed_distance(center) >= -radius)
+    }
+}
+
+impl<S> SpotLight<S> where S: ScalarFloat {
+    /// The square frustum circumscribing the spotlight's outer cone between
+    /// the distances `near` and `far` from the light, in world space. It
+    /// matches the frustum of the light's cookie projection, so the same
+    /// frustum serves for culling the casters of a spot shadow map rendered
+    /// with it. The outer cone must be narrower than a hemisphere.
+    pub fn frustum(&self, near: S, far: S) -> SpotFrustum<S> {
+        let one = S::one();
+        let half_angle = self.model().outer_cutoff.max(-one).min(one).acos();
+        let (sin_half_angle, cos_half_angle) = half_angle.sin_cos();
+        let tan_half_angle = sin_half_angle / cos_half_angle;
+        let apex = self.position();
+        let forward = self.forward_axis();
+        let right = self.right_axis();
+        let up = self.up_axis();
+
+        let side = |axis: Vector3<S>| {
+            Plane::from_point_normal(&apex, &(forward * sin_half_angle - axis * cos_half_angle))
+        };
+        let planes = [
+            Plane::from_point_normal(&(apex + forward * near), &forward),
+            Plane::from_point_normal(&(apex + forward * far), &(-forward)),
+            side(-right),
+            side(right),
+            side(-up),
+            side(up),
+        ];
+
+        let mut corners = [Vector3::zero(); 8];
+        for (i, distance) in [near, far].iter().enumerate() {
+            let center = apex + forward * *distance;
+            let half_width = right * (*distance * tan_half_angle);
+            let half_height = up * (*distance * tan_half_angle);
+            corners[4 * i] = center - half_width - half_height;
+            corners[4 * i + 1] = center + half_width - half_height;
+            corners[4 * i + 2] = center + half_width + half_height;
+            corners[4 * i + 3] = center - half_width + half_height;
+        }
+
+        SpotFrustum {
+            planes: planes,
+            corners: corners,
+        }
+    }
+}
@@ -0,0 +1,298 @@
+use cglinalg::{
+    Magnitude,
+    Vector3,
+    Radians,
+    ScalarFloat,
+};
+use crate::{
+    DirectionalLight,
+    DirectionalLightModelSpec,
+    LightAttitudeSpec,
+};
+use crate::color::{
+    luminance,
+    Chromaticity,
+    ColorSpace,
+};
+use crate::environment::EnvironmentSource;
+
+
+/// The illuminance of sunlight at the top of the atmosphere in lux.
+const EXTRATERRESTRIAL_ILLUMINANCE: f64 = 128_000.0;
+
+/// The wavelengths in micrometers the sun's transmittance is evaluated at for
+/// the red, green, and blue channels.
+const WAVELENGTHS: [f64; 3] = [0.65, 0.57, 0.475];
+
+/// The coefficients of the Perez sky distribution function as linear
+/// functions `a * turbidity + b` of the turbidity, for the luminance and the
+/// two chromaticity coordinates.
+const PEREZ_LUMINANCE: [[f64; 2]; 5] = [
+    [ 0.1787, -1.4630],
+    [-0.3554,  0.4275],
+    [-0.0227,  5.3251],
+    [ 0.1206, -2.5771],
+    [-0.0670,  0.3703],
+];
+const PEREZ_X: [[f64; 2]; 5] = [
+    [-0.0193, -0.2592],
+    [-0.0665,  0.0008],
+    [-0.0004,  0.2125],
+    [-0.0641, -0.8989],
+    [-0.0033,  0.0452],
+];
+const PEREZ_Y: [[f64; 2]; 5] = [
+    [-0.0167, -0.2608],
+    [-0.0950,  0.0092],
+    [-0.0079,  0.2102],
+    [-0.0441, -1.6537],
+    [-0.0109,  0.0529],
+];
+
+/// The zenith chromaticity coordinates as polynomials in the turbidity and
+/// the sun's zenith angle.
+const ZENITH_X: [[f64; 4]; 3] = [
+    [ 0.00166, -0.00375,  0.00209, 0.0],
+    [-0.02903,  0.06377, -0.03202, 0.00394],
+    [ 0.11693, -0.21196,  0.06052, 0.25886],
+];
+const ZENITH_Y: [[f64; 4]; 3] = [
+    [ 0.00275, -0.00610,  0.00317, 0.0],
+    [-0.04214,  0.08970, -0.04153, 0.00516],
+    [ 0.15346, -0.26756,  0.06670, 0.26688],
+];
+
+
+/// A physically based daylight model combining the sun, as a directional
+/// light, with the radiance of a clear sky.
+///
+/// The sky follows the analytic model of Preetham, Shirley, and Smits, *A
+/// Practical Analytic Model for Daylight*, SIGGRAPH 1999, which is valid for
+/// turbidities from 2 (a very clear sky) to 10 (a hazy sky). The world's up
+/// direction is the **positive y-axis**, and the sun's azimuth is measured
+/// from the **positive x-axis** towards the **positive z-axis**, matching
+/// `sampling::equirectangular_direction`. Sky radiance is in candela per
+/// square meter and sun illuminance in lux.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SunSkyLight<S> {
+    turbidity: S,
+    sun_direction: Vector3<S>,
+    /// The luminance and chromaticity at the zenith.
+    zenith: [S; 3],
+    /// The Perez coefficients for the luminance and chromaticity.
+    perez: [[S; 5]; 3],
+}
+
+impl<S> SunSkyLight<S> where S: ScalarFloat {
+    /// Construct a sun and sky for a turbidity and the sun's elevation above
+    /// the horizon and azimuth. The turbidity is clamped to the model's valid
+    /// range, and the sky is evaluated with the sun no lower than the horizon.
+    pub fn new<A: Into<Radians<S>>>(turbidity: S, elevation: A, azimuth: A) -> SunSkyLight<S> {
+        let pi: S = num_traits::cast(core::f64::consts::PI).unwrap();
+        let two = S::one() + S::one();
+        let turbidity = turbidity.max(cast(2.0)).min(cast(10.0));
+        let elevation = elevation.into().0;
+        let azimuth = azimuth.into().0;
+        let sun_direction = Vector3::new(
+            elevation.cos() * azimuth.cos(),
+            elevation.sin(),
+            elevation.cos() * azimuth.sin(),
+        );
+
+        let theta_sun = (pi / two - elevation).max(S::zero()).min(pi / two);
+        let chi = (cast::<S>(4.0 / 9.0) - turbidity / cast(120.0)) * (pi - two * theta_sun);
+        let zenith_luminance = (cast::<S>(4.0453) * turbidity - cast(4.9710)) * chi.tan()
+            - cast::<S>(0.2155) * turbidity
+            + cast(2.4192);
+        let zenith = [
+            // The model gives the zenith luminance in kilocandela per square
+            // meter.
+            zenith_luminance.max(S::zero()) * cast(1000.0),
+            zenith_chromaticity(&ZENITH_X, turbidity, theta_sun),
+            zenith_chromaticity(&ZENITH_Y, turbidity, theta_sun),
+        ];
+        let perez = [
+            perez_coefficients(&PEREZ_LUMINANCE, turbidity),
+            perez_coefficients(&PEREZ_X, turbidity),
+            perez_coefficients(&PEREZ_Y, turbidity),
+        ];
+
+        SunSkyLight {
+            turbidity: turbidity,
+            sun_direction: sun_direction,
+            zenith: zenith,
+            perez: perez,
+        }
+    }
+
+    /// The turbidity of the atmosphere.
+    #[inline]
+    pub fn turbidity(&self) -> S {
+        self.turbidity
+    }
+
+    /// The unit direction from the ground towards the sun.
+    #[inline]
+    pub fn sun_direction(&self) -> Vector3<S> {
+        self.sun_direction
+    }
+
+    /// The sky's radiance arriving from the unit direction `direction` in
+    /// the CIE **XYZ** color space. The ground below the horizon is black.
+    pub fn sky_radiance_xyz(&self, direction: &Vector3<S>) -> Vector3<S> {
+        if direction.y <= S::zero() {
+            return Vector3::zero();
+        }
+
+        let pi: S = num_traits::cast(core::f64::consts::PI).unwrap();
+        let two = S::one() + S::one();
+        let cos_theta = direction.y.min(S::one());
+        let cos_theta_sun = self.sun_direction.y.max(S::zero()).min(S::one());
+        let theta_sun = cos_theta_sun.acos().min(pi / two);
+        let cos_gamma = direction.dot(&self.sun_direction).max(-S::one()).min(S::one());
+        let gamma = cos_gamma.acos();
+        let value = |channel: usize| {
+            let perez = &self.perez[channel];
+
+            self.zenith[channel] * perez_function(perez, cos_theta, gamma)
+                / perez_function(perez, S::one(), theta_sun)
+        };
+        let chromaticity = Chromaticity::new(value(1), value(2));
+
+        chromaticity.to_xyz(value(0))
+    }
+
+    /// The sky's radiance arriving from the unit direction `direction` in a
+    /// linear RGB color space. The ground below the horizon is black.
+    #[inline]
+    pub fn sky_radiance(&self, direction: &Vector3<S>, color_space: ColorSpace) -> Vector3<S> {
+        color_space.xyz_to_rgb(&self.sky_radiance_xyz(direction))
+    }
+
+    /// The fraction of sunlight in each of the red, green, and blue channels
+    /// that passes through the atmosphere, accounting for Rayleigh and
+    /// aerosol scattering. The sun is fully blocked below the horizon.
+    pub fn sun_transmittance(&self) -> Vector3<S> {
+        let elevation = self.sun_direction.y.max(-S::one()).min(S::one()).asin();
+        if elevation <= S::zero() {
+            return Vector3::zero();
+        }
+
+        // The relative optical mass of the atmosphere along the sun's ray,
+        // from Kasten's formula with the zenith angle in degrees.
+        let zenith_degrees = cast::<S>(90.0) - elevation.to_degrees();
+        let optical_mass = S::one()
+            / (self.sun_direction.y + cast::<S>(0.15) * (cast::<S>(93.885) - zenith_degrees).powf(cast(-1.253)));
+        let beta = cast::<S>(0.04608) * self.turbidity - cast(0.04586);
+        let alpha: S = cast(1.3);
+        let transmittance = |wavelength: f64| {
+            let wavelength: S = cast(wavelength);
+            let rayleigh = (-cast::<S>(0.008735) * wavelength.powf(cast(-4.08)) * optical_mass).exp();
+            let aerosol = (-beta * wavelength.powf(-alpha) * optical_mass).exp();
+
+            rayleigh * aerosol
+        };
+
+        Vector3::new(
+            transmittance(WAVELENGTHS[0]),
+            transmittance(WAVELENGTHS[1]),
+            transmittance(WAVELENGTHS[2]),
+        )
+    }
+
+    /// The illuminance of direct sunlight on a surface facing the sun, in
+    /// lux.
+    #[inline]
+    pub fn sun_illuminance(&self) -> S {
+        cast::<S>(EXTRATERRESTRIAL_ILLUMINANCE) * luminance(&self.sun_transmittance())
+    }
+
+    /// The specification of a directional light reproducing the sun. The
+    /// light's color is the sun's transmitted color scaled to its illuminance
+    /// and multiplied by `scale`, which maps lux to the renderer's units.
+    pub fn sun_model_spec(&self, scale: S) -> DirectionalLightModelSpec<S> {
+        let transmittance = self.sun_transmittance();
+        let transmittance_luminance = luminance(&transmittance);
+        let color = if transmittance_luminance > S::zero() {
+            transmittance * (self.sun_illuminance() * scale / transmittance_luminance)
+        } else {
+            Vector3::zero()
+        };
+
+        DirectionalLightModelSpec::new(Vector3::zero(), color, color)
+    }
+
+    /// The specification of an attitude at the origin whose forward axis
+    /// points from the sun towards the ground.
+    pub fn sun_attitude_spec(&self) -> LightAttitudeSpec<S> {
+        let zero = S::zero();
+        let one = S::one();
+        let forward = -self.sun_direction;
+        let world_up = Vector3::new(zero, one, zero);
+        let right = forward.cross(&world_up);
+        let right = if right.magnitude_squared() > S::epsilon() {
+            right.normalize()
+        } else {
+            // The sun is at the zenith or the nadir.
+            Vector3::new(one, zero, zero)
+        };
+        let up = right.cross(&forward);
+
+        LightAttitudeSpec::new(Vector3::zero(), forward, right, up, forward)
+    }
+
+    /// Construct a directional light reproducing the sun, as described by
+    /// `sun_model_spec` and `sun_attitude_spec`.
+    #[inline]
+    pub fn sun_light(&self, scale: S) -> DirectionalLight<S> {
+        DirectionalLight::new(&self.sun_model_spec(scale), &self.sun_attitude_spec())
+    }
+}
+
+impl<S> EnvironmentSource<S> for SunSkyLight<S> where S: ScalarFloat + Send + Sync {
+    /// The sky's radiance in linear sRGB, excluding the sun's disk.
+    #[inline]
+    fn radiance(&self, direction: &Vector3<S>) -> Vector3<S> {
+        self.sky_radiance(direction, ColorSpace::LinearSrgb)
+    }
+}
+
+#[inline]
+fn cast<S: ScalarFloat>(value: f64) -> S {
+    num_traits::cast(value).unwrap()
+}
+
+/// Evaluate the Perez coefficients of a sky channel for a turbidity.
+fn perez_coefficients<S: ScalarFloat>(table: &[[f64; 2]; 5], turbidity: S) -> [S; 5] {
+    let mut coefficients = [S::zero(); 5];
+    for (coefficient, &[slope, intercept]) in coefficients.iter_mut().zip(table.iter()) {
+        *coefficient = cast::<S>(slope) * turbidity + cast(intercept);
+    }
+
+    coefficients
+}
+
+/// Evaluate the Perez sky distribution function for a view direction with
+/// zenith angle cosine `cos_theta` at an angle `gamma` from the sun.
+fn perez_function<S: ScalarFloat>(perez: &[S; 5], cos_theta: S, gamma: S) -> S {
+    let [a, b, c, d, e] = *perez;
+    let cos_theta = cos_theta.max(cast(0.01));
+    let cos_gamma = gamma.cos();
+
+    (S::one() + a * (b / cos_theta).exp()) * (S::one() + c * (d * gamma).exp() + e * cos_gamma * cos_gamma)
+}
+
+/// Evaluate a zenith chromaticity coordinate for a turbidity and the sun's
+/// zenith angle.
+fn zenith_chromaticity<S: ScalarFloat>(table: &[[f64; 4]; 3], turbidity: S, theta_sun: S) -> S {
+    let turbidity_powers = [turbidity * turbidity, turbidity, S::one()];
+    let theta_powers = [theta_sun * theta_sun * theta_sun, theta_sun * theta_sun, theta_sun, S::one()];
+    let mut value = S::zero();
+    for (row, &turbidity_power) in table.iter().zip(turbidity_powers.iter()) {
+        for (&entry, &theta_power) in row.iter().zip(theta_powers.iter()) {
+            value += turbidity_power * cast::<S>(entry) * theta_power;
+        }
+    }
+
+    value
+}
@@ -0,0 +1,333 @@
+use cglinalg::{
+    Vector3,
+    ScalarFloat,
+};
+use crate::{
+    DirectionalLight,
+    PointLight,
+    SpotLight,
+};
+use crate::gpu::ATTENUATION_NONE;
+use core::mem;
+use core::ops;
+
+
+/// The stride in bytes of every column of a `LightArraySoA` except the
+/// attenuation kinds. Each element is a `vec4`, so the columns can be bound
+/// directly as std430 arrays.
+pub const SOA_STRIDE: usize = 16;
+
+#[inline]
+fn to_f32<S: ScalarFloat>(value: S) -> f32 {
+    num_traits::cast(value).unwrap()
+}
+
+#[inline]
+fn vector_to_f32<S: ScalarFloat>(vector: &Vector3<S>, w: f32) -> [f32; 4] {
+    [to_f32(vector.x), to_f32(vector.y), to_f32(vector.z), w]
+}
+
+#[inline]
+fn extend(vector: &[f32; 3], w: f32) -> [f32; 4] {
+    [vector[0], vector[1], vector[2], w]
+}
+
+#[inline]
+fn column_as_bytes<T: Copy>(column: &[T]) -> &[u8] {
+    // SAFETY: The columns hold only `[f32; 4]` and `u32` values, which have
+    // no padding and no invalid bit patterns, and the returned slice borrows
+    // the column, so it covers exactly the column's initialized memory.
+    unsafe {
+        core::slice::from_raw_parts(column.as_ptr() as *const u8, mem::size_of_val(column))
+    }
+}
+
+/// A light that can be stored in a `LightArraySoA`.
+pub trait SoaLight {
+    /// Write the light into every column of an array at `index`.
+    fn write_soa(&self, array: &mut LightArraySoA, index: usize);
+}
+
+impl<S> SoaLight for PointLight<S> where S: ScalarFloat {
+    fn write_soa(&self, array: &mut LightArraySoA, index: usize) {
+        let light = self.to_gpu();
+        array.positions[index] = extend(&light.position, 1.0);
+        array.directions[index] = [0.0; 4];
+        array.ambient[index] = extend(&light.ambient, 0.0);
+        array.diffuse[index] = extend(&light.diffuse, 0.0);
+        array.specular[index] = extend(&light.specular, 0.0);
+        array.shadow_tints[index] = extend(&light.shadow_tint, 0.0);
+        array.attenuations[index] = light.attenuation;
+        array.attenuation_kinds[index] = light.attenuation_kind;
+        array.cones[index] = [-1.0, -1.0, -1.0, light.polarity];
+    }
+}
+
+impl<S> SoaLight for SpotLight<S> where S: ScalarFloat {
+    fn write_soa(&self, array: &mut LightArraySoA, index: usize) {
+        let light = self.to_gpu();
+        array.positions[index] = extend(&light.position, 1.0);
+        array.directions[index] = extend(&light.direction, 0.0);
+        array.ambient[index] = extend(&light.ambient, 0.0);
+        array.diffuse[index] = extend(&light.diffuse, 0.0);
+        array.specular[index] = extend(&light.specular, 0.0);
+        array.shadow_tints[index] = extend(&light.shadow_tint, 0.0);
+        array.attenuations[index] = light.attenuation;
+        array.attenuation_kinds[index] = light.attenuation_kind;
+        array.cones[index] = [light.cutoff, light.outer_cutoff, light.cone_exponent, light.polarity];
+    }
+}
+
+impl<S> SoaLight for DirectionalLight<S> where S: ScalarFloat {
+    fn write_soa(&self, array: &mut LightArraySoA, index: usize) {
+        let light = self.to_gpu();
+        array.positions[index] = [0.0; 4];
+        array.directions[index] = extend(&light.direction, 0.0);
+        array.ambient[index] = extend(&light.ambient, 0.0);
+        array.diffuse[index] = extend(&light.diffuse, 0.0);
+        array.specular[index] = extend(&light.specular, 0.0);
+        array.shadow_tints[index] = extend(&light.shadow_tint, 0.0);
+        array.attenuations[index] = [1.0, 0.0, 0.0, 0.0];
+        array.attenuation_kinds[index] = ATTENUATION_NONE;
+        array.cones[index] = [-1.0, -1.0, -1.0, 1.0];
+    }
+}
+
+/// Single precision light data stored as a structure of arrays, for
+/// streaming large numbers of lights to the GPU.
+///
+/// Each property of the lights lives in its own contiguous column, so a
+/// renderer can update one property of thousands of lights, e.g. their
+/// positions, and upload only that column, without repacking whole light
+/// structs. Point, spot, and directional lights share one array:
+///
+/// * `positions`: the position, with `w = 1`, or zero for directional lights.
+/// * `directions`: the unit direction of a spotlight's axis or a directional
+///   light's rays, with `w = 0`, or zero for point lights.
+/// * `ambient`, `diffuse`, `specular`, `shadow_tints`: the colors, with
+///   `w = 0`.
+/// * `attenuations`: the attenuation parameters, as in `gpu::GpuPointLight`.
+/// * `attenuation_kinds`: one of the `gpu::ATTENUATION_*` constants, as a
+///   tightly packed `uint` array.
+/// * `cones`: the inner and outer cutoff cosines, the cone exponent, and the
+///   polarity. Point and directional lights have cutoffs and an exponent of
+///   `-1`, which selects the smoothstep falloff of `spot_cone_factor` with
+///   coinciding cutoffs, so every direction lies inside their cones.
+///
+/// The array tracks the range of lights changed since the last call to
+/// [`clear_dirty`](#method.clear_dirty), so uploads can be limited to the
+/// changed part of each column.
+#[derive(Clone, Debug, Default)]
+pub struct LightArraySoA {
+    positions: Vec<[f32; 4]>,
+    directions: Vec<[f32; 4]>,
+    ambient: Vec<[f32; 4]>,
+    diffuse: Vec<[f32; 4]>,
+    specular: Vec<[f32; 4]>,
+    shadow_tints: Vec<[f32; 4]>,
+    attenuations: Vec<[f32; 4]>,
+    attenuation_kinds: Vec<u32>,
+    cones: Vec<[f32; 4]>,
+    dirty: Option<ops::Range<usize>>,
+}
+
+impl LightArraySoA {
+    /// Construct an empty light array.
+    pub fn new() -> LightArraySoA {
+        LightArraySoA::default()
+    }
+
+    /// Construct an empty light array with room for `capacity` lights.
+    pub fn with_capacity(capacity: usize) -> LightArraySoA {
+        LightArraySoA {
+            positions: Vec::with_capacity(capacity),
+            directions: Vec::with_capacity(capacity),
+            ambient: Vec::with_capacity(capacity),
+            diffuse: Vec::with_capacity(capacity),
+            specular: Vec::with_capacity(capacity),
+            shadow_tints: Vec::with_capacity(capacity),
+            attenuations: Vec::with_capacity(capacity),
+            attenuation_kinds: Vec::with_capacity(capacity),
+            cones: Vec::with_capacity(capacity),
+            dirty: None,
+        }
+    }
+
+    /// The number of lights in the array.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.positions.len()
+    }
+
+    /// Determine whether the array holds no lights.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.positions.is_empty()
+    }
+
+    fn mark_dirty(&mut self, range: ops::Range<usize>) {
+        self.dirty = match self.dirty.take() {
+            Some(dirty) => Some(dirty.start.min(range.start)..dirty.end.max(range.end)),
+            None => Some(range),
+        };
+    }
+
+    /// Append a light to the array, returning its index.
+    pub fn push<L: SoaLight>(&mut self, light: &L) -> usize {
+        let index = self.len();
+        self.positions.push([0.0; 4]);
+        self.directions.push([0.0; 4]);
+        self.ambient.push([0.0; 4]);
+        self.diffuse.push([0.0; 4]);
+        self.specular.push([0.0; 4]);
+        self.shadow_tints.push([0.0; 4]);
+        self.attenuations.push([0.0; 4]);
+        self.attenuation_kinds.push(ATTENUATION_NONE);
+        self.cones.push([0.0; 4]);
+        light.write_soa(self, index);
+        self.mark_dirty(index..index + 1);
+
+        index
+    }
+
+    /// Replace every property of the light at `index`.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn update<L: SoaLight>(&mut self, index: usize, light: &L) {
+        assert!(index < self.len(), "light index {} out of bounds for length {}", index, self.len());
+        light.write_soa(self, index);
+        self.mark_dirty(index..index + 1);
+    }
+
+    /// Replace only the position of the light at `index`, for lights that
+    /// move without changing their other properties.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn set_position<S: ScalarFloat>(&mut self, index: usize, position: &Vector3<S>) {
+        let w = self.positions[index][3];
+        self.positions[index] = vector_to_f32(position, w);
+        self.mark_dirty(index..index + 1);
+    }
+
+    /// Replace only the direction of the light at `index`, for lights that
+    /// turn without changing their other properties. The direction should
+    /// have unit length.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn set_direction<S: ScalarFloat>(&mut self, index: usize, direction: &Vector3<S>) {
+        self.directions[index] = vector_to_f32(direction, 0.0);
+        self.mark_dirty(index..index + 1);
+    }
+
+    /// Remove the light at `index` by moving the last light into its place.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn swap_remove(&mut self, index: usize) {
+        let last = self.len() - 1;
+        self.positions.swap_remove(index);
+        self.directions.swap_remove(index);
+        self.ambient.swap_remove(index);
+        self.diffuse.swap_remove(index);
+        self.specular.swap_remove(index);
+        self.shadow_tints.swap_remove(index);
+        self.attenuations.swap_remove(index);
+        self.attenuation_kinds.swap_remove(index);
+        self.cones.swap_remove(index);
+        if index < last {
+            self.mark_dirty(index..index + 1);
+        }
+        self.dirty = self.dirty.take()
+            .map(|dirty| dirty.start.min(last)..dirty.end.min(last))
+            .filter(|dirty| dirty.start < dirty.end);
+    }
+
+    /// Remove every light from the array.
+    pub fn clear(&mut self) {
+        self.positions.clear();
+        self.directions.clear();
+        self.ambient.clear();
+        self.diffuse.clear();
+        self.specular.clear();
+        self.shadow_tints.clear();
+        self.attenuations.clear();
+        self.attenuation_kinds.clear();
+        self.cones.clear();
+        self.dirty = None;
+    }
+
+    /// The range of light indices changed since the last call to
+    /// `clear_dirty`, if any. Multiply by `SOA_STRIDE`, or by four for the
+    /// attenuation kinds, to find the byte range of each column to upload.
+    #[inline]
+    pub fn dirty_range(&self) -> Option<ops::Range<usize>> {
+        self.dirty.clone()
+    }
+
+    /// Mark every light as uploaded.
+    #[inline]
+    pub fn clear_dirty(&mut self) {
+        self.dirty = None;
+    }
+
+    /// The bytes of the positions column.
+    #[inline]
+    pub fn as_bytes_positions(&self) -> &[u8] {
+        column_as_bytes(&self.positions)
+    }
+
+    /// The bytes of the directions column.
+    #[inline]
+    pub fn as_bytes_directions(&self) -> &[u8] {
+        column_as_bytes(&self.directions)
+    }
+
+    /// The bytes of the ambient colors column.
+    #[inline]
+    pub fn as_bytes_ambient(&self) -> &[u8] {
+        column_as_bytes(&self.ambient)
+    }
+
+    /// The bytes of the diffuse colors column.
+    #[inline]
+    pub fn as_bytes_diffuse(&self) -> &[u8] {
+        column_as_bytes(&self.diffuse)
+    }
+
+    /// The bytes of the specular colors column.
+    #[inline]
+    pub fn as_bytes_specular(&self) -> &[u8] {
+        column_as_bytes(&self.specular)
+    }
+
+    /// The bytes of the shadow tints column.
+    #[inline]
+    pub fn as_bytes_shadow_tints(&self) -> &[u8] {
+        column_as_bytes(&self.shadow_tints)
+    }
+
+    /// The bytes of the attenuation parameters column.
+    #[inline]
+    pub fn as_bytes_attenuations(&self) -> &[u8] {
+        column_as_bytes(&self.attenuations)
+    }
+
+    /// The bytes of the attenuation kinds column.
+    #[inline]
+    pub fn as_bytes_attenuation_kinds(&self) -> &[u8] {
+        column_as_bytes(&self.attenuation_kinds)
+    }
+
+    /// The bytes of the cones column.
+    #[inline]
+    pub fn as_bytes_cones(&self) -> &[u8] {
+        column_as_bytes(&self.cones)
+    }
+}
@@ -0,0 +1,308 @@
+use cglinalg::{
+    Vector3,
+    ScalarFloat,
+};
+use crate::{
+    Dimmable,
+    IlluminationModel,
+    Light,
+};
+use crate::attenuation::Attenuation;
+use crate::color::ColorSpace;
+use crate::expression::{
+    BindableModel,
+    LightProperty,
+};
+use crate::lod::Importance;
+
+
+/// The shortest wavelength a spectrum covers, in nanometers.
+pub const MIN_WAVELENGTH: f64 = 380.0;
+
+/// The longest wavelength a spectrum covers, in nanometers.
+pub const MAX_WAVELENGTH: f64 = 780.0;
+
+/// Evaluate the CIE 1931 2° standard observer color matching functions at a
+/// wavelength in nanometers.
+///
+/// The functions are the multi-lobe piecewise Gaussian fit of Wyman, Sloan
+/// and Shirley, which is accurate to within the variation between human
+/// observers.
+pub fn color_matching_functions<S: ScalarFloat>(wavelength: S) -> Vector3<S> {
+    let lambda = num_traits::cast::<S, f64>(wavelength).unwrap();
+    let lobe = |mean: f64, sigma_below: f64, sigma_above: f64| {
+        let sigma = if lambda < mean { sigma_below } else { sigma_above };
+        let t = (lambda - mean) / sigma;
+
+        (-0.5 * t * t).exp()
+    };
+    let x = 1.056 * lobe(599.8, 37.9, 31.0) + 0.362 * lobe(442.0, 16.0, 26.7)
+        - 0.065 * lobe(501.1, 20.4, 26.2);
+    let y = 0.821 * lobe(568.8, 46.9, 40.5) + 0.286 * lobe(530.9, 16.3, 31.1);
+    let z = 1.217 * lobe(437.0, 11.8, 36.0) + 0.681 * lobe(459.0, 26.0, 13.8);
+
+    Vector3::new(
+        num_traits::cast(x).unwrap(),
+        num_traits::cast(y).unwrap(),
+        num_traits::cast(z).unwrap(),
+    )
+}
+
+/// A spectral power distribution sampled in equal width bins over the visible
+/// range from `MIN_WAVELENGTH` to `MAX_WAVELENGTH`.
+///
+/// Each sample is the spectrum's value at the center of its bin. Spectra are
+/// relative: a constant spectrum of one has unit luminance.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Spectrum<S> {
+    samples: Vec<S>,
+}
+
+impl<S> Spectrum<S> where S: ScalarFloat {
+    /// Construct a spectrum from its samples. The number of samples is the
+    /// number of bins. The result is `None` if there are no samples.
+    pub fn new(samples: Vec<S>) -> Option<Spectrum<S>> {
+        if samples.is_empty() {
+            return None;
+        }
+
+        Some(Spectrum {
+            samples: samples,
+        })
+    }
+
+    /// Construct a spectrum with the same value in every bin.
+    #[inline]
+    pub fn constant(bin_count: usize, value: S) -> Option<Spectrum<S>> {
+        Self::new(vec![value; bin_count])
+    }
+
+    /// Construct the spectrum with the smallest energy in the given number of
+    /// bins whose color is a linear RGB color in the given working color
+    /// space.
+    ///
+    /// The spectrum is a combination of the color matching functions, so it
+    /// is smooth, but saturated colors produce spectra with negative values.
+    /// The result is `None` if `bin_count` is zero.
+    pub fn from_rgb(rgb: &Vector3<S>, color_space: ColorSpace, bin_count: usize) -> Option<Spectrum<S>> {
+        let basis = Self::constant(bin_count, S::zero())?;
+        let xyz = color_space.rgb_to_xyz(rgb);
+
+        // The color of a combination `a` of the matching functions is `G a`,
+        // where `G` is the Gram matrix of the functions over the bins.
+        let mut gram = [[S::zero(); 3]; 3];
+        for i in 0..bin_count {
+            let cmf = color_matching_functions(basis.wavelength(i));
+            let cmf = [cmf.x, cmf.y, cmf.z];
+            for row in 0..3 {
+                for column in 0..3 {
+                    gram[row][column] += cmf[row] * cmf[column];
+                }
+            }
+        }
+        let scale = basis.bin_width() / basis.normalization();
+        for row in gram.iter_mut() {
+            for entry in row.iter_mut() {
+                *entry *= scale;
+            }
+        }
+        let weights = solve3(&gram, &[xyz.x, xyz.y, xyz.z])?;
+
+        let samples = (0..bin_count).map(|i| {
+            let cmf = color_matching_functions(basis.wavelength(i));
+
+            weights[0] * cmf.x + weights[1] * cmf.y + weights[2] * cmf.z
+        }).collect();
+
+        Self::new(samples)
+    }
+
+    /// The number of bins.
+    #[inline]
+    pub fn bin_count(&self) -> usize {
+        self.samples.len()
+    }
+
+    /// The samples of the spectrum, one per bin in order of increasing
+    /// wavelength.
+    #[inline]
+    pub fn samples(&self) -> &[S] {
+        &self.samples
+    }
+
+    /// The width of each bin in nanometers.
+    #[inline]
+    pub fn bin_width(&self) -> S {
+        let range: S = num_traits::cast(MAX_WAVELENGTH - MIN_WAVELENGTH).unwrap();
+
+        range / num_traits::cast(self.samples.len()).unwrap()
+    }
+
+    /// The wavelength at the center of a bin in nanometers.
+    #[inline]
+    pub fn wavelength(&self, bin: usize) -> S {
+        let min_wavelength: S = num_traits::cast(MIN_WAVELENGTH).unwrap();
+        let one_half: S = num_traits::cast(0.5).unwrap();
+        let bin: S = num_traits::cast(bin).unwrap();
+
+        min_wavelength + (bin + one_half) * self.bin_width()
+    }
+
+    /// Evaluate the spectrum at a wavelength in nanometers, interpolating
+    /// linearly between bin centers. Wavelengths beyond the outermost bin
+    /// centers take the value of the nearest bin.
+    pub fn evaluate(&self, wavelength: S) -> S {
+        let min_wavelength: S = num_traits::cast(MIN_WAVELENGTH).unwrap();
+        let one_half: S = num_traits::cast(0.5).unwrap();
+        let last = self.samples.len() - 1;
+        let position = ((wavelength - min_wavelength) / self.bin_width() - one_half).max(S::zero());
+        let index = position.floor();
+        let lower = num_traits::cast::<S, usize>(index).unwrap_or(last).min(last);
+        let upper = (lower + 1).min(last);
+        let t = (position - index).min(S::one());
+
+        self.samples[lower] + (self.samples[upper] - self.samples[lower]) * t
+    }
+
+    /// The CIE XYZ tristimulus values of the spectrum.
+    pub fn to_xyz(&self) -> Vector3<S> {
+        let sum = (0..self.samples.len()).fold(Vector3::zero(), |sum, i| {
+            sum + color_matching_functions(self.wavelength(i)) * self.samples[i]
+        });
+
+        sum * (self.bin_width() / self.normalization())
+    }
+
+    /// The linear RGB color of the spectrum in the given working color space.
+    #[inline]
+    pub fn to_rgb(&self, color_space: ColorSpace) -> Vector3<S> {
+        color_space.xyz_to_rgb(&self.to_xyz())
+    }
+
+    /// The integral of the luminous efficiency function over the bins, which
+    /// scales a constant spectrum of one to unit luminance.
+    fn normalization(&self) -> S {
+        let sum = (0..self.samples.len()).fold(S::zero(), |sum, i| {
+            sum + color_matching_functions(self.wavelength(i)).y
+        });
+
+        sum * self.bin_width()
+    }
+}
+
+/// Solve a 3x3 linear system by Cramer's rule. The result is `None` if the
+/// matrix is singular.
+fn solve3<S: ScalarFloat>(matrix: &[[S; 3]; 3], rhs: &[S; 3]) -> Option<[S; 3]> {
+    let determinant = |m: &[[S; 3]; 3]| {
+        m[0][0] * (m[1][1] * m[2][2] - m[1][2] * m[2][1])
+            - m[0][1] * (m[1][0] * m[2][2] - m[1][2] * m[2][0])
+            + m[0][2] * (m[1][0] * m[2][1] - m[1][1] * m[2][0])
+    };
+    let denominator = determinant(matrix);
+    if denominator == S::zero() {
+        return None;
+    }
+
+    let mut solution = [S::zero(); 3];
+    for (column, value) in solution.iter_mut().enumerate() {
+        let mut replaced = *matrix;
+        for row in 0..3 {
+            replaced[row][column] = rhs[row];
+        }
+        *value = determinant(&replaced) / denominator;
+    }
+
+    Some(solution)
+}
+
+/// A specification describing a punctual light whose emission is a sampled
+/// spectrum, for spectral renderers.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SpectralLightModelSpec<S> {
+    /// The relative spectral power distribution of the light.
+    pub spectrum: Spectrum<S>,
+    /// A multiplier on the spectrum.
+    pub intensity: S,
+    /// The falloff of the light's emission with distance.
+    pub attenuation: Attenuation<S>,
+    /// The fraction of the light's emission that reaches shadowed regions in
+    /// each color channel. Zero casts black shadows.
+    pub shadow_tint: Vector3<S>,
+}
+
+impl<S> SpectralLightModelSpec<S> where S: ScalarFloat {
+    /// Construct a new spectral light specification. The light falls off with
+    /// the inverse square law.
+    #[inline]
+    pub fn new(spectrum: Spectrum<S>, intensity: S) -> SpectralLightModelSpec<S> {
+        SpectralLightModelSpec {
+            spectrum: spectrum,
+            intensity: intensity,
+            attenuation: Attenuation::InverseSquare,
+            shadow_tint: Vector3::zero(),
+        }
+    }
+
+    /// Replace the light's distance attenuation.
+    #[inline]
+    pub fn with_attenuation(self, attenuation: Attenuation<S>) -> SpectralLightModelSpec<S> {
+        SpectralLightModelSpec {
+            attenuation: attenuation,
+            ..self
+        }
+    }
+}
+
+impl_with_shadow_tint!(SpectralLightModelSpec);
+
+/// The illumination model of a punctual light with a sampled emission
+/// spectrum, emitting equally in all directions.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SpectralLightModel<S> {
+    /// The relative spectral power distribution of the light.
+    pub spectrum: Spectrum<S>,
+    /// A multiplier on the spectrum.
+    pub intensity: S,
+    /// The falloff of the light's emission with distance.
+    pub attenuation: Attenuation<S>,
+    /// The fraction of the light's emission that reaches shadowed regions in
+    /// each color channel. Zero casts black shadows.
+    pub shadow_tint: Vector3<S>,
+}
+
+impl_illumination_model!(SpectralLightModel, SpectralLightModelSpec, [spectrum, intensity, attenuation, shadow_tint]);
+
+impl_dimmable!(SpectralLightModel, [intensity]);
+
+impl<S> SpectralLightModel<S> where S: ScalarFloat {
+    /// The light's emission at a wavelength in nanometers.
+    #[inline]
+    pub fn emission(&self, wavelength: S) -> S {
+        self.spectrum.evaluate(wavelength) * self.intensity
+    }
+
+    /// The linear RGB color of the light's emission in the given working
+    /// color space, for rendering the light in an RGB pipeline.
+    #[inline]
+    pub fn to_rgb(&self, color_space: ColorSpace) -> Vector3<S> {
+        self.spectrum.to_rgb(color_space) * self.intensity
+    }
+}
+
+impl<S> Importance<S> for SpectralLightModel<S> where S: ScalarFloat {
+    #[inline]
+    fn brightness(&self) -> S {
+        self.spectrum.to_xyz().y * self.intensity
+    }
+}
+
+impl<S> BindableModel<S> for SpectralLightModel<S> where S: ScalarFloat {
+    #[inline]
+    fn apply_property(&mut self, property: LightProperty, value: S) {
+        if property == LightProperty::Intensity {
+            self.intensity *= value;
+        }
+    }
+}
+
+pub type SpectralLight<S> = Light<S, SpectralLightModel<S>>;
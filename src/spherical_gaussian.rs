@@ -0,0 +1,242 @@
+use cglinalg::{
+    Magnitude,
+    Vector3,
+    ScalarFloat,
+};
+use crate::{
+    DiskLight,
+    PointLight,
+    SphereLight,
+    SpotLight,
+};
+use crate::normalization::AreaEmitter;
+
+
+/// A spherical Gaussian lobe
+/// ```text
+/// G(v) = amplitude * exp(sharpness * (dot(axis, v) - 1))
+/// ```
+/// over unit directions `v`, with an RGB amplitude.
+///
+/// Spherical Gaussians are closed under products and have closed form
+/// integrals, which makes them a convenient basis for specular probes and
+/// baked lighting.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct SphericalGaussian<S> {
+    /// The unit direction of the lobe's peak.
+    pub axis: Vector3<S>,
+    /// The sharpness of the lobe. Larger values give narrower lobes.
+    pub sharpness: S,
+    /// The value of the lobe at its peak in each color channel.
+    pub amplitude: Vector3<S>,
+}
+
+impl<S> SphericalGaussian<S> where S: ScalarFloat {
+    /// Construct a new spherical Gaussian. The axis is normalized.
+    #[inline]
+    pub fn new(axis: Vector3<S>, sharpness: S, amplitude: Vector3<S>) -> SphericalGaussian<S> {
+        SphericalGaussian {
+            axis: axis.normalize(),
+            sharpness: sharpness,
+            amplitude: amplitude,
+        }
+    }
+
+    /// The standard fit of a clamped cosine lobe `max(dot(normal, v), 0)`
+    /// by a single spherical Gaussian.
+    #[inline]
+    pub fn clamped_cosine_lobe(normal: &Vector3<S>) -> SphericalGaussian<S> {
+        let sharpness: S = num_traits::cast(2.133).unwrap();
+        let amplitude: S = num_traits::cast(1.17).unwrap();
+
+        SphericalGaussian::new(*normal, sharpness, Vector3::new(amplitude, amplitude, amplitude))
+    }
+
+    /// Construct a lobe approximating light arriving from a punctual light.
+    ///
+    /// The lobe is centered on `direction_to_light`, has the given sharpness,
+    /// and its amplitude is chosen so that the lobe integrates to
+    /// `irradiance`, the irradiance the light delivers to a surface facing it.
+    pub fn from_punctual(
+        direction_to_light: &Vector3<S>,
+        irradiance: &Vector3<S>,
+        sharpness: S) -> SphericalGaussian<S>
+    {
+        let scale = S::one() / unit_integral(sharpness);
+
+        SphericalGaussian::new(*direction_to_light, sharpness, irradiance * scale)
+    }
+
+    /// Construct a lobe approximating the light a point light delivers to a
+    /// shading point, including the light's distance attenuation. The lobe of
+    /// a subtractive light has a negative amplitude, and the lobe of a shading
+    /// point at the light's position is zero.
+    pub fn from_point_light(
+        light: &PointLight<S>,
+        shading_point: &Vector3<S>,
+        sharpness: S) -> SphericalGaussian<S>
+    {
+        let model = light.model();
+        let direction_to_light = light.position() - shading_point;
+        if direction_to_light.magnitude_squared() <= S::zero() {
+            return SphericalGaussian::new(light.forward_axis(), sharpness, Vector3::zero());
+        }
+        let scale = model.attenuation(direction_to_light.magnitude()) * model.polarity.sign();
+
+        Self::from_punctual(&direction_to_light, &(model.diffuse * scale), sharpness)
+    }
+
+    /// Construct a lobe approximating the light a spotlight delivers to a
+    /// shading point, including the spotlight's distance attenuation and
+    /// angular falloff. The lobe of a subtractive light has a negative
+    /// amplitude, and the lobe of a shading point at the spotlight's position
+    /// is zero.
+    pub fn from_spot_light(
+        light: &SpotLight<S>,
+        shading_point: &Vector3<S>,
+        sharpness: S) -> SphericalGaussian<S>
+    {
+        let model = light.model();
+        let direction_to_light = light.position() - shading_point;
+        let distance = direction_to_light.magnitude();
+        if distance <= S::zero() {
+            return SphericalGaussian::new(light.forward_axis(), sharpness, Vector3::zero());
+        }
+        let cos_angle = -(direction_to_light / distance).dot(&light.forward_axis().normalize());
+        let scale = model.attenuation(distance) * model.cone_factor(cos_angle) * model.polarity.sign();
+
+        Self::from_punctual(&direction_to_light, &(model.diffuse * scale), sharpness)
+    }
+
+    /// Construct a lobe approximating the light a sphere light delivers to a
+    /// shading point.
+    ///
+    /// The lobe is centered on the sphere and peaks at the sphere's
+    /// radiance, and its sharpness is chosen so that it integrates to the
+    /// radiance over the solid angle the sphere subtends, capped at a
+    /// hemisphere. A shading point at the sphere's center has no direction
+    /// to the light, so its lobe is centered on the light's forward axis.
+    pub fn from_sphere_light(light: &SphereLight<S>, shading_point: &Vector3<S>) -> SphericalGaussian<S> {
+        let direction_to_light = light.position() - shading_point;
+        let direction_to_light = if direction_to_light.magnitude_squared() > S::zero() {
+            direction_to_light
+        } else {
+            light.forward_axis()
+        };
+        let sharpness = sharpness_for_solid_angle(light.solid_angle(shading_point));
+
+        SphericalGaussian::new(direction_to_light, sharpness, light.model().emitted_radiance())
+    }
+
+    /// Construct a lobe approximating the light a disk light delivers to a
+    /// shading point, fitted like [`from_sphere_light`](#method.from_sphere_light)
+    /// to the disk's solid angle. The disk emits only from its front face,
+    /// so the lobe of a point behind it is zero.
+    pub fn from_disk_light(light: &DiskLight<S>, shading_point: &Vector3<S>) -> SphericalGaussian<S> {
+        let two_pi: S = num_traits::cast(2.0 * core::f64::consts::PI).unwrap();
+        let direction_to_light = light.position() - shading_point;
+        let distance_squared = direction_to_light.magnitude_squared();
+        let cos_disk = -direction_to_light.dot(&light.forward_axis());
+        if distance_squared <= S::zero() || cos_disk <= S::zero() {
+            return SphericalGaussian::new(light.forward_axis(), S::one(), Vector3::zero());
+        }
+        let cos_disk = cos_disk / distance_squared.sqrt();
+
+        // The solid angle of a disk seen along its axis is
+        // `2π(1 - d / sqrt(d^2 + r^2))`. Foreshortening the disk's area by
+        // the cosine of the viewing angle extends this off the axis, and
+        // keeps the far field limit `πr^2 cos(θ) / d^2`.
+        let radius = light.model().radius;
+        let projected = radius * radius * cos_disk / distance_squared;
+        let solid_angle = two_pi * (S::one() - S::one() / (S::one() + projected).sqrt());
+        let sharpness = sharpness_for_solid_angle(solid_angle);
+
+        SphericalGaussian::new(direction_to_light, sharpness, light.model().emitted_radiance())
+    }
+
+    /// Evaluate the lobe in a direction.
+    #[inline]
+    pub fn evaluate(&self, direction: &Vector3<S>) -> Vector3<S> {
+        let cos_angle = self.axis.dot(&direction.normalize());
+
+        self.amplitude * (self.sharpness * (cos_angle - S::one())).exp()
+    }
+
+    /// Integrate the lobe over the sphere.
+    #[inline]
+    pub fn integral(&self) -> Vector3<S> {
+        self.amplitude * unit_integral(self.sharpness)
+    }
+
+    /// Compute the product of two lobes, which is another spherical Gaussian.
+    pub fn product(&self, other: &SphericalGaussian<S>) -> SphericalGaussian<S> {
+        let weighted_axis = self.axis * self.sharpness + other.axis * other.sharpness;
+        let sharpness = weighted_axis.magnitude();
+        let scale = (sharpness - self.sharpness - other.sharpness).exp();
+        let amplitude = component_product(&self.amplitude, &other.amplitude) * scale;
+        let axis = if sharpness > S::zero() {
+            weighted_axis / sharpness
+        } else {
+            self.axis
+        };
+
+        SphericalGaussian {
+            axis: axis,
+            sharpness: sharpness,
+            amplitude: amplitude,
+        }
+    }
+
+    /// Integrate the product of two lobes over the sphere.
+    pub fn inner_product(&self, other: &SphericalGaussian<S>) -> Vector3<S> {
+        let two_pi: S = num_traits::cast(2.0 * core::f64::consts::PI).unwrap();
+        let d = (self.axis * self.sharpness + other.axis * other.sharpness).magnitude();
+        let sum = self.sharpness + other.sharpness;
+        let amplitude = component_product(&self.amplitude, &other.amplitude);
+        // 4 pi exp(-sum) sinh(d) / d, written to avoid overflow for sharp lobes.
+        let scale = if d > S::epsilon() {
+            two_pi * ((d - sum).exp() - (-d - sum).exp()) / d
+        } else {
+            (two_pi + two_pi) * (-sum).exp()
+        };
+
+        amplitude * scale
+    }
+
+    /// Compute the irradiance the lobe delivers to a surface with the given
+    /// normal, using the spherical Gaussian fit of the clamped cosine lobe.
+    #[inline]
+    pub fn irradiance(&self, normal: &Vector3<S>) -> Vector3<S> {
+        self.inner_product(&Self::clamped_cosine_lobe(normal))
+    }
+}
+
+/// The integral over the sphere of a spherical Gaussian with unit amplitude.
+#[inline]
+fn unit_integral<S: ScalarFloat>(sharpness: S) -> S {
+    let two_pi: S = num_traits::cast(2.0 * core::f64::consts::PI).unwrap();
+    let two = S::one() + S::one();
+
+    two_pi / sharpness * (S::one() - (-two * sharpness).exp())
+}
+
+/// The sharpness of the lobe whose integral with unit amplitude is
+/// `solid_angle`. Solid angles are capped at a hemisphere, where the
+/// fixed point iteration below converges quickly.
+fn sharpness_for_solid_angle<S: ScalarFloat>(solid_angle: S) -> S {
+    let two_pi: S = num_traits::cast(2.0 * core::f64::consts::PI).unwrap();
+    let two = S::one() + S::one();
+    let solid_angle = solid_angle.min(two_pi).max(S::epsilon());
+    // For sharp lobes the integral is close to `2π / sharpness`.
+    let mut sharpness = two_pi / solid_angle;
+    for _ in 0..16 {
+        sharpness = two_pi * (S::one() - (-two * sharpness).exp()) / solid_angle;
+    }
+
+    sharpness
+}
+
+#[inline]
+fn component_product<S: ScalarFloat>(a: &Vector3<S>, b: &Vector3<S>) -> Vector3<S> {
+    Vector3::new(a.x * b.x, a.y * b.y, a.z * b.z)
+}
@@ -0,0 +1,111 @@
+#[cfg(feature = "instrumentation")]
+use core::sync::atomic::{
+    AtomicU64,
+    Ordering,
+};
+
+
+/// A statistic counted by the crate's subsystems.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub(crate) enum Counter {
+    LightsUpdated,
+    ViewMatricesRebuilt,
+    LightsReduced,
+    LightsCulled,
+    CasterTests,
+    BytesPacked,
+    ClusterEntries,
+    TileEntries,
+}
+
+/// The number of counters.
+#[cfg(feature = "instrumentation")]
+const COUNTER_COUNT: usize = 8;
+
+#[cfg(feature = "instrumentation")]
+static COUNTERS: [AtomicU64; COUNTER_COUNT] = [
+    AtomicU64::new(0),
+    AtomicU64::new(0),
+    AtomicU64::new(0),
+    AtomicU64::new(0),
+    AtomicU64::new(0),
+    AtomicU64::new(0),
+    AtomicU64::new(0),
+    AtomicU64::new(0),
+];
+
+/// Add to a counter. Without the `instrumentation` feature this does nothing.
+#[inline]
+pub(crate) fn record(counter: Counter, amount: usize) {
+    #[cfg(feature = "instrumentation")]
+    COUNTERS[counter as usize].fetch_add(amount as u64, Ordering::Relaxed);
+    #[cfg(not(feature = "instrumentation"))]
+    let _ = (counter, amount);
+}
+
+/// The statistics collected by the crate's subsystems since they were last
+/// reset, for surfacing the cost of lighting in a profiler.
+///
+/// The counters are global and shared by every thread. Take the statistics
+/// once per frame with [`take`] to get per-frame numbers.
+#[cfg(feature = "instrumentation")]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Stats {
+    /// The number of times a light's position or orientation was updated.
+    pub lights_updated: u64,
+    /// The number of light view matrices computed.
+    pub view_matrices_rebuilt: u64,
+    /// The number of lights the level of detail policy reduced.
+    pub lights_reduced: u64,
+    /// The number of lights the level of detail policy culled.
+    pub lights_culled: u64,
+    /// The number of shadow caster bounds tested against shadow volumes.
+    pub caster_tests: u64,
+    /// The number of bytes written into GPU light buffers.
+    pub bytes_packed: u64,
+    /// The number of light indices written into cluster light lists.
+    pub cluster_entries: u64,
+    /// The number of light indices written into tile light lists.
+    pub tile_entries: u64,
+}
+
+#[cfg(feature = "instrumentation")]
+impl Stats {
+    fn load(counter: Counter) -> u64 {
+        COUNTERS[counter as usize].load(Ordering::Relaxed)
+    }
+
+    fn swap(counter: Counter) -> u64 {
+        COUNTERS[counter as usize].swap(0, Ordering::Relaxed)
+    }
+}
+
+/// Read the statistics without resetting them.
+#[cfg(feature = "instrumentation")]
+pub fn snapshot() -> Stats {
+    Stats {
+        lights_updated: Stats::load(Counter::LightsUpdated),
+        view_matrices_rebuilt: Stats::load(Counter::ViewMatricesRebuilt),
+        lights_reduced: Stats::load(Counter::LightsReduced),
+        lights_culled: Stats::load(Counter::LightsCulled),
+        caster_tests: Stats::load(Counter::CasterTests),
+        bytes_packed: Stats::load(Counter::BytesPacked),
+        cluster_entries: Stats::load(Counter::ClusterEntries),
+        tile_entries: Stats::load(Counter::TileEntries),
+    }
+}
+
+/// Read the statistics and reset them to zero.
+#[cfg(feature = "instrumentation")]
+pub fn take() -> Stats {
+    Stats {
+        lights_updated: Stats::swap(Counter::LightsUpdated),
+        view_matrices_rebuilt: Stats::swap(Counter::ViewMatricesRebuilt),
+        lights_reduced: Stats::swap(Counter::LightsReduced),
+        lights_culled: Stats::swap(Counter::LightsCulled),
+        caster_tests: Stats::swap(Counter::CasterTests),
+        bytes_packed: Stats::swap(Counter::BytesPacked),
+        cluster_entries: Stats::swap(Counter::ClusterEntries),
+        tile_entries: Stats::swap(Counter::TileEntries),
+    }
+}
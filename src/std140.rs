@@ -0,0 +1,296 @@
+use cglinalg::{
+    ScalarFloat,
+};
+use crate::{
+    DirectionalLight,
+    PointLight,
+    SpotLight,
+};
+use crate::gpu::{
+    GpuDirectionalLight,
+    GpuPointLight,
+    GpuSpotLight,
+};
+use core::mem;
+
+
+/// A point light laid out by the std140 rules, for OpenGL uniform blocks and
+/// Vulkan uniform buffers. It matches the GLSL declaration
+///
+/// ```glsl
+/// struct PointLight {
+///     vec3 position;
+///     uint attenuation_kind;
+///     vec3 ambient;
+///     float polarity;
+///     vec3 diffuse;
+///     vec3 specular;
+///     vec3 shadow_tint;
+///     vec4 attenuation;
+/// };
+/// ```
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Std140PointLight {
+    pub position: [f32; 3],
+    pub attenuation_kind: u32,
+    pub ambient: [f32; 3],
+    pub polarity: f32,
+    pub diffuse: [f32; 3],
+    _padding0: f32,
+    pub specular: [f32; 3],
+    _padding1: f32,
+    pub shadow_tint: [f32; 3],
+    _padding2: f32,
+    pub attenuation: [f32; 4],
+}
+
+/// A spotlight laid out by the std140 rules. It matches the GLSL
+/// declaration
+///
+/// ```glsl
+/// struct SpotLight {
+///     vec3 position;
+///     float cutoff;
+///     vec3 direction;
+///     float outer_cutoff;
+///     vec3 ambient;
+///     float cone_exponent;
+///     vec3 diffuse;
+///     float polarity;
+///     vec3 specular;
+///     uint attenuation_kind;
+///     vec3 shadow_tint;
+///     vec4 attenuation;
+/// };
+/// ```
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Std140SpotLight {
+    pub position: [f32; 3],
+    pub cutoff: f32,
+    pub direction: [f32; 3],
+    pub outer_cutoff: f32,
+    pub ambient: [f32; 3],
+    pub cone_exponent: f32,
+    pub diffuse: [f32; 3],
+    pub polarity: f32,
+    pub specular: [f32; 3],
+    pub attenuation_kind: u32,
+    pub shadow_tint: [f32; 3],
+    _padding0: f32,
+    pub attenuation: [f32; 4],
+}
+
+/// A directional light laid out by the std140 rules. It matches the GLSL
+/// declaration
+///
+/// ```glsl
+/// struct DirectionalLight {
+///     vec3 direction;
+///     vec3 ambient;
+///     vec3 diffuse;
+///     vec3 specular;
+///     vec3 shadow_tint;
+/// };
+/// ```
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Std140DirectionalLight {
+    pub direction: [f32; 3],
+    _padding0: f32,
+    pub ambient: [f32; 3],
+    _padding1: f32,
+    pub diffuse: [f32; 3],
+    _padding2: f32,
+    pub specular: [f32; 3],
+    _padding3: f32,
+    pub shadow_tint: [f32; 3],
+    _padding4: f32,
+}
+
+// The offsets std140 assigns to each member of the GLSL declarations. A
+// `vec3` or `vec4` starts on a 16 byte boundary, a scalar may fill the last
+// four bytes of a preceding `vec3`, and an array of structs advances by a
+// multiple of 16 bytes.
+const _: () = {
+    assert!(mem::offset_of!(Std140PointLight, position) == 0);
+    assert!(mem::offset_of!(Std140PointLight, attenuation_kind) == 12);
+    assert!(mem::offset_of!(Std140PointLight, ambient) == 16);
+    assert!(mem::offset_of!(Std140PointLight, polarity) == 28);
+    assert!(mem::offset_of!(Std140PointLight, diffuse) == 32);
+    assert!(mem::offset_of!(Std140PointLight, specular) == 48);
+    assert!(mem::offset_of!(Std140PointLight, shadow_tint) == 64);
+    assert!(mem::offset_of!(Std140PointLight, attenuation) == 80);
+    assert!(mem::size_of::<Std140PointLight>() == 96);
+
+    assert!(mem::offset_of!(Std140SpotLight, position) == 0);
+    assert!(mem::offset_of!(Std140SpotLight, cutoff) == 12);
+    assert!(mem::offset_of!(Std140SpotLight, direction) == 16);
+    assert!(mem::offset_of!(Std140SpotLight, outer_cutoff) == 28);
+    assert!(mem::offset_of!(Std140SpotLight, ambient) == 32);
+    assert!(mem::offset_of!(Std140SpotLight, cone_exponent) == 44);
+    assert!(mem::offset_of!(Std140SpotLight, diffuse) == 48);
+    assert!(mem::offset_of!(Std140SpotLight, polarity) == 60);
+    assert!(mem::offset_of!(Std140SpotLight, specular) == 64);
+    assert!(mem::offset_of!(Std140SpotLight, attenuation_kind) == 76);
+    assert!(mem::offset_of!(Std140SpotLight, shadow_tint) == 80);
+    assert!(mem::offset_of!(Std140SpotLight, attenuation) == 96);
+    assert!(mem::size_of::<Std140SpotLight>() == 112);
+
+    assert!(mem::offset_of!(Std140DirectionalLight, direction) == 0);
+    assert!(mem::offset_of!(Std140DirectionalLight, ambient) == 16);
+    assert!(mem::offset_of!(Std140DirectionalLight, diffuse) == 32);
+    assert!(mem::offset_of!(Std140DirectionalLight, specular) == 48);
+    assert!(mem::offset_of!(Std140DirectionalLight, shadow_tint) == 64);
+    assert!(mem::size_of::<Std140DirectionalLight>() == 80);
+};
+
+#[cfg(feature = "bytemuck")]
+unsafe impl bytemuck::Zeroable for Std140PointLight {}
+
+#[cfg(feature = "bytemuck")]
+unsafe impl bytemuck::Pod for Std140PointLight {}
+
+#[cfg(feature = "bytemuck")]
+unsafe impl bytemuck::Zeroable for Std140SpotLight {}
+
+#[cfg(feature = "bytemuck")]
+unsafe impl bytemuck::Pod for Std140SpotLight {}
+
+#[cfg(feature = "bytemuck")]
+unsafe impl bytemuck::Zeroable for Std140DirectionalLight {}
+
+#[cfg(feature = "bytemuck")]
+unsafe impl bytemuck::Pod for Std140DirectionalLight {}
+
+impl From<GpuPointLight> for Std140PointLight {
+    #[inline]
+    fn from(light: GpuPointLight) -> Std140PointLight {
+        Std140PointLight {
+            position: light.position,
+            attenuation_kind: light.attenuation_kind,
+            ambient: light.ambient,
+            polarity: light.polarity,
+            diffuse: light.diffuse,
+            _padding0: 0.0,
+            specular: light.specular,
+            _padding1: 0.0,
+            shadow_tint: light.shadow_tint,
+            _padding2: 0.0,
+            attenuation: light.attenuation,
+        }
+    }
+}
+
+impl From<GpuSpotLight> for Std140SpotLight {
+    #[inline]
+    fn from(light: GpuSpotLight) -> Std140SpotLight {
+        Std140SpotLight {
+            position: light.position,
+            cutoff: light.cutoff,
+            direction: light.direction,
+            outer_cutoff: light.outer_cutoff,
+            ambient: light.ambient,
+            cone_exponent: light.cone_exponent,
+            diffuse: light.diffuse,
+            polarity: light.polarity,
+            specular: light.specular,
+            attenuation_kind: light.attenuation_kind,
+            shadow_tint: light.shadow_tint,
+            _padding0: 0.0,
+            attenuation: light.attenuation,
+        }
+    }
+}
+
+impl From<GpuDirectionalLight> for Std140DirectionalLight {
+    #[inline]
+    fn from(light: GpuDirectionalLight) -> Std140DirectionalLight {
+        Std140DirectionalLight {
+            direction: light.direction,
+            _padding0: 0.0,
+            ambient: light.ambient,
+            _padding1: 0.0,
+            diffuse: light.diffuse,
+            _padding2: 0.0,
+            specular: light.specular,
+            _padding3: 0.0,
+            shadow_tint: light.shadow_tint,
+            _padding4: 0.0,
+        }
+    }
+}
+
+impl<S> PointLight<S> where S: ScalarFloat {
+    /// Convert the light to its std140 representation.
+    #[inline]
+    pub fn to_std140(&self) -> Std140PointLight {
+        Std140PointLight::from(self.to_gpu())
+    }
+}
+
+impl<S> SpotLight<S> where S: ScalarFloat {
+    /// Convert the light to its std140 representation.
+    #[inline]
+    pub fn to_std140(&self) -> Std140SpotLight {
+        Std140SpotLight::from(self.to_gpu())
+    }
+}
+
+impl<S> DirectionalLight<S> where S: ScalarFloat {
+    /// Convert the light to its std140 representation.
+    #[inline]
+    pub fn to_std140(&self) -> Std140DirectionalLight {
+        Std140DirectionalLight::from(self.to_gpu())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+
+    #[test]
+    fn test_std140_point_light_layout() {
+        assert_eq!(mem::size_of::<Std140PointLight>(), 96);
+        assert_eq!(mem::align_of::<Std140PointLight>(), 4);
+        assert_eq!(mem::offset_of!(Std140PointLight, position), 0);
+        assert_eq!(mem::offset_of!(Std140PointLight, attenuation_kind), 12);
+        assert_eq!(mem::offset_of!(Std140PointLight, ambient), 16);
+        assert_eq!(mem::offset_of!(Std140PointLight, polarity), 28);
+        assert_eq!(mem::offset_of!(Std140PointLight, diffuse), 32);
+        assert_eq!(mem::offset_of!(Std140PointLight, specular), 48);
+        assert_eq!(mem::offset_of!(Std140PointLight, shadow_tint), 64);
+        assert_eq!(mem::offset_of!(Std140PointLight, attenuation), 80);
+    }
+
+    #[test]
+    fn test_std140_spot_light_layout() {
+        assert_eq!(mem::size_of::<Std140SpotLight>(), 112);
+        assert_eq!(mem::align_of::<Std140SpotLight>(), 4);
+        assert_eq!(mem::offset_of!(Std140SpotLight, position), 0);
+        assert_eq!(mem::offset_of!(Std140SpotLight, cutoff), 12);
+        assert_eq!(mem::offset_of!(Std140SpotLight, direction), 16);
+        assert_eq!(mem::offset_of!(Std140SpotLight, outer_cutoff), 28);
+        assert_eq!(mem::offset_of!(Std140SpotLight, ambient), 32);
+        assert_eq!(mem::offset_of!(Std140SpotLight, cone_exponent), 44);
+        assert_eq!(mem::offset_of!(Std140SpotLight, diffuse), 48);
+        assert_eq!(mem::offset_of!(Std140SpotLight, polarity), 60);
+        assert_eq!(mem::offset_of!(Std140SpotLight, specular), 64);
+        assert_eq!(mem::offset_of!(Std140SpotLight, attenuation_kind), 76);
+        assert_eq!(mem::offset_of!(Std140SpotLight, shadow_tint), 80);
+        assert_eq!(mem::offset_of!(Std140SpotLight, attenuation), 96);
+    }
+
+    #[test]
+    fn test_std140_directional_light_layout() {
+        assert_eq!(mem::size_of::<Std140DirectionalLight>(), 80);
+        assert_eq!(mem::align_of::<Std140DirectionalLight>(), 4);
+        assert_eq!(mem::offset_of!(Std140DirectionalLight, direction), 0);
+        assert_eq!(mem::offset_of!(Std140DirectionalLight, ambient), 16);
+        assert_eq!(mem::offset_of!(Std140DirectionalLight, diffuse), 32);
+        assert_eq!(mem::offset_of!(Std140DirectionalLight, specular), 48);
+        assert_eq!(mem::offset_of!(Std140DirectionalLight, shadow_tint), 64);
+    }
+}
@@ -0,0 +1,230 @@
+use cglinalg::{
+    ScalarFloat,
+};
+use crate::{
+    DirectionalLight,
+    PointLight,
+    SpotLight,
+};
+use crate::std140::{
+    Std140DirectionalLight,
+    Std140PointLight,
+    Std140SpotLight,
+};
+use crate::stats;
+use crate::stats::Counter;
+use core::mem;
+
+
+/// The size in bytes of the header before the lights in a std430 light
+/// buffer. The header holds the light count as a `uint`, padded to the
+/// 16 byte alignment of the light array that follows.
+pub const STD430_HEADER_SIZE: usize = 16;
+
+#[inline]
+fn push_f32(bytes: &mut Vec<u8>, value: f32) {
+    bytes.extend_from_slice(&value.to_ne_bytes());
+}
+
+#[inline]
+fn push_u32(bytes: &mut Vec<u8>, value: u32) {
+    bytes.extend_from_slice(&value.to_ne_bytes());
+}
+
+#[inline]
+fn push_vector(bytes: &mut Vec<u8>, vector: &[f32]) {
+    for component in vector {
+        push_f32(bytes, *component);
+    }
+}
+
+/// A light with a std430 representation, for shader storage buffers.
+///
+/// For the light structs in this crate the std430 rules give the same
+/// layout as the std140 rules, so each light's std430 representation has
+/// the layout of its std140 struct, e.g. `Std140PointLight`.
+pub trait Std430Light {
+    /// The size in bytes of one light, which is also the array stride.
+    const STD430_SIZE: usize;
+
+    /// Append the light's std430 representation to a buffer, in native byte
+    /// order.
+    fn write_std430(&self, bytes: &mut Vec<u8>);
+}
+
+impl<S> Std430Light for PointLight<S> where S: ScalarFloat {
+    const STD430_SIZE: usize = mem::size_of::<Std140PointLight>();
+
+    fn write_std430(&self, bytes: &mut Vec<u8>) {
+        let light = self.to_std140();
+        push_vector(bytes, &light.position);
+        push_u32(bytes, light.attenuation_kind);
+        push_vector(bytes, &light.ambient);
+        push_f32(bytes, light.polarity);
+        push_vector(bytes, &light.diffuse);
+        push_f32(bytes, 0.0);
+        push_vector(bytes, &light.specular);
+        push_f32(bytes, 0.0);
+        push_vector(bytes, &light.shadow_tint);
+        push_f32(bytes, 0.0);
+        push_vector(bytes, &light.attenuation);
+    }
+}
+
+impl<S> Std430Light for SpotLight<S> where S: ScalarFloat {
+    const STD430_SIZE: usize = mem::size_of::<Std140SpotLight>();
+
+    fn write_std430(&self, bytes: &mut Vec<u8>) {
+        let light = self.to_std140();
+        push_vector(bytes, &light.position);
+        push_f32(bytes, light.cutoff);
+        push_vector(bytes, &light.direction);
+        push_f32(bytes, light.outer_cutoff);
+        push_vector(bytes, &light.ambient);
+        push_f32(bytes, light.cone_exponent);
+        push_vector(bytes, &light.diffuse);
+        push_f32(bytes, light.polarity);
+        push_vector(bytes, &light.specular);
+        push_u32(bytes, light.attenuation_kind);
+        push_vector(bytes, &light.shadow_tint);
+        push_f32(bytes, 0.0);
+        push_vector(bytes, &light.attenuation);
+    }
+}
+
+impl<S> Std430Light for DirectionalLight<S> where S: ScalarFloat {
+    const STD430_SIZE: usize = mem::size_of::<Std140DirectionalLight>();
+
+    fn write_std430(&self, bytes: &mut Vec<u8>) {
+        let light = self.to_std140();
+        for vector in [light.direction, light.ambient, light.diffuse, light.specular, light.shadow_tint].iter() {
+            push_vector(bytes, vector);
+            push_f32(bytes, 0.0);
+        }
+    }
+}
+
+/// Pack a set of lights into a std430 shader storage buffer, for clustered
+/// and forward+ renderers. The buffer matches the GLSL declaration
+///
+/// ```glsl
+/// layout(std430) buffer PointLights {
+///     uint count;
+///     PointLight lights[];
+/// };
+/// ```
+///
+/// with the light count in the first four bytes and the lights starting at
+/// `STD430_HEADER_SIZE`.
+pub fn pack_std430<L: Std430Light>(lights: &[L]) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(STD430_HEADER_SIZE + lights.len() * L::STD430_SIZE);
+    push_u32(&mut bytes, lights.len() as u32);
+    bytes.resize(STD430_HEADER_SIZE, 0);
+    for light in lights {
+        light.write_std430(&mut bytes);
+    }
+    debug_assert_eq!(bytes.len(), STD430_HEADER_SIZE + lights.len() * L::STD430_SIZE);
+    stats::record(Counter::BytesPacked, bytes.len());
+
+    bytes
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cglinalg::Vector3;
+    use crate::{
+        DirectionalLightModelSpec,
+        LightAttitudeSpec,
+        PointLightModelSpec,
+    };
+
+
+    fn attitude_spec() -> LightAttitudeSpec<f32> {
+        LightAttitudeSpec::new(
+            Vector3::new(1.0, 2.0, 3.0),
+            Vector3::new(0.0, 0.0, -1.0),
+            Vector3::new(1.0, 0.0, 0.0),
+            Vector3::new(0.0, 1.0, 0.0),
+            Vector3::new(0.0, 0.0, 1.0),
+        )
+    }
+
+    fn point_light() -> PointLight<f32> {
+        let model_spec = PointLightModelSpec::new(
+            Vector3::new(0.1, 0.1, 0.1),
+            Vector3::new(0.8, 0.7, 0.6),
+            Vector3::new(1.0, 1.0, 1.0),
+        );
+
+        PointLight::new(&model_spec, &attitude_spec())
+    }
+
+    fn read_f32(bytes: &[u8], offset: usize) -> f32 {
+        f32::from_ne_bytes([bytes[offset], bytes[offset + 1], bytes[offset + 2], bytes[offset + 3]])
+    }
+
+    fn read_u32(bytes: &[u8], offset: usize) -> u32 {
+        u32::from_ne_bytes([bytes[offset], bytes[offset + 1], bytes[offset + 2], bytes[offset + 3]])
+    }
+
+    #[test]
+    fn test_std430_light_sizes_match_std140() {
+        assert_eq!(<PointLight<f32> as Std430Light>::STD430_SIZE, mem::size_of::<Std140PointLight>());
+        assert_eq!(<SpotLight<f32> as Std430Light>::STD430_SIZE, mem::size_of::<Std140SpotLight>());
+        assert_eq!(
+            <DirectionalLight<f32> as Std430Light>::STD430_SIZE,
+            mem::size_of::<Std140DirectionalLight>()
+        );
+    }
+
+    #[test]
+    fn test_pack_std430_length_and_header() {
+        let lights = vec![point_light(), point_light(), point_light()];
+        let bytes = pack_std430(&lights);
+
+        assert_eq!(bytes.len(), STD430_HEADER_SIZE + 3 * mem::size_of::<Std140PointLight>());
+        assert_eq!(read_u32(&bytes, 0), 3);
+        assert!(bytes[4..STD430_HEADER_SIZE].iter().all(|&byte| byte == 0));
+    }
+
+    #[test]
+    fn test_pack_std430_light_matches_std140_offsets() {
+        let light = point_light();
+        let expected = light.to_std140();
+        let bytes = pack_std430(&[light]);
+        let base = STD430_HEADER_SIZE;
+
+        assert_eq!(read_f32(&bytes, base + mem::offset_of!(Std140PointLight, position)), expected.position[0]);
+        assert_eq!(read_u32(&bytes, base + mem::offset_of!(Std140PointLight, attenuation_kind)), expected.attenuation_kind);
+        assert_eq!(read_f32(&bytes, base + mem::offset_of!(Std140PointLight, diffuse) + 8), expected.diffuse[2]);
+        assert_eq!(read_f32(&bytes, base + mem::offset_of!(Std140PointLight, attenuation)), expected.attenuation[0]);
+    }
+
+    #[test]
+    fn test_pack_std430_empty() {
+        let lights: Vec<DirectionalLight<f32>> = Vec::new();
+        let bytes = pack_std430(&lights);
+
+        assert_eq!(bytes.len(), STD430_HEADER_SIZE);
+        assert_eq!(read_u32(&bytes, 0), 0);
+    }
+
+    #[test]
+    fn test_pack_std430_directional_light_length() {
+        let model_spec = DirectionalLightModelSpec::new(
+            Vector3::new(0.0, 0.0, 0.0),
+            Vector3::new(1.0, 1.0, 1.0),
+            Vector3::new(1.0, 1.0, 1.0),
+        );
+        let lights = vec![
+            DirectionalLight::new(&model_spec, &attitude_spec()),
+            DirectionalLight::new(&model_spec, &attitude_spec()),
+        ];
+        let bytes = pack_std430(&lights);
+
+        assert_eq!(bytes.len(), STD430_HEADER_SIZE + 2 * mem::size_of::<Std140DirectionalLight>());
+        assert_eq!(read_u32(&bytes, 0), 2);
+    }
+}
@@ -0,0 +1,401 @@
+use cglinalg::{
+    Vector3,
+    Quaternion,
+    ScalarFloat,
+};
+use crate::{
+    IlluminationModel,
+    Light,
+    LightAttitude,
+    ViewMatrixPolicy,
+};
+use crate::layers::LightLayers;
+use crate::sampling::LightJitter;
+use crate::scene::{
+    LightHandle,
+    LightScene,
+};
+use crate::shadow::ShadowSettings;
+use std::error;
+use core::fmt;
+
+
+/// The largest quantized coordinate of a light's position within a chunk.
+const QUANTIZATION_LEVELS: u16 = u16::MAX;
+
+/// The largest number of entries in a chunk's palettes.
+const MAX_PALETTE_SIZE: usize = u16::MAX as usize + 1;
+
+/// An error produced while encoding lights into a chunk.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ChunkError {
+    /// The light with the given index lies outside the chunk's bounds.
+    OutOfBounds(usize),
+    /// The lights have more distinct illumination models, orientations, or
+    /// settings than a palette can hold.
+    PaletteOverflow,
+}
+
+impl fmt::Display for ChunkError {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ChunkError::OutOfBounds(index) => write!(formatter, "light {} lies outside the chunk", index),
+            ChunkError::PaletteOverflow => write!(formatter, "too many distinct light parameters for a palette"),
+        }
+    }
+}
+
+impl error::Error for ChunkError {}
+
+/// A compressed chunk of a large static light set, such as the street lamps
+/// and windows of one block of an open world city.
+///
+/// Each light's position is quantized to 16 bits per axis relative to the
+/// chunk's origin, and its illumination model, orientation, and remaining
+/// settings are stored as 16 bit indices into palettes shared by the chunk's
+/// lights. The settings are a light's jitter, exposure compensation, layers,
+/// diffuse and specular factors, and shadow settings. A chunk of lights with
+/// a few dozen distinct parameter sets takes twelve bytes per light plus its
+/// palettes.
+///
+/// Quantization snaps each position to a grid with a spacing of the chunk's
+/// size divided by 65535. The palettes store everything else exactly.
+/// Decoding a chunk and encoding the result again reproduces the chunk
+/// exactly, so lights can be edited and written back without drifting.
+///
+/// Chunks stream into a `LightScene` with
+/// [`LightScene::load_chunk`](../scene/struct.LightScene.html#method.load_chunk)
+/// and out of it with
+/// [`LightScene::unload_chunk`](../scene/struct.LightScene.html#method.unload_chunk).
+#[derive(Clone, Debug)]
+pub struct LightChunk<S, M>
+    where M: IlluminationModel
+{
+    origin: Vector3<S>,
+    size: S,
+    positions: Vec<[u16; 3]>,
+    model_indices: Vec<u16>,
+    orientation_indices: Vec<u16>,
+    settings_indices: Vec<u16>,
+    models: Vec<M::Spec>,
+    orientations: Vec<Quaternion<S>>,
+    settings: Vec<LightSettings<S>>,
+}
+
+/// The settings of a light other than its model and attitude, as stored in
+/// a chunk's settings palette.
+#[derive(Copy, Clone, Debug, PartialEq)]
+struct LightSettings<S> {
+    jitter: LightJitter<S>,
+    exposure_compensation: S,
+    layers: LightLayers,
+    diffuse_factor: S,
+    specular_factor: S,
+    shadow_settings: Option<ShadowSettings<S>>,
+}
+
+impl<S, M> LightChunk<S, M>
+    where S: ScalarFloat,
+          M: IlluminationModel,
+          M::Spec: PartialEq,
+{
+    /// Encode lights into a chunk covering the cube from `origin` to
+    /// `origin + (size, size, size)`.
+    pub fn encode(origin: Vector3<S>, size: S, lights: &[Light<S, M>]) -> Result<LightChunk<S, M>, ChunkError> {
+        let mut chunk = LightChunk {
+            origin: origin,
+            size: size,
+            positions: Vec::with_capacity(lights.len()),
+            model_indices: Vec::with_capacity(lights.len()),
+            orientation_indices: Vec::with_capacity(lights.len()),
+            settings_indices: Vec::with_capacity(lights.len()),
+            models: Vec::new(),
+            orientations: Vec::new(),
+            settings: Vec::new(),
+        };
+        for (i, light) in lights.iter().enumerate() {
+            let position = chunk.quantize(&light.position()).ok_or(ChunkError::OutOfBounds(i))?;
+            let model_index = palette_index(&mut chunk.models, light.model_spec())?;
+            let orientation_index = palette_index(&mut chunk.orientations, light.attitude.orientation)?;
+            let settings = LightSettings {
+                jitter: light.jitter,
+                exposure_compensation: light.exposure_compensation,
+                layers: light.layers,
+                diffuse_factor: light.diffuse_factor,
+                specular_factor: light.specular_factor,
+                shadow_settings: light.shadow_settings,
+            };
+            let settings_index = palette_index(&mut chunk.settings, settings)?;
+            chunk.positions.push(position);
+            chunk.model_indices.push(model_index);
+            chunk.orientation_indices.push(orientation_index);
+            chunk.settings_indices.push(settings_index);
+        }
+
+        Ok(chunk)
+    }
+
+    /// Decode the chunk's lights, appending them to `lights`. The lights
+    /// compute their view matrices under the given policy. Loading chunks
+    /// into a shared collection as they stream in avoids reallocating a
+    /// vector per chunk.
+    pub fn decode_into(&self, policy: ViewMatrixPolicy, lights: &mut Vec<Light<S, M>>) {
+        lights.reserve(self.len());
+        for i in 0..self.len() {
+            let model = M::from_spec(&self.models[self.model_indices[i] as usize]);
+            let position = self.dequantize(&self.positions[i]);
+            let orientation = self.orientations[self.orientation_indices[i] as usize];
+            let settings = self.settings[self.settings_indices[i] as usize];
+            lights.push(Light {
+                model: model,
+                attitude: LightAttitude::from_parts(position, orientation, policy),
+                jitter: settings.jitter,
+                exposure_compensation: settings.exposure_compensation,
+                layers: settings.layers,
+                diffuse_factor: settings.diffuse_factor,
+                specular_factor: settings.specular_factor,
+                shadow_settings: settings.shadow_settings,
+            });
+        }
+    }
+
+    /// Decode the chunk's lights.
+    #[inline]
+    pub fn decode(&self, policy: ViewMatrixPolicy) -> Vec<Light<S, M>> {
+        let mut lights = Vec::with_capacity(self.len());
+        self.decode_into(policy, &mut lights);
+
+        lights
+    }
+
+    /// The corner of the chunk with the smallest coordinates.
+    #[inline]
+    pub fn origin(&self) -> Vector3<S> {
+        self.origin
+    }
+
+    /// The edge length of the chunk.
+    #[inline]
+    pub fn size(&self) -> S {
+        self.size
+    }
+
+    /// The number of lights in the chunk.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.positions.len()
+    }
+
+    /// Determine whether the chunk has no lights.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.positions.is_empty()
+    }
+
+    /// The distinct illumination model parameters of the chunk's lights.
+    #[inline]
+    pub fn model_palette(&self) -> &[M::Spec] {
+        &self.models
+    }
+
+    /// The distinct orientations of the chunk's lights.
+    #[inline]
+    pub fn orientation_palette(&self) -> &[Quaternion<S>] {
+        &self.orientations
+    }
+
+    /// The number of distinct combinations of jitter, exposure compensation,
+    /// layers, contribution factors, and shadow settings among the chunk's
+    /// lights.
+    #[inline]
+    pub fn settings_palette_len(&self) -> usize {
+        self.settings.len()
+    }
+
+    /// The spacing of the grid that positions are snapped to.
+    #[inline]
+    pub fn position_precision(&self) -> S {
+        let levels: S = num_traits::cast(QUANTIZATION_LEVELS).unwrap();
+
+        self.size / levels
+    }
+
+    /// Quantize a position relative to the chunk's origin, if it lies
+    /// within the chunk.
+    fn quantize(&self, position: &Vector3<S>) -> Option<[u16; 3]> {
+        let levels: S = num_traits::cast(QUANTIZATION_LEVELS).unwrap();
+        let local = position - self.origin;
+        let mut quantized = [0_u16; 3];
+        for (value, coordinate) in quantized.iter_mut().zip([local.x, local.y, local.z].iter()) {
+            let scaled = (*coordinate / self.size * levels).round();
+            if !(scaled >= S::zero() && scaled <= levels) {
+                return None;
+            }
+            *value = num_traits::cast(scaled).unwrap();
+        }
+
+        Some(quantized)
+    }
+
+    /// Recover a position from its quantized coordinates.
+    fn dequantize(&self, quantized: &[u16; 3]) -> Vector3<S> {
+        let levels: S = num_traits::cast(QUANTIZATION_LEVELS).unwrap();
+        let x: S = num_traits::cast(quantized[0]).unwrap();
+        let y: S = num_traits::cast(quantized[1]).unwrap();
+        let z: S = num_traits::cast(quantized[2]).unwrap();
+
+        self.origin + Vector3::new(x, y, z) * (self.size / levels)
+    }
+}
+
+/// The lights of a chunk loaded into a `LightScene` by
+/// [`LightScene::load_chunk`](../scene/struct.LightScene.html#method.load_chunk).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LoadedChunk {
+    handles: Vec<LightHandle>,
+}
+
+impl LoadedChunk {
+    /// The handles of the chunk's lights in the scene, in the chunk's order.
+    #[inline]
+    pub fn handles(&self) -> &[LightHandle] {
+        &self.handles
+    }
+}
+
+impl<S, M> LightScene<S, M>
+    where S: ScalarFloat,
+          M: IlluminationModel,
+          M::Spec: PartialEq,
+{
+    /// Decode a chunk's lights into the scene, e.g. as the chunk streams in
+    /// around the camera. The lights compute their view matrices under the
+    /// given policy. The result identifies the lights for
+    /// [`unload_chunk`](#method.unload_chunk).
+    pub fn load_chunk(&mut self, chunk: &LightChunk<S, M>, policy: ViewMatrixPolicy) -> LoadedChunk {
+        let mut lights = Vec::with_capacity(chunk.len());
+        chunk.decode_into(policy, &mut lights);
+        let handles = lights.into_iter().map(|light| self.insert(light)).collect();
+
+        LoadedChunk {
+            handles: handles,
+        }
+    }
+
+    /// Remove a loaded chunk's lights from the scene, e.g. as the chunk
+    /// streams out, returning the lights still in the scene in the chunk's
+    /// order. Encoding the result writes back any edits made to the lights
+    /// while they were loaded.
+    pub fn unload_chunk(&mut self, loaded: LoadedChunk) -> Vec<Light<S, M>> {
+        loaded.handles.into_iter()
+            .filter_map(|handle| self.remove(handle))
+            .collect()
+    }
+}
+
+/// Find an entry in a palette, adding it if it is not there yet.
+fn palette_index<T: PartialEq>(palette: &mut Vec<T>, entry: T) -> Result<u16, ChunkError> {
+    if let Some(index) = palette.iter().position(|existing| *existing == entry) {
+        return Ok(index as u16);
+    }
+    if palette.len() >= MAX_PALETTE_SIZE {
+        return Err(ChunkError::PaletteOverflow);
+    }
+    palette.push(entry);
+
+    Ok((palette.len() - 1) as u16)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cglinalg::Magnitude;
+    use crate::{
+        LightAttitudeSpec,
+        PointLight,
+        PointLightModel,
+        PointLightModelSpec,
+    };
+
+
+    fn point_light(position: Vector3<f32>) -> PointLight<f32> {
+        let model_spec = PointLightModelSpec::new(
+            Vector3::new(0.1, 0.1, 0.1),
+            Vector3::new(0.8, 0.7, 0.6),
+            Vector3::new(1.0, 1.0, 1.0),
+        );
+        let attitude_spec = LightAttitudeSpec::new(
+            position,
+            Vector3::new(0.0, 0.0, -1.0),
+            Vector3::new(1.0, 0.0, 0.0),
+            Vector3::new(0.0, 1.0, 0.0),
+            Vector3::new(0.0, 0.0, 1.0),
+        );
+
+        PointLight::new(&model_spec, &attitude_spec)
+    }
+
+    fn lights() -> Vec<PointLight<f32>> {
+        let mut lights = vec![
+            point_light(Vector3::new(1.0, 2.0, 3.0)),
+            point_light(Vector3::new(4.0, 5.0, 6.0)),
+            point_light(Vector3::new(7.0, 8.0, 9.0)),
+        ];
+        lights[1].set_jitter(LightJitter::new(0.25, 7));
+        lights[1].set_exposure_compensation(-1.5);
+        lights[1].set_layers(LightLayers::from_bits(0b110));
+        lights[1].set_diffuse_factor(0.5);
+        lights[1].set_specular_factor(2.0);
+        lights[1].set_shadow_settings(Some(ShadowSettings::new(0.1, 50.0)));
+
+        lights
+    }
+
+    fn assert_same_settings(decoded: &PointLight<f32>, original: &PointLight<f32>) {
+        assert_eq!(decoded.jitter(), original.jitter());
+        assert_eq!(decoded.exposure_compensation(), original.exposure_compensation());
+        assert_eq!(decoded.layers(), original.layers());
+        assert_eq!(decoded.diffuse_factor(), original.diffuse_factor());
+        assert_eq!(decoded.specular_factor(), original.specular_factor());
+        assert_eq!(decoded.shadow_settings(), original.shadow_settings());
+    }
+
+    #[test]
+    fn test_chunk_round_trip_keeps_light_settings() {
+        let lights = lights();
+        let chunk = LightChunk::encode(Vector3::zero(), 16.0, &lights).unwrap();
+        let decoded = chunk.decode(ViewMatrixPolicy::Lazy);
+
+        assert_eq!(chunk.settings_palette_len(), 2);
+        assert_eq!(decoded.len(), lights.len());
+        for (decoded, original) in decoded.iter().zip(lights.iter()) {
+            assert_same_settings(decoded, original);
+            assert!((decoded.position() - original.position()).magnitude() <= chunk.position_precision());
+        }
+    }
+
+    #[test]
+    fn test_chunk_load_and_unload() {
+        let lights = lights();
+        let chunk = LightChunk::encode(Vector3::zero(), 16.0, &lights).unwrap();
+        let mut scene = LightScene::<f32, PointLightModel<f32>>::new();
+        let resident = scene.insert(point_light(Vector3::new(0.0, 0.0, 0.0)));
+
+        let loaded = scene.load_chunk(&chunk, ViewMatrixPolicy::Eager);
+        assert_eq!(scene.len(), 4);
+        assert_same_settings(scene.get(loaded.handles()[1]).unwrap(), &lights[1]);
+
+        let edited = loaded.handles()[2];
+        scene.get_mut(edited).unwrap().set_diffuse_factor(0.25);
+        let unloaded = scene.unload_chunk(loaded.clone());
+        assert_eq!(scene.len(), 1);
+        assert!(scene.contains(resident));
+        assert!(loaded.handles().iter().all(|handle| !scene.contains(*handle)));
+        assert_eq!(unloaded.len(), 3);
+        assert_eq!(unloaded[2].diffuse_factor(), 0.25);
+
+        let rewritten = LightChunk::encode(Vector3::zero(), 16.0, &unloaded).unwrap();
+        assert_eq!(rewritten.settings_palette_len(), 3);
+    }
+}
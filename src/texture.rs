@@ -0,0 +1,119 @@
+use cglinalg::{
+    Vector2,
+    Vector3,
+    ScalarFloat,
+};
+use crate::color::luminance;
+use crate::sampling::{
+    grid_index,
+    Distribution2D,
+};
+
+
+/// An image modulating the emission of a light across its surface, such as
+/// the picture on a television or a monitor.
+///
+/// Texture coordinates range over the unit square with the origin at the
+/// first texel of the image. The texture carries a distribution proportional
+/// to its luminance, so that samples of the light concentrate on its
+/// brightest texels.
+#[derive(Clone, Debug, PartialEq)]
+pub struct EmissionTexture<S> {
+    width: usize,
+    height: usize,
+    texels: Vec<Vector3<S>>,
+    distribution: Distribution2D<S>,
+}
+
+impl<S> EmissionTexture<S> where S: ScalarFloat {
+    /// Construct an emission texture from a `width` by `height` image of
+    /// linear RGB texels stored in row-major order. The result is `None` if
+    /// the image is empty or the number of texels does not match its size.
+    pub fn new(width: usize, height: usize, texels: Vec<Vector3<S>>) -> Option<EmissionTexture<S>> {
+        if width == 0 || height == 0 || texels.len() != width * height {
+            return None;
+        }
+
+        let weights: Vec<S> = texels.iter().map(|texel| luminance(texel).max(S::zero())).collect();
+        let distribution = Distribution2D::new(width, height, &weights);
+
+        Some(EmissionTexture {
+            width: width,
+            height: height,
+            texels: texels,
+            distribution: distribution,
+        })
+    }
+
+    /// The width of the image in texels.
+    #[inline]
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// The height of the image in texels.
+    #[inline]
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// The texels of the image in row-major order.
+    #[inline]
+    pub fn texels(&self) -> &[Vector3<S>] {
+        &self.texels
+    }
+
+    /// Get the texel containing a texture coordinate. Coordinates outside the
+    /// unit square are clamped to the edge of the image.
+    #[inline]
+    pub fn texel(&self, uv: &Vector2<S>) -> Vector3<S> {
+        let column = grid_index(uv.x, self.width);
+        let row = grid_index(uv.y, self.height);
+
+        self.texels[row * self.width + column]
+    }
+
+    /// Look up the emission at a texture coordinate with bilinear filtering.
+    /// Coordinates outside the unit square are clamped to the edge of the
+    /// image.
+    pub fn lookup(&self, uv: &Vector2<S>) -> Vector3<S> {
+        let one_half: S = num_traits::cast(0.5).unwrap();
+        let width: S = num_traits::cast(self.width).unwrap();
+        let height: S = num_traits::cast(self.height).unwrap();
+        let x = (uv.x * width - one_half).max(S::zero()).min(width - S::one());
+        let y = (uv.y * height - one_half).max(S::zero()).min(height - S::one());
+        let x0: usize = num_traits::cast(x.floor()).unwrap();
+        let y0: usize = num_traits::cast(y.floor()).unwrap();
+        let x1 = (x0 + 1).min(self.width - 1);
+        let y1 = (y0 + 1).min(self.height - 1);
+        let tx = x - x.floor();
+        let ty = y - y.floor();
+        let texel = |column: usize, row: usize| self.texels[row * self.width + column];
+        let top = texel(x0, y0) * (S::one() - tx) + texel(x1, y0) * tx;
+        let bottom = texel(x0, y1) * (S::one() - tx) + texel(x1, y1) * tx;
+
+        top * (S::one() - ty) + bottom * ty
+    }
+
+    /// Importance sample a texture coordinate proportionally to the texture's
+    /// luminance from a uniform sample in the unit square, returning the
+    /// coordinate and its probability density with respect to area in the
+    /// unit square.
+    #[inline]
+    pub fn sample(&self, u: S, v: S) -> (Vector2<S>, S) {
+        self.distribution.sample(u, v)
+    }
+
+    /// The probability density of sampling a texture coordinate with respect
+    /// to area in the unit square.
+    #[inline]
+    pub fn pdf(&self, uv: &Vector2<S>) -> S {
+        self.distribution.pdf(uv)
+    }
+
+    /// The luminance distribution the texture is sampled from.
+    #[inline]
+    pub fn distribution(&self) -> &Distribution2D<S> {
+        &self.distribution
+    }
+}
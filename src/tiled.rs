@@ -0,0 +1,287 @@
+use cglinalg::{
+    Magnitude,
+    Vector3,
+    Matrix4x4,
+    ScalarFloat,
+};
+use crate::{
+    IlluminationModel,
+    Light,
+};
+use crate::any_light::LightModelInfo;
+use crate::shadow::{
+    ShadowCone,
+    ShadowVolume,
+};
+use crate::shadow_map::Plane;
+use crate::stats;
+use crate::stats::Counter;
+
+
+/// The plane `a * x + b * y + c * z + d >= 0` with its normal normalized.
+#[inline]
+fn plane<S: ScalarFloat>(coefficients: [S; 4]) -> Plane<S> {
+    let normal = Vector3::new(coefficients[0], coefficients[1], coefficients[2]);
+    let length = normal.magnitude();
+
+    Plane {
+        normal: normal / length,
+        distance: coefficients[3] / length,
+    }
+}
+
+/// The linear combination `a * p + b * q` of two rows of a matrix.
+#[inline]
+fn combine<S: ScalarFloat>(a: S, p: &[S; 4], b: S, q: &[S; 4]) -> [S; 4] {
+    [a * p[0] + b * q[0], a * p[1] + b * q[1], a * p[2] + b * q[2], a * p[3] + b * q[3]]
+}
+
+/// Determine whether a light's volume may reach the inside of every plane
+/// of a tile frustum.
+fn intersects<S: ScalarFloat>(volume: &ShadowVolume<S>, planes: &[Plane<S>; 6]) -> bool {
+    match *volume {
+        ShadowVolume::Sphere { center, radius } => {
+            planes.iter().all(|plane| plane.signed_distance(&center) >= -radius)
+        }
+        ShadowVolume::Cone { apex, axis, cos_half_angle, range } => {
+            // The lit region lies inside both the range sphere and the cone
+            // of the same angle truncated at `range` along its axis. The
+            // farthest point of the truncated cone along a plane's normal is
+            // its apex or a point on the rim of its base.
+            let min_cos: S = num_traits::cast(1e-4).unwrap();
+            let sin_half_angle = (S::one() - cos_half_angle * cos_half_angle).max(S::zero()).sqrt();
+            let base_radius = range * sin_half_angle / cos_half_angle.max(min_cos);
+            let base = apex + axis * range;
+            planes.iter().all(|plane| {
+                let apex_distance = plane.signed_distance(&apex);
+                let n_dot_axis = plane.normal.dot(&axis);
+                let rim = base_radius * (S::one() - n_dot_axis * n_dot_axis).max(S::zero()).sqrt();
+                let cone_reach = apex_distance.max(plane.signed_distance(&base) + rim);
+
+                apex_distance >= -range && cone_reach >= S::zero()
+            })
+        }
+    }
+}
+
+/// Per-tile light lists for tiled (forward+) shading: the screen divided
+/// into square tiles, with a list of the lights reaching each tile's
+/// frustum.
+///
+/// Tiles are numbered row by row from the **top left** corner of the
+/// screen, as in `screen::Viewport`, so tile `(x, y)` is at index
+/// `x + columns * y`. Like `clustering::ClusterGrid`, the lists are stored
+/// as one `[offset, count]` pair per tile into one shared array of light
+/// indices. Tiles span the whole depth range, so the lists are longer than
+/// cluster lists, but they need no depth information to build.
+#[derive(Clone, Debug, Default)]
+pub struct TiledLightLists {
+    tile_size: u32,
+    columns: u32,
+    rows: u32,
+    tiles: Vec<[u32; 2]>,
+    light_indices: Vec<u32>,
+}
+
+impl TiledLightLists {
+    /// Construct empty lists for tiles of `tile_size` pixels on a side.
+    pub fn new(tile_size: u32) -> TiledLightLists {
+        TiledLightLists {
+            tile_size: tile_size.max(1),
+            columns: 0,
+            rows: 0,
+            tiles: Vec::new(),
+            light_indices: Vec::new(),
+        }
+    }
+
+    /// The number of pixels on a side of a tile.
+    #[inline]
+    pub fn tile_size(&self) -> u32 {
+        self.tile_size
+    }
+
+    /// The number of columns and rows of tiles.
+    #[inline]
+    pub fn dimensions(&self) -> [u32; 2] {
+        [self.columns, self.rows]
+    }
+
+    /// The index of the tile containing a pixel, with the origin at the top
+    /// left of the screen.
+    #[inline]
+    pub fn tile_index(&self, pixel_x: u32, pixel_y: u32) -> usize {
+        ((pixel_x / self.tile_size) + self.columns * (pixel_y / self.tile_size)) as usize
+    }
+
+    /// The `[offset, count]` pair of each tile into the light indices.
+    #[inline]
+    pub fn tiles(&self) -> &[[u32; 2]] {
+        &self.tiles
+    }
+
+    /// The light indices of every tile, concatenated.
+    #[inline]
+    pub fn light_indices(&self) -> &[u32] {
+        &self.light_indices
+    }
+
+    /// The indices of the lights reaching a tile.
+    #[inline]
+    pub fn tile_lights(&self, index: usize) -> &[u32] {
+        let [offset, count] = self.tiles[index];
+
+        &self.light_indices[offset as usize..(offset + count) as usize]
+    }
+
+    /// Build the light lists of a screen of `width` by `height` pixels seen
+    /// through an OpenGL view projection matrix, replacing the previous
+    /// lists. Point lights are tested by their range spheres and spotlights
+    /// by their cones against each tile's frustum, with ranges at which
+    /// attenuation falls below `threshold`. Lights without distance
+    /// attenuation reach every tile, so they are left out of the lists for
+    /// the renderer to shade separately.
+    pub fn build<S, M>(
+        &mut self,
+        view_projection: &Matrix4x4<S>,
+        width: u32,
+        height: u32,
+        lights: &[Light<S, M>],
+        threshold: S)
+        where S: ScalarFloat,
+              M: IlluminationModel + LightModelInfo<S> + ShadowCone<S>,
+    {
+        let volumes: Vec<ShadowVolume<S>> = lights.iter().map(|light| light.shadow_volume(threshold)).collect();
+
+        self.build_volumes(view_projection, width, height, &volumes);
+    }
+
+    /// Build the light lists of a screen from the volumes lights of any
+    /// illumination model reach, such as those of `Light::shadow_volume`,
+    /// replacing the previous lists. The light indices in the lists are
+    /// indices into `volumes`. Volumes with an infinite range reach every
+    /// tile, and are left out of the lists as in [`build`](#method.build).
+    pub fn build_volumes<S>(
+        &mut self,
+        view_projection: &Matrix4x4<S>,
+        width: u32,
+        height: u32,
+        volumes: &[ShadowVolume<S>])
+        where S: ScalarFloat,
+    {
+        self.columns = width.div_ceil(self.tile_size);
+        self.rows = height.div_ceil(self.tile_size);
+
+        let volumes: Vec<(u32, ShadowVolume<S>)> = volumes.iter().enumerate().filter_map(|(i, &volume)| {
+            let range = match volume {
+                ShadowVolume::Sphere { radius, .. } => radius,
+                ShadowVolume::Cone { range, .. } => range,
+            };
+
+            if range.is_finite() { Some((i as u32, volume)) } else { None }
+        }).collect();
+
+        // The rows of the matrix, which is stored by columns.
+        let columns: &[[S; 4]; 4] = view_projection.as_ref();
+        let row = |r: usize| [columns[0][r], columns[1][r], columns[2][r], columns[3][r]];
+        let (row_x, row_y, row_z, row_w) = (row(0), row(1), row(2), row(3));
+        let one = S::one();
+        let two = one + one;
+        let width_scalar: S = num_traits::cast(width.max(1)).unwrap();
+        let height_scalar: S = num_traits::cast(height.max(1)).unwrap();
+        let tile_size: S = num_traits::cast(self.tile_size).unwrap();
+        let ndc_x = |pixel: S| (two * pixel / width_scalar - one).min(one);
+        let ndc_y = |pixel: S| (one - two * pixel / height_scalar).max(-one);
+        let near = plane(combine(one, &row_z, one, &row_w));
+        let far = plane(combine(-one, &row_z, one, &row_w));
+
+        self.tiles.clear();
+        self.light_indices.clear();
+        for y in 0..self.rows {
+            let top = ndc_y(num_traits::cast::<u32, S>(y).unwrap() * tile_size);
+            let bottom = ndc_y(num_traits::cast::<u32, S>(y + 1).unwrap() * tile_size);
+            for x in 0..self.columns {
+                let left = ndc_x(num_traits::cast::<u32, S>(x).unwrap() * tile_size);
+                let right = ndc_x(num_traits::cast::<u32, S>(x + 1).unwrap() * tile_size);
+                // A clip space point is inside the tile when
+                // `left * w <= x <= right * w`, and likewise for `y`.
+                let planes = [
+                    plane(combine(one, &row_x, -left, &row_w)),
+                    plane(combine(-one, &row_x, right, &row_w)),
+                    plane(combine(one, &row_y, -bottom, &row_w)),
+                    plane(combine(-one, &row_y, top, &row_w)),
+                    near,
+                    far,
+                ];
+
+                let offset = self.light_indices.len() as u32;
+                for &(light, ref volume) in volumes.iter() {
+                    if intersects(volume, &planes) {
+                        self.light_indices.push(light);
+                    }
+                }
+                self.tiles.push([offset, self.light_indices.len() as u32 - offset]);
+            }
+        }
+        stats::record(Counter::TileEntries, self.light_indices.len());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+
+    /// The view projection matrix of a camera at the origin looking down the
+    /// negative z axis, with a 90 degree field of view.
+    fn view_projection() -> Matrix4x4<f64> {
+        let (near, far) = (1.0, 100.0);
+
+        Matrix4x4::new(
+            1.0, 0.0, 0.0, 0.0,
+            0.0, 1.0, 0.0, 0.0,
+            0.0, 0.0, (far + near) / (near - far), -1.0,
+            0.0, 0.0, 2.0 * far * near / (near - far), 0.0,
+        )
+    }
+
+    /// The tiles a light was assigned to.
+    fn tiles_of(lists: &TiledLightLists, light: u32) -> Vec<usize> {
+        (0..lists.tiles().len()).filter(|&tile| lists.tile_lights(tile).contains(&light)).collect()
+    }
+
+    #[test]
+    fn test_build_volumes_assigns_tiles_from_top_left() {
+        let mut lists = TiledLightLists::new(16);
+        let volumes = [
+            ShadowVolume::Sphere { center: Vector3::new(-6.25, 6.25, -10.0), radius: 0.5 },
+            ShadowVolume::Sphere { center: Vector3::new(0.0, 0.0, -10.0), radius: 100.0 },
+            ShadowVolume::Sphere { center: Vector3::new(0.0, 0.0, -10.0), radius: f64::INFINITY },
+            ShadowVolume::Cone {
+                apex: Vector3::new(0.0, 0.0, -5.0),
+                axis: Vector3::new(0.0, 0.0, -1.0),
+                cos_half_angle: 5.0_f64.to_radians().cos(),
+                range: 50.0,
+            },
+        ];
+        lists.build_volumes(&view_projection(), 64, 64, &volumes);
+
+        assert_eq!(lists.dimensions(), [4, 4]);
+        assert_eq!(lists.tile_index(12, 12), 0);
+        assert_eq!(tiles_of(&lists, 0), vec![0]);
+        assert_eq!(tiles_of(&lists, 1), (0..16).collect::<Vec<usize>>());
+        // Lights without distance attenuation are in no tile.
+        assert!(tiles_of(&lists, 2).is_empty());
+        assert_eq!(tiles_of(&lists, 3), vec![5, 6, 9, 10]);
+    }
+
+    #[test]
+    fn test_build_volumes_culls_lights_behind_the_camera() {
+        let mut lists = TiledLightLists::new(16);
+        let volumes = [ShadowVolume::Sphere { center: Vector3::new(0.0, 0.0, 10.0), radius: 2.0 }];
+        lists.build_volumes(&view_projection(), 64, 48, &volumes);
+
+        assert_eq!(lists.dimensions(), [4, 3]);
+        assert!(lists.light_indices().is_empty());
+    }
+}
@@ -0,0 +1,278 @@
+use cglinalg::{
+    Vector3,
+    ScalarFloat,
+};
+use crate::{
+    AmbientLightModel,
+    ConeFalloff,
+    DirectionalLightModel,
+    HemisphereLightModel,
+    IlluminationModel,
+    Light,
+    PointLightModel,
+    SpotLightModel,
+};
+use crate::attenuation::Attenuation;
+use crate::gpu::attenuation_to_f32;
+use crate::pbr::PbrLightModel;
+use crate::polarity::Polarity;
+use crate::shadow::ShadowFilter;
+use crate::volumetric::VolumetricParameters;
+
+
+/// The value of one uniform, in the types OpenGL uniforms take.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum UniformValue {
+    Float(f32),
+    Uint(u32),
+    Vec3([f32; 3]),
+    Vec4([f32; 4]),
+    /// A 4x4 matrix in column-major order.
+    Mat4([f32; 16]),
+}
+
+/// A destination for named uniform values, such as a glow or glium program.
+/// Any closure taking a name and a value is a uniform writer.
+pub trait UniformWriter {
+    /// Set the uniform `name` to `value`.
+    fn write(&mut self, name: &str, value: UniformValue);
+}
+
+impl<F> UniformWriter for F where F: FnMut(&str, UniformValue) {
+    #[inline]
+    fn write(&mut self, name: &str, value: UniformValue) {
+        self(name, value)
+    }
+}
+
+/// An illumination model whose parameters can be written as uniforms.
+///
+/// Each parameter is written as the uniform `prefix.name`, or `name` with an
+/// empty prefix, so with a prefix naming a GLSL struct uniform the names
+/// address the struct's fields. The names and encodings of the point, spot,
+/// and directional models match the structs of the `shadergen` module.
+pub trait ModelUniforms {
+    /// Write the model's parameters to a uniform writer.
+    fn write_uniforms<W: UniformWriter + ?Sized>(&self, prefix: &str, writer: &mut W);
+}
+
+#[inline]
+fn uniform_name(prefix: &str, field: &str) -> String {
+    if prefix.is_empty() {
+        String::from(field)
+    } else {
+        format!("{}.{}", prefix, field)
+    }
+}
+
+#[inline]
+fn to_f32<S: ScalarFloat>(value: S) -> f32 {
+    num_traits::cast(value).unwrap()
+}
+
+#[inline]
+fn write_float<S, W>(writer: &mut W, prefix: &str, field: &str, value: S)
+    where S: ScalarFloat,
+          W: UniformWriter + ?Sized,
+{
+    writer.write(&uniform_name(prefix, field), UniformValue::Float(to_f32(value)));
+}
+
+#[inline]
+fn write_uint<W: UniformWriter + ?Sized>(writer: &mut W, prefix: &str, field: &str, value: u32) {
+    writer.write(&uniform_name(prefix, field), UniformValue::Uint(value));
+}
+
+#[inline]
+fn write_vec3<S, W>(writer: &mut W, prefix: &str, field: &str, value: &Vector3<S>)
+    where S: ScalarFloat,
+          W: UniformWriter + ?Sized,
+{
+    let value = [to_f32(value.x), to_f32(value.y), to_f32(value.z)];
+    writer.write(&uniform_name(prefix, field), UniformValue::Vec3(value));
+}
+
+fn write_attenuation<S, W>(writer: &mut W, prefix: &str, attenuation: &Attenuation<S>)
+    where S: ScalarFloat,
+          W: UniformWriter + ?Sized,
+{
+    let (kind, parameters) = attenuation_to_f32(attenuation);
+    writer.write(&uniform_name(prefix, "attenuation"), UniformValue::Vec4(parameters));
+    write_uint(writer, prefix, "attenuation_kind", kind);
+}
+
+#[inline]
+fn write_polarity<S, W>(writer: &mut W, prefix: &str, polarity: Polarity)
+    where S: ScalarFloat,
+          W: UniformWriter + ?Sized,
+{
+    write_float(writer, prefix, "polarity", polarity.sign::<S>());
+}
+
+/// Write the volumetric parameters as the fields of a nested `volumetric`
+/// struct, with zeros for a light that does not scatter in media.
+fn write_volumetric<S, W>(writer: &mut W, prefix: &str, volumetric: &Option<VolumetricParameters<S>>)
+    where S: ScalarFloat,
+          W: UniformWriter + ?Sized,
+{
+    let prefix = uniform_name(prefix, "volumetric");
+    let (scattering, anisotropy, intensity) = match *volumetric {
+        Some(parameters) => (parameters.scattering, parameters.anisotropy, parameters.intensity),
+        None => (S::zero(), S::zero(), S::zero()),
+    };
+    write_float(writer, &prefix, "scattering", scattering);
+    write_float(writer, &prefix, "anisotropy", anisotropy);
+    write_float(writer, &prefix, "intensity", intensity);
+}
+
+impl<S> ModelUniforms for AmbientLightModel<S> where S: ScalarFloat {
+    fn write_uniforms<W: UniformWriter + ?Sized>(&self, prefix: &str, writer: &mut W) {
+        write_vec3(writer, prefix, "ambient", &self.ambient);
+    }
+}
+
+impl<S> ModelUniforms for HemisphereLightModel<S> where S: ScalarFloat {
+    fn write_uniforms<W: UniformWriter + ?Sized>(&self, prefix: &str, writer: &mut W) {
+        write_vec3(writer, prefix, "sky", &self.sky);
+        write_vec3(writer, prefix, "ground", &self.ground);
+    }
+}
+
+/// Falloff curves are not written; they belong in lookup textures baked
+/// with `FalloffCurve::bake`, and `attenuation` holds the base attenuation.
+impl<S> ModelUniforms for PointLightModel<S> where S: ScalarFloat {
+    fn write_uniforms<W: UniformWriter + ?Sized>(&self, prefix: &str, writer: &mut W) {
+        write_vec3(writer, prefix, "ambient", &self.ambient);
+        write_vec3(writer, prefix, "diffuse", &self.diffuse);
+        write_vec3(writer, prefix, "specular", &self.specular);
+        write_vec3(writer, prefix, "shadow_tint", &self.shadow_tint);
+        write_attenuation(writer, prefix, &self.attenuation);
+        write_polarity::<S, _>(writer, prefix, self.polarity);
+        write_volumetric(writer, prefix, &self.volumetric);
+    }
+}
+
+/// Falloff curves and angular profiles are not written; they belong in
+/// lookup textures baked with `FalloffCurve::bake` and
+/// `AngularProfile::bake`, and `attenuation` holds the base attenuation.
+impl<S> ModelUniforms for SpotLightModel<S> where S: ScalarFloat {
+    fn write_uniforms<W: UniformWriter + ?Sized>(&self, prefix: &str, writer: &mut W) {
+        let cone_exponent = match self.cone_falloff {
+            ConeFalloff::Smoothstep => -S::one(),
+            ConeFalloff::Exponent(exponent) => exponent,
+        };
+        write_float(writer, prefix, "cutoff", self.cutoff);
+        write_float(writer, prefix, "outer_cutoff", self.outer_cutoff);
+        write_float(writer, prefix, "cone_exponent", cone_exponent);
+        write_vec3(writer, prefix, "ambient", &self.ambient);
+        write_vec3(writer, prefix, "diffuse", &self.diffuse);
+        write_vec3(writer, prefix, "specular", &self.specular);
+        write_vec3(writer, prefix, "shadow_tint", &self.shadow_tint);
+        write_attenuation(writer, prefix, &self.attenuation);
+        write_polarity::<S, _>(writer, prefix, self.polarity);
+        write_volumetric(writer, prefix, &self.volumetric);
+    }
+}
+
+impl<S> ModelUniforms for DirectionalLightModel<S> where S: ScalarFloat {
+    fn write_uniforms<W: UniformWriter + ?Sized>(&self, prefix: &str, writer: &mut W) {
+        write_vec3(writer, prefix, "ambient", &self.ambient);
+        write_vec3(writer, prefix, "diffuse", &self.diffuse);
+        write_vec3(writer, prefix, "specular", &self.specular);
+        write_vec3(writer, prefix, "shadow_tint", &self.shadow_tint);
+    }
+}
+
+/// The color is written resolved to linear RGB.
+impl<S> ModelUniforms for PbrLightModel<S> where S: ScalarFloat {
+    fn write_uniforms<W: UniformWriter + ?Sized>(&self, prefix: &str, writer: &mut W) {
+        write_vec3(writer, prefix, "color", &self.color.resolved_color());
+        write_float(writer, prefix, "intensity", self.intensity);
+        write_vec3(writer, prefix, "shadow_tint", &self.shadow_tint);
+        write_attenuation(writer, prefix, &self.attenuation);
+        write_polarity::<S, _>(writer, prefix, self.polarity);
+    }
+}
+
+impl<S, M> Light<S, M>
+    where S: ScalarFloat,
+          M: IlluminationModel + ModelUniforms,
+{
+    /// Write every parameter of the light as a uniform named
+    /// `prefix.name`, so a whole light can be uploaded with one call.
+    ///
+    /// The light writes its model's parameters, then
+    ///
+    /// * `position`, `direction` (the forward axis), and `view_matrix`,
+    /// * `exposure_compensation`, `diffuse_factor`, `specular_factor`, and
+    ///   `layers` (the layer mask bits),
+    /// * `jitter.radius` and `jitter.seed`,
+    /// * `casts_shadows` (zero or one) and the shadow settings as the fields
+    ///   `near`, `far`, `constant_bias`, `slope_scaled_bias`,
+    ///   `normal_offset`, `filter` (zero for depth, one for variance, and two
+    ///   for exponential shadow maps), and `filter_parameters` (the minimum
+    ///   variance, the light bleeding reduction, and the exponent) of a
+    ///   nested `shadow` struct, all zero for a light without shadows.
+    ///
+    /// Programs may declare only the uniforms they use, and writers should
+    /// ignore names the program does not have.
+    pub fn write_uniforms<W: UniformWriter + ?Sized>(&self, prefix: &str, writer: &mut W) {
+        self.model.write_uniforms(prefix, writer);
+
+        write_vec3(writer, prefix, "position", &self.position());
+        write_vec3(writer, prefix, "direction", &self.forward_axis());
+        let view_matrix = self.view_matrix();
+        let view_matrix: &[S; 16] = view_matrix.as_ref();
+        let mut view_matrix_f32 = [0.0; 16];
+        for (element, value) in view_matrix_f32.iter_mut().zip(view_matrix.iter()) {
+            *element = to_f32(*value);
+        }
+        writer.write(&uniform_name(prefix, "view_matrix"), UniformValue::Mat4(view_matrix_f32));
+
+        write_float(writer, prefix, "exposure_compensation", self.exposure_compensation);
+        write_float(writer, prefix, "diffuse_factor", self.diffuse_factor);
+        write_float(writer, prefix, "specular_factor", self.specular_factor);
+        write_uint(writer, prefix, "layers", self.layers.bits());
+
+        let jitter_prefix = uniform_name(prefix, "jitter");
+        write_float(writer, &jitter_prefix, "radius", self.jitter.radius);
+        write_uint(writer, &jitter_prefix, "seed", self.jitter.seed);
+
+        write_uint(writer, prefix, "casts_shadows", self.shadow_settings.is_some() as u32);
+        let shadow_prefix = uniform_name(prefix, "shadow");
+        let zero = S::zero();
+        let (near, far, constant_bias, slope_scaled_bias, normal_offset, filter) = match self.shadow_settings {
+            Some(settings) => (
+                settings.near,
+                settings.far,
+                settings.constant_bias,
+                settings.slope_scaled_bias,
+                settings.normal_offset,
+                settings.filter,
+            ),
+            None => (zero, zero, zero, zero, zero, ShadowFilter::Depth),
+        };
+        let (filter_kind, filter_parameters) = match filter {
+            ShadowFilter::Depth => (0, [zero, zero, zero]),
+            ShadowFilter::Variance { min_variance, light_bleeding_reduction } => {
+                (1, [min_variance, light_bleeding_reduction, zero])
+            }
+            ShadowFilter::Exponential { exponent } => (2, [zero, zero, exponent]),
+        };
+        write_float(writer, &shadow_prefix, "near", near);
+        write_float(writer, &shadow_prefix, "far", far);
+        write_float(writer, &shadow_prefix, "constant_bias", constant_bias);
+        write_float(writer, &shadow_prefix, "slope_scaled_bias", slope_scaled_bias);
+        write_float(writer, &shadow_prefix, "normal_offset", normal_offset);
+        write_uint(writer, &shadow_prefix, "filter", filter_kind);
+        writer.write(
+            &uniform_name(&shadow_prefix, "filter_parameters"),
+            UniformValue::Vec4([
+                to_f32(filter_parameters[0]),
+                to_f32(filter_parameters[1]),
+                to_f32(filter_parameters[2]),
+                0.0,
+            ]),
+        );
+    }
+}
@@ -0,0 +1,87 @@
+use cglinalg::{
+    ScalarFloat,
+};
+use crate::{
+    IlluminationModel,
+    Light,
+    PointLightModel,
+    SpotLightModel,
+};
+
+
+/// The parameters of a light's contribution to participating media, for
+/// renderers that ray march volumetric light shafts.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct VolumetricParameters<S> {
+    /// The scattering coefficient of the medium around the light, per world
+    /// unit.
+    pub scattering: S,
+    /// The Henyey-Greenstein anisotropy of the medium, from -1 for full back
+    /// scattering through 0 for isotropic scattering to 1 for full forward
+    /// scattering.
+    pub anisotropy: S,
+    /// A multiplier on the light's emission in the medium, independent of
+    /// its surface lighting.
+    pub intensity: S,
+}
+
+impl<S> VolumetricParameters<S> where S: ScalarFloat {
+    /// Construct new volumetric parameters. The anisotropy is clamped to the
+    /// open interval between -1 and 1.
+    #[inline]
+    pub fn new(scattering: S, anisotropy: S, intensity: S) -> VolumetricParameters<S> {
+        let limit: S = num_traits::cast(0.999).unwrap();
+
+        VolumetricParameters {
+            scattering: scattering,
+            anisotropy: anisotropy.max(-limit).min(limit),
+            intensity: intensity,
+        }
+    }
+
+    /// Evaluate the Henyey-Greenstein phase function for light scattering by
+    /// an angle with cosine `cos_angle` from its direction of travel.
+    #[inline]
+    pub fn phase(&self, cos_angle: S) -> S {
+        let four_pi: S = num_traits::cast(4_f64 * core::f64::consts::PI).unwrap();
+        let two = S::one() + S::one();
+        let g = self.anisotropy;
+        let denominator = (S::one() + g * g - two * g * cos_angle).max(S::epsilon());
+
+        (S::one() - g * g) / (four_pi * denominator * denominator.sqrt())
+    }
+}
+
+/// A lighting model that may carry volumetric parameters.
+pub trait Volumetric<S> {
+    /// The model's volumetric parameters, or `None` if the light does not
+    /// illuminate participating media.
+    fn volumetric(&self) -> Option<VolumetricParameters<S>>;
+}
+
+impl<S> Volumetric<S> for PointLightModel<S> where S: ScalarFloat {
+    #[inline]
+    fn volumetric(&self) -> Option<VolumetricParameters<S>> {
+        self.volumetric
+    }
+}
+
+impl<S> Volumetric<S> for SpotLightModel<S> where S: ScalarFloat {
+    #[inline]
+    fn volumetric(&self) -> Option<VolumetricParameters<S>> {
+        self.volumetric
+    }
+}
+
+impl<S, M> Light<S, M>
+    where S: ScalarFloat,
+          M: IlluminationModel + Volumetric<S>,
+{
+    /// The light's volumetric parameters, or `None` if the light does not
+    /// illuminate participating media.
+    #[inline]
+    pub fn volumetric(&self) -> Option<VolumetricParameters<S>> {
+        self.model().volumetric()
+    }
+}
@@ -0,0 +1,126 @@
+use crate::std430::{
+    Std430Light,
+    STD430_HEADER_SIZE,
+    pack_std430,
+};
+use core::marker;
+use core::num;
+
+
+/// A wgpu storage buffer holding a collection of lights in the std430 layout
+/// of `std430::pack_std430`, for renderers that would rather not write the
+/// upload plumbing themselves.
+///
+/// The buffer has room for a fixed number of lights. When an update holds
+/// more lights than that, the buffer is replaced by a larger one, and any
+/// bind groups referring to the old buffer must be recreated.
+#[derive(Debug)]
+pub struct LightBuffer<L> {
+    buffer: wgpu::Buffer,
+    label: Option<String>,
+    capacity: usize,
+    len: usize,
+    _marker: marker::PhantomData<L>,
+}
+
+impl<L> LightBuffer<L> where L: Std430Light {
+    /// The size in bytes of a buffer with room for `capacity` lights.
+    #[inline]
+    fn buffer_size(capacity: usize) -> u64 {
+        (STD430_HEADER_SIZE + capacity.max(1) * L::STD430_SIZE) as u64
+    }
+
+    fn create_buffer(device: &wgpu::Device, label: Option<&str>, capacity: usize) -> wgpu::Buffer {
+        device.create_buffer(&wgpu::BufferDescriptor {
+            label: label,
+            size: Self::buffer_size(capacity),
+            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        })
+    }
+
+    /// Construct an empty light buffer with room for `capacity` lights.
+    pub fn with_capacity(device: &wgpu::Device, label: Option<&str>, capacity: usize) -> LightBuffer<L> {
+        LightBuffer {
+            buffer: Self::create_buffer(device, label, capacity),
+            label: label.map(String::from),
+            capacity: capacity.max(1),
+            len: 0,
+            _marker: marker::PhantomData,
+        }
+    }
+
+    /// Construct a light buffer holding a set of lights.
+    pub fn new(device: &wgpu::Device, queue: &wgpu::Queue, label: Option<&str>, lights: &[L]) -> LightBuffer<L> {
+        let mut buffer = Self::with_capacity(device, label, lights.len());
+        buffer.update(device, queue, lights);
+
+        buffer
+    }
+
+    /// Upload a new set of lights, replacing the previous ones. If the lights
+    /// do not fit, the buffer is replaced by one with room for at least
+    /// twice as many lights, and the result is `true` to signal that bind
+    /// groups referring to the buffer must be recreated.
+    pub fn update(&mut self, device: &wgpu::Device, queue: &wgpu::Queue, lights: &[L]) -> bool {
+        let reallocated = lights.len() > self.capacity;
+        if reallocated {
+            let capacity = lights.len().max(2 * self.capacity).next_power_of_two();
+            self.buffer = Self::create_buffer(device, self.label.as_deref(), capacity);
+            self.capacity = capacity;
+        }
+        queue.write_buffer(&self.buffer, 0, &pack_std430(lights));
+        self.len = lights.len();
+
+        reallocated
+    }
+
+    /// The underlying wgpu buffer.
+    #[inline]
+    pub fn buffer(&self) -> &wgpu::Buffer {
+        &self.buffer
+    }
+
+    /// The number of lights in the last upload.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Determine whether the last upload held no lights.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The number of lights the buffer has room for.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// The bind group entry binding the whole buffer at `binding`.
+    #[inline]
+    pub fn bind_group_entry(&self, binding: u32) -> wgpu::BindGroupEntry<'_> {
+        wgpu::BindGroupEntry {
+            binding: binding,
+            resource: self.buffer.as_entire_binding(),
+        }
+    }
+
+    /// The bind group layout entry of a read only light buffer at `binding`,
+    /// visible to the given shader stages. The minimum binding size is the
+    /// header and one light, the smallest buffer a shader can index.
+    pub fn layout_entry(binding: u32, visibility: wgpu::ShaderStages) -> wgpu::BindGroupLayoutEntry {
+        wgpu::BindGroupLayoutEntry {
+            binding: binding,
+            visibility: visibility,
+            ty: wgpu::BindingType::Buffer {
+                ty: wgpu::BufferBindingType::Storage { read_only: true },
+                has_dynamic_offset: false,
+                min_binding_size: num::NonZeroU64::new(Self::buffer_size(1)),
+            },
+            count: None,
+        }
+    }
+}